@@ -0,0 +1,32 @@
+#!/usr/bin/env python3
+"""Convert the Silero VAD TorchScript release to safetensors for the daemon
+
+Usage: convert-silero-vad.py silero_vad.jit silero_vad.safetensors
+
+The input is `src/silero_vad/data/silero_vad.jit` from the Silero VAD repository
+(v5). Only the 16 kHz network is kept. Needs `torch` and `safetensors`.
+"""
+
+import sys
+
+import torch
+from safetensors.torch import save_file
+
+
+def main() -> None:
+    if len(sys.argv) != 3:
+        sys.exit(__doc__.splitlines()[2])
+    source, target = sys.argv[1:]
+
+    model = torch.jit.load(source, map_location="cpu")
+    # `_model` runs 16 kHz audio, `_model_8k` the 8 kHz variant
+    weights = {
+        name: tensor.float().contiguous()
+        for name, tensor in model._model.state_dict().items()
+    }
+    save_file(weights, target)
+    print(f"Wrote {len(weights)} tensors to {target}")
+
+
+if __name__ == "__main__":
+    main()
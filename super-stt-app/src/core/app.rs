@@ -4,11 +4,13 @@ use crate::audio::{parse_audio_level_from_udp, parse_recording_state_from_udp};
 
 use crate::daemon::client::{
     cancel_download, fetch_daemon_config, get_current_device, get_current_model,
-    get_download_status, get_preview_typing, list_available_models, load_audio_themes, ping_daemon,
+    get_download_status, get_preview_typing, list_models_detailed, load_audio_themes, ping_daemon,
     send_record_command, set_and_test_audio_theme, set_device, set_model, set_preview_typing,
-    test_daemon_connection,
+    test_audio_input, test_daemon_connection, watch_daemon_events,
+};
+use crate::state::{
+    AudioTheme, ContextPage, DaemonStatus, MenuAction, MicCheck, Page, RecordingStatus,
 };
-use crate::state::{AudioTheme, ContextPage, DaemonStatus, MenuAction, Page, RecordingStatus};
 use crate::ui::messages::Message;
 use crate::ui::views;
 use cosmic::app::context_drawer;
@@ -18,9 +20,11 @@ use cosmic::widget::{icon, menu, nav_bar};
 use futures_util::SinkExt;
 use log::{info, warn};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use super_stt_shared::UdpAuth;
+use super_stt_shared::models::protocol::ModelInfo;
 use super_stt_shared::stt_model::STTModel;
 use tokio::net::UdpSocket;
 use tokio::time::Duration;
@@ -61,8 +65,8 @@ pub struct AppModel {
     // Super STT specific state
     /// Socket path for daemon communication
     pub socket_path: PathBuf,
-    /// UDP port for audio streaming
-    pub udp_port: u16,
+    /// Daemon address for UDP audio streaming
+    pub udp_addr: SocketAddr,
     /// Current daemon connection status
     pub daemon_status: DaemonStatus,
     /// Current recording status
@@ -73,6 +77,8 @@ pub struct AppModel {
     pub audio_level: f32,
     /// Whether speech is currently detected
     pub is_speech_detected: bool,
+    /// Microphone check on the Testing page
+    pub mic_check: MicCheck,
     /// Available audio themes
     pub audio_themes: Vec<AudioTheme>,
     /// Currently selected audio theme
@@ -83,8 +89,8 @@ pub struct AppModel {
     pub last_udp_data: std::time::Instant,
 
     // Model management state
-    /// Available models from daemon
-    pub available_models: Vec<STTModel>,
+    /// Available models from daemon, with whether each is downloaded
+    pub available_models: Vec<ModelInfo>,
     /// Currently loaded model
     pub current_model: STTModel,
     /// The model we had before starting a download (to revert to on cancel)
@@ -164,12 +170,13 @@ impl cosmic::Application for AppModel {
             nav,
             // Initialize Super STT state using proper socket path
             socket_path: super_stt_shared::validation::get_secure_socket_path(),
-            udp_port: 8765,
+            udp_addr: super_stt_shared::daemon_udp_addr(),
             daemon_status: DaemonStatus::Disconnected,
             recording_status: RecordingStatus::Idle,
             transcription_text: String::new(),
             audio_level: 0.0,
             is_speech_detected: false,
+            mic_check: MicCheck::default(),
             audio_themes: Vec::new(),
             selected_audio_theme: AudioTheme::default(),
             udp_restart_counter: 0,
@@ -273,7 +280,7 @@ impl cosmic::Application for AppModel {
             return views::connection::page(
                 &self.daemon_status,
                 self.socket_path.to_string_lossy().to_string(),
-                self.udp_port,
+                self.udp_addr,
             );
         }
 
@@ -301,11 +308,12 @@ impl cosmic::Application for AppModel {
                 &self.transcription_text,
                 self.audio_level,
                 self.is_speech_detected,
+                &self.mic_check,
             ),
             Page::Connection => views::connection::page(
                 &self.daemon_status,
                 self.socket_path.to_string_lossy().to_string(),
-                self.udp_port,
+                self.udp_addr,
             ),
         }
     }
@@ -319,12 +327,16 @@ impl cosmic::Application for AppModel {
         // Connection monitoring constants
         const PING_INTERVAL_SECS: u64 = 5;
 
+        let daemon_addr = self.udp_addr;
+        let socket_path = self.socket_path.clone();
+
         Subscription::batch(vec![
             // UDP audio level streaming subscription with restart capability
             Subscription::run_with_id(
                 self.udp_restart_counter,
-                cosmic::iced::stream::channel(100, |mut channel| async move {
-                    let socket = match UdpSocket::bind("127.0.0.1:0").await {
+                cosmic::iced::stream::channel(100, move |mut channel| async move {
+                    let bind_addr = super_stt_shared::client_bind_addr(daemon_addr);
+                    let socket = match UdpSocket::bind(bind_addr).await {
                         Ok(socket) => Arc::new(socket),
                         Err(e) => {
                             warn!("Failed to bind UDP socket: {e}");
@@ -350,7 +362,7 @@ impl cosmic::Application for AppModel {
                     };
 
                     if let Err(e) = socket
-                        .send_to(registration_msg.as_bytes(), "127.0.0.1:8765")
+                        .send_to(registration_msg.as_bytes(), daemon_addr)
                         .await
                     {
                         warn!("Failed to register with daemon: {e}");
@@ -389,9 +401,8 @@ impl cosmic::Application for AppModel {
                     loop {
                         match socket.recv_from(&mut buffer).await {
                             Ok((len, addr)) => {
-                                // Validate source address - only accept from localhost
-                                if addr.ip() != std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST)
-                                {
+                                // Validate source address - only accept from the daemon's loopback family
+                                if !super_stt_shared::is_daemon_source(addr, daemon_addr) {
                                     warn!("Rejected UDP packet from unauthorized source: {addr}");
                                     continue;
                                 }
@@ -402,6 +413,23 @@ impl cosmic::Application for AppModel {
                                     continue;
                                 }
 
+                                // The daemon rotated its secret; re-register before the grace period ends
+                                if buffer[0] == super_stt_shared::udp::SECRET_ROTATED_PACKET {
+                                    match auth.create_auth_message("applet") {
+                                        Ok(msg) => {
+                                            if let Err(e) =
+                                                socket.send_to(msg.as_bytes(), daemon_addr).await
+                                            {
+                                                warn!("Failed to re-register with daemon: {e}");
+                                            }
+                                        }
+                                        Err(e) => {
+                                            warn!("Failed to create registration message: {e}")
+                                        }
+                                    }
+                                    continue;
+                                }
+
                                 let data = buffer[..len].to_vec();
                                 if channel.send(Message::UdpDataReceived(data)).await.is_err() {
                                     break;
@@ -415,6 +443,21 @@ impl cosmic::Application for AppModel {
                     }
                 }),
             ),
+            // Daemon state changes; the stream reconnects itself when heartbeats stop
+            Subscription::run_with_id(
+                "daemon-events",
+                cosmic::iced::stream::channel(16, move |mut channel| async move {
+                    watch_daemon_events(
+                        socket_path,
+                        vec!["daemon_status_changed".to_string()],
+                        |_| {
+                            // A refresh already queued covers this event too
+                            let _ = channel.try_send(Message::RefreshDaemonStatus);
+                        },
+                    )
+                    .await;
+                }),
+            ),
             // Periodic connection monitoring
             cosmic::iced::time::every(std::time::Duration::from_secs(PING_INTERVAL_SECS))
                 .map(|_| Message::PingTimeout),
@@ -545,6 +588,33 @@ impl cosmic::Application for AppModel {
                 self.is_speech_detected = is_speech;
             }
 
+            Message::CheckMicrophone => {
+                self.mic_check.running = true;
+                self.mic_check.message = "Recording, say something...".to_string();
+                return Task::perform(
+                    test_audio_input(self.socket_path.clone(), self.mic_check.playback),
+                    |result| cosmic::Action::App(Message::MicCheckCompleted(result)),
+                );
+            }
+
+            Message::MicCheckPlaybackToggled(playback) => {
+                self.mic_check.playback = playback;
+            }
+
+            Message::MicCheckCompleted(result) => {
+                self.mic_check.running = false;
+                match result {
+                    Ok((levels, message)) => {
+                        self.mic_check.result = Some(levels);
+                        self.mic_check.message = message;
+                    }
+                    Err(e) => {
+                        self.mic_check.result = None;
+                        self.mic_check.message = format!("Error: {e}");
+                    }
+                }
+            }
+
             Message::AudioThemeSelected(theme) => {
                 self.selected_audio_theme = theme;
                 // Audio theme preference is now saved by the daemon automatically
@@ -665,7 +735,16 @@ impl AppModel {
                 })
             }
 
-            Message::DaemonConfigReceived(config) => {
+            Message::DaemonConfigReceived(info) => {
+                let config = info.config;
+                // Re-register on the daemon's actual address if it is not the one in use
+                let udp_addr = super_stt_shared::reported_daemon_udp_addr(info.udp_addr);
+                if udp_addr != self.udp_addr {
+                    info!("Daemon streams on {udp_addr}, restarting UDP subscription");
+                    self.udp_addr = udp_addr;
+                    self.udp_restart_counter += 1;
+                }
+
                 // Parse daemon configuration and sync audio theme settings
                 if let Some(audio_config) = config
                     .get("audio")
@@ -792,13 +871,28 @@ impl AppModel {
     }
 
     /// Handle download progress messages
-    #[allow(clippy::too_many_lines)]
+    #[allow(
+        clippy::too_many_lines,
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
     fn handle_download_messages(&mut self, message: Message) -> Task<cosmic::Action<Message>> {
         match message {
-            Message::DownloadProgressUpdate(progress) => {
+            Message::DownloadProgressUpdate(mut progress) => {
+                // Older daemons send no ETA, so derive it from the transfer rate
+                if progress.eta_seconds.is_none()
+                    && progress.speed_bps > 0.0
+                    && progress.total_bytes > progress.bytes_downloaded
+                {
+                    let remaining = (progress.total_bytes - progress.bytes_downloaded) as f64;
+                    progress.eta_seconds = Some((remaining / progress.speed_bps).ceil() as u64);
+                }
+
                 // We have an actual download in progress
                 self.download_progress = Some(progress.clone());
-                self.download_state = if progress.status == "downloading" {
+                let in_progress = matches!(progress.status.as_str(), "downloading" | "verifying");
+                self.download_state = if in_progress {
                     DownloadState::Active
                 } else {
                     DownloadState::Idle
@@ -935,7 +1029,7 @@ impl AppModel {
         match message {
             Message::LoadModels => Task::batch([
                 Task::perform(
-                    list_available_models(self.socket_path.clone()),
+                    list_models_detailed(self.socket_path.clone()),
                     |result| match result {
                         Ok(models) => cosmic::Action::App(Message::AvailableModelsLoaded(models)),
                         Err(e) => cosmic::Action::App(Message::ModelError(e)),
@@ -1083,8 +1177,7 @@ impl AppModel {
                 }
 
                 // Display sanitized error message to user
-                let sanitized_error = err
-                    .replace(&std::env::var("HOME").unwrap_or_default(), "$HOME")
+                let sanitized_error = super_stt_shared::utils::sanitize::sanitize_error(&err)
                     .chars()
                     .take(200)
                     .collect::<String>();
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use log::warn;
 use std::path::PathBuf;
 use std::sync::OnceLock;
+use std::time::Duration;
+use super_stt_shared::models::protocol::{InputTestResult, ModelInfo, NotificationEvent};
 use super_stt_shared::stt_model::STTModel;
 
 use crate::state::AudioTheme;
@@ -26,6 +29,47 @@ pub async fn send_record_command(socket_path: PathBuf) -> Result<String, String>
     }
 }
 
+/// Follow the daemon's `event_types` events for as long as the app runs
+///
+/// The stream is reopened whenever it dies, including when heartbeats stop arriving,
+/// and asks for the events missed while it was down.
+pub async fn watch_daemon_events(
+    socket_path: PathBuf,
+    event_types: Vec<String>,
+    mut on_event: impl FnMut(NotificationEvent),
+) {
+    const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+    let mut last_timestamp: Option<String> = None;
+    loop {
+        let since_timestamp = last_timestamp.clone();
+        let result = super_stt_shared::daemon::client::stream_daemon_events(
+            socket_path.clone(),
+            event_types.clone(),
+            since_timestamp,
+            get_client_id(),
+            |event| {
+                last_timestamp = Some(event.timestamp.clone());
+                on_event(event);
+            },
+        )
+        .await;
+        match result {
+            Ok(()) => warn!("Daemon closed the event stream, reconnecting"),
+            Err(e) => warn!("Event stream lost, reconnecting: {e}"),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Record a short clip on the daemon and get its levels with the daemon's verdict
+pub async fn test_audio_input(
+    socket_path: PathBuf,
+    playback: bool,
+) -> Result<(InputTestResult, String), String> {
+    super_stt_shared::daemon::client::test_audio_input(socket_path, playback, get_client_id()).await
+}
+
 /// Test daemon connection
 pub async fn test_daemon_connection(socket_path: PathBuf) -> Result<(), String> {
     super_stt_shared::daemon::client::test_daemon_connection(socket_path, get_client_id()).await
@@ -85,9 +129,9 @@ pub async fn set_model(socket_path: PathBuf, model: STTModel) -> Result<String,
     super_stt_shared::daemon::client::set_model(socket_path, model, get_client_id()).await
 }
 
-/// List all available models from daemon
-pub async fn list_available_models(socket_path: PathBuf) -> Result<Vec<STTModel>, String> {
-    super_stt_shared::daemon::client::list_available_models(socket_path, get_client_id()).await
+/// List all available models from daemon, with whether each is downloaded or loaded
+pub async fn list_models_detailed(socket_path: PathBuf) -> Result<Vec<ModelInfo>, String> {
+    super_stt_shared::daemon::client::list_models_detailed(socket_path, get_client_id()).await
 }
 
 /// Cancel any ongoing download
@@ -113,7 +157,9 @@ pub async fn set_device(socket_path: PathBuf, device: String) -> Result<(), Stri
 }
 
 /// Get current daemon configuration
-pub async fn fetch_daemon_config(socket_path: PathBuf) -> Result<serde_json::Value, String> {
+pub async fn fetch_daemon_config(
+    socket_path: PathBuf,
+) -> Result<super_stt_shared::daemon::client::DaemonConfigInfo, String> {
     super_stt_shared::daemon::client::fetch_daemon_config(socket_path, get_client_id()).await
 }
 
@@ -6,5 +6,6 @@ pub mod models;
 
 // Re-export commonly used types
 pub use models::{
-    AudioLevelData, AudioTheme, ContextPage, DaemonStatus, MenuAction, Page, RecordingStatus,
+    AudioLevelData, AudioTheme, ContextPage, DaemonStatus, MenuAction, MicCheck, Page,
+    RecordingStatus,
 };
@@ -5,6 +5,8 @@
 // Re-export AudioTheme from shared crate
 pub use super_stt_shared::models::theme::AudioTheme;
 
+use super_stt_shared::models::protocol::InputTestResult;
+
 /// Daemon connection status
 #[derive(Debug, Clone, Default, PartialEq)]
 pub enum DaemonStatus {
@@ -44,6 +46,19 @@ pub enum MenuAction {
     About,
 }
 
+/// State of the microphone check on the Testing page
+#[derive(Debug, Clone, Default)]
+pub struct MicCheck {
+    /// Whether the daemon is recording the check clip
+    pub running: bool,
+    /// Whether to play the clip back once recorded
+    pub playback: bool,
+    /// Levels of the latest check
+    pub result: Option<InputTestResult>,
+    /// The daemon's verdict on the latest check, or its error
+    pub message: String,
+}
+
 /// Audio level data from UDP packets
 #[derive(Debug)]
 pub struct AudioLevelData {
@@ -2,6 +2,7 @@
 
 //! Message types for the Super STT application.
 
+use super_stt_shared::models::protocol::{InputTestResult, ModelInfo};
 use super_stt_shared::stt_model::STTModel;
 
 use crate::state::{AudioTheme, ContextPage};
@@ -21,7 +22,7 @@ pub enum Message {
     ConnectToDaemon,
     DaemonConnectionResult(Result<(), String>),
     DaemonConnected,
-    DaemonConfigReceived(serde_json::Value),
+    DaemonConfigReceived(super_stt_shared::daemon::client::DaemonConfigInfo),
     DaemonError(String),
     TranscriptionReceived(String),
     AudioThemeSelected(AudioTheme),
@@ -37,14 +38,19 @@ pub enum Message {
         is_speech: bool,
     },
 
+    // Microphone check messages
+    CheckMicrophone,
+    MicCheckPlaybackToggled(bool),
+    MicCheckCompleted(Result<(InputTestResult, String), String>),
+
     // Model management messages
     LoadModels,
     ModelSelected(STTModel),
     ModelsLoaded {
         current: STTModel,
-        available: Vec<STTModel>,
+        available: Vec<ModelInfo>,
     },
-    AvailableModelsLoaded(Vec<STTModel>),
+    AvailableModelsLoaded(Vec<ModelInfo>),
     CurrentModelLoaded(STTModel),
     ModelChanged(STTModel),
     ModelError(String),
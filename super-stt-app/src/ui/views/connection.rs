@@ -6,12 +6,13 @@ use cosmic::{
     Element,
     widget::{settings, text},
 };
+use std::net::SocketAddr;
 
 /// Settings page view using cosmic-settings style
 pub fn page(
     daemon_status: &DaemonStatus,
     socket_path: String,
-    udp_port: u16,
+    udp_addr: SocketAddr,
 ) -> Element<'_, Message> {
     let status_text = match daemon_status {
         DaemonStatus::Connected => "✅ Connected".to_string(),
@@ -25,7 +26,10 @@ pub fn page(
             .title("Connection Information")
             .add(settings::item("Connection", text::body(status_text)))
             .add(settings::item("Socket Path", text::body(socket_path)))
-            .add(settings::item("UDP Port", text::body(udp_port.to_string())))
+            .add(settings::item(
+                "UDP Address",
+                text::body(udp_addr.to_string()),
+            ))
             .into(),
     ];
 
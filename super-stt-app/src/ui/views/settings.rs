@@ -5,7 +5,10 @@ use cosmic::iced_widget::{column, row};
 use cosmic::widget::{self, button, settings, text};
 use super_stt_shared::theme::AudioTheme;
 // Reuse shared models
-use super_stt_shared::{models::protocol::DownloadProgress, stt_model::STTModel};
+use super_stt_shared::{
+    models::protocol::{DownloadProgress, ModelInfo},
+    stt_model::STTModel,
+};
 
 use super::common::page_layout;
 use crate::ui::messages::Message;
@@ -104,6 +107,13 @@ fn download_progress_widget(
                 progress.total_files,
                 progress.percentage
             )
+        } else if progress.status == "verifying" {
+            format!(
+                "Verifying {} ({}/{})",
+                progress.model_name,
+                progress.file_index + 1,
+                progress.total_files
+            )
         } else {
             format!("{}: {}", progress.model_name, progress.status)
         };
@@ -127,7 +137,12 @@ fn download_progress_widget(
         let bytes_text = if progress.total_bytes > 0 {
             let mb_downloaded = progress.bytes_downloaded as f64 / (1024.0 * 1024.0);
             let mb_total = progress.total_bytes as f64 / (1024.0 * 1024.0);
-            format!("{mb_downloaded:.1} / {mb_total:.1} MB")
+            if progress.speed_bps > 0.0 {
+                let mb_per_second = progress.speed_bps / (1024.0 * 1024.0);
+                format!("{mb_downloaded:.1} / {mb_total:.1} MB at {mb_per_second:.1} MB/s")
+            } else {
+                format!("{mb_downloaded:.1} / {mb_total:.1} MB")
+            }
         } else {
             String::new()
         };
@@ -166,9 +181,23 @@ fn download_progress_widget(
     }
 }
 
+/// Dropdown label of a model, marking the ones that would need a download
+#[allow(clippy::cast_precision_loss)]
+fn model_label(info: &ModelInfo) -> String {
+    if !info.downloaded {
+        return format!("⬇ {}", info.model);
+    }
+    let size_mb = info.size_bytes as f64 / (1024.0 * 1024.0);
+    if size_mb >= 1024.0 {
+        format!("✓ {} ({:.1} GB)", info.model, size_mb / 1024.0)
+    } else {
+        format!("✓ {} ({size_mb:.0} MB)", info.model)
+    }
+}
+
 /// Create the model selection widget using cosmic-settings style
 fn model_selection_settings_widget<'a>(
-    available_models: &'a [STTModel],
+    available_models: &'a [ModelInfo],
     current_model: &'a STTModel,
     download_active: bool,
     current_device: &'a str,
@@ -183,12 +212,9 @@ fn model_selection_settings_widget<'a>(
     } else {
         let selected_index = available_models
             .iter()
-            .position(|model| model == current_model);
+            .position(|info| info.model == *current_model);
 
-        let model_names: Vec<String> = available_models
-            .iter()
-            .map(std::string::ToString::to_string)
-            .collect();
+        let model_names: Vec<String> = available_models.iter().map(model_label).collect();
 
         if download_active {
             section = section.add(settings::item(
@@ -200,8 +226,8 @@ fn model_selection_settings_widget<'a>(
             section = section.add(settings::item(
                 "Model",
                 widget::dropdown(model_names, selected_index, move |index| {
-                    if let Some(model) = available_models_clone.get(index) {
-                        Message::ModelSelected(*model)
+                    if let Some(info) = available_models_clone.get(index) {
+                        Message::ModelSelected(info.model)
                     } else {
                         Message::ModelError("Invalid model selection".to_string())
                     }
@@ -267,7 +293,7 @@ fn model_selection_settings_widget<'a>(
 pub fn page<'a>(
     audio_themes: &'a [AudioTheme],
     selected_audio_theme: &'a AudioTheme,
-    available_models: &'a [STTModel],
+    available_models: &'a [ModelInfo],
     current_model: &'a STTModel,
     download_progress: Option<&'a DownloadProgress>,
     download_active: bool,
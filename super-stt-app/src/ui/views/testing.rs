@@ -5,7 +5,7 @@ use cosmic::iced_widget::row;
 use cosmic::widget::{self, button, settings, text};
 
 use super::common::page_layout;
-use crate::state::RecordingStatus;
+use crate::state::{MicCheck, RecordingStatus};
 use crate::ui::messages::Message;
 
 /// Main dashboard view using cosmic-settings style
@@ -14,6 +14,7 @@ pub fn page<'a>(
     transcription_text: &'a str,
     audio_level: f32,
     is_speech_detected: bool,
+    mic_check: &'a MicCheck,
 ) -> Element<'a, Message> {
     let recording_text = match recording_status {
         RecordingStatus::Recording => {
@@ -56,7 +57,47 @@ pub fn page<'a>(
         .width(Length::Fill)
     };
 
+    // Microphone check widget, showing the peak level of the last check
+    let mic_check_widget = row![
+        button::standard("Check Microphone")
+            .on_press_maybe((!mic_check.running).then_some(Message::CheckMicrophone)),
+        widget::progress_bar(
+            0.0..=1.0,
+            mic_check.result.as_ref().map_or(0.0, |result| result.peak)
+        )
+        .width(Length::Fill),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(10);
+
+    let mic_check_summary = mic_check.result.as_ref().map_or_else(
+        || "Records two seconds from the input device".to_string(),
+        |result| {
+            format!(
+                "Peak {:.0}%, RMS {:.0}%, {}",
+                result.peak * 100.0,
+                result.rms * 100.0,
+                if result.speech_detected {
+                    "speech detected"
+                } else {
+                    "no speech"
+                }
+            )
+        },
+    );
+
     let sections = settings::view_column(vec![
+        // Microphone Check Section
+        settings::section()
+            .title("Microphone Check")
+            .add(settings::flex_item("Input Level", mic_check_widget))
+            .add(settings::item(
+                "Play Back Recording",
+                widget::toggler(mic_check.playback).on_toggle(Message::MicCheckPlaybackToggled),
+            ))
+            .add(settings::item("Levels", text::body(mic_check_summary)))
+            .add(settings::item("Result", text::body(&mic_check.message)))
+            .into(),
         // Recording Test Section
         settings::section()
             .title("Recording Test")
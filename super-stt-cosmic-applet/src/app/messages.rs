@@ -4,7 +4,7 @@ use super_stt_shared::theme::AudioTheme;
 
 use crate::models::{
     state::{IsOpen, RecordingState},
-    theme::{VisualizationColor, VisualizationTheme},
+    theme::{AmplitudeMapping, VisualizationColor, VisualizationTheme},
 };
 
 #[derive(Debug, Clone)]
@@ -12,7 +12,7 @@ pub enum Message {
     TogglePopup,
     CloseRequested(window::Id),
     DaemonConnected,
-    DaemonConfigReceived(serde_json::Value),
+    DaemonConfigReceived(super_stt_shared::daemon::client::DaemonConfigInfo),
     DaemonError(String),
     RecordingStateChanged(RecordingState),
     AudioLevelUpdate {
@@ -32,6 +32,9 @@ pub enum Message {
     RevealerToggle(IsOpen),
     SetAudioTheme(AudioTheme),
     AudioThemesLoaded(Vec<AudioTheme>),
+    SetThemeVolume(f32),
+    ApplyThemeVolume, // Slider released, send the volume to the daemon
+    SetThemeMuted(bool),
     SetVisualizationTheme(VisualizationTheme),
     SetAppletWidth(u32),
     SetShowIcon(bool),
@@ -39,4 +42,5 @@ pub enum Message {
     SetShowVisualizations(bool),
     SetVisualizationColor(VisualizationColor, bool), // Color and is_dark flag
     SetColorThemeEntity(Entity),                     // Theme selector for color configuration
+    SetAmplitudeMapping(AmplitudeMapping),
 }
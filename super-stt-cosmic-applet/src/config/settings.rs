@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: GPL-3.0-only
-use crate::models::theme::{VisualizationColorConfig, VisualizationTheme};
+use crate::models::theme::{PeakHoldConfig, VisualizationColorConfig, VisualizationTheme};
 use crate::VisualizationSide;
 use log::{debug, error, warn};
 use serde::{Deserialize, Serialize};
@@ -19,11 +19,35 @@ pub struct VisualizationConfig {
     pub theme: VisualizationTheme,
     pub side: VisualizationSide, // This will be fixed per binary but stored for completeness
     pub colors: VisualizationColorConfig,
+    #[serde(default)]
+    pub peak_hold: PeakHoldConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
     pub theme: AudioTheme,
+    #[serde(default)]
+    pub udp_rate_limit: UdpRateLimitConfig,
+}
+
+/// How many UDP packets from the daemon the applet processes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UdpRateLimitConfig {
+    /// Sustained packets per second
+    pub packets_per_second: u32,
+    /// Packets accepted at once before throttling kicks in
+    pub burst: u32,
+}
+
+impl Default for UdpRateLimitConfig {
+    // Enough for normal audio streaming while still preventing flooding
+    fn default() -> Self {
+        Self {
+            packets_per_second: 100,
+            burst: 50,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,9 +66,11 @@ impl Default for AppletConfig {
                 theme: VisualizationTheme::CenteredEqualizer,
                 side: VisualizationSide::Full,
                 colors: VisualizationColorConfig::default(),
+                peak_hold: PeakHoldConfig::default(),
             },
             audio: AudioConfig {
                 theme: AudioTheme::default(),
+                udp_rate_limit: UdpRateLimitConfig::default(),
             },
             ui: UiConfig {
                 last_popup_state: "None".to_string(),
@@ -25,8 +25,28 @@ pub async fn set_and_test_audio_theme(
         .await
 }
 
+/// Set the theme sound volume and mute state, then play the theme at that level
+pub async fn set_and_test_theme_volume(
+    socket_path: PathBuf,
+    volume: f32,
+    muted: bool,
+) -> Result<String, String> {
+    let patch = serde_json::json!({
+        "audio": { "theme_volume": volume.clamp(0.0, 1.0), "theme_muted": muted }
+    });
+    super_stt_shared::daemon::client::update_daemon_config(
+        socket_path.clone(),
+        patch,
+        get_client_id(),
+    )
+    .await?;
+    super_stt_shared::daemon::client::test_audio_theme(socket_path, get_client_id()).await
+}
+
 /// Get current daemon configuration
-pub async fn fetch_daemon_config(socket_path: PathBuf) -> Result<serde_json::Value, String> {
+pub async fn fetch_daemon_config(
+    socket_path: PathBuf,
+) -> Result<super_stt_shared::daemon::client::DaemonConfigInfo, String> {
     super_stt_shared::daemon::client::fetch_daemon_config(socket_path, get_client_id()).await
 }
 
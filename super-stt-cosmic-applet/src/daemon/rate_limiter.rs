@@ -31,12 +31,11 @@ impl TokenBucketRateLimiter {
         }
     }
 
-    /// Create a rate limiter suitable for audio data processing
+    /// Create a rate limiter from a sustained rate and burst size
     ///
-    /// Allows bursts of up to 50 packets with sustained rate of 100 packets/second
-    /// This should handle normal audio streaming while preventing flooding
-    pub fn for_audio_processing() -> Self {
-        Self::new(50, 100)
+    /// Both are raised to at least 1 so a zero in the config cannot block all packets.
+    pub fn with_rate(tokens_per_sec: u32, burst: u32) -> Self {
+        Self::new(burst.max(1), tokens_per_sec.max(1))
     }
 
     /// Try to consume a token for packet processing
@@ -91,6 +90,7 @@ impl TokenBucketRateLimiter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::UdpRateLimitConfig;
     use std::thread;
 
     #[test]
@@ -125,7 +125,9 @@ mod tests {
 
     #[test]
     fn test_audio_processing_defaults() {
-        let mut limiter = TokenBucketRateLimiter::for_audio_processing();
+        let defaults = UdpRateLimitConfig::default();
+        let mut limiter =
+            TokenBucketRateLimiter::with_rate(defaults.packets_per_second, defaults.burst);
 
         // Should handle burst of 50 packets
         for _ in 0..50 {
@@ -135,4 +137,38 @@ mod tests {
         // Should be rate limited after burst
         assert!(!limiter.try_consume());
     }
+
+    #[test]
+    fn test_with_rate_limits_burst() {
+        let mut limiter = TokenBucketRateLimiter::with_rate(20, 3);
+
+        for _ in 0..3 {
+            assert!(limiter.try_consume());
+            assert_eq!(limiter.time_until_next_token(), None);
+        }
+        assert!(!limiter.try_consume());
+
+        // 20 tokens/sec = 1 token per 50ms
+        assert_eq!(
+            limiter.time_until_next_token(),
+            Some(Duration::from_millis(50))
+        );
+        thread::sleep(Duration::from_millis(70));
+        assert_eq!(limiter.time_until_next_token(), None);
+        assert!(limiter.try_consume());
+        assert!(!limiter.try_consume());
+    }
+
+    #[test]
+    fn test_with_rate_rejects_zero() {
+        let mut limiter = TokenBucketRateLimiter::with_rate(0, 0);
+
+        // Zero is raised to one packet per second with a burst of one
+        assert!(limiter.try_consume());
+        assert!(!limiter.try_consume());
+        assert_eq!(
+            limiter.time_until_next_token(),
+            Some(Duration::from_secs(1))
+        );
+    }
 }
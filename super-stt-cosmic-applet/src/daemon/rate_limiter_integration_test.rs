@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 #[cfg(test)]
 mod integration_tests {
+    use crate::config::UdpRateLimitConfig;
     use crate::daemon::rate_limiter::TokenBucketRateLimiter;
     use std::time::Duration;
 
@@ -43,7 +44,9 @@ mod integration_tests {
 
     #[test]
     fn test_normal_audio_streaming() {
-        let mut limiter = TokenBucketRateLimiter::for_audio_processing();
+        let defaults = UdpRateLimitConfig::default();
+        let mut limiter =
+            TokenBucketRateLimiter::with_rate(defaults.packets_per_second, defaults.burst);
 
         // Simulate normal audio streaming at ~44.1kHz sample rate
         // With packet size of ~1024 samples, that's ~43 packets/second
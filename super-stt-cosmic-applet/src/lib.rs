@@ -40,7 +40,7 @@ use crate::{
 use crate::{
     daemon::{
         client::load_audio_themes, fetch_daemon_config, ping_daemon, ping_daemon_with_status,
-        set_and_test_audio_theme, RetryStrategy, TokenBucketRateLimiter,
+        set_and_test_audio_theme, set_and_test_theme_volume, RetryStrategy, TokenBucketRateLimiter,
     },
     models::theme::ThemeConfig,
 };
@@ -51,6 +51,8 @@ use super_stt_shared::{
 
 // Connection monitoring constants
 const PING_INTERVAL_SECS: u64 = 5; // Ping every 5 seconds to check daemon health
+const UDP_KEEPALIVE_SECS: u64 = 5; // The daemon answers each keep-alive, so silence means it forgot us
+const UDP_STALE_SECS: u64 = 15; // Re-register when nothing arrived over UDP for this long
 const VISUALIZATION_HEIGHT: f32 = 100.0; // Visualization height in pixels
 
 use cosmic::iced::{Length, Size};
@@ -83,6 +85,7 @@ pub struct SuperSttApplet {
     is_open: IsOpen,
     theme_config: ThemeConfig,
     udp_restart_counter: u64,
+    udp_addr: std::net::SocketAddr,
     visualization: VisualizationComponent,
     last_udp_data: std::time::Instant,
     config: AppletConfig,
@@ -117,16 +120,19 @@ impl cosmic::Application for SuperSttApplet {
         let theme_config = ThemeConfig {
             visualization_theme: config.visualization.theme.clone(),
             audio_theme: config.audio.theme,
+            theme_volume: 1.0,
+            theme_muted: false,
             visualization_color_config: config.visualization.colors.clone(),
         };
 
-        let visualization = VisualizationComponent::new(
+        let mut visualization = VisualizationComponent::new(
             0.0,
             false,
             config.visualization.theme.clone(),
             visualization_side,
             config.visualization.colors.clone(),
         );
+        visualization.set_peak_hold(config.visualization.peak_hold.clone());
 
         // Initialize icon alignment model
         let mut icon_alignment_model = SingleSelectModel::default();
@@ -168,6 +174,7 @@ impl cosmic::Application for SuperSttApplet {
             is_open: IsOpen::None,
             theme_config,
             udp_restart_counter: 0,
+            udp_addr: super_stt_shared::daemon_udp_addr(),
             visualization,
             last_udp_data: std::time::Instant::now(),
             config,
@@ -213,12 +220,17 @@ impl cosmic::Application for SuperSttApplet {
     }
 
     fn subscription(&self) -> Subscription<Message> {
+        // Read when the UDP subscription (re)starts
+        let udp_rate = self.config.audio.udp_rate_limit.clone();
+        let daemon_addr = self.udp_addr;
+
         Subscription::batch([
             // UDP subscription for audio level monitoring that restarts when daemon reconnects
             Subscription::run_with_id(
                 self.udp_restart_counter,
-                cosmic::iced::stream::channel(100, |mut channel| async move {
-                    let socket = match UdpSocket::bind("127.0.0.1:0").await {
+                cosmic::iced::stream::channel(100, move |mut channel| async move {
+                    let bind_addr = super_stt_shared::client_bind_addr(daemon_addr);
+                    let socket = match UdpSocket::bind(bind_addr).await {
                         Ok(socket) => socket,
                         Err(e) => {
                             warn!("Failed to bind UDP socket: {e}");
@@ -244,7 +256,7 @@ impl cosmic::Application for SuperSttApplet {
                     };
 
                     if let Err(e) = socket
-                        .send_to(registration_msg.as_bytes(), "127.0.0.1:8765")
+                        .send_to(registration_msg.as_bytes(), daemon_addr)
                         .await
                     {
                         warn!("Failed to register with daemon: {e}");
@@ -252,14 +264,17 @@ impl cosmic::Application for SuperSttApplet {
                     }
 
                     // Test if registration was successful by sending a test message
-                    if let Err(e) = socket.send_to(b"PING", "127.0.0.1:8765").await {
+                    if let Err(e) = socket.send_to(b"PING", daemon_addr).await {
                         warn!("Failed to send ping to daemon: {e}");
                     }
 
                     let mut buffer = [0u8; 1024];
-                    let mut rate_limiter = TokenBucketRateLimiter::for_audio_processing();
+                    let mut rate_limiter = TokenBucketRateLimiter::with_rate(
+                        udp_rate.packets_per_second,
+                        udp_rate.burst,
+                    );
                     let mut keepalive_interval =
-                        tokio::time::interval(tokio::time::Duration::from_secs(60));
+                        tokio::time::interval(tokio::time::Duration::from_secs(UDP_KEEPALIVE_SECS));
 
                     loop {
                         tokio::select! {
@@ -282,6 +297,19 @@ impl cosmic::Application for SuperSttApplet {
                                             continue;
                                         }
 
+                                        // The daemon rotated its secret; re-register before the grace period ends
+                                        if len > 0 && buffer[0] == super_stt_shared::udp::SECRET_ROTATED_PACKET {
+                                            match auth.create_auth_message("applet") {
+                                                Ok(msg) => {
+                                                    if let Err(e) = socket.send_to(msg.as_bytes(), daemon_addr).await {
+                                                        warn!("Failed to re-register with daemon: {e}");
+                                                    }
+                                                }
+                                                Err(e) => warn!("Failed to create registration message: {e}"),
+                                            }
+                                            continue;
+                                        }
+
                                         let data = buffer[..len].to_vec();
                                         if channel.send(Message::UdpData(data)).await.is_err() {
                                             break;
@@ -296,7 +324,7 @@ impl cosmic::Application for SuperSttApplet {
                             // Send periodic keep-alive pings
                             _ = keepalive_interval.tick() => {
                                 // Send keep-alive ping to maintain connection
-                                if let Err(e) = socket.send_to(b"PING", "127.0.0.1:8765").await {
+                                if let Err(e) = socket.send_to(b"PING", daemon_addr).await {
                                     warn!("Failed to send UDP keep-alive: {e}");
                                 }
                             }
@@ -396,7 +424,16 @@ impl cosmic::Application for SuperSttApplet {
                     });
                 }
             }
-            Message::DaemonConfigReceived(config) => {
+            Message::DaemonConfigReceived(info) => {
+                let config = info.config;
+                // Re-register on the daemon's actual address if it is not the one in use
+                let udp_addr = super_stt_shared::reported_daemon_udp_addr(info.udp_addr);
+                if udp_addr != self.udp_addr {
+                    info!("Daemon streams on {udp_addr}, restarting UDP subscription");
+                    self.udp_addr = udp_addr;
+                    self.udp_restart_counter += 1;
+                }
+
                 // Parse daemon configuration and sync theme settings
                 if let Some(audio_config) = config
                     .get("audio")
@@ -415,6 +452,22 @@ impl cosmic::Application for SuperSttApplet {
                 } else {
                     warn!("No audio theme found in daemon configuration");
                 }
+                if let Some(audio) = config.get("audio") {
+                    if let Some(volume) = audio
+                        .get("theme_volume")
+                        .and_then(serde_json::Value::as_f64)
+                    {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let volume = volume as f32;
+                        self.theme_config.theme_volume = volume;
+                    }
+                    if let Some(muted) = audio
+                        .get("theme_muted")
+                        .and_then(serde_json::Value::as_bool)
+                    {
+                        self.theme_config.theme_muted = muted;
+                    }
+                }
             }
             Message::DaemonError(err) => {
                 warn!("Daemon error: {err}");
@@ -492,6 +545,41 @@ impl cosmic::Application for SuperSttApplet {
             Message::AudioThemesLoaded(themes) => {
                 self.available_audio_themes = themes;
             }
+            Message::SetThemeVolume(volume) => {
+                // Only track the slider here, the daemon is updated once it is released
+                self.theme_config.theme_volume = volume;
+            }
+            Message::ApplyThemeVolume => {
+                return cosmic_app::Task::perform(
+                    set_and_test_theme_volume(
+                        self.socket_path.clone(),
+                        self.theme_config.theme_volume,
+                        self.theme_config.theme_muted,
+                    ),
+                    |result| {
+                        cosmic::Action::App(match result {
+                            Ok(_) => Message::DaemonConnected,
+                            Err(e) => Message::DaemonError(e),
+                        })
+                    },
+                );
+            }
+            Message::SetThemeMuted(muted) => {
+                self.theme_config.theme_muted = muted;
+                return cosmic_app::Task::perform(
+                    set_and_test_theme_volume(
+                        self.socket_path.clone(),
+                        self.theme_config.theme_volume,
+                        muted,
+                    ),
+                    |result| {
+                        cosmic::Action::App(match result {
+                            Ok(_) => Message::DaemonConnected,
+                            Err(e) => Message::DaemonError(e),
+                        })
+                    },
+                );
+            }
             Message::AudioLevelUpdate { level, is_speech } => {
                 self.audio_level = level;
                 self.is_speech_detected = is_speech;
@@ -599,6 +687,17 @@ impl cosmic::Application for SuperSttApplet {
             Message::PingTimeout => {
                 // Always check daemon health when we think we're connected
                 if self.daemon_state == DaemonConnectionState::Connected {
+                    // A restarted daemon no longer knows our UDP client, so rebuild the subscription
+                    if self.last_udp_data.elapsed() > std::time::Duration::from_secs(UDP_STALE_SECS)
+                    {
+                        self.udp_restart_counter += 1;
+                        self.last_udp_data = std::time::Instant::now();
+                        info!(
+                            "No UDP data for {UDP_STALE_SECS}s, restarting UDP subscription (counter: {})",
+                            self.udp_restart_counter
+                        );
+                    }
+
                     // Regularly ping daemon to check if connection is still active
                     return cosmic_app::Task::perform(
                         ping_daemon_with_status(self.socket_path.clone()),
@@ -726,6 +825,19 @@ impl cosmic::Application for SuperSttApplet {
                 self.visualization.update_colors(updated_colors);
                 // Don't close settings for color changes
             }
+            Message::SetAmplitudeMapping(mapping) => {
+                self.theme_config
+                    .visualization_color_config
+                    .amplitude_mapping = mapping;
+                let updated_colors = self.theme_config.visualization_color_config.clone();
+                self.config
+                    .update_visualization_colors(updated_colors.clone(), &self.variant_name);
+                self.visualization.update_colors(updated_colors);
+                // Close the curve picker but keep the gamma slider usable
+                if self.is_open == IsOpen::AmplitudeMapping {
+                    self.is_open = IsOpen::None;
+                }
+            }
 
             Message::SetColorThemeEntity(entity) => {
                 self.theme_selector_model.activate(entity);
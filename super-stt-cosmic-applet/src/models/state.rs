@@ -19,5 +19,6 @@ pub enum IsOpen {
     AudioTheme,
     VisualizationTheme,
     VisualizationColors,
+    AmplitudeMapping,
     AppletSettings,
 }
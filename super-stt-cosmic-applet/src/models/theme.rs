@@ -10,6 +10,7 @@ pub enum VisualizationTheme {
     #[default]
     CenteredEqualizer,
     Waveform,
+    Spectrogram,
 }
 
 impl std::fmt::Display for VisualizationTheme {
@@ -19,6 +20,7 @@ impl std::fmt::Display for VisualizationTheme {
             VisualizationTheme::BottomEqualizer => write!(f, "b_equalizer"),
             VisualizationTheme::CenteredEqualizer => write!(f, "c_equalizer"),
             VisualizationTheme::Waveform => write!(f, "waveform"),
+            VisualizationTheme::Spectrogram => write!(f, "spectrogram"),
         }
     }
 }
@@ -30,6 +32,7 @@ impl VisualizationTheme {
             "b_equalizer" => VisualizationTheme::BottomEqualizer,
             "c_equalizer" => VisualizationTheme::CenteredEqualizer,
             "waveform" => VisualizationTheme::Waveform,
+            "spectrogram" => VisualizationTheme::Spectrogram,
             _ => VisualizationTheme::default(),
         }
     }
@@ -40,6 +43,7 @@ impl VisualizationTheme {
             VisualizationTheme::BottomEqualizer => "Equalizer".to_string(),
             VisualizationTheme::CenteredEqualizer => "Centered Bars".to_string(),
             VisualizationTheme::Waveform => "Waveform".to_string(),
+            VisualizationTheme::Spectrogram => "Spectrogram".to_string(),
         }
     }
 }
@@ -252,10 +256,65 @@ impl VisualizationColor {
     }
 }
 
+/// Gamma picked when switching to the gamma curve, lifting quiet input a little
+pub const DEFAULT_AMPLITUDE_GAMMA: f32 = 0.6;
+
+/// Curve from normalized band amplitude to drawn height
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum AmplitudeMapping {
+    #[default]
+    Linear,
+    /// Lifts quiet input strongly while keeping loud input below the top
+    Logarithmic,
+    /// `amplitude^gamma`: below 1.0 lifts quiet input, above 1.0 suppresses it
+    Gamma(f32),
+}
+
+impl std::fmt::Display for AmplitudeMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AmplitudeMapping::Linear => write!(f, "linear"),
+            AmplitudeMapping::Logarithmic => write!(f, "logarithmic"),
+            AmplitudeMapping::Gamma(_) => write!(f, "gamma"),
+        }
+    }
+}
+
+impl AmplitudeMapping {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "logarithmic" => AmplitudeMapping::Logarithmic,
+            "gamma" => AmplitudeMapping::Gamma(DEFAULT_AMPLITUDE_GAMMA),
+            _ => AmplitudeMapping::Linear,
+        }
+    }
+
+    pub fn pretty_name(&self) -> String {
+        match self {
+            AmplitudeMapping::Linear => "Linear".to_string(),
+            AmplitudeMapping::Logarithmic => "Logarithmic".to_string(),
+            AmplitudeMapping::Gamma(_) => "Gamma".to_string(),
+        }
+    }
+
+    /// Map an amplitude in 0.0..=1.0 onto the same range
+    pub fn apply(self, amplitude: f32) -> f32 {
+        let amplitude = amplitude.clamp(0.0, 1.0);
+        match self {
+            AmplitudeMapping::Linear => amplitude,
+            // log10(1 + 9x) stays within 0.0..=1.0 and rises steeply near zero
+            AmplitudeMapping::Logarithmic => (1.0 + 9.0 * amplitude).log10(),
+            AmplitudeMapping::Gamma(gamma) => amplitude.powf(gamma.max(f32::EPSILON)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VisualizationColorConfig {
     pub light_colors: VisualizationColor,
     pub dark_colors: VisualizationColor,
+    #[serde(default)]
+    pub amplitude_mapping: AmplitudeMapping,
 }
 
 impl Default for VisualizationColorConfig {
@@ -263,6 +322,7 @@ impl Default for VisualizationColorConfig {
         Self {
             light_colors: VisualizationColor::SystemAccent,
             dark_colors: VisualizationColor::SystemAccent,
+            amplitude_mapping: AmplitudeMapping::default(),
         }
     }
 }
@@ -293,11 +353,37 @@ impl VisualizationColorConfig {
         let color = self.get_color(is_dark);
         color.to_color_with_theme(cosmic_theme)
     }
+
+    /// Apply the configured amplitude curve to a normalized amplitude
+    pub fn map_amplitude(&self, amplitude: f32) -> f32 {
+        self.amplitude_mapping.apply(amplitude)
+    }
+}
+
+/// Peak markers drawn above the equalizer bars
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeakHoldConfig {
+    pub enabled: bool,
+    /// How fast a held peak falls, in full bar heights per second
+    pub decay_rate: f32,
+}
+
+impl Default for PeakHoldConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            decay_rate: 1.5,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct ThemeConfig {
     pub audio_theme: AudioTheme,
+    // Mirrored from the daemon config, which owns them
+    pub theme_volume: f32,
+    pub theme_muted: bool,
     pub visualization_theme: VisualizationTheme,
     pub visualization_color_config: VisualizationColorConfig,
 }
@@ -12,14 +12,16 @@ use cosmic::{
 
 use crate::{
     config::{
-        DEFAULT_VISUALIZATION_WAVE_FREQUENCY, FREQUENCY_CONFIDENCE_THRESHOLD, FREQUENCY_SMOOTHING,
-        MAX_AUDIO_FREQUENCY, MAX_VISUALIZATION_WAVE_FREQUENCY, MIN_AUDIO_FREQUENCY,
-        MIN_VISUALIZATION_WAVE_FREQUENCY,
+        DEFAULT_VISUALIZATION_WAVE_FREQUENCY, FREQUENCY_CONFIDENCE_THRESHOLD,
+        FREQUENCY_NORMALIZATION_MAX, FREQUENCY_SMOOTHING, MAX_AUDIO_FREQUENCY,
+        MAX_VISUALIZATION_WAVE_FREQUENCY, MIN_AUDIO_FREQUENCY, MIN_VISUALIZATION_WAVE_FREQUENCY,
+    },
+    models::theme::{
+        PeakHoldConfig, VisualizationColorConfig, VisualizationSide, VisualizationTheme,
     },
-    models::theme::{VisualizationColorConfig, VisualizationSide, VisualizationTheme},
     ui::components::visualizations::{
         CenteredBarsVisualization, EqualizerVisualization, PulseVisualization,
-        VisualizationRenderer, WaveformVisualization,
+        SpectrogramVisualization, VisualizationRenderer, WaveformVisualization,
     },
 };
 use super_stt_shared::{AudioAnalyzer, FrequencyData};
@@ -35,6 +37,10 @@ pub struct VisualizationComponent {
     audio_analyzer: AudioAnalyzer,
     audio_samples: Vec<f32>, // Store recent audio samples for analysis
     frequency_data: FrequencyData,
+    spectrogram: SpectrogramVisualization, // Keeps its own history of frequency frames
+    peak_hold: PeakHoldConfig,
+    held_peaks: Vec<f32>, // Per-band peaks for the equalizer, decaying between updates
+    last_peak_update: Option<std::time::Instant>,
     visualization_colors: VisualizationColorConfig,
     smoothed_visualization_frequency: f32, // Smoothed wave frequency for stable visualization
 }
@@ -58,6 +64,10 @@ impl VisualizationComponent {
             audio_analyzer: AudioAnalyzer::new(SAMPLE_RATE, BUFFER_SIZE),
             audio_samples: Vec::with_capacity(BUFFER_SIZE),
             frequency_data: FrequencyData::default(),
+            spectrogram: SpectrogramVisualization::default(),
+            peak_hold: PeakHoldConfig::default(),
+            held_peaks: Vec::new(),
+            last_peak_update: None,
             visualization_colors,
             smoothed_visualization_frequency: DEFAULT_VISUALIZATION_WAVE_FREQUENCY,
         }
@@ -66,6 +76,9 @@ impl VisualizationComponent {
     /// Clear the visualization data to ensure clean transition to icon
     pub fn clear(&mut self) {
         self.frequency_data = FrequencyData::default();
+        self.spectrogram.clear();
+        self.held_peaks.clear();
+        self.last_peak_update = None;
         self.audio_samples.clear();
         self.audio_level = 0.0;
         // Reset to default frequency
@@ -77,6 +90,36 @@ impl VisualizationComponent {
         self.visualization_theme = theme;
     }
 
+    /// Change the equalizer peak markers, dropping any held peaks when disabled
+    pub fn set_peak_hold(&mut self, peak_hold: PeakHoldConfig) {
+        if !peak_hold.enabled {
+            self.held_peaks.clear();
+            self.last_peak_update = None;
+        }
+        self.peak_hold = peak_hold;
+    }
+
+    /// Raise held peaks to the current bands and let the others fall at the decay rate
+    fn update_held_peaks(&mut self) {
+        if !self.peak_hold.enabled {
+            return;
+        }
+
+        let now = std::time::Instant::now();
+        let elapsed = self
+            .last_peak_update
+            .map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        self.last_peak_update = Some(now);
+        // Peaks are kept in band units, where FREQUENCY_NORMALIZATION_MAX is a full bar
+        let decay = self.peak_hold.decay_rate * FREQUENCY_NORMALIZATION_MAX * elapsed;
+
+        let bands = &self.frequency_data.bands;
+        self.held_peaks.resize(bands.len(), 0.0);
+        for (peak, &band) in self.held_peaks.iter_mut().zip(bands) {
+            *peak = (*peak - decay).max(band);
+        }
+    }
+
     /// Update visualization side without recreating the component
     pub fn update_side(&mut self, side: VisualizationSide) {
         self.visualization_side = side;
@@ -103,6 +146,8 @@ impl VisualizationComponent {
 
         // Now update with the computed dynamic wave frequency
         self.frequency_data.dynamic_wave_frequency = Some(self.smoothed_visualization_frequency);
+        self.spectrogram.push(&self.frequency_data);
+        self.update_held_peaks();
     }
 
     /// Update with new audio samples for frequency analysis
@@ -127,6 +172,8 @@ impl VisualizationComponent {
             // Set the dynamic wave frequency
             self.frequency_data.dynamic_wave_frequency =
                 Some(self.smoothed_visualization_frequency);
+            self.spectrogram.push(&self.frequency_data);
+            self.update_held_peaks();
         }
     }
 
@@ -144,6 +191,8 @@ impl VisualizationComponent {
 
         // Set the dynamic wave frequency
         self.frequency_data.dynamic_wave_frequency = Some(self.smoothed_visualization_frequency);
+        self.spectrogram.push(&self.frequency_data);
+        self.update_held_peaks();
     }
 
     /// Update the smoothed wave frequency based on current frequency data
@@ -232,7 +281,12 @@ impl Program<Message, Theme, Renderer> for VisualizationComponent {
                 );
             }
             VisualizationTheme::BottomEqualizer => {
-                EqualizerVisualization::default().draw(
+                let equalizer = if self.peak_hold.enabled {
+                    EqualizerVisualization::default().with_peaks(self.held_peaks.clone())
+                } else {
+                    EqualizerVisualization::default()
+                };
+                equalizer.draw(
                     &mut frame,
                     bounds,
                     &self.frequency_data,
@@ -264,6 +318,17 @@ impl Program<Message, Theme, Renderer> for VisualizationComponent {
                     cosmic_theme,
                 );
             }
+            VisualizationTheme::Spectrogram => {
+                self.spectrogram.draw(
+                    &mut frame,
+                    bounds,
+                    &self.frequency_data,
+                    &self.visualization_side,
+                    &self.visualization_colors,
+                    is_dark,
+                    cosmic_theme,
+                );
+            }
         }
 
         vec![frame.into_geometry()]
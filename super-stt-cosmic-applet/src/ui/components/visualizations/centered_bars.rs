@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::config::FREQUENCY_NORMALIZATION_MAX;
 use crate::models::theme::{VisualizationColorConfig, VisualizationSide};
-use crate::ui::components::visualizations::{VisualizationConfig, VisualizationRenderer};
+use crate::ui::components::visualizations::{
+    display_band_amplitude, VisualizationConfig, VisualizationRenderer, MAX_DISPLAY_BANDS,
+};
 use cosmic::iced::{
     core::Rectangle,
     widget::canvas::{path, stroke, Fill, Frame},
@@ -50,8 +52,8 @@ impl VisualizationRenderer for CenteredBarsVisualization {
     ) {
         let effective_bounds = self.config.effective_bounds(bounds);
 
-        // Display bars directly from frequency data (32 bands -> 32 bars)
-        let total_bars = frequency_data.bands.len().min(32);
+        // Up to 32 bars, each averaging its share of the frequency bands
+        let total_bars = frequency_data.bands.len().min(MAX_DISPLAY_BANDS);
 
         // Determine which bars to show based on VisualizationSide
         let (bars_to_show, bar_start_index) = match visualization_side {
@@ -73,17 +75,13 @@ impl VisualizationRenderer for CenteredBarsVisualization {
         // You can easily adjust FREQUENCY_NORMALIZATION_MAX in config.rs to tune sensitivity
         let normalization_factor = 1.0 / FREQUENCY_NORMALIZATION_MAX;
 
-        // Use frequency bands directly (no downsampling needed)
         for display_bar in 0..bars_to_show {
             let x = start_x + (display_bar as f32 * (bar_width + spacing));
 
             // Map to the correct frequency band index based on wave_side
             let band_index = bar_start_index + display_bar;
-            let average_amplitude = if band_index < frequency_data.bands.len() {
-                frequency_data.bands[band_index]
-            } else {
-                0.0
-            };
+            let average_amplitude =
+                display_band_amplitude(&frequency_data.bands, band_index, total_bars);
 
             // Normalize based on the actual maximum value we're receiving
             let height_factor = average_amplitude * normalization_factor;
@@ -91,7 +89,7 @@ impl VisualizationRenderer for CenteredBarsVisualization {
             // Use shared config for height calculation based on effective bounds
             let max_height = self.config.max_element_height(effective_bounds.height);
             let capped_height_factor = height_factor.min(1.0); // Never exceed 1.0
-            let bar_height = max_height * capped_height_factor;
+            let bar_height = max_height * color_config.map_amplitude(capped_height_factor);
             let clamped_height = self
                 .config
                 .clamped_element_height(bar_height, effective_bounds.height);
@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::config::FREQUENCY_NORMALIZATION_MAX;
 use crate::models::theme::{VisualizationColorConfig, VisualizationSide};
-use crate::ui::components::visualizations::{VisualizationConfig, VisualizationRenderer};
+use crate::ui::components::visualizations::{
+    display_band_amplitude, VisualizationConfig, VisualizationRenderer, MAX_DISPLAY_BANDS,
+};
 use cosmic::iced::{
     core::Rectangle,
     widget::canvas::{path, stroke, Fill, Frame},
@@ -10,9 +12,14 @@ use cosmic::iced::{
 use cosmic::iced::{Padding, Radius};
 use super_stt_shared::FrequencyData;
 
+/// Height of a held-peak marker in pixels
+const PEAK_MARKER_HEIGHT: f32 = 2.0;
+
 /// Bars that are aligned to the bottom of the screen.
 pub struct EqualizerVisualization {
     config: VisualizationConfig,
+    /// Held peak per frequency band, drawn as a marker above each bar
+    peaks: Option<Vec<f32>>,
 }
 
 impl Default for EqualizerVisualization {
@@ -29,10 +36,20 @@ impl Default for EqualizerVisualization {
                 min_element_height: 4.0,
                 height_safety_margin: 0.0,
             },
+            peaks: None,
         }
     }
 }
 
+impl EqualizerVisualization {
+    /// Draw a marker at each band's held peak
+    #[must_use]
+    pub fn with_peaks(mut self, peaks: Vec<f32>) -> Self {
+        self.peaks = Some(peaks);
+        self
+    }
+}
+
 impl VisualizationRenderer for EqualizerVisualization {
     #[allow(
         clippy::cast_precision_loss,
@@ -51,7 +68,7 @@ impl VisualizationRenderer for EqualizerVisualization {
     ) {
         // Use shared config for proper margin handling
         let effective_bounds = self.config.effective_bounds(bounds);
-        let total_bars = frequency_data.bands.len().min(32);
+        let total_bars = frequency_data.bands.len().min(MAX_DISPLAY_BANDS);
 
         // Determine which bars to show based on VisualizationSide
         let (bars_to_show, bar_start_index) = match visualization_side {
@@ -76,11 +93,8 @@ impl VisualizationRenderer for EqualizerVisualization {
 
             // Map to the correct frequency band index based on wave_side
             let band_index = bar_start_index + display_bar;
-            let average_amplitude = if band_index < frequency_data.bands.len() {
-                frequency_data.bands[band_index]
-            } else {
-                0.0
-            };
+            let average_amplitude =
+                display_band_amplitude(&frequency_data.bands, band_index, total_bars);
 
             // Normalize based on the actual maximum value we're receiving
             let height_factor = average_amplitude * normalization_factor;
@@ -88,7 +102,7 @@ impl VisualizationRenderer for EqualizerVisualization {
             // Use shared config for height calculation based on effective bounds
             let max_height = self.config.max_element_height(effective_bounds.height);
             let capped_height_factor = height_factor.min(1.0); // Never exceed 1.0
-            let bar_height = max_height * capped_height_factor;
+            let bar_height = max_height * color_config.map_amplitude(capped_height_factor);
             let clamped_height = self
                 .config
                 .clamped_element_height(bar_height, effective_bounds.height);
@@ -114,6 +128,28 @@ impl VisualizationRenderer for EqualizerVisualization {
                     ..Default::default()
                 },
             );
+
+            // Peak marker, only once it has risen clear of the bar
+            if let Some(peaks) = &self.peaks {
+                let peak = display_band_amplitude(peaks, band_index, total_bars);
+                let peak_height =
+                    max_height * color_config.map_amplitude((peak * normalization_factor).min(1.0));
+                if peak_height > clamped_height + PEAK_MARKER_HEIGHT {
+                    let marker_y = effective_bounds.y + effective_bounds.height - peak_height;
+                    let mut marker_builder = path::Builder::new();
+                    marker_builder.rectangle(
+                        Point { x, y: marker_y },
+                        cosmic::iced::Size::new(bar_width, PEAK_MARKER_HEIGHT),
+                    );
+                    frame.fill(
+                        &marker_builder.build(),
+                        Fill {
+                            style: stroke::Style::Solid(base),
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
         }
     }
 }
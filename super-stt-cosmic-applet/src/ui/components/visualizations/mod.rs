@@ -2,11 +2,13 @@
 pub mod centered_bars;
 pub mod equalizer;
 pub mod pulse;
+pub mod spectrogram;
 pub mod waveform;
 
 pub use centered_bars::CenteredBarsVisualization;
 pub use equalizer::EqualizerVisualization;
 pub use pulse::PulseVisualization;
+pub use spectrogram::SpectrogramVisualization;
 pub use waveform::WaveformVisualization;
 
 use cosmic::{
@@ -17,6 +19,26 @@ use cosmic::{
 use crate::models::theme::{VisualizationColorConfig, VisualizationSide};
 use super_stt_shared::FrequencyData;
 
+/// Most bars or wave points a visualization draws, however many bands the daemon sends
+pub const MAX_DISPLAY_BANDS: usize = 32;
+
+/// Amplitude of display element `index` out of `count`, averaging the bands it covers
+///
+/// Lets renderers draw a fixed number of elements from any band count: with more
+/// bands than elements each element averages a span, with fewer they map one to one.
+#[allow(clippy::cast_precision_loss)]
+pub fn display_band_amplitude(bands: &[f32], index: usize, count: usize) -> f32 {
+    if count == 0 || index >= count {
+        return 0.0;
+    }
+    let start = index * bands.len() / count;
+    let end = ((index + 1) * bands.len() / count).max(start + 1);
+    match bands.get(start..end.min(bands.len())) {
+        Some(span) if !span.is_empty() => span.iter().sum::<f32>() / span.len() as f32,
+        _ => 0.0,
+    }
+}
+
 /// Shared configuration for visualization rendering with proper margin and height management
 #[derive(Debug, Clone)]
 pub struct VisualizationConfig {
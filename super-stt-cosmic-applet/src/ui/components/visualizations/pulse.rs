@@ -51,9 +51,10 @@ impl VisualizationRenderer for PulseVisualization {
         // Calculate overall audio intensity from frequency data
         let normalization_factor = 1.0 / FREQUENCY_NORMALIZATION_MAX;
 
-        // Focus on vocal frequencies for pulse intensity (bands 8-24)
-        let vocal_start = 8;
-        let vocal_end = 24;
+        // Focus on vocal frequencies for pulse intensity (bands 8-24 of the default 64)
+        let band_count = frequency_data.bands.len();
+        let vocal_start = band_count / 8;
+        let vocal_end = (band_count * 3) / 8;
         let mut vocal_energy = 0.0;
         let mut vocal_bands = 0;
 
@@ -69,7 +70,8 @@ impl VisualizationRenderer for PulseVisualization {
         };
 
         // Normalize and create pulse intensity
-        let pulse_intensity = (average_vocal_energy * normalization_factor).min(1.0);
+        let pulse_intensity =
+            color_config.map_amplitude((average_vocal_energy * normalization_factor).min(1.0));
 
         // The line always spans the full width of the effective bounds
         let line_width = effective_bounds.width;
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::collections::VecDeque;
+
+use crate::config::FREQUENCY_NORMALIZATION_MAX;
+use crate::models::theme::{VisualizationColorConfig, VisualizationSide};
+use crate::ui::components::visualizations::{
+    display_band_amplitude, VisualizationConfig, VisualizationRenderer, MAX_DISPLAY_BANDS,
+};
+use cosmic::iced::{
+    core::Rectangle,
+    widget::canvas::{Frame, Path},
+    Color, Point, Size,
+};
+use cosmic::iced::{Padding, Radius};
+use super_stt_shared::FrequencyData;
+
+/// Number of past frames kept, one per drawn column
+const HISTORY_LENGTH: usize = 48;
+
+/// Intensities below this are left transparent so silence stays clean
+const MIN_VISIBLE_INTENSITY: f32 = 0.05;
+
+/// Scrolling heatmap of recent frequency frames, newest on the right
+#[derive(Debug, Clone)]
+pub struct SpectrogramVisualization {
+    config: VisualizationConfig,
+    history: VecDeque<FrequencyData>,
+}
+
+impl Default for SpectrogramVisualization {
+    fn default() -> Self {
+        Self {
+            config: VisualizationConfig {
+                margins: Padding {
+                    top: 1.0,
+                    right: 2.0,
+                    bottom: 1.0,
+                    left: 2.0,
+                },
+                corner_radius: Radius::new(0.0),
+                min_element_height: 1.0,
+                height_safety_margin: 0.0,
+            },
+            history: VecDeque::with_capacity(HISTORY_LENGTH),
+        }
+    }
+}
+
+impl SpectrogramVisualization {
+    /// Add the latest frame, dropping the oldest once the history is full
+    pub fn push(&mut self, frequency_data: &FrequencyData) {
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(frequency_data.clone());
+    }
+
+    /// Forget all frames, e.g. when the visualization is hidden
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl VisualizationRenderer for SpectrogramVisualization {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn draw(
+        &self,
+        frame: &mut Frame<cosmic::Renderer>,
+        bounds: Rectangle,
+        frequency_data: &FrequencyData,
+        visualization_side: &VisualizationSide,
+        color_config: &VisualizationColorConfig,
+        is_dark: bool,
+        cosmic_theme: &cosmic::cosmic_theme::Theme,
+    ) {
+        let effective_bounds = self.config.effective_bounds(bounds);
+
+        // Rows follow the bands like the bars of the other renderers
+        let total_rows = frequency_data.bands.len().min(MAX_DISPLAY_BANDS);
+        let (rows_to_show, row_start_index) = match visualization_side {
+            VisualizationSide::Left => (total_rows / 2, 0),
+            VisualizationSide::Right => (total_rows / 2, total_rows / 2),
+            VisualizationSide::Full => (total_rows, 0),
+        };
+        if rows_to_show == 0 || self.history.is_empty() {
+            return;
+        }
+
+        let column_width = effective_bounds.width / HISTORY_LENGTH as f32;
+        let row_height = effective_bounds.height / rows_to_show as f32;
+        let normalization_factor = 1.0 / FREQUENCY_NORMALIZATION_MAX;
+        let base = color_config.get_color_with_theme(is_dark, cosmic_theme);
+        // Faint cells wash out against a light panel, so lift them a little there
+        let alpha_floor = if is_dark { 0.0 } else { 0.15 };
+
+        // Newest frame in the rightmost column, older ones scroll off to the left
+        let first_column = HISTORY_LENGTH - self.history.len();
+        for (age, frame_data) in self.history.iter().enumerate() {
+            let x = effective_bounds.x + (first_column + age) as f32 * column_width;
+
+            for display_row in 0..rows_to_show {
+                let band_index = row_start_index + display_row;
+                let amplitude = display_band_amplitude(&frame_data.bands, band_index, total_rows);
+                let intensity =
+                    color_config.map_amplitude((amplitude * normalization_factor).min(1.0));
+                if intensity < MIN_VISIBLE_INTENSITY {
+                    continue;
+                }
+
+                // Low frequencies at the bottom
+                let y = effective_bounds.y + effective_bounds.height
+                    - (display_row + 1) as f32 * row_height;
+                let cell = Path::rectangle(Point { x, y }, Size::new(column_width, row_height));
+                frame.fill(
+                    &cell,
+                    Color {
+                        a: base.a * (alpha_floor + (1.0 - alpha_floor) * intensity),
+                        ..base
+                    },
+                );
+            }
+        }
+    }
+}
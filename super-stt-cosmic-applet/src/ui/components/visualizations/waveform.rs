@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::config::FREQUENCY_NORMALIZATION_MAX;
-use crate::ui::components::visualizations::{VisualizationConfig, VisualizationRenderer};
+use crate::ui::components::visualizations::{
+    display_band_amplitude, VisualizationConfig, VisualizationRenderer, MAX_DISPLAY_BANDS,
+};
 use cosmic::iced::{Padding, Radius};
 use super_stt_shared::FrequencyData;
 
@@ -60,7 +62,7 @@ impl VisualizationRenderer for WaveformVisualization {
         let effective_bounds = self.config.effective_bounds(bounds);
 
         // Determine which frequency bands to show based on VisualizationSide
-        let total_bands = frequency_data.bands.len().min(32);
+        let total_bands = frequency_data.bands.len().min(MAX_DISPLAY_BANDS);
         let (bands_to_show, band_start_index) = match visualization_side {
             VisualizationSide::Left => (total_bands / 2, 0),
             VisualizationSide::Right => (total_bands / 2, total_bands / 2),
@@ -93,11 +95,11 @@ impl VisualizationRenderer for WaveformVisualization {
         // Map each frequency band to a control point
         for display_band in 0..bands_to_show {
             let band_index = band_start_index + display_band;
-            let amplitude = if band_index < frequency_data.bands.len() {
-                frequency_data.bands[band_index] * normalization_factor
-            } else {
-                0.0
-            };
+            let amplitude = color_config.map_amplitude(
+                (display_band_amplitude(&frequency_data.bands, band_index, total_bands)
+                    * normalization_factor)
+                    .min(1.0),
+            );
 
             // Position along x-axis (normalized 0.0 to 1.0)
             let x_position = match visualization_side {
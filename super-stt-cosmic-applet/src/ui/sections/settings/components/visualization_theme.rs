@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::{
     app::Message,
-    models::theme::{VisualizationColor, VisualizationColorConfig, VisualizationTheme},
+    models::theme::{
+        AmplitudeMapping, VisualizationColor, VisualizationColorConfig, VisualizationTheme,
+        DEFAULT_AMPLITUDE_GAMMA,
+    },
     ui::components::{
         color_buttons::{create_color_button, create_system_accent_button},
         common::{revealer, revealer_head},
@@ -11,8 +14,8 @@ use crate::{
 use cosmic::{
     applet::padded_control,
     iced::{
-        widget::{column, row},
-        Length,
+        widget::{column, row, slider},
+        Alignment, Length,
     },
     iced_widget::Row,
     theme,
@@ -29,6 +32,7 @@ pub fn create_visualization_theme_selector<'a>(
         VisualizationTheme::BottomEqualizer,
         VisualizationTheme::CenteredEqualizer,
         VisualizationTheme::Waveform,
+        VisualizationTheme::Spectrogram,
     ];
 
     let options: Vec<(String, String)> = vis_themes
@@ -47,6 +51,49 @@ pub fn create_visualization_theme_selector<'a>(
     .apply(Element::from)
 }
 
+pub fn create_amplitude_mapping_selector<'a>(
+    mapping: AmplitudeMapping,
+    is_open: &IsOpen,
+) -> Element<'a, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    let options: Vec<(String, String)> = [
+        AmplitudeMapping::Linear,
+        AmplitudeMapping::Logarithmic,
+        AmplitudeMapping::Gamma(DEFAULT_AMPLITUDE_GAMMA),
+    ]
+    .into_iter()
+    .map(|mapping| (mapping.to_string(), mapping.pretty_name()))
+    .collect();
+
+    let mut selector = column![revealer(
+        *is_open == IsOpen::AmplitudeMapping,
+        "Amplitude Curve".to_string(),
+        mapping.pretty_name(),
+        &options,
+        Message::RevealerToggle(IsOpen::AmplitudeMapping),
+        |mapping_str| Message::SetAmplitudeMapping(AmplitudeMapping::from_str(&mapping_str)),
+    )];
+
+    // Gamma slider, only for the adjustable curve
+    if let AmplitudeMapping::Gamma(gamma) = mapping {
+        selector = selector.push(padded_control(
+            row![
+                text::caption(format!("{gamma:.2}")),
+                slider(0.2..=2.0, gamma, |gamma| {
+                    Message::SetAmplitudeMapping(AmplitudeMapping::Gamma(gamma))
+                })
+                .step(0.05)
+                .width(Length::Fill)
+            ]
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center),
+        ));
+    }
+
+    selector.apply(Element::from)
+}
+
 pub fn create_visualization_color_selector<'a>(
     color_config: &VisualizationColorConfig,
     is_open: &IsOpen,
@@ -20,7 +20,8 @@ use crate::{
     ui::{
         components::common::revealer,
         sections::settings::components::visualization_theme::{
-            create_visualization_color_selector, create_visualization_theme_selector,
+            create_amplitude_mapping_selector, create_visualization_color_selector,
+            create_visualization_theme_selector,
         },
     },
     IsOpen,
@@ -54,6 +55,43 @@ pub fn create_audio_theme_selector<'a>(
     .apply(Element::from)
 }
 
+/// Volume slider and mute toggle for the audio theme's sounds
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn create_theme_volume_control<'a>(theme_config: &ThemeConfig) -> Element<'a, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    padded_control(
+        column![
+            row![
+                text::body("Sound Volume"),
+                Space::new(Length::Fill, Length::Shrink),
+                text::caption("Mute"),
+                toggler(theme_config.theme_muted).on_toggle(Message::SetThemeMuted)
+            ]
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center),
+            row![
+                text::caption(format!(
+                    "{}%",
+                    (theme_config.theme_volume * 100.0).round() as u32
+                )),
+                slider(
+                    0.0..=1.0,
+                    theme_config.theme_volume,
+                    Message::SetThemeVolume
+                )
+                .step(0.05)
+                .on_release(Message::ApplyThemeVolume)
+                .width(Length::Fill)
+            ]
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(spacing.space_xxs),
+    )
+    .apply(Element::from)
+}
+
 pub fn create_applet_settings_section<'a>(
     config: &AppletConfig,
     theme_config: &ThemeConfig,
@@ -67,6 +105,7 @@ pub fn create_applet_settings_section<'a>(
 
     let mut settings_column = column![
         create_audio_theme_selector(theme_config.audio_theme, is_open, available_audio_themes),
+        create_theme_volume_control(theme_config),
         padded_control(divider::horizontal::default())
             .padding([0, spacing.space_s])
             .apply(Element::from),
@@ -104,6 +143,10 @@ pub fn create_applet_settings_section<'a>(
                     .apply(Element::from)
                 ),
                 create_visualization_theme_selector(&theme_config.visualization_theme, is_open),
+                create_amplitude_mapping_selector(
+                    theme_config.visualization_color_config.amplitude_mapping,
+                    is_open
+                ),
                 create_visualization_color_selector(
                     &theme_config.visualization_color_config,
                     is_open,
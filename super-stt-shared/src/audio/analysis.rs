@@ -16,7 +16,7 @@ pub struct FrequencyData {
 impl Default for FrequencyData {
     fn default() -> Self {
         Self {
-            bands: vec![0.0; 64], // Default 64 frequency bands for richer visualization
+            bands: vec![0.0; DEFAULT_FREQUENCY_BANDS], // 64 bands for richer visualization
             total_energy: 0.0,
             dominant_frequency: 440.0, // Default to A4 (440Hz) when no audio
             frequency_confidence: 0.0,
@@ -27,13 +27,29 @@ impl Default for FrequencyData {
 
 /// Number of frequency bands to compute for visualization
 /// Using 64 bands provides richer visualization detail
-const NUM_FREQUENCY_BANDS: usize = 64;
+pub const DEFAULT_FREQUENCY_BANDS: usize = 64;
+
+/// Largest band count an analyzer accepts, keeping frequency band packets
+/// (12 bytes plus 4 per band) far below the UDP packet size limit
+pub const MAX_FREQUENCY_BANDS: usize = 256;
+
+/// Default lower edge of the analyzed range
+pub const DEFAULT_MIN_HZ: f32 = 50.0;
+
+/// Default upper edge of the analyzed range
+pub const DEFAULT_MAX_HZ: f32 = 16000.0;
+
+/// Bands are spaced linearly below this frequency and logarithmically above it
+const LINEAR_LOG_CROSSOVER_HZ: f32 = 800.0;
 
 /// Audio analyzer that converts time-domain audio samples to frequency bands
 #[derive(Debug, Clone)]
 pub struct AudioAnalyzer {
     sample_rate: f32,
     buffer_size: usize,
+    band_count: usize,
+    min_hz: f32,
+    max_hz: f32,
 }
 
 impl AudioAnalyzer {
@@ -42,6 +58,101 @@ impl AudioAnalyzer {
         Self {
             sample_rate,
             buffer_size,
+            band_count: DEFAULT_FREQUENCY_BANDS,
+            min_hz: DEFAULT_MIN_HZ,
+            max_hz: DEFAULT_MAX_HZ,
+        }
+    }
+
+    /// Create an analyzer producing `band_count` bands between `min_hz` and `max_hz`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the band layout is rejected by `validate_bands`.
+    pub fn with_bands(
+        sample_rate: f32,
+        buffer_size: usize,
+        band_count: usize,
+        min_hz: f32,
+        max_hz: f32,
+    ) -> Result<Self, String> {
+        Self::validate_bands(band_count, min_hz, max_hz)?;
+        Ok(Self {
+            sample_rate,
+            buffer_size,
+            band_count,
+            min_hz,
+            max_hz,
+        })
+    }
+
+    /// Check a band layout before building an analyzer from it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `band_count` is 0 or above `MAX_FREQUENCY_BANDS`, or
+    /// the range is not `0 < min_hz < max_hz`.
+    pub fn validate_bands(band_count: usize, min_hz: f32, max_hz: f32) -> Result<(), String> {
+        if band_count == 0 || band_count > MAX_FREQUENCY_BANDS {
+            return Err(format!(
+                "band_count must be between 1 and {MAX_FREQUENCY_BANDS}, got {band_count}"
+            ));
+        }
+        if !(min_hz > 0.0 && min_hz < max_hz) {
+            return Err(format!(
+                "frequency range must satisfy 0 < min_hz < max_hz, got {min_hz}-{max_hz} Hz"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of bands each analysis produces
+    #[must_use]
+    pub fn band_count(&self) -> usize {
+        self.band_count
+    }
+
+    /// Split of the bands into linearly and logarithmically spaced ones, and the
+    /// frequency where one gives way to the other
+    fn band_layout(&self) -> (usize, usize, f32) {
+        let crossover = LINEAR_LOG_CROSSOVER_HZ.clamp(self.min_hz, self.max_hz);
+        let linear_bands = if crossover <= self.min_hz {
+            0
+        } else if crossover >= self.max_hz {
+            self.band_count
+        } else {
+            (self.band_count * 5) / 16
+        };
+        let log_bands = self.band_count - linear_bands;
+        // With no bands on one side the other covers the whole range
+        let crossover = match (linear_bands, log_bands) {
+            (0, _) => self.min_hz,
+            (_, 0) => self.max_hz,
+            _ => crossover,
+        };
+        (linear_bands, log_bands, crossover)
+    }
+
+    /// Lower and upper edge of band `index`
+    #[allow(clippy::cast_precision_loss)]
+    fn band_range(&self, index: usize) -> (f32, f32) {
+        let (linear_bands, log_bands, crossover) = self.band_layout();
+        if index < linear_bands {
+            let t1 = index as f32 / linear_bands as f32;
+            let t2 = (index + 1) as f32 / linear_bands as f32;
+            (
+                self.min_hz + t1 * (crossover - self.min_hz),
+                self.min_hz + t2 * (crossover - self.min_hz),
+            )
+        } else {
+            let log_min = crossover.ln();
+            let log_max = self.max_hz.ln();
+            let t1 = (index - linear_bands) as f32 / log_bands as f32;
+            let t2 = (index - linear_bands + 1) as f32 / log_bands as f32;
+            (
+                (log_min + t1 * (log_max - log_min)).exp(),
+                (log_min + t2 * (log_max - log_min)).exp(),
+            )
         }
     }
 
@@ -56,7 +167,7 @@ impl AudioAnalyzer {
     pub fn analyze(&self, samples: &[f32]) -> FrequencyData {
         if samples.is_empty() {
             return FrequencyData {
-                bands: vec![0.0; NUM_FREQUENCY_BANDS],
+                bands: vec![0.0; self.band_count],
                 total_energy: 0.0,
                 dominant_frequency: 440.0, // Default A4
                 frequency_confidence: 0.0,
@@ -94,7 +205,7 @@ impl AudioAnalyzer {
             Err(e) => {
                 log::warn!("FFT analysis failed: {e}, returning zero bands");
                 return FrequencyData {
-                    bands: vec![0.0; NUM_FREQUENCY_BANDS],
+                    bands: vec![0.0; self.band_count],
                     total_energy: 0.0,
                     dominant_frequency: 440.0, // Default A4
                     frequency_confidence: 0.0,
@@ -105,53 +216,22 @@ impl AudioAnalyzer {
 
         // Generate hybrid frequency bands: linear for low frequencies, logarithmic for high
         // This ensures good resolution where spectrum points are sparse (low freq)
-        // and logarithmic spacing where human perception needs it (high freq)
-        let mut band_amplitudes = Vec::with_capacity(NUM_FREQUENCY_BANDS);
+        // and logarithmic spacing where human perception needs it (high freq).
+        // With the default layout that is 20 linear bands (50Hz-800Hz) and 44
+        // logarithmic ones (800Hz-16kHz), emphasizing where speech detail lies.
+        let mut band_amplitudes = Vec::with_capacity(self.band_count);
         let mut total_energy = 0.0;
+        let (linear_bands, log_bands, _) = self.band_layout();
 
-        // Define the transition point between linear and logarithmic spacing
-        // Reduce linear band dominance by allocating fewer bands to low frequencies
-        let linear_max_freq: f32 = 800.0; // Use linear spacing up to 800Hz (reduced from 1kHz)
-        let log_min_freq: f32 = 800.0; // Start logarithmic spacing from 800Hz
-        let log_max_freq: f32 = 16000.0; // End at 16kHz for extended range
-
-        // Allocate bands: 20 for linear (50Hz-800Hz), 44 for logarithmic (800Hz-16kHz)
-        // This gives more emphasis to mid-high frequencies where speech detail lies
-        let linear_bands = (NUM_FREQUENCY_BANDS * 5) / 16; // ~20 bands
-        let log_bands = NUM_FREQUENCY_BANDS - linear_bands; // ~44 bands
-
-        // Generate linear frequency bands (50Hz - 800Hz)
-        let linear_min_freq = 50.0;
-        for i in 0..linear_bands {
-            let t1 = i as f32 / linear_bands as f32;
-            let t2 = (i + 1) as f32 / linear_bands as f32;
-
-            let low_freq = linear_min_freq + t1 * (linear_max_freq - linear_min_freq);
-            let high_freq = linear_min_freq + t2 * (linear_max_freq - linear_min_freq);
-
-            let amplitude = self.calculate_band_amplitude(&spectrum, low_freq, high_freq);
-            band_amplitudes.push(amplitude);
-            total_energy += amplitude * amplitude;
-        }
-
-        // Generate logarithmic frequency bands (800Hz - 16kHz)
-        let log_min = log_min_freq.ln();
-        let log_max = log_max_freq.ln();
-
-        for i in 0..log_bands {
-            let t1 = i as f32 / log_bands as f32;
-            let t2 = (i + 1) as f32 / log_bands as f32;
-
-            let low_freq = (log_min + t1 * (log_max - log_min)).exp();
-            let high_freq = (log_min + t2 * (log_max - log_min)).exp();
-
+        for i in 0..self.band_count {
+            let (low_freq, high_freq) = self.band_range(i);
             let amplitude = self.calculate_band_amplitude(&spectrum, low_freq, high_freq);
             band_amplitudes.push(amplitude);
             total_energy += amplitude * amplitude;
         }
 
         // Calculate RMS total energy
-        total_energy = (total_energy / NUM_FREQUENCY_BANDS as f32).sqrt();
+        total_energy = (total_energy / self.band_count as f32).sqrt();
 
         // Apply smart amplitude scaling with noise floor handling
         let input_rms = (samples_to_use.iter().map(|&x| x * x).sum::<f32>()
@@ -179,30 +259,28 @@ impl AudioAnalyzer {
         }
 
         // Apply frequency-specific balancing for better visualization
-        if band_amplitudes.len() >= 64 {
-            // Reduce dominance of low frequencies (first ~20 bands) by applying gentle dampening
-            for i in 0..linear_bands {
-                if i < band_amplitudes.len() {
-                    // Apply progressive dampening: more dampening for lower frequencies
-                    let damping_factor = 0.7 + (i as f32 / linear_bands as f32) * 0.3; // 0.7 to 1.0
-                    band_amplitudes[i] *= damping_factor;
-                }
-            }
-
-            // Boost mid-high frequencies (logarithmic bands) which are often weaker
-            band_amplitudes
-                .iter_mut()
-                .skip(linear_bands)
-                .take(20)
-                .for_each(|v| *v *= 1.4); // 40% boost for mid frequencies
-
-            // Additional boost for high frequencies which are typically very weak
-            band_amplitudes
-                .iter_mut()
-                .skip(linear_bands + 20)
-                .for_each(|v| *v *= 1.8); // 80% boost for high frequencies
+        // Reduce dominance of low frequencies (linear bands) by applying gentle dampening
+        for (i, band) in band_amplitudes.iter_mut().take(linear_bands).enumerate() {
+            // Apply progressive dampening: more dampening for lower frequencies
+            let damping_factor = 0.7 + (i as f32 / linear_bands as f32) * 0.3; // 0.7 to 1.0
+            *band *= damping_factor;
         }
 
+        // Boost mid-high frequencies (first 20 of 44 logarithmic bands by default)
+        // which are often weaker
+        let mid_bands = (log_bands * 5) / 11;
+        band_amplitudes
+            .iter_mut()
+            .skip(linear_bands)
+            .take(mid_bands)
+            .for_each(|v| *v *= 1.4); // 40% boost for mid frequencies
+
+        // Additional boost for high frequencies which are typically very weak
+        band_amplitudes
+            .iter_mut()
+            .skip(linear_bands + mid_bands)
+            .for_each(|v| *v *= 1.8); // 80% boost for high frequencies
+
         // Extract dominant frequency for dynamic wave visualization
         let (dominant_frequency, frequency_confidence) =
             self.extract_dominant_frequency(&spectrum, &band_amplitudes);
@@ -351,7 +429,6 @@ impl AudioAnalyzer {
 
     /// Estimate dominant frequency from frequency bands when direct spectrum analysis is uncertain
     /// This provides a fallback method that looks at energy distribution across bands
-    #[allow(clippy::unused_self, clippy::cast_precision_loss)]
     fn estimate_frequency_from_bands(&self, bands: &[f32]) -> Option<f32> {
         if bands.len() < 32 {
             return None;
@@ -368,24 +445,8 @@ impl AudioAnalyzer {
             }
         }
 
-        // Convert band index to approximate frequency
-        // Our bands are: first ~20 linear from 50-800Hz, then ~44 logarithmic from 800Hz-16kHz
-        let linear_bands = (NUM_FREQUENCY_BANDS * 5) / 16; // ~20 bands
-
-        let estimated_freq = if max_band_idx < linear_bands {
-            // Linear frequency mapping (50Hz - 800Hz)
-            let t = max_band_idx as f32 / linear_bands as f32;
-            50.0 + t * (800.0 - 50.0)
-        } else {
-            // Logarithmic frequency mapping (800Hz - 16kHz)
-            let log_bands = NUM_FREQUENCY_BANDS - linear_bands;
-            let log_idx = max_band_idx - linear_bands;
-            let t = log_idx as f32 / log_bands as f32;
-
-            let log_min = 800.0f32.ln();
-            let log_max = 16000.0f32.ln();
-            (log_min + t * (log_max - log_min)).exp()
-        };
+        // Convert band index to approximate frequency: the lower edge of the band
+        let (estimated_freq, _) = self.band_range(max_band_idx);
 
         Some(estimated_freq)
     }
@@ -427,4 +488,33 @@ mod tests {
             "Should have some confidence in frequency detection"
         );
     }
+
+    #[test]
+    fn test_custom_band_layout() {
+        let analyzer = AudioAnalyzer::with_bands(44100.0, 1024, 128, 100.0, 8000.0).unwrap();
+        let samples: Vec<f32> = (0..1024)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin())
+            .collect();
+        assert_eq!(analyzer.analyze(&samples).bands.len(), 128);
+        assert_eq!(analyzer.analyze(&[]).bands.len(), 128);
+
+        // Bands tile the requested range without gaps
+        assert!((analyzer.band_range(0).0 - 100.0).abs() < 0.01);
+        assert!((analyzer.band_range(127).1 - 8000.0).abs() < 0.5);
+        for i in 1..128 {
+            assert!((analyzer.band_range(i).0 - analyzer.band_range(i - 1).1).abs() < 0.01);
+        }
+
+        // A range entirely above the crossover is all logarithmic
+        let high = AudioAnalyzer::with_bands(44100.0, 1024, 8, 1000.0, 4000.0).unwrap();
+        assert!((high.band_range(0).0 - 1000.0).abs() < 0.01);
+        assert!((high.band_range(7).1 - 4000.0).abs() < 0.5);
+
+        assert!(AudioAnalyzer::with_bands(44100.0, 1024, 0, 50.0, 16000.0).is_err());
+        assert!(
+            AudioAnalyzer::with_bands(44100.0, 1024, MAX_FREQUENCY_BANDS + 1, 50.0, 16000.0)
+                .is_err()
+        );
+        assert!(AudioAnalyzer::with_bands(44100.0, 1024, 64, 8000.0, 100.0).is_err());
+    }
 }
@@ -1,16 +1,41 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use anyhow::{Context, Result};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::HashMap;
+use std::fmt::Write;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Default interval between shared secret rotations
+pub const SECRET_ROTATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How long the previous secret is still accepted after a rotation
+pub const SECRET_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Default maximum age (or future drift) of a registration message's timestamp
+pub const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// Random bytes in a generated secret, written out as hex
+const SECRET_BYTES: usize = 32;
 
 /// UDP Authentication using a shared secret file
 ///
 /// This provides authentication for UDP connections where process credentials
 /// are not available. A shared secret is generated and stored in a file
-/// accessible only by the user.
+/// accessible only by the user. The daemon rotates the secret periodically;
+/// the previous one stays valid for a short grace window.
 #[derive(Clone)]
 pub struct UdpAuth {
     secret_file: PathBuf,
+    rotation_interval: Duration,
+    // Secret replaced by the last rotation and when it stops being accepted
+    previous_secret: Arc<Mutex<Option<(String, Instant)>>>,
+    max_clock_skew: Duration,
+    // Nonces of accepted registrations still inside the skew window, with their timestamps
+    seen_nonces: Arc<Mutex<HashMap<String, u64>>>,
 }
 
 impl UdpAuth {
@@ -20,18 +45,45 @@ impl UdpAuth {
     /// This function will return an error if the secret file cannot be created.
     pub fn new() -> Result<Self> {
         let secret_file = Self::get_secret_file_path()?;
-        eprintln!("[DEBUG UdpAuth] Secret file path: {:?}", secret_file);
-        let auth = Self { secret_file };
+        let auth = Self {
+            secret_file,
+            rotation_interval: SECRET_ROTATION_INTERVAL,
+            previous_secret: Arc::new(Mutex::new(None)),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            seen_nonces: Arc::new(Mutex::new(HashMap::new())),
+        };
 
         // CRITICAL: Generate/load secret immediately to avoid race conditions
         // This ensures the secret file exists before any clients try to read it
-        let _secret = auth.get_or_create_secret()?;
-        eprintln!("[DEBUG UdpAuth] Secret loaded/created: {}", _secret);
-        log::debug!("UDP authentication initialized with secret at {:?}", auth.secret_file);
+        auth.get_or_create_secret()?;
+        log::debug!(
+            "UDP authentication initialized with secret at {:?}",
+            auth.secret_file
+        );
 
         Ok(auth)
     }
 
+    /// Set how often the daemon should rotate the secret
+    #[must_use]
+    pub fn with_rotation_interval(mut self, rotation_interval: Duration) -> Self {
+        self.rotation_interval = rotation_interval;
+        self
+    }
+
+    /// Interval between secret rotations
+    #[must_use]
+    pub fn rotation_interval(&self) -> Duration {
+        self.rotation_interval
+    }
+
+    /// Set how far a registration timestamp may differ from the local clock
+    #[must_use]
+    pub fn with_max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+        self.max_clock_skew = max_clock_skew;
+        self
+    }
+
     /// Get the path to the secret file
     fn get_secret_file_path() -> Result<PathBuf> {
         let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
@@ -72,11 +124,8 @@ impl UdpAuth {
     }
 
     fn load_secret(&self) -> Result<String> {
-        eprintln!("[DEBUG UdpAuth] Loading secret from: {:?}", self.secret_file);
         let secret = fs::read_to_string(&self.secret_file).context("Failed to read secret file")?;
-        let trimmed = secret.trim().to_string();
-        eprintln!("[DEBUG UdpAuth] Loaded secret: {}", trimmed);
-        Ok(trimmed)
+        Ok(secret.trim().to_string())
     }
 
     /// Generate a new random secret and save it
@@ -84,61 +133,172 @@ impl UdpAuth {
     /// # Errors
     /// This function will return an error if the secret file cannot be read.
     fn generate_secret(&self) -> Result<String> {
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        // Generate a simple but unpredictable secret using timestamp and process ID
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let pid = std::process::id();
-        let secret = format!("stt_{timestamp}_{pid}");
-
-        eprintln!("[DEBUG UdpAuth] Generating new secret: {}", secret);
-        eprintln!("[DEBUG UdpAuth] Writing to: {:?}", self.secret_file);
+        let mut bytes = [0u8; SECRET_BYTES];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| anyhow::anyhow!("Failed to generate a random secret"))?;
+        let secret = encode_hex(&bytes);
 
-        // Write to file with restrictive permissions
-        fs::write(&self.secret_file, &secret).context("Failed to write secret file")?;
+        // Write to a temporary file with restrictive permissions, then swap it in so
+        // clients never read a partially written secret during rotation
+        let tmp_file = self.secret_file.with_extension("tmp");
+        fs::write(&tmp_file, &secret).context("Failed to write secret file")?;
 
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
             let perms = fs::Permissions::from_mode(0o600);
-            fs::set_permissions(&self.secret_file, perms)
+            fs::set_permissions(&tmp_file, perms)
                 .context("Failed to set secret file permissions")?;
         }
 
+        fs::rename(&tmp_file, &self.secret_file).context("Failed to replace secret file")?;
+
         log::info!("Generated new UDP authentication secret");
         Ok(secret)
     }
 
+    /// Replace the shared secret with a freshly generated one
+    ///
+    /// The old secret keeps verifying for [`SECRET_GRACE_PERIOD`] so clients that
+    /// read it just before the rotation can still register.
+    ///
+    /// # Errors
+    /// This function will return an error if the new secret cannot be written.
+    pub fn rotate(&self) -> Result<String> {
+        let old_secret = self.get_or_create_secret().ok();
+        let new_secret = self.generate_secret()?;
+
+        *self
+            .previous_secret
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) =
+            old_secret.map(|secret| (secret, Instant::now() + SECRET_GRACE_PERIOD));
+
+        log::info!("Rotated UDP authentication secret");
+        Ok(new_secret)
+    }
+
+    /// Whether `provided` is the previous secret and its grace window is still open
+    fn matches_previous_secret(&self, provided: &str) -> bool {
+        self.previous_secret_in_grace()
+            .is_some_and(|secret| secrets_match(&secret, provided))
+    }
+
+    /// The previous secret, while its grace window is still open
+    fn previous_secret_in_grace(&self) -> Option<String> {
+        self.previous_secret
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .filter(|(_, expires_at)| Instant::now() < *expires_at)
+            .map(|(secret, _)| secret.clone())
+    }
+
+    /// Whether `provided` is the current secret, or the previous one within its grace period
+    ///
+    /// The comparison takes the same time however much of `provided` is right.
+    ///
+    /// # Errors
+    /// This function will return an error if the secret file cannot be read.
+    pub fn verify_secret(&self, provided: &str) -> Result<bool> {
+        Ok(secrets_match(&self.get_or_create_secret()?, provided)
+            || self.matches_previous_secret(provided))
+    }
+
     /// Create an authenticated registration message
     ///
+    /// The message has the form `REGISTER:client_type:timestamp_ms:nonce:mac`, where
+    /// `mac` is the hex HMAC-SHA256 of `client_type|timestamp_ms|nonce` keyed with the
+    /// secret, so the secret itself never goes over the socket. The timestamp and a
+    /// random nonce make every message single-use.
+    ///
     /// # Errors
     /// This function will return an error if the secret file cannot be read.
     pub fn create_auth_message(&self, client_type: &str) -> Result<String> {
         let secret = self.get_or_create_secret()?;
-        Ok(format!("REGISTER:{client_type}:{secret}"))
+        let nonce = uuid::Uuid::new_v4().simple().to_string();
+        let timestamp_ms = unix_time_ms().to_string();
+        Ok(registration_message(
+            &secret,
+            client_type,
+            &timestamp_ms,
+            &nonce,
+        ))
     }
 
     /// Verify an authenticated registration message
     ///
+    /// Rejects messages signed with a wrong secret, a timestamp further than the
+    /// allowed clock skew from now, or a nonce that was already used.
+    ///
     /// # Errors
     /// This function will return an error if the secret file cannot be read.
     pub fn verify_auth_message(&self, message: &str) -> Result<Option<String>> {
         let secret = self.get_or_create_secret()?;
 
-        if let Some(rest) = message.strip_prefix("REGISTER:")
-            && let Some((client_type, provided_secret)) = rest.split_once(':')
+        let Some(rest) = message.strip_prefix("REGISTER:") else {
+            return Ok(None);
+        };
+        let mut fields = rest.splitn(4, ':');
+        let (Some(client_type), Some(timestamp), Some(nonce), Some(mac)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            log::warn!("UDP authentication failed: malformed registration message");
+            return Ok(None);
+        };
+        let Some(mac) = decode_hex(mac) else {
+            log::warn!("UDP authentication failed: malformed registration message");
+            return Ok(None);
+        };
+
+        let signed = signed_fields(client_type, timestamp, nonce);
+        if verify_mac(&secret, &signed, &mac) {
+            // Current secret
+        } else if self
+            .previous_secret_in_grace()
+            .is_some_and(|previous| verify_mac(&previous, &signed, &mac))
         {
-            if provided_secret == secret {
-                return Ok(Some(client_type.to_string()));
-            } else {
-                log::warn!("UDP authentication failed: secret mismatch");
-            }
+            log::debug!("UDP client authenticated with previous secret during grace period");
+        } else {
+            log::warn!("UDP authentication failed: signature mismatch");
+            return Ok(None);
+        }
+
+        let Ok(timestamp_ms) = timestamp.parse::<u64>() else {
+            log::warn!("UDP authentication failed: invalid timestamp");
+            return Ok(None);
+        };
+        let skew_ms = u64::try_from(self.max_clock_skew.as_millis()).unwrap_or(u64::MAX);
+        if unix_time_ms().abs_diff(timestamp_ms) > skew_ms {
+            log::warn!("UDP authentication failed: registration timestamp outside allowed skew");
+            return Ok(None);
         }
 
-        Ok(None)
+        if nonce.is_empty() || !self.remember_nonce(nonce, timestamp_ms, skew_ms) {
+            log::warn!("UDP authentication failed: replayed registration nonce");
+            return Ok(None);
+        }
+
+        Ok(Some(client_type.to_string()))
+    }
+
+    /// Record a nonce as used, returning `false` if it was seen before
+    ///
+    /// A nonce is only forgotten once its timestamp has left the skew window, after
+    /// which a replay would be rejected by the timestamp check anyway.
+    fn remember_nonce(&self, nonce: &str, timestamp_ms: u64, skew_ms: u64) -> bool {
+        let mut seen = self
+            .seen_nonces
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let now = unix_time_ms();
+        seen.retain(|_, seen_at| seen_at.saturating_add(skew_ms) >= now);
+        if seen.contains_key(nonce) {
+            return false;
+        }
+        seen.insert(nonce.to_string(), timestamp_ms);
+        true
     }
 
     /// Clean up the secret file (e.g., on daemon shutdown)
@@ -154,6 +314,66 @@ impl UdpAuth {
     }
 }
 
+/// Registration message for `client_type`, signed with `secret`
+pub(crate) fn registration_message(
+    secret: &str,
+    client_type: &str,
+    timestamp: &str,
+    nonce: &str,
+) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, &signed_fields(client_type, timestamp, nonce));
+    let mac = encode_hex(tag.as_ref());
+    format!("REGISTER:{client_type}:{timestamp}:{nonce}:{mac}")
+}
+
+/// The part of a registration message covered by its MAC
+fn signed_fields(client_type: &str, timestamp: &str, nonce: &str) -> Vec<u8> {
+    format!("{client_type}|{timestamp}|{nonce}").into_bytes()
+}
+
+/// Constant-time comparison of a provided secret, such as a bearer token, with `secret`
+#[must_use]
+pub fn secrets_match(secret: &str, provided: &str) -> bool {
+    // Verifying `provided` against the MAC of `secret` compares fixed-size tags in
+    // constant time, so neither the contents nor the length of `secret` leak
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let expected = hmac::sign(&key, secret.as_bytes());
+    verify_mac(secret, provided.as_bytes(), expected.as_ref())
+}
+
+/// Constant-time check of `mac` against the HMAC of `data` under `secret`
+fn verify_mac(secret: &str, data: &[u8], mac: &[u8]) -> bool {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, data, mac).is_ok()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Milliseconds since the Unix epoch, shared by clients and the daemon
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+        })
+}
+
 impl Default for UdpAuth {
     fn default() -> Self {
         Self::new().expect("Failed to initialize UDP authentication")
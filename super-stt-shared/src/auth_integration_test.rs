@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 #[cfg(test)]
 mod integration_tests {
-    use crate::auth::UdpAuth;
+    use crate::auth::{UdpAuth, registration_message};
     use std::env;
     use std::sync::Mutex;
+    use std::time::Duration;
 
     // Ensure tests run sequentially to avoid race conditions with environment variables
     static TEST_MUTEX: Mutex<()> = Mutex::new(());
@@ -115,4 +116,200 @@ mod integration_tests {
             }
         }
     }
+
+    #[test]
+    fn test_secret_rotation_grace_period() {
+        let _guard = TEST_MUTEX
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let original_runtime_dir = use_temp_runtime_dir("super_stt_rotation_test");
+
+        let daemon_auth = UdpAuth::new().unwrap();
+        let client_auth = UdpAuth::new().unwrap();
+
+        // A client that read the secret just before rotation
+        let stale_message = client_auth.create_auth_message("applet").unwrap();
+
+        let old_secret = daemon_auth.get_or_create_secret().unwrap();
+        let new_secret = daemon_auth.rotate().unwrap();
+        assert_ne!(old_secret, new_secret);
+        // 32 random bytes, hex-encoded
+        assert_eq!(new_secret.len(), 64);
+        assert!(new_secret.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // Clients pick up the new secret from the shared file
+        assert_eq!(client_auth.get_or_create_secret().unwrap(), new_secret);
+        let fresh_message = client_auth.create_auth_message("applet").unwrap();
+        assert_eq!(
+            daemon_auth.verify_auth_message(&fresh_message).unwrap(),
+            Some("applet".to_string())
+        );
+
+        // The old secret is still accepted during the grace period
+        assert_eq!(
+            daemon_auth.verify_auth_message(&stale_message).unwrap(),
+            Some("applet".to_string())
+        );
+
+        // Bearer tokens follow the same rules
+        assert!(daemon_auth.verify_secret(&new_secret).unwrap());
+        assert!(daemon_auth.verify_secret(&old_secret).unwrap());
+        assert!(
+            !daemon_auth
+                .verify_secret(&new_secret[..new_secret.len() - 1])
+                .unwrap()
+        );
+        assert!(!daemon_auth.verify_secret("").unwrap());
+
+        // A second rotation retires the original secret entirely
+        daemon_auth.rotate().unwrap();
+        assert!(!daemon_auth.verify_secret(&old_secret).unwrap());
+        assert_eq!(
+            daemon_auth.verify_auth_message(&stale_message).unwrap(),
+            None
+        );
+
+        daemon_auth.cleanup().unwrap();
+        restore_runtime_dir(original_runtime_dir);
+    }
+
+    /// Point the secret file at a fresh temporary directory, returning the previous value
+    fn use_temp_runtime_dir(name: &str) -> Option<String> {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let thread_id = std::thread::current().id();
+        let temp_dir = env::temp_dir().join(format!("{name}_{timestamp}_{thread_id:?}"));
+
+        let original_runtime_dir = env::var("XDG_RUNTIME_DIR").ok();
+        unsafe {
+            env::set_var("XDG_RUNTIME_DIR", &temp_dir);
+        }
+        original_runtime_dir
+    }
+
+    fn restore_runtime_dir(original_runtime_dir: Option<String>) {
+        unsafe {
+            match original_runtime_dir {
+                Some(original) => env::set_var("XDG_RUNTIME_DIR", original),
+                None => env::remove_var("XDG_RUNTIME_DIR"),
+            }
+        }
+    }
+
+    fn now_ms() -> u64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        u64::try_from(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_replayed_registration_rejected() {
+        let _guard = TEST_MUTEX
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let original_runtime_dir = use_temp_runtime_dir("super_stt_replay_test");
+
+        let daemon_auth = UdpAuth::new().unwrap();
+        let client_auth = UdpAuth::new().unwrap();
+
+        let message = client_auth.create_auth_message("applet").unwrap();
+        let secret = daemon_auth.get_or_create_secret().unwrap();
+        assert!(!message.contains(&secret));
+        assert_eq!(
+            daemon_auth.verify_auth_message(&message).unwrap(),
+            Some("applet".to_string())
+        );
+
+        // The exact same message must not register twice
+        assert_eq!(daemon_auth.verify_auth_message(&message).unwrap(), None);
+
+        // Nor after many other registrations, while it is still inside the skew window
+        for _ in 0..300 {
+            let other = client_auth.create_auth_message("applet").unwrap();
+            assert!(daemon_auth.verify_auth_message(&other).unwrap().is_some());
+        }
+        assert_eq!(daemon_auth.verify_auth_message(&message).unwrap(), None);
+
+        // A message signed with another secret is rejected
+        let forged = registration_message("not-the-secret", "applet", &now_ms().to_string(), "x");
+        assert_eq!(daemon_auth.verify_auth_message(&forged).unwrap(), None);
+
+        // A fresh message from the same client is fine
+        let next_message = client_auth.create_auth_message("applet").unwrap();
+        assert_ne!(message, next_message);
+        assert_eq!(
+            daemon_auth.verify_auth_message(&next_message).unwrap(),
+            Some("applet".to_string())
+        );
+
+        // The legacy format without timestamp and nonce is rejected
+        let legacy_message = format!("REGISTER:applet:{secret}");
+        assert_eq!(
+            daemon_auth.verify_auth_message(&legacy_message).unwrap(),
+            None
+        );
+
+        daemon_auth.cleanup().unwrap();
+        restore_runtime_dir(original_runtime_dir);
+    }
+
+    #[test]
+    fn test_registration_clock_skew() {
+        let _guard = TEST_MUTEX
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let original_runtime_dir = use_temp_runtime_dir("super_stt_skew_test");
+
+        let daemon_auth = UdpAuth::new()
+            .unwrap()
+            .with_max_clock_skew(Duration::from_secs(30));
+        let secret = daemon_auth.get_or_create_secret().unwrap();
+        let message_at = |timestamp_ms: u64, nonce: &str| {
+            registration_message(&secret, "applet", &timestamp_ms.to_string(), nonce)
+        };
+
+        let now = now_ms();
+
+        // Inside the window on either side of the local clock
+        let recent = message_at(now - 25_000, "recent");
+        assert_eq!(
+            daemon_auth.verify_auth_message(&recent).unwrap(),
+            Some("applet".to_string())
+        );
+        let slightly_ahead = message_at(now + 25_000, "ahead");
+        assert_eq!(
+            daemon_auth.verify_auth_message(&slightly_ahead).unwrap(),
+            Some("applet".to_string())
+        );
+
+        // Outside the window in the past and the future
+        let stale = message_at(now - 35_000, "stale");
+        assert_eq!(daemon_auth.verify_auth_message(&stale).unwrap(), None);
+        let future = message_at(now + 35_000, "future");
+        assert_eq!(daemon_auth.verify_auth_message(&future).unwrap(), None);
+
+        // A rejected message does not burn its nonce
+        let retried = message_at(now_ms(), "stale");
+        assert_eq!(
+            daemon_auth.verify_auth_message(&retried).unwrap(),
+            Some("applet".to_string())
+        );
+
+        // Garbage timestamps are rejected rather than treated as zero
+        let garbage = registration_message(&secret, "applet", "soon", "garbage");
+        assert_eq!(daemon_auth.verify_auth_message(&garbage).unwrap(), None);
+
+        daemon_auth.cleanup().unwrap();
+        restore_runtime_dir(original_runtime_dir);
+    }
 }
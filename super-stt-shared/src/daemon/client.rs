@@ -1,12 +1,63 @@
 // SPDX-License-Identifier: GPL-3.0-only
 //! Shared daemon client functionality for Super STT applications
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 
-use crate::models::protocol::{DaemonRequest, DaemonResponse, DownloadProgress};
+use crate::models::protocol::{
+    DaemonRequest, DaemonResponse, DownloadProgress, HEARTBEAT_EVENT, HEARTBEAT_INTERVAL,
+    HistoryEntry, InputTestResult, ModelInfo, NotificationEvent,
+};
 use crate::stt_model::STTModel;
+use crate::validation::limits;
+
+/// Failure to read one length-prefixed message
+#[derive(Debug, thiserror::Error)]
+pub enum FrameError {
+    #[error("Message of {size} bytes exceeds the {max} byte limit")]
+    TooLarge { size: u64, max: usize },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Read one message framed by an 8-byte big-endian size, `Ok(None)` if the stream
+/// ends before it starts
+///
+/// The size is checked against `max_size` before the buffer is allocated.
+///
+/// # Errors
+///
+/// Returns [`FrameError::TooLarge`] for an oversized frame, after which the stream is
+/// out of step and should be closed, or [`FrameError::Io`] if reading fails.
+pub async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_size: usize,
+) -> Result<Option<Vec<u8>>, FrameError> {
+    let mut size_buf = [0u8; 8];
+    match reader.read_exact(&mut size_buf).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let size = u64::from_be_bytes(size_buf);
+    let len = match usize::try_from(size) {
+        Ok(len) if len <= max_size => len,
+        _ => {
+            return Err(FrameError::TooLarge {
+                size,
+                max: max_size,
+            });
+        }
+    };
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).await?;
+    Ok(Some(buf))
+}
 
 /// Basic daemon connection utility with improved error handling
 async fn connect_to_daemon(socket_path: &PathBuf) -> Result<UnixStream, String> {
@@ -26,16 +77,21 @@ async fn connect_to_daemon(socket_path: &PathBuf) -> Result<UnixStream, String>
 /// # Errors
 ///
 /// Returns an error when connecting, serializing, writing, reading, or parsing fails,
-/// or if the response length does not fit in `usize` on this platform.
+/// or if the response exceeds [`limits::MAX_FRAME_SIZE`].
 async fn send_daemon_request(
     socket_path: &PathBuf,
     request: DaemonRequest,
 ) -> Result<DaemonResponse, String> {
     let mut stream = connect_to_daemon(socket_path).await?;
+    write_request(&mut stream, &request).await?;
+    read_response(&mut stream).await
+}
 
+/// Write one size-prefixed request
+async fn write_request(stream: &mut UnixStream, request: &DaemonRequest) -> Result<(), String> {
     // Serialize request and get size
     let request_data =
-        serde_json::to_vec(&request).map_err(|e| format!("Failed to serialize request: {e}"))?;
+        serde_json::to_vec(request).map_err(|e| format!("Failed to serialize request: {e}"))?;
 
     // Send size header (8 bytes, big-endian)
     let size = request_data.len() as u64;
@@ -48,45 +104,91 @@ async fn send_daemon_request(
     stream
         .write_all(&request_data)
         .await
-        .map_err(|e| format!("Failed to write request: {e}"))?;
+        .map_err(|e| format!("Failed to write request: {e}"))
+}
 
-    // Read size header from response
-    let mut size_buf = [0u8; 8];
-    stream
-        .read_exact(&mut size_buf)
+/// Read and parse one size-prefixed response
+async fn read_response(stream: &mut UnixStream) -> Result<DaemonResponse, String> {
+    let response_buf = read_frame(stream, limits::MAX_FRAME_SIZE)
         .await
-        .map_err(|e| format!("Failed to read response size: {e}"))?;
+        .map_err(|e| format!("Failed to read response: {e}"))?
+        .ok_or("Daemon closed the connection without responding")?;
 
-    // Read exact response size
-    let response_size = u64::from_be_bytes(size_buf);
-    let response_len = usize::try_from(response_size)
-        .map_err(|_| "Response too large for this platform".to_string())?;
-    let mut response_buf = vec![0u8; response_len];
-    stream
-        .read_exact(&mut response_buf)
+    serde_json::from_slice(&response_buf).map_err(|e| format!("Failed to parse response: {e}"))
+}
+
+/// How long an event stream may stay silent before it is treated as dead: three
+/// missed heartbeats
+pub const EVENT_STREAM_TIMEOUT: Duration = HEARTBEAT_INTERVAL.saturating_mul(3);
+
+/// Subscribe to `event_types` and pass each event to `on_event` until the stream ends
+///
+/// Events after `since_timestamp` are replayed first. Heartbeats are consumed here
+/// rather than passed on, and a stream without one for [`EVENT_STREAM_TIMEOUT`]
+/// counts as dead, so the caller can reconnect.
+///
+/// # Errors
+///
+/// Returns an error if the subscription fails or the connection drops, fails, or
+/// goes silent. `Ok` means the daemon closed the stream.
+pub async fn stream_daemon_events(
+    socket_path: PathBuf,
+    event_types: Vec<String>,
+    since_timestamp: Option<String>,
+    client_id: &str,
+    on_event: impl FnMut(NotificationEvent),
+) -> Result<(), String> {
+    let mut stream = connect_to_daemon(&socket_path).await?;
+    let request = DaemonRequest {
+        event_types: Some(event_types),
+        since_timestamp,
+        ..create_daemon_request("subscribe", client_id)
+    };
+    write_request(&mut stream, &request).await?;
+
+    let response = tokio::time::timeout(EVENT_STREAM_TIMEOUT, read_response(&mut stream))
         .await
-        .map_err(|e| format!("Failed to read response: {e}"))?;
+        .map_err(|_| "Daemon did not answer the subscription".to_string())??;
+    if response.status != "success" {
+        return Err(response
+            .message
+            .unwrap_or_else(|| "Subscription failed".to_string()));
+    }
 
-    // Parse response
-    serde_json::from_slice(&response_buf).map_err(|e| format!("Failed to parse response: {e}"))
+    read_events(&mut stream, EVENT_STREAM_TIMEOUT, on_event).await
+}
+
+/// Read event frames until the stream ends or stays silent for `timeout`
+async fn read_events<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    timeout: Duration,
+    mut on_event: impl FnMut(NotificationEvent),
+) -> Result<(), String> {
+    loop {
+        let frame = tokio::time::timeout(timeout, read_frame(reader, limits::MAX_FRAME_SIZE))
+            .await
+            .map_err(|_| format!("No heartbeat from the daemon for {}s", timeout.as_secs()))?
+            .map_err(|e| format!("Failed to read event: {e}"))?;
+        let Some(frame) = frame else {
+            return Ok(());
+        };
+
+        // Responses to requests sent on the stream are not events
+        let Ok(event) = serde_json::from_slice::<NotificationEvent>(&frame) else {
+            continue;
+        };
+        if event.event_type != HEARTBEAT_EVENT {
+            on_event(event);
+        }
+    }
 }
 
 /// Create a basic daemon request with client identification
 #[must_use]
 pub fn create_daemon_request(command: &str, client_id: &str) -> DaemonRequest {
     DaemonRequest {
-        command: command.to_string(),
-        data: None,
         client_id: Some(client_id.to_string()),
-        language: None,
-        audio_data: None,
-        sample_rate: None,
-        event_types: None,
-        client_info: None,
-        since_timestamp: None,
-        limit: None,
-        event_type: None,
-        enabled: None,
+        ..DaemonRequest::new(command)
     }
 }
 
@@ -166,6 +268,14 @@ pub async fn send_record_command(socket_path: PathBuf, client_id: &str) -> Resul
     }
 }
 
+/// The daemon's configuration, with the address its UDP streamer is bound to
+#[derive(Debug, Clone)]
+pub struct DaemonConfigInfo {
+    pub config: serde_json::Value,
+    /// `None` from daemons that predate reporting it
+    pub udp_addr: Option<SocketAddr>,
+}
+
 /// Get current daemon configuration
 ///
 /// # Errors
@@ -174,10 +284,40 @@ pub async fn send_record_command(socket_path: PathBuf, client_id: &str) -> Resul
 pub async fn fetch_daemon_config(
     socket_path: PathBuf,
     client_id: &str,
-) -> Result<serde_json::Value, String> {
+) -> Result<DaemonConfigInfo, String> {
     let request = create_daemon_request("get_config", client_id);
     let response = send_daemon_request(&socket_path, request).await?;
 
+    if response.status == "success" {
+        let config = response
+            .daemon_config
+            .ok_or_else(|| "No daemon config in response".to_string())?;
+        Ok(DaemonConfigInfo {
+            config,
+            udp_addr: response.udp_addr,
+        })
+    } else {
+        Err(response
+            .message
+            .unwrap_or_else(|| "Failed to get daemon config".to_string()))
+    }
+}
+
+/// Apply a partial config update on the daemon and return the resulting config
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the daemon rejects the patch.
+pub async fn update_daemon_config(
+    socket_path: PathBuf,
+    patch: serde_json::Value,
+    client_id: &str,
+) -> Result<serde_json::Value, String> {
+    let mut request = create_daemon_request("set_config", client_id);
+    request.data = Some(patch);
+
+    let response = send_daemon_request(&socket_path, request).await?;
+
     if response.status == "success" {
         response
             .daemon_config
@@ -185,7 +325,7 @@ pub async fn fetch_daemon_config(
     } else {
         Err(response
             .message
-            .unwrap_or_else(|| "Failed to get daemon config".to_string()))
+            .unwrap_or_else(|| "Failed to update daemon config".to_string()))
     }
 }
 
@@ -249,6 +389,33 @@ pub async fn set_and_test_audio_theme(
     test_audio_theme(socket_path, client_id).await
 }
 
+/// Record a short clip from the daemon's input device and get its levels
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the daemon could not record.
+pub async fn test_audio_input(
+    socket_path: PathBuf,
+    playback: bool,
+    client_id: &str,
+) -> Result<(InputTestResult, String), String> {
+    let mut request = create_daemon_request("test_audio_input", client_id);
+    request.data = Some(serde_json::json!({"playback": playback}));
+
+    let response = send_daemon_request(&socket_path, request).await?;
+
+    if response.status == "success" {
+        let result = response
+            .input_test
+            .ok_or_else(|| "No input test result in response".to_string())?;
+        Ok((result, response.message.unwrap_or_default()))
+    } else {
+        Err(response
+            .message
+            .unwrap_or_else(|| "Failed to test audio input".to_string()))
+    }
+}
+
 /// Send a generic command to the daemon with optional data
 ///
 /// # Errors
@@ -336,6 +503,63 @@ pub async fn list_available_models(
     }
 }
 
+/// List all models with their download and load state
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the daemon doesn't return model details.
+pub async fn list_models_detailed(
+    socket_path: PathBuf,
+    client_id: &str,
+) -> Result<Vec<ModelInfo>, String> {
+    let request = create_daemon_request("list_models_detailed", client_id);
+    let response = send_daemon_request(&socket_path, request).await?;
+
+    if response.status == "success" {
+        Ok(response.model_details.unwrap_or_default())
+    } else {
+        Err(response
+            .message
+            .unwrap_or_else(|| "Failed to get model details".to_string()))
+    }
+}
+
+/// List the language codes the loaded model can transcribe, empty for English-only models
+///
+/// # Errors
+///
+/// Returns an error if the request fails or no model is loaded.
+pub async fn list_languages(socket_path: PathBuf, client_id: &str) -> Result<Vec<String>, String> {
+    let request = create_daemon_request("list_languages", client_id);
+    let response = send_daemon_request(&socket_path, request).await?;
+
+    if response.status == "success" {
+        Ok(response.languages.unwrap_or_default())
+    } else {
+        Err(response
+            .message
+            .unwrap_or_else(|| "Failed to list languages".to_string()))
+    }
+}
+
+/// Fetch the 16kHz samples of the daemon's latest recording
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the daemon does not keep recordings.
+pub async fn get_last_audio(socket_path: PathBuf, client_id: &str) -> Result<Vec<f32>, String> {
+    let request = create_daemon_request("get_last_audio", client_id);
+    let response = send_daemon_request(&socket_path, request).await?;
+
+    if response.status == "success" {
+        Ok(response.audio_data.unwrap_or_default())
+    } else {
+        Err(response
+            .message
+            .unwrap_or_else(|| "Failed to get the last recording".to_string()))
+    }
+}
+
 /// Cancel any ongoing download
 ///
 /// # Errors
@@ -476,3 +700,99 @@ pub async fn get_preview_typing(socket_path: PathBuf, client_id: &str) -> Result
             .unwrap_or_else(|| "Failed to get preview typing setting".to_string()))
     }
 }
+
+/// Fetch past transcriptions from the daemon, newest first
+///
+/// # Errors
+///
+/// Returns an error if the request fails.
+pub async fn get_history(
+    socket_path: PathBuf,
+    since_timestamp: Option<String>,
+    limit: u32,
+    client_id: &str,
+) -> Result<Vec<HistoryEntry>, String> {
+    let mut request = create_daemon_request("get_history", client_id);
+    request.since_timestamp = since_timestamp;
+    request.limit = Some(limit);
+
+    let response = send_daemon_request(&socket_path, request).await?;
+
+    if response.status == "success" {
+        Ok(response.history.unwrap_or_default())
+    } else {
+        Err(response
+            .message
+            .unwrap_or_else(|| "Failed to get history".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_frame_rejects_oversized_prefix() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client.write_all(&u64::MAX.to_be_bytes()).await.unwrap();
+
+        let result = read_frame(&mut server, limits::MAX_FRAME_SIZE).await;
+        assert!(matches!(
+            result,
+            Err(FrameError::TooLarge { size: u64::MAX, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_reads_message_and_end_of_stream() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client.write_all(&5u64.to_be_bytes()).await.unwrap();
+        client.write_all(b"hello").await.unwrap();
+        drop(client);
+
+        let frame = read_frame(&mut server, 16).await.unwrap();
+        assert_eq!(frame.as_deref(), Some(&b"hello"[..]));
+        assert!(read_frame(&mut server, 16).await.unwrap().is_none());
+    }
+
+    async fn write_event(writer: &mut tokio::io::DuplexStream, event: &NotificationEvent) {
+        let json = serde_json::to_vec(event).unwrap();
+        writer
+            .write_all(&(json.len() as u64).to_be_bytes())
+            .await
+            .unwrap();
+        writer.write_all(&json).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_events_times_out_on_silent_stream() {
+        // The daemon side stays open but never writes, as when it hangs or the host sleeps
+        let (_daemon, mut client) = tokio::io::duplex(1024);
+
+        let mut received = 0;
+        let result = read_events(&mut client, Duration::from_millis(50), |_| received += 1).await;
+
+        assert!(result.is_err());
+        assert_eq!(received, 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_events_skips_heartbeats() {
+        let (mut daemon, mut client) = tokio::io::duplex(4096);
+        let mut event = NotificationEvent::heartbeat();
+        event.event_type = "config_changed".to_string();
+        write_event(&mut daemon, &NotificationEvent::heartbeat()).await;
+        write_event(&mut daemon, &event).await;
+        write_event(&mut daemon, &NotificationEvent::heartbeat()).await;
+        drop(daemon);
+
+        let mut received = Vec::new();
+        let result = read_events(&mut client, Duration::from_secs(5), |event| {
+            received.push(event.event_type);
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(received, ["config_changed"]);
+    }
+}
@@ -3,13 +3,16 @@
 pub struct RecordingStateData {
     pub is_recording: bool,
     pub timestamp_ms: u64,
+    /// Rate the input device records at, 0 when stopped or unknown
+    pub input_sample_rate: u32,
 }
 
 impl RecordingStateData {
     #[must_use]
-    pub fn new(is_recording: bool) -> Self {
+    pub fn new(is_recording: bool, input_sample_rate: u32) -> Self {
         Self {
             is_recording,
+            input_sample_rate,
             timestamp_ms: u64::try_from(
                 std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
@@ -21,10 +24,11 @@ impl RecordingStateData {
     }
 
     #[must_use]
-    pub fn to_bytes(&self) -> [u8; 9] {
-        let mut bytes = [0u8; 9];
+    pub fn to_bytes(&self) -> [u8; 13] {
+        let mut bytes = [0u8; 13];
         bytes[0] = u8::from(self.is_recording);
         bytes[1..9].copy_from_slice(&self.timestamp_ms.to_le_bytes());
+        bytes[9..13].copy_from_slice(&self.input_sample_rate.to_le_bytes());
         bytes
     }
 }
@@ -1,7 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    str::FromStr,
+};
 
 use crate::models::theme::AudioTheme;
 use crate::stt_model::STTModel;
@@ -34,6 +38,30 @@ pub struct DaemonRequest {
     pub language: Option<String>,
     #[serde(default)]
     pub enabled: Option<bool>,
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+impl DaemonRequest {
+    /// A request for `command` with every other field unset
+    #[must_use]
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            audio_data: None,
+            sample_rate: None,
+            client_id: None,
+            event_types: None,
+            client_info: None,
+            since_timestamp: None,
+            limit: None,
+            event_type: None,
+            data: None,
+            language: None,
+            enabled: None,
+            format: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -52,6 +80,8 @@ pub struct DaemonResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub available_models: Option<Vec<STTModel>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_details: Option<Vec<ModelInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub available_devices: Option<Vec<String>>,
 
     // Notification system fields
@@ -83,6 +113,9 @@ pub struct DaemonResponse {
     // Daemon configuration fields
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daemon_config: Option<Value>,
+    /// Address the daemon's UDP audio streamer is bound to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udp_addr: Option<SocketAddr>,
 
     // Connection status fields
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -91,8 +124,104 @@ pub struct DaemonResponse {
     // Preview typing fields
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preview_typing_enabled: Option<bool>,
+
+    // Timestamped transcription fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<Segment>>,
+
+    // Language detection fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Confidence in the detected language or the transcription, in `0.0..=1.0`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_candidates: Option<Vec<LanguageCandidate>>,
+    /// Language codes the loaded model accepts, empty for English-only models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<String>>,
+
+    // Audio input device fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_devices: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_device: Option<String>,
+
+    // Streamed recording fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_final: Option<bool>,
+
+    // Where a recording's final transcription was delivered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputMode>,
+
+    // Transcription history fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<HistoryEntry>>,
+
+    // Model warmup fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmup_ms: Option<u64>,
+
+    // Model deletion fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freed_bytes: Option<u64>,
+
+    // Memory usage fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu_memory_used_mb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu_memory_total_mb: Option<u64>,
+    /// Resident memory of the daemon process, which holds the model when it runs on CPU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_memory_mb: Option<u64>,
+
+    // Recording queue fields
+    /// 1-based place of a queued `record` request behind the active recording
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+
+    // Health check fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthStatus>,
+
+    // Microphone check fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_test: Option<InputTestResult>,
+    /// Rate the input device was negotiated at for the latest recording
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_sample_rate: Option<u32>,
+
+    // Recording timing fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<TranscriptionTimings>,
+
+    // Structured transcription fields
+    /// The transcription with its metadata, next to the legacy `transcription` string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<TranscriptionOutput>,
+
+    // Debug audio fields
+    /// 16kHz samples of the latest recording, as handed to the audio processing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_data: Option<Vec<f32>>,
+    /// WAV file the latest recording was written to, ready for `transcribe_file`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_path: Option<String>,
+
+    // Request validation fields
+    /// Request field that failed validation, kept when the message is sanitized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_field: Option<String>,
+    /// Machine-readable cause of an error, such as [`MEL_GENERATION_FAILED`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
 }
 
+/// Error code of a Voxtral transcription whose mel features came out invalid;
+/// switching to another model avoids it
+pub const MEL_GENERATION_FAILED: &str = "MEL_GENERATION_FAILED";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DownloadProgress {
     pub model_name: String,
@@ -102,9 +231,263 @@ pub struct DownloadProgress {
     pub bytes_downloaded: u64,
     pub total_bytes: u64,
     pub percentage: f32,
-    pub status: String, // "downloading", "cancelled", "completed", "error"
+    pub status: String, // "downloading", "verifying", "cancelled", "completed", "error"
     pub started_at: String,
     pub eta_seconds: Option<u64>,
+    /// Average transfer rate of the current file in bytes per second, 0 when unknown
+    #[serde(default)]
+    pub speed_bps: f64,
+}
+
+/// Output format for timestamped transcriptions
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Text,
+    Srt,
+    Vtt,
+}
+
+impl FromStr for TranscriptFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "srt" => Ok(Self::Srt),
+            "vtt" => Ok(Self::Vtt),
+            _ => Err(format!("Unknown format: {s} (expected text, srt or vtt)")),
+        }
+    }
+}
+
+/// How `get_last_audio` hands the latest recording back
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LastAudioFormat {
+    /// The samples themselves, in the response's `audio_data`
+    #[default]
+    Samples,
+    /// A WAV file written by the daemon, named in the response's `audio_path`
+    Wav,
+}
+
+impl FromStr for LastAudioFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "samples" => Ok(Self::Samples),
+            "wav" => Ok(Self::Wav),
+            _ => Err(format!("Unknown format: {s} (expected samples or wav)")),
+        }
+    }
+}
+
+/// Where a written recording delivers its final transcription
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Type into the focused window
+    #[default]
+    Type,
+    /// Place on the clipboard without typing anything
+    Clipboard,
+    /// Type and place on the clipboard
+    Both,
+}
+
+impl OutputMode {
+    /// Whether text is typed into the focused window
+    #[must_use]
+    pub fn types(self) -> bool {
+        matches!(self, Self::Type | Self::Both)
+    }
+
+    /// Whether text is placed on the clipboard
+    #[must_use]
+    pub fn copies(self) -> bool {
+        matches!(self, Self::Clipboard | Self::Both)
+    }
+}
+
+impl FromStr for OutputMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "type" => Ok(Self::Type),
+            "clipboard" => Ok(Self::Clipboard),
+            "both" => Ok(Self::Both),
+            _ => Err(format!(
+                "Unknown output: {s} (expected type, clipboard or both)"
+            )),
+        }
+    }
+}
+
+/// A span of transcribed text with its position in the source audio, in seconds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    /// Speaker the segment is attributed to, 0 when diarization did not run
+    #[serde(default)]
+    pub speaker_id: u32,
+}
+
+/// Disk and load state of one model, as listed by `list_models_detailed`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelInfo {
+    pub model: STTModel,
+    /// Every file of the model is in the download cache or the local model source
+    pub downloaded: bool,
+    /// Space the model's files take on disk, 0 when not downloaded
+    pub size_bytes: u64,
+    pub loaded: bool,
+}
+
+/// A past final transcription kept in the daemon's history
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// When the transcription finished, in RFC 3339
+    pub timestamp: String,
+    pub text: String,
+    pub model: String,
+    pub device: String,
+    /// Length of the transcribed audio
+    pub duration_ms: u64,
+}
+
+/// State of each daemon subsystem, as reported by the `health` command
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct HealthStatus {
+    pub model_loaded: bool,
+    pub udp_streamer_bound: bool,
+    pub dbus_connected: bool,
+    pub input_device_available: bool,
+    /// Open socket connections and the most the daemon accepts
+    #[serde(default)]
+    pub connections: usize,
+    #[serde(default)]
+    pub max_connections: usize,
+    /// Most recent failure per subsystem, keyed by the names above without their suffix
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub last_errors: BTreeMap<String, String>,
+}
+
+impl HealthStatus {
+    /// Whether every subsystem is up
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.model_loaded
+            && self.udp_streamer_bound
+            && self.dbus_connected
+            && self.input_device_available
+    }
+}
+
+/// Levels of a short test recording, as reported by the `test_audio_input` command
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InputTestResult {
+    /// Largest absolute sample, 0.0 to 1.0
+    pub peak: f32,
+    pub rms: f32,
+    pub speech_detected: bool,
+    pub duration_ms: u64,
+}
+
+/// Where the time of a transcription went, in milliseconds, sent back when a
+/// `record` or `transcribe` request asks for `timings`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranscriptionTimings {
+    /// Capturing audio until silence or a stop, 0 for audio sent by the client
+    pub record_ms: u64,
+    /// Converting the audio to the model's 16kHz
+    #[serde(default)]
+    pub resample_ms: u64,
+    /// Noise suppression, gain and filtering before the model sees the audio
+    pub process_ms: u64,
+    /// Model inference, including any wait for the model
+    pub transcribe_ms: u64,
+}
+
+/// A finished transcription with what is known about it, sent as `result` by
+/// `record` and `transcribe_file`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TranscriptionOutput {
+    pub text: String,
+    /// Language the request asked for, `None` when the model picked one itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Model confidence in the text, in `0.0..=1.0`
+    pub confidence: f32,
+    /// Length of the transcribed audio
+    pub duration_ms: u64,
+    /// Model that transcribed the audio, empty if it was unloaded since
+    pub model: String,
+    /// Device the model ran on
+    pub device: String,
+    /// Timed spans of the text, empty when the command does not produce them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<Segment>,
+}
+
+/// A detected language and the model's confidence in it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LanguageCandidate {
+    pub language: String,
+    pub confidence: f32,
+}
+
+/// Per-request overrides for the daemon's silence detection settings
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct SilenceOverride {
+    /// Minimum RMS level treated as speech
+    pub threshold: Option<f32>,
+    /// How long silence must last after speech before recording stops
+    pub min_silence_ms: Option<u32>,
+    /// Hard cap on the length of a single recording
+    pub max_duration_s: Option<u32>,
+}
+
+/// Per-request overrides for the daemon's decoding settings; Whisper ignores them
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct DecodingOverride {
+    /// Upper bound on the tokens generated for one transcription
+    pub max_new_tokens: Option<usize>,
+    /// Sampling temperature; 0.0 always picks the most likely token
+    pub temperature: Option<f64>,
+    /// Nucleus sampling cutoff, used when the temperature is above 0.0
+    pub top_p: Option<f64>,
+    /// Times a phrase may repeat back to back before generation stops; 0 never stops
+    pub repetition_limit: Option<u32>,
+}
+
+/// How the word filter hides a matched word
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WordFilterMode {
+    /// Replace each letter with an asterisk
+    #[default]
+    Mask,
+    /// Remove the word and the space before it
+    Drop,
+}
+
+/// Per-request overrides for the daemon's word filter
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct WordFilterOverride {
+    /// Words to hide in place of the configured list; an empty list turns the filter off
+    pub words: Option<Vec<String>>,
+    /// Whether matches are masked or dropped
+    pub mode: Option<WordFilterMode>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -117,6 +500,26 @@ pub struct NotificationEvent {
     pub data: Value,
 }
 
+/// Event type of the frames an event stream receives at `HEARTBEAT_INTERVAL`, so
+/// clients can tell an idle stream from a dead one
+pub const HEARTBEAT_EVENT: &str = "heartbeat";
+/// How often heartbeats are sent; a client that misses a few should reconnect
+pub const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(20);
+
+impl NotificationEvent {
+    /// A heartbeat frame, sent on every event stream whatever it subscribed to
+    #[must_use]
+    pub fn heartbeat() -> Self {
+        Self {
+            event_type_field: "notification".to_string(),
+            event_type: HEARTBEAT_EVENT.to_string(),
+            client_id: "daemon".to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            data: Value::Null,
+        }
+    }
+}
+
 impl DaemonResponse {
     #[must_use]
     pub fn success() -> Self {
@@ -128,6 +531,7 @@ impl DaemonResponse {
             model_loaded: None,
             current_model: None,
             available_models: None,
+            model_details: None,
             available_devices: None,
             subscribed_to: None,
             total_subscribers: None,
@@ -140,8 +544,34 @@ impl DaemonResponse {
             available_audio_themes: None,
             download_progress: None,
             daemon_config: None,
+            udp_addr: None,
             connection_active: None,
             preview_typing_enabled: None,
+            segments: None,
+            language: None,
+            confidence: None,
+            language_candidates: None,
+            languages: None,
+            audio_devices: None,
+            input_device: None,
+            is_final: None,
+            output: None,
+            history: None,
+            warmup_ms: None,
+            freed_bytes: None,
+            gpu_memory_used_mb: None,
+            gpu_memory_total_mb: None,
+            model_memory_mb: None,
+            queue_position: None,
+            health: None,
+            input_test: None,
+            input_sample_rate: None,
+            timings: None,
+            result: None,
+            audio_data: None,
+            audio_path: None,
+            invalid_field: None,
+            error_code: None,
         }
     }
 
@@ -150,13 +580,16 @@ impl DaemonResponse {
         // Sanitize error messages before exposing to clients over the Unix socket.
         // Full details remain available in daemon logs.
         fn sanitize_error_message(message: &str) -> String {
+            // User paths are redacted even from detailed errors
+            let message = &crate::utils::sanitize::sanitize_error(message);
+
             // Opt-in detailed errors for local debugging
             let debug = std::env::var("SUPER_STT_DEBUG_ERRORS")
                 .map(|v| matches!(v.as_str(), "1"))
                 .unwrap_or(false)
                 || cfg!(debug_assertions);
             if debug {
-                return message.to_string();
+                return message.clone();
             }
 
             // Keep only the first line and trim internal details after a colon
@@ -176,6 +609,7 @@ impl DaemonResponse {
             model_loaded: None,
             current_model: None,
             available_models: None,
+            model_details: None,
             available_devices: None,
             subscribed_to: None,
             total_subscribers: None,
@@ -188,11 +622,45 @@ impl DaemonResponse {
             available_audio_themes: None,
             download_progress: None,
             daemon_config: None,
+            udp_addr: None,
             connection_active: None,
             preview_typing_enabled: None,
+            segments: None,
+            language: None,
+            confidence: None,
+            language_candidates: None,
+            languages: None,
+            audio_devices: None,
+            input_device: None,
+            is_final: None,
+            output: None,
+            history: None,
+            warmup_ms: None,
+            freed_bytes: None,
+            gpu_memory_used_mb: None,
+            gpu_memory_total_mb: None,
+            model_memory_mb: None,
+            queue_position: None,
+            health: None,
+            input_test: None,
+            input_sample_rate: None,
+            timings: None,
+            result: None,
+            audio_data: None,
+            audio_path: None,
+            invalid_field: None,
+            error_code: None,
         }
     }
 
+    /// Rejection of a request that failed validation, naming the offending field
+    #[must_use]
+    pub fn invalid_request(error: &ValidationError) -> Self {
+        let mut response = Self::error(&format!("Request validation failed: {error}"));
+        response.invalid_field = error.field().map(str::to_string);
+        response
+    }
+
     #[must_use]
     pub fn with_transcription(mut self, transcription: String) -> Self {
         self.transcription = Some(transcription);
@@ -271,6 +739,12 @@ impl DaemonResponse {
         self
     }
 
+    #[must_use]
+    pub fn with_model_details(mut self, models: Vec<ModelInfo>) -> Self {
+        self.model_details = Some(models);
+        self
+    }
+
     #[must_use]
     pub fn with_download_progress(mut self, progress: DownloadProgress) -> Self {
         self.download_progress = Some(progress);
@@ -289,6 +763,12 @@ impl DaemonResponse {
         self
     }
 
+    #[must_use]
+    pub fn with_udp_addr(mut self, udp_addr: SocketAddr) -> Self {
+        self.udp_addr = Some(udp_addr);
+        self
+    }
+
     #[must_use]
     pub fn with_connection_active(mut self, active: bool) -> Self {
         self.connection_active = Some(active);
@@ -300,6 +780,144 @@ impl DaemonResponse {
         self.preview_typing_enabled = Some(enabled);
         self
     }
+
+    #[must_use]
+    pub fn with_segments(mut self, segments: Vec<Segment>) -> Self {
+        self.segments = Some(segments);
+        self
+    }
+
+    #[must_use]
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = Some(confidence);
+        self
+    }
+
+    /// Attach ranked language candidates, reporting the first as the detected language
+    #[must_use]
+    pub fn with_language_candidates(mut self, candidates: Vec<LanguageCandidate>) -> Self {
+        if let Some(top) = candidates.first() {
+            self.language = Some(top.language.clone());
+            self.confidence = Some(top.confidence);
+        }
+        self.language_candidates = Some(candidates);
+        self
+    }
+
+    #[must_use]
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = Some(languages);
+        self
+    }
+
+    #[must_use]
+    pub fn with_audio_devices(mut self, devices: Vec<String>) -> Self {
+        self.audio_devices = Some(devices);
+        self
+    }
+
+    #[must_use]
+    pub fn with_input_device(mut self, device: String) -> Self {
+        self.input_device = Some(device);
+        self
+    }
+
+    #[must_use]
+    pub fn with_is_final(mut self, is_final: bool) -> Self {
+        self.is_final = Some(is_final);
+        self
+    }
+
+    #[must_use]
+    pub fn with_output(mut self, output: OutputMode) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    #[must_use]
+    pub fn with_history(mut self, history: Vec<HistoryEntry>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    #[must_use]
+    pub fn with_warmup_ms(mut self, warmup_ms: u64) -> Self {
+        self.warmup_ms = Some(warmup_ms);
+        self
+    }
+
+    #[must_use]
+    pub fn with_freed_bytes(mut self, freed_bytes: u64) -> Self {
+        self.freed_bytes = Some(freed_bytes);
+        self
+    }
+
+    #[must_use]
+    pub fn with_gpu_memory_mb(mut self, used_mb: u64, total_mb: u64) -> Self {
+        self.gpu_memory_used_mb = Some(used_mb);
+        self.gpu_memory_total_mb = Some(total_mb);
+        self
+    }
+
+    #[must_use]
+    pub fn with_model_memory_mb(mut self, model_memory_mb: u64) -> Self {
+        self.model_memory_mb = Some(model_memory_mb);
+        self
+    }
+
+    #[must_use]
+    pub fn with_queue_position(mut self, queue_position: usize) -> Self {
+        self.queue_position = Some(queue_position);
+        self
+    }
+
+    #[must_use]
+    pub fn with_health(mut self, health: HealthStatus) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    #[must_use]
+    pub fn with_input_test(mut self, input_test: InputTestResult) -> Self {
+        self.input_test = Some(input_test);
+        self
+    }
+
+    #[must_use]
+    pub fn with_input_sample_rate(mut self, input_sample_rate: u32) -> Self {
+        self.input_sample_rate = Some(input_sample_rate);
+        self
+    }
+
+    #[must_use]
+    pub fn with_timings(mut self, timings: TranscriptionTimings) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    #[must_use]
+    pub fn with_audio_data(mut self, audio_data: Vec<f32>) -> Self {
+        self.audio_data = Some(audio_data);
+        self
+    }
+
+    #[must_use]
+    pub fn with_audio_path(mut self, audio_path: String) -> Self {
+        self.audio_path = Some(audio_path);
+        self
+    }
+
+    #[must_use]
+    pub fn with_error_code(mut self, code: &str) -> Self {
+        self.error_code = Some(code.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn with_result(mut self, result: TranscriptionOutput) -> Self {
+        self.result = Some(result);
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -308,6 +926,29 @@ pub enum Command {
         audio_data: Vec<f32>,
         sample_rate: u32,
         client_id: String,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        decoding: Option<DecodingOverride>,
+        /// Send back where the time went
+        timings: bool,
+    },
+    TranscribeFile {
+        path: String,
+        client_id: String,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        decoding: Option<DecodingOverride>,
+    },
+    TranscribeTimestamped {
+        audio_data: Vec<f32>,
+        sample_rate: u32,
+        client_id: String,
+        format: TranscriptFormat,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
     },
     Subscribe {
         event_types: Vec<String>,
@@ -320,6 +961,10 @@ pub enum Command {
         limit: u32,
     },
     GetSubscriberInfo,
+    GetHistory {
+        since_timestamp: Option<String>,
+        limit: u32,
+    },
     Notify {
         event_type: String,
         client_id: String,
@@ -329,6 +974,7 @@ pub enum Command {
         client_id: Option<String>,
     },
     Status,
+    Health,
     StartRealTimeTranscription {
         client_id: String,
         sample_rate: Option<u32>,
@@ -340,23 +986,100 @@ pub enum Command {
         sample_rate: u32,
     },
     Record {
+        /// Recordings from different clients run side by side; `None` shares one default slot
+        client_id: Option<String>,
+        write_mode: bool,
+        silence: Option<SilenceOverride>,
+        language: Option<String>,
+        device_name: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        output: Option<OutputMode>,
+        queue: bool,
+        /// Skip typing the result, for measuring latency; implies `timings`
+        dry_run: bool,
+        /// Send back where the time went
+        timings: bool,
+    },
+    /// Push-to-talk: start capturing until `RecordStop` from the same client
+    RecordStart {
+        client_id: String,
+        write_mode: bool,
+        /// Only `max_duration_s` applies; silence never ends a push-to-talk recording
+        silence: Option<SilenceOverride>,
+        language: Option<String>,
+        device_name: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        output: Option<OutputMode>,
+    },
+    RecordStop {
+        client_id: String,
+    },
+    RecordContinuous {
+        client_id: Option<String>,
         write_mode: bool,
+        silence: Option<SilenceOverride>,
+        language: Option<String>,
+        device_name: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+    },
+    RecordStream {
+        write_mode: bool,
+        silence: Option<SilenceOverride>,
+        language: Option<String>,
+        device_name: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+    },
+    Stop,
+    /// Cancel the recordings of `client_id`, or every recording when it is `None`
+    CancelRecording {
+        client_id: Option<String>,
+    },
+    ListAudioDevices,
+    StartMonitor,
+    StopMonitor,
+    DetectLanguage {
+        audio_data: Option<Vec<f32>>,
+        sample_rate: u32,
+        path: Option<String>,
+        client_id: String,
     },
     SetAudioTheme {
         theme: String,
     },
     GetAudioTheme,
     TestAudioTheme,
+    /// Record a short clip from the input device and report its levels
+    TestAudioInput {
+        /// Play the clip back through the default output device afterwards
+        playback: bool,
+    },
     SetModel {
         model: STTModel,
     },
     GetModel,
     ListModels,
+    /// List every model with whether it is downloaded, its size on disk and whether it is loaded
+    ListModelsDetailed,
+    /// List the language codes the loaded model can transcribe
+    ListLanguages,
+    /// Remove a downloaded model from the cache; it must not be the loaded one
+    DeleteModel {
+        model: STTModel,
+    },
+    /// Free the loaded model's memory; the next transcription reloads it
+    UnloadModel,
     SetDevice {
         device: String, // "cpu" or "cuda"
     },
     GetDevice,
     GetConfig,
+    SetConfig {
+        patch: Value,
+    },
     CancelDownload,
     GetDownloadStatus,
     ListAudioThemes,
@@ -364,6 +1087,13 @@ pub enum Command {
         enabled: bool,
     },
     GetPreviewTyping,
+    PreviewUndo,
+    PreviewRedo,
+    Warmup,
+    /// Return the latest recording's audio, kept while `debug.keep_last_audio` is on
+    GetLastAudio {
+        format: LastAudioFormat,
+    },
 }
 
 impl Validate for DaemonRequest {
@@ -402,6 +1132,11 @@ impl Validate for DaemonRequest {
             "language",
             validation::limits::MAX_NAME_LENGTH,
         )?;
+        validation::validate_optional_string(
+            &self.format,
+            "format",
+            validation::limits::MAX_NAME_LENGTH,
+        )?;
 
         // Validate event types if present
         if let Some(ref event_types) = self.event_types {
@@ -433,38 +1168,62 @@ impl TryFrom<DaemonRequest> for Command {
     type Error = String;
 
     fn try_from(request: DaemonRequest) -> Result<Self, Self::Error> {
-        // Validate the request first
-        if let Err(e) = request.validate() {
-            return Err(format!("Request validation failed: {e}"));
-        }
         match request.command.as_str() {
             "transcribe" => cmd_transcribe(&request),
+            "transcribe_file" => cmd_transcribe_file(&request),
+            "transcribe_timestamped" => cmd_transcribe_timestamped(&request),
             "subscribe" => cmd_subscribe(&request),
             "unsubscribe" => Ok(Command::Unsubscribe),
             "get_events" => cmd_get_events(&request),
+            "get_history" => cmd_get_history(&request),
             "get_subscriber_info" => Ok(Command::GetSubscriberInfo),
             "notify" => cmd_notify(&request),
             "ping" => Ok(Command::Ping {
                 client_id: request.client_id.clone(),
             }),
             "status" => Ok(Command::Status),
-            "start_realtime" => Ok(cmd_start_realtime(&request)),
+            "health" => Ok(Command::Health),
+            "start_realtime" => cmd_start_realtime(&request),
             "realtime_audio" => cmd_realtime_audio(&request),
-            "record" => Ok(cmd_record(&request)),
+            "record" => cmd_record(&request),
+            "record_start" => cmd_record_start(&request),
+            "record_stop" => cmd_record_stop(&request),
+            "record_continuous" => cmd_record_continuous(&request),
+            "record_stream" => cmd_record_stream(&request),
+            "stop" => Ok(Command::Stop),
+            "cancel_recording" => Ok(Command::CancelRecording {
+                client_id: request.client_id.clone(),
+            }),
+            "list_audio_devices" => Ok(Command::ListAudioDevices),
+            "start_monitor" => Ok(Command::StartMonitor),
+            "stop_monitor" => Ok(Command::StopMonitor),
+            "detect_language" => cmd_detect_language(&request),
             "set_audio_theme" => cmd_set_audio_theme(&request),
             "get_audio_theme" => Ok(Command::GetAudioTheme),
             "test_audio_theme" => Ok(Command::TestAudioTheme),
+            "test_audio_input" => Ok(cmd_test_audio_input(&request)),
             "set_model" => cmd_set_model(&request),
             "get_model" => Ok(Command::GetModel),
             "list_models" => Ok(Command::ListModels),
+            "list_models_detailed" => Ok(Command::ListModelsDetailed),
+            "list_languages" => Ok(Command::ListLanguages),
+            "delete_model" => Ok(Command::DeleteModel {
+                model: request_model(&request)?,
+            }),
+            "unload_model" => Ok(Command::UnloadModel),
             "set_device" => cmd_set_device(&request),
             "get_device" => Ok(Command::GetDevice),
             "get_config" => Ok(Command::GetConfig),
+            "set_config" => cmd_set_config(&request),
             "cancel_download" => Ok(Command::CancelDownload),
             "get_download_status" => Ok(Command::GetDownloadStatus),
             "list_audio_themes" => Ok(Command::ListAudioThemes),
             "set_preview_typing" => cmd_set_preview_typing(&request),
             "get_preview_typing" => Ok(Command::GetPreviewTyping),
+            "preview_undo" => Ok(Command::PreviewUndo),
+            "preview_redo" => Ok(Command::PreviewRedo),
+            "warmup" => Ok(Command::Warmup),
+            "get_last_audio" => cmd_get_last_audio(&request),
             _ => Err(format!("Unknown command: {}", request.command)),
         }
     }
@@ -480,10 +1239,107 @@ fn cmd_transcribe(request: &DaemonRequest) -> Result<Command, String> {
         .client_id
         .clone()
         .unwrap_or_else(|| format!("client_{}", uuid::Uuid::new_v4()));
+    let language = parse_language(request)?;
+    let replacements = parse_replacements(request)?;
     Ok(Command::Transcribe {
         audio_data,
         sample_rate,
         client_id,
+        language,
+        replacements,
+        word_filter: parse_word_filter(request)?,
+        decoding: parse_decoding(request)?,
+        timings: data_flag(request, "timings"),
+    })
+}
+
+fn cmd_transcribe_timestamped(request: &DaemonRequest) -> Result<Command, String> {
+    let audio_data = request
+        .audio_data
+        .clone()
+        .ok_or("Missing audio_data for transcribe_timestamped command")?;
+    let sample_rate = request.sample_rate.unwrap_or(16000);
+    let client_id = request
+        .client_id
+        .clone()
+        .unwrap_or_else(|| format!("client_{}", uuid::Uuid::new_v4()));
+    let format = match request.format.as_deref() {
+        Some(format) => TranscriptFormat::from_str(format)?,
+        None => TranscriptFormat::default(),
+    };
+    let language = parse_language(request)?;
+    let replacements = parse_replacements(request)?;
+    Ok(Command::TranscribeTimestamped {
+        audio_data,
+        sample_rate,
+        client_id,
+        format,
+        language,
+        replacements,
+        word_filter: parse_word_filter(request)?,
+    })
+}
+
+fn cmd_transcribe_file(request: &DaemonRequest) -> Result<Command, String> {
+    let path = request
+        .data
+        .as_ref()
+        .and_then(|data| data.get("path"))
+        .and_then(|v| v.as_str())
+        .ok_or("Missing path for transcribe_file command")?
+        .to_string();
+
+    if let Err(e) =
+        validation::validate_string(&path, "path", validation::limits::MAX_STRING_LENGTH)
+    {
+        return Err(e.to_string());
+    }
+
+    let client_id = request
+        .client_id
+        .clone()
+        .unwrap_or_else(|| format!("client_{}", uuid::Uuid::new_v4()));
+    let language = parse_language(request)?;
+    let replacements = parse_replacements(request)?;
+    Ok(Command::TranscribeFile {
+        path,
+        client_id,
+        language,
+        replacements,
+        word_filter: parse_word_filter(request)?,
+        decoding: parse_decoding(request)?,
+    })
+}
+
+fn cmd_detect_language(request: &DaemonRequest) -> Result<Command, String> {
+    let path = request
+        .data
+        .as_ref()
+        .and_then(|data| data.get("path"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+
+    match (&request.audio_data, &path) {
+        (Some(_), Some(_)) => {
+            return Err("detect_language accepts either audio_data or a path, not both".into());
+        }
+        (None, None) => return Err("Missing audio_data or path for detect_language command".into()),
+        (None, Some(path)) => {
+            validation::validate_string(path, "path", validation::limits::MAX_STRING_LENGTH)
+                .map_err(|e| e.to_string())?;
+        }
+        (Some(_), None) => {}
+    }
+
+    let client_id = request
+        .client_id
+        .clone()
+        .unwrap_or_else(|| format!("client_{}", uuid::Uuid::new_v4()));
+    Ok(Command::DetectLanguage {
+        audio_data: request.audio_data.clone(),
+        sample_rate: request.sample_rate.unwrap_or(16000),
+        path,
+        client_id,
     })
 }
 
@@ -493,6 +1349,17 @@ fn cmd_subscribe(request: &DaemonRequest) -> Result<Command, String> {
         .clone()
         .ok_or("Missing event_types for subscribe command")?;
     let client_info = request.client_info.clone().unwrap_or_default();
+    // Checked here so a bad timestamp fails the subscription instead of its replay
+    if let Some(since) = &request.since_timestamp
+        && let Err(e) = chrono::DateTime::parse_from_rfc3339(since)
+    {
+        return Err(format!("Invalid since_timestamp '{since}': {e}"));
+    }
+    if let Some(limit) = request.limit
+        && let Err(e) = validation::validate_limit(limit)
+    {
+        return Err(e.to_string());
+    }
     Ok(Command::Subscribe {
         event_types,
         client_info,
@@ -511,6 +1378,25 @@ fn cmd_get_events(request: &DaemonRequest) -> Result<Command, String> {
     })
 }
 
+fn cmd_get_last_audio(request: &DaemonRequest) -> Result<Command, String> {
+    let format = match request.format.as_deref() {
+        Some(format) => LastAudioFormat::from_str(format)?,
+        None => LastAudioFormat::default(),
+    };
+    Ok(Command::GetLastAudio { format })
+}
+
+fn cmd_get_history(request: &DaemonRequest) -> Result<Command, String> {
+    let limit = request.limit.unwrap_or(100);
+    if let Err(e) = validation::validate_limit(limit) {
+        return Err(e.to_string());
+    }
+    Ok(Command::GetHistory {
+        since_timestamp: request.since_timestamp.clone(),
+        limit,
+    })
+}
+
 fn cmd_notify(request: &DaemonRequest) -> Result<Command, String> {
     let event_type = request
         .event_type
@@ -531,16 +1417,16 @@ fn cmd_notify(request: &DaemonRequest) -> Result<Command, String> {
     })
 }
 
-fn cmd_start_realtime(request: &DaemonRequest) -> Command {
+fn cmd_start_realtime(request: &DaemonRequest) -> Result<Command, String> {
     let client_id = request
         .client_id
         .clone()
         .unwrap_or_else(|| format!("realtime_{}", uuid::Uuid::new_v4()));
-    Command::StartRealTimeTranscription {
+    Ok(Command::StartRealTimeTranscription {
         client_id,
         sample_rate: request.sample_rate,
-        language: request.language.clone(),
-    }
+        language: parse_language(request)?,
+    })
 }
 
 fn cmd_realtime_audio(request: &DaemonRequest) -> Result<Command, String> {
@@ -560,14 +1446,212 @@ fn cmd_realtime_audio(request: &DaemonRequest) -> Result<Command, String> {
     })
 }
 
-fn cmd_record(request: &DaemonRequest) -> Command {
+fn cmd_record(request: &DaemonRequest) -> Result<Command, String> {
+    let (write_mode, silence, device_name) = parse_record_options(request)?;
+    let language = parse_language(request)?;
+    let replacements = parse_replacements(request)?;
+    let output = parse_output(request)?;
+    Ok(Command::Record {
+        client_id: request.client_id.clone(),
+        write_mode,
+        silence,
+        language,
+        device_name,
+        replacements,
+        word_filter: parse_word_filter(request)?,
+        output,
+        queue: data_flag(request, "queue"),
+        dry_run: data_flag(request, "dry_run"),
+        timings: data_flag(request, "timings"),
+    })
+}
+
+/// A boolean option in the request data, false when absent
+fn data_flag(request: &DaemonRequest, key: &str) -> bool {
+    request
+        .data
+        .as_ref()
+        .and_then(|data| data.get(key))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn cmd_record_start(request: &DaemonRequest) -> Result<Command, String> {
+    let client_id = request
+        .client_id
+        .clone()
+        .ok_or("Missing client_id for record_start command")?;
+    let (write_mode, silence, device_name) = parse_record_options(request)?;
+    Ok(Command::RecordStart {
+        client_id,
+        write_mode,
+        silence,
+        language: parse_language(request)?,
+        device_name,
+        replacements: parse_replacements(request)?,
+        word_filter: parse_word_filter(request)?,
+        output: parse_output(request)?,
+    })
+}
+
+fn cmd_record_stop(request: &DaemonRequest) -> Result<Command, String> {
+    let client_id = request
+        .client_id
+        .clone()
+        .ok_or("Missing client_id for record_stop command")?;
+    Ok(Command::RecordStop { client_id })
+}
+
+fn cmd_record_continuous(request: &DaemonRequest) -> Result<Command, String> {
+    let (write_mode, silence, device_name) = parse_record_options(request)?;
+    let language = parse_language(request)?;
+    let replacements = parse_replacements(request)?;
+    Ok(Command::RecordContinuous {
+        client_id: request.client_id.clone(),
+        write_mode,
+        silence,
+        language,
+        device_name,
+        replacements,
+        word_filter: parse_word_filter(request)?,
+    })
+}
+
+fn cmd_record_stream(request: &DaemonRequest) -> Result<Command, String> {
+    let (write_mode, silence, device_name) = parse_record_options(request)?;
+    let language = parse_language(request)?;
+    let replacements = parse_replacements(request)?;
+    Ok(Command::RecordStream {
+        write_mode,
+        silence,
+        language,
+        device_name,
+        replacements,
+        word_filter: parse_word_filter(request)?,
+    })
+}
+
+/// Read the optional output mode from `data.output`
+fn parse_output(request: &DaemonRequest) -> Result<Option<OutputMode>, String> {
+    match request.data.as_ref().and_then(|data| data.get("output")) {
+        Some(Value::String(output)) => OutputMode::from_str(output).map(Some),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => Err("Invalid output: expected a string".to_string()),
+    }
+}
+
+/// Read optional per-request text replacement rules from `data.replacements`
+fn parse_replacements(request: &DaemonRequest) -> Result<Option<Vec<(String, String)>>, String> {
+    let Some(value) = request
+        .data
+        .as_ref()
+        .and_then(|data| data.get("replacements"))
+    else {
+        return Ok(None);
+    };
+
+    let replacements: Vec<(String, String)> =
+        serde_json::from_value(value.clone()).map_err(|e| format!("Invalid replacements: {e}"))?;
+    validation::validate_replacements(&replacements).map_err(|e| e.to_string())?;
+    Ok(Some(replacements))
+}
+
+/// Read optional per-request word filter settings from `data.word_filter`
+fn parse_word_filter(request: &DaemonRequest) -> Result<Option<WordFilterOverride>, String> {
+    let Some(value) = request
+        .data
+        .as_ref()
+        .and_then(|data| data.get("word_filter"))
+    else {
+        return Ok(None);
+    };
+
+    let word_filter: WordFilterOverride =
+        serde_json::from_value(value.clone()).map_err(|e| format!("Invalid word filter: {e}"))?;
+    if let Some(words) = &word_filter.words {
+        validation::validate_filtered_words(words).map_err(|e| e.to_string())?;
+    }
+    Ok(Some(word_filter))
+}
+
+/// Read optional per-request decoding settings from `data.decoding`
+fn parse_decoding(request: &DaemonRequest) -> Result<Option<DecodingOverride>, String> {
+    let Some(value) = request.data.as_ref().and_then(|data| data.get("decoding")) else {
+        return Ok(None);
+    };
+
+    let decoding: DecodingOverride = serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid decoding settings: {e}"))?;
+    validation::validate_decoding(
+        decoding.max_new_tokens,
+        decoding.temperature,
+        decoding.top_p,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(Some(decoding))
+}
+
+/// Read the optional transcription language, rejecting unknown codes
+fn parse_language(request: &DaemonRequest) -> Result<Option<String>, String> {
+    match request.language.as_deref() {
+        Some(language) => {
+            validation::validate_language(language).map_err(|e| e.to_string())?;
+            Ok(Some(language.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Read the `write_mode` flag, optional silence overrides and capture device shared by
+/// the record commands
+fn parse_record_options(
+    request: &DaemonRequest,
+) -> Result<(bool, Option<SilenceOverride>, Option<String>), String> {
     let write_mode = request
         .data
         .as_ref()
         .and_then(|data| data.get("write_mode"))
         .and_then(serde_json::Value::as_bool)
         .unwrap_or(false);
-    Command::Record { write_mode }
+
+    let silence = match request.data.as_ref().and_then(|data| data.get("silence")) {
+        Some(value) => {
+            let silence: SilenceOverride = serde_json::from_value(value.clone())
+                .map_err(|e| format!("Invalid silence settings: {e}"))?;
+            if let Some(threshold) = silence.threshold
+                && !(threshold > 0.0 && threshold <= 1.0)
+            {
+                return Err(format!(
+                    "Invalid silence threshold: {threshold} (expected 0.0 < threshold <= 1.0)"
+                ));
+            }
+            if silence.min_silence_ms == Some(0) {
+                return Err("min_silence_ms must be greater than 0".to_string());
+            }
+            if silence.max_duration_s == Some(0) {
+                return Err("max_duration_s must be greater than 0".to_string());
+            }
+            Some(silence)
+        }
+        None => None,
+    };
+
+    let device_name = match request
+        .data
+        .as_ref()
+        .and_then(|data| data.get("device_name"))
+    {
+        Some(value) => {
+            let name = value.as_str().ok_or("device_name must be a string")?.trim();
+            if name.is_empty() {
+                return Err("device_name must not be empty".to_string());
+            }
+            Some(name.to_string())
+        }
+        None => None,
+    };
+
+    Ok((write_mode, silence, device_name))
 }
 
 fn cmd_set_audio_theme(request: &DaemonRequest) -> Result<Command, String> {
@@ -589,13 +1673,16 @@ fn cmd_set_audio_theme(request: &DaemonRequest) -> Result<Command, String> {
 }
 
 fn cmd_set_model(request: &DaemonRequest) -> Result<Command, String> {
+    Ok(Command::SetModel {
+        model: request_model(request)?,
+    })
+}
+
+fn request_model(request: &DaemonRequest) -> Result<STTModel, String> {
     let model_value = request.data.as_ref().and_then(|data| data.get("model"));
     let model_str = model_value.and_then(|v| v.as_str());
     if let Some(model_str) = model_str {
-        match STTModel::from_str(model_str) {
-            Ok(model) => Ok(Command::SetModel { model }),
-            Err(err) => Err(format!("Failed to parse model: {err}")),
-        }
+        STTModel::from_str(model_str).map_err(|err| format!("Failed to parse model: {err}"))
     } else {
         Err("Model string is empty".to_string())
     }
@@ -619,6 +1706,28 @@ fn cmd_set_device(request: &DaemonRequest) -> Result<Command, String> {
     Ok(Command::SetDevice { device })
 }
 
+fn cmd_test_audio_input(request: &DaemonRequest) -> Command {
+    let playback = request
+        .data
+        .as_ref()
+        .and_then(|data| data.get("playback"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    Command::TestAudioInput { playback }
+}
+
+fn cmd_set_config(request: &DaemonRequest) -> Result<Command, String> {
+    let patch = request
+        .data
+        .clone()
+        .ok_or("Missing data for set_config command")?;
+    if !patch.is_object() {
+        return Err("set_config data must be an object".to_string());
+    }
+
+    Ok(Command::SetConfig { patch })
+}
+
 fn cmd_set_preview_typing(request: &DaemonRequest) -> Result<Command, String> {
     let enabled = request
         .enabled
@@ -626,3 +1735,189 @@ fn cmd_set_preview_typing(request: &DaemonRequest) -> Result<Command, String> {
 
     Ok(Command::SetPreviewTyping { enabled })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::limits;
+    use serde_json::json;
+
+    fn request(command: &str) -> DaemonRequest {
+        DaemonRequest {
+            client_id: Some("test_client".to_string()),
+            ..DaemonRequest::new(command)
+        }
+    }
+
+    fn rejected_field(request: &DaemonRequest) -> Option<String> {
+        let error = request.validate().expect_err("request should be rejected");
+        DaemonResponse::invalid_request(&error).invalid_field
+    }
+
+    #[test]
+    fn test_valid_request_passes() {
+        let mut valid = request("transcribe");
+        valid.audio_data = Some(vec![0.0, 0.1, -0.1]);
+        valid.sample_rate = Some(16000);
+        valid.language = Some("en".to_string());
+        valid.event_types = Some(vec!["recording_state_changed".to_string()]);
+        valid.limit = Some(10);
+        valid.data = Some(json!({ "write_mode": true }));
+        assert!(valid.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_invalid_command() {
+        assert_eq!(
+            rejected_field(&request("record; rm -rf")),
+            Some("command".to_string())
+        );
+        assert_eq!(
+            rejected_field(&request(&"a".repeat(limits::MAX_NAME_LENGTH + 1))),
+            Some("command".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_strings() {
+        let mut long_client = request("ping");
+        long_client.client_id = Some("c".repeat(limits::MAX_STRING_LENGTH + 1));
+        assert_eq!(rejected_field(&long_client), Some("client_id".to_string()));
+
+        let mut control_language = request("transcribe");
+        control_language.language = Some("en\u{0}".to_string());
+        assert_eq!(
+            rejected_field(&control_language),
+            Some("language".to_string())
+        );
+
+        let mut long_format = request("transcribe_timestamped");
+        long_format.format = Some("f".repeat(limits::MAX_NAME_LENGTH + 1));
+        assert_eq!(rejected_field(&long_format), Some("format".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_audio() {
+        let mut bad_rate = request("transcribe");
+        bad_rate.sample_rate = Some(limits::MAX_SAMPLE_RATE + 1);
+        assert_eq!(rejected_field(&bad_rate), Some("sample_rate".to_string()));
+
+        let mut too_long = request("transcribe");
+        too_long.audio_data = Some(vec![0.0; limits::MAX_AUDIO_SAMPLES + 1]);
+        assert_eq!(rejected_field(&too_long), Some("audio_data".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_limit() {
+        let mut zero = request("get_events");
+        zero.limit = Some(0);
+        assert_eq!(rejected_field(&zero), Some("limit".to_string()));
+
+        let mut too_many = request("get_events");
+        too_many.limit = Some(limits::MAX_EVENTS_LIMIT + 1);
+        assert_eq!(rejected_field(&too_many), Some("limit".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_event_types() {
+        let mut too_many = request("subscribe");
+        too_many.event_types = Some(vec!["event".to_string(); limits::MAX_EVENT_TYPES + 1]);
+        assert_eq!(rejected_field(&too_many), Some("event_types".to_string()));
+
+        let mut bad_name = request("subscribe");
+        bad_name.event_types = Some(vec!["bad\u{7}event".to_string()]);
+        assert_eq!(rejected_field(&bad_name), Some("event_type".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_json() {
+        let mut deep = json!("leaf");
+        for _ in 0..=limits::MAX_JSON_DEPTH {
+            deep = json!({ "nested": deep });
+        }
+        let mut deep_data = request("set_config");
+        deep_data.data = Some(deep.clone());
+        assert!(matches!(
+            deep_data.validate(),
+            Err(ValidationError::JsonTooDeep { .. })
+        ));
+
+        let mut deep_info = request("subscribe");
+        deep_info.client_info = Some(HashMap::from([("info".to_string(), deep)]));
+        assert!(matches!(
+            deep_info.validate(),
+            Err(ValidationError::JsonTooDeep { .. })
+        ));
+
+        let mut large_data = request("set_config");
+        large_data.data = Some(json!("x".repeat(limits::MAX_JSON_SIZE)));
+        assert!(matches!(
+            large_data.validate(),
+            Err(ValidationError::JsonTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_request_keeps_field_when_sanitized() {
+        let error = ValidationError::InvalidLimit {
+            limit: 0,
+            max: limits::MAX_EVENTS_LIMIT,
+        };
+        let response = DaemonResponse::invalid_request(&error);
+        assert_eq!(response.status, "error");
+        assert_eq!(response.invalid_field.as_deref(), Some("limit"));
+        assert!(
+            response
+                .message
+                .is_some_and(|m| m.starts_with("Request validation failed"))
+        );
+    }
+
+    #[test]
+    fn test_get_last_audio_format() {
+        let mut last_audio = request("get_last_audio");
+        assert!(matches!(
+            Command::try_from(last_audio.clone()),
+            Ok(Command::GetLastAudio {
+                format: LastAudioFormat::Samples
+            })
+        ));
+
+        last_audio.format = Some("wav".to_string());
+        assert!(matches!(
+            Command::try_from(last_audio.clone()),
+            Ok(Command::GetLastAudio {
+                format: LastAudioFormat::Wav
+            })
+        ));
+
+        last_audio.format = Some("mp3".to_string());
+        assert!(Command::try_from(last_audio).is_err());
+    }
+
+    #[test]
+    fn test_result_sits_next_to_legacy_transcription() {
+        let output = TranscriptionOutput {
+            text: "Hello world.".to_string(),
+            language: None,
+            confidence: 0.9,
+            duration_ms: 1500,
+            model: "whisper-base".to_string(),
+            device: "cpu".to_string(),
+            segments: Vec::new(),
+        };
+        let response = DaemonResponse::success()
+            .with_transcription(output.text.clone())
+            .with_result(output.clone());
+
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["transcription"], "Hello world.");
+        assert_eq!(value["result"]["duration_ms"], 1500);
+        // Unknown language and missing segments are left out
+        assert!(value["result"].get("language").is_none());
+        assert!(value["result"].get("segments").is_none());
+
+        let parsed: DaemonResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.result, Some(output));
+    }
+}
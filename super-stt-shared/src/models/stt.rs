@@ -2,6 +2,8 @@
 #[derive(Debug, Clone)]
 pub struct STTData {
     pub text: String,
+    /// Geometric mean of the token probabilities, in `0.0..=1.0`; 0.0 when the
+    /// model cannot score its output
     pub confidence: f32,
 }
 
@@ -28,6 +28,7 @@ pub enum AudioTheme {
     Nature,  // Natural, organic sounds
     Retro,   // 8-bit style sounds
     Silent,  // No audio feedback
+    Custom,  // User-supplied WAV files, with Classic tones for any left unset
 }
 
 impl std::fmt::Display for AudioTheme {
@@ -41,6 +42,7 @@ impl std::fmt::Display for AudioTheme {
             AudioTheme::Nature => write!(f, "nature"),
             AudioTheme::Retro => write!(f, "retro"),
             AudioTheme::Silent => write!(f, "silent"),
+            AudioTheme::Custom => write!(f, "custom"),
         }
     }
 }
@@ -59,6 +61,7 @@ impl AudioTheme {
             AudioTheme::Nature => "Nature".to_string(),
             AudioTheme::Retro => "Retro".to_string(),
             AudioTheme::Silent => "Silent".to_string(),
+            AudioTheme::Custom => "Custom".to_string(),
         }
     }
 
@@ -73,7 +76,7 @@ impl AudioTheme {
     pub fn start_sound(&self) -> (Vec<f32>, u64, u64, u64) {
         // Returns (frequencies, duration_ms, fade_in_ms, fade_out_ms)
         match self {
-            AudioTheme::Classic => (vec![440.0, 554.0, 659.0], 150, 5, 15),
+            AudioTheme::Classic | AudioTheme::Custom => (vec![440.0, 554.0, 659.0], 150, 5, 15),
             AudioTheme::Gentle => (vec![261.6, 329.6, 392.0], 200, 10, 15),
             AudioTheme::Minimal => (vec![262.0], 100, 5, 25),
             AudioTheme::SciFi => (vec![400.0, 600.0, 900.0, 1100.0], 160, 15, 40),
@@ -89,7 +92,7 @@ impl AudioTheme {
     pub fn end_sound(&self) -> (Vec<f32>, u64, u64, u64) {
         // Returns (frequencies, duration_ms, fade_in_ms, fade_out_ms)
         match self {
-            AudioTheme::Classic => (vec![659.0, 554.0, 441.0], 150, 5, 15),
+            AudioTheme::Classic | AudioTheme::Custom => (vec![659.0, 554.0, 441.0], 150, 5, 15),
             AudioTheme::Gentle => (vec![392.0, 329.6, 261.6], 200, 10, 15),
             AudioTheme::Minimal => (vec![523.0], 100, 5, 25),
             AudioTheme::SciFi => (vec![1100.0, 900.0, 600.0, 400.0], 160, 40, 15),
@@ -114,6 +117,7 @@ impl std::str::FromStr for AudioTheme {
             "nature" => AudioTheme::Nature,
             "retro" => AudioTheme::Retro,
             "silent" => AudioTheme::Silent,
+            "custom" => AudioTheme::Custom,
             _ => AudioTheme::default(),
         };
         Ok(theme)
@@ -134,6 +138,7 @@ mod tests {
         assert_eq!(AudioTheme::Nature.to_string(), "nature");
         assert_eq!(AudioTheme::Retro.to_string(), "retro");
         assert_eq!(AudioTheme::Silent.to_string(), "silent");
+        assert_eq!(AudioTheme::Custom.to_string(), "custom");
     }
 
     #[test]
@@ -160,7 +165,7 @@ mod tests {
     #[test]
     fn test_all_themes_count() {
         let themes = AudioTheme::all_themes();
-        assert_eq!(themes.len(), 8);
+        assert_eq!(themes.len(), 9);
         assert!(themes.contains(&AudioTheme::Classic));
         assert!(themes.contains(&AudioTheme::Silent));
     }
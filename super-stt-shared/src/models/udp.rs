@@ -7,6 +7,7 @@ pub const FINAL_STT_PACKET: u8 = 3;
 pub const AUDIO_SAMPLES_PACKET: u8 = 4;
 pub const RECORDING_STATE_PACKET: u8 = 5;
 pub const FREQUENCY_BANDS_PACKET: u8 = 6;
+pub const SECRET_ROTATED_PACKET: u8 = 7; // Header only; clients should re-register
 
 // Maximum packet size for UDP
 pub const MAX_PACKET_SIZE: usize = 1400;
@@ -1,4 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
+pub mod udp_addr;
 pub mod udp_parsing;
 
+pub use udp_addr::*;
 pub use udp_parsing::*;
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Default port of the daemon's UDP audio streamer
+pub const DEFAULT_UDP_PORT: u16 = 8765;
+
+/// Environment variable clients read to find a daemon streaming on another address
+pub const UDP_ADDR_ENV: &str = "SUPER_STT_UDP_ADDR";
+
+/// Address clients should send UDP registrations and pings to.
+///
+/// Defaults to `127.0.0.1:8765`. Setting `SUPER_STT_UDP_ADDR` to an IPv4 or IPv6
+/// socket address (e.g. `[::1]:8765`) points clients at a daemon bound elsewhere.
+#[must_use]
+pub fn daemon_udp_addr() -> SocketAddr {
+    let default = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), DEFAULT_UDP_PORT);
+    match std::env::var(UDP_ADDR_ENV) {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            log::warn!("Ignoring invalid {UDP_ADDR_ENV} value '{value}', using {default}");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// Address to reach the daemon at, from the one it reports in `get_config`.
+///
+/// The reported address is used as it is, except that a daemon bound to every
+/// interface is reached over loopback. Daemons that predate the field leave it
+/// out, which falls back to [`daemon_udp_addr`].
+#[must_use]
+pub fn reported_daemon_udp_addr(reported: Option<SocketAddr>) -> SocketAddr {
+    match reported {
+        Some(addr) if addr.ip().is_unspecified() => {
+            let ip = match addr.ip() {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            };
+            SocketAddr::new(ip, addr.port())
+        }
+        Some(addr) => addr,
+        None => daemon_udp_addr(),
+    }
+}
+
+/// Local address for a client socket talking to `daemon_addr`.
+///
+/// Picks an ephemeral port in the same address family, staying on loopback when
+/// the daemon is on loopback.
+#[must_use]
+pub fn client_bind_addr(daemon_addr: SocketAddr) -> SocketAddr {
+    let ip = match daemon_addr.ip() {
+        IpAddr::V4(ip) if ip.is_loopback() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(ip) if ip.is_loopback() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    SocketAddr::new(ip, 0)
+}
+
+/// Whether a packet from `source` can have come from the daemon at `daemon_addr`.
+///
+/// Accepts the daemon's own address and any loopback address of the same family,
+/// since a daemon bound to a loopback alias may answer from a different one.
+#[must_use]
+pub fn is_daemon_source(source: SocketAddr, daemon_addr: SocketAddr) -> bool {
+    let (source, daemon) = (source.ip(), daemon_addr.ip());
+    source == daemon
+        || (source.is_loopback() && daemon.is_loopback() && source.is_ipv4() == daemon.is_ipv4())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_bind_addr_matches_family() {
+        let v4: SocketAddr = "127.0.0.1:8765".parse().unwrap();
+        let v6: SocketAddr = "[::1]:8765".parse().unwrap();
+
+        assert_eq!(client_bind_addr(v4), "127.0.0.1:0".parse().unwrap());
+        assert_eq!(client_bind_addr(v6), "[::1]:0".parse().unwrap());
+    }
+
+    #[test]
+    fn test_reported_daemon_udp_addr() {
+        let v6: SocketAddr = "[::1]:8799".parse().unwrap();
+        assert_eq!(reported_daemon_udp_addr(Some(v6)), v6);
+        let lan: SocketAddr = "192.168.1.10:8765".parse().unwrap();
+        assert_eq!(reported_daemon_udp_addr(Some(lan)), lan);
+        assert_eq!(
+            reported_daemon_udp_addr(Some("0.0.0.0:8799".parse().unwrap())),
+            "127.0.0.1:8799".parse().unwrap()
+        );
+        assert_eq!(
+            reported_daemon_udp_addr(Some("[::]:8799".parse().unwrap())),
+            "[::1]:8799".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_daemon_source() {
+        let v4: SocketAddr = "127.0.0.1:8765".parse().unwrap();
+        let v6: SocketAddr = "[::1]:8765".parse().unwrap();
+
+        assert!(is_daemon_source("127.0.0.2:8765".parse().unwrap(), v4));
+        assert!(is_daemon_source("[::1]:8765".parse().unwrap(), v6));
+        assert!(!is_daemon_source("[::1]:8765".parse().unwrap(), v4));
+        assert!(!is_daemon_source("192.168.1.10:8765".parse().unwrap(), v4));
+    }
+}
@@ -109,7 +109,8 @@ pub fn parse_audio_samples_from_udp(data: &[u8]) -> Result<AudioSamplesData, Str
 /// Returns an error if the buffer is too short or the packet type is wrong.
 pub fn parse_recording_state_from_udp(data: &[u8]) -> Result<RecordingStateData, String> {
     // Parse UDP packet following daemon's protocol
-    // Packet structure: Header (11 bytes) + RecordingStateData (9 bytes) = 20 bytes
+    // Packet structure: Header (11 bytes) + RecordingStateData (9 bytes) = 20 bytes,
+    // followed by the input sample rate (4 bytes) from daemons that send it
     if data.len() < 20 {
         return Err("Packet too short".to_string());
     }
@@ -138,10 +139,14 @@ pub fn parse_recording_state_from_udp(data: &[u8]) -> Result<RecordingStateData,
         state_data[8],
     ];
     let timestamp_ms = u64::from_le_bytes(timestamp_bytes);
+    let input_sample_rate = state_data.get(9..13).map_or(0, |bytes| {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    });
 
     Ok(RecordingStateData {
         is_recording,
         timestamp_ms,
+        input_sample_rate,
     })
 }
 
@@ -293,4 +298,20 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_parse_recording_state_with_and_without_sample_rate() {
+        let mut packet = vec![RECORDING_STATE_PACKET];
+        packet.extend_from_slice(&[0u8; 10]);
+        packet.extend_from_slice(&RecordingStateData::new(true, 48000).to_bytes());
+
+        let state = parse_recording_state_from_udp(&packet).unwrap();
+        assert!(state.is_recording);
+        assert_eq!(state.input_sample_rate, 48000);
+
+        // Daemons predating the rate send only the first 9 bytes of state
+        let state = parse_recording_state_from_udp(&packet[..20]).unwrap();
+        assert!(state.is_recording);
+        assert_eq!(state.input_sample_rate, 0);
+    }
 }
@@ -17,6 +17,10 @@ use tokio::sync::RwLock;
 pub struct ResourceLimits {
     /// Maximum number of concurrent connections
     pub max_connections: usize,
+    /// Maximum number of concurrent connections from one client
+    pub max_connections_per_client: usize,
+    /// Maximum requests per client per second
+    pub max_requests_per_second: u32,
     /// Maximum requests per client per minute
     pub max_requests_per_minute: u32,
     /// Maximum requests per client per hour
@@ -31,6 +35,8 @@ impl Default for ResourceLimits {
     fn default() -> Self {
         Self {
             max_connections: 100,            // Reasonable for a desktop daemon
+            max_connections_per_client: 16,  // Subscriptions plus concurrent requests
+            max_requests_per_second: 20,     // Bursts such as a client starting up
             max_requests_per_minute: 120,    // 2 requests per second
             max_requests_per_hour: 3600,     // 1 request per second average
             connection_timeout_seconds: 300, // 5 minutes
@@ -45,6 +51,8 @@ impl ResourceLimits {
     pub fn development() -> Self {
         Self {
             max_connections: 50,
+            max_connections_per_client: 16,
+            max_requests_per_second: 20,
             max_requests_per_minute: 300, // More lenient for development
             max_requests_per_hour: 7200,
             connection_timeout_seconds: 600, // 10 minutes
@@ -57,6 +65,8 @@ impl ResourceLimits {
     pub fn production() -> Self {
         Self {
             max_connections: 20,             // More restrictive for production
+            max_connections_per_client: 8,   // Leaves room for other clients
+            max_requests_per_second: 10,     // Bursts such as a client starting up
             max_requests_per_minute: 60,     // 1 request per second
             max_requests_per_hour: 1800,     // 0.5 requests per second average
             connection_timeout_seconds: 180, // 3 minutes
@@ -71,6 +81,13 @@ pub enum ResourceError {
     #[error("Connection limit exceeded: {current}/{max} connections")]
     ConnectionLimitExceeded { current: usize, max: usize },
 
+    #[error("Connection limit exceeded for {client}: {current}/{max} connections")]
+    ClientConnectionLimitExceeded {
+        client: String,
+        current: usize,
+        max: usize,
+    },
+
     #[error("Rate limit exceeded: {requests} requests in {window}s (max: {limit})")]
     RateLimitExceeded {
         requests: u32,
@@ -133,6 +150,32 @@ impl RequestHistory {
             .count();
         u32::try_from(count).unwrap_or(u32::MAX)
     }
+
+    /// Add a request and check it against every rate limit
+    fn add_request_and_check_limits(
+        &mut self,
+        limits: &ResourceLimits,
+    ) -> Result<(), ResourceError> {
+        let now = Utc::now();
+        self.add_request(now, limits.rate_limit_window_seconds);
+
+        for (window, limit) in [
+            (1, limits.max_requests_per_second),
+            (60, limits.max_requests_per_minute),
+            (3600, limits.max_requests_per_hour),
+        ] {
+            let requests = self.count_requests_in_window(now, window);
+            if requests > limit {
+                return Err(ResourceError::RateLimitExceeded {
+                    requests,
+                    window,
+                    limit,
+                });
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Connection information for resource tracking
@@ -142,10 +185,10 @@ pub struct ConnectionInfo {
     pub connected_at: DateTime<Utc>,
     /// Last activity timestamp
     pub last_activity: DateTime<Utc>,
-    /// Request history for rate limiting
-    request_history: RequestHistory,
     /// Connection identifier (client ID or generated)
     pub client_id: String,
+    /// Client the connection belongs to, sharing its limits with the client's other connections
+    pub client_key: String,
     /// Optional client address for logging
     pub client_addr: Option<SocketAddr>,
 }
@@ -153,13 +196,13 @@ pub struct ConnectionInfo {
 impl ConnectionInfo {
     /// Create a new connection info
     #[must_use]
-    pub fn new(client_id: String, client_addr: Option<SocketAddr>) -> Self {
+    pub fn new(client_id: String, client_key: String, client_addr: Option<SocketAddr>) -> Self {
         let now = Utc::now();
         Self {
             connected_at: now,
             last_activity: now,
-            request_history: RequestHistory::new(),
             client_id,
+            client_key,
             client_addr,
         }
     }
@@ -176,43 +219,6 @@ impl ConnectionInfo {
         let timeout_duration = Duration::seconds(secs);
         Utc::now().signed_duration_since(self.last_activity) > timeout_duration
     }
-
-    /// Add a request and check rate limits
-    ///
-    /// # Errors
-    /// Returns an error if the rate limit is exceeded.
-    pub fn add_request_and_check_limits(
-        &mut self,
-        limits: &ResourceLimits,
-    ) -> Result<(), ResourceError> {
-        let now = Utc::now();
-
-        // Add the request to history
-        self.request_history
-            .add_request(now, limits.rate_limit_window_seconds);
-        self.last_activity = now;
-
-        // Check rate limits
-        let requests_per_minute = self.request_history.count_requests_in_window(now, 60);
-        if requests_per_minute > limits.max_requests_per_minute {
-            return Err(ResourceError::RateLimitExceeded {
-                requests: requests_per_minute,
-                window: 60,
-                limit: limits.max_requests_per_minute,
-            });
-        }
-
-        let requests_per_hour = self.request_history.count_requests_in_window(now, 3600);
-        if requests_per_hour > limits.max_requests_per_hour {
-            return Err(ResourceError::RateLimitExceeded {
-                requests: requests_per_hour,
-                window: 3600,
-                limit: limits.max_requests_per_hour,
-            });
-        }
-
-        Ok(())
-    }
 }
 
 /// Resource manager for tracking connections and enforcing limits
@@ -222,6 +228,8 @@ pub struct ResourceManager {
     limits: ResourceLimits,
     /// Active connections
     connections: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
+    /// Request history of each client, kept across its connections
+    clients: Arc<RwLock<HashMap<String, RequestHistory>>>,
     /// Background cleanup task handle
     cleanup_handle: Option<tokio::task::JoinHandle<()>>,
 }
@@ -237,17 +245,20 @@ impl ResourceManager {
     #[must_use]
     pub fn with_limits(limits: ResourceLimits) -> Self {
         let connections = Arc::new(RwLock::new(HashMap::new()));
+        let clients = Arc::new(RwLock::new(HashMap::new()));
 
         // Start background cleanup task
         let cleanup_connections = Arc::clone(&connections);
+        let cleanup_clients = Arc::clone(&clients);
         let cleanup_limits = limits.clone();
         let cleanup_handle = tokio::spawn(async move {
-            Self::cleanup_task(cleanup_connections, cleanup_limits).await;
+            Self::cleanup_task(cleanup_connections, cleanup_clients, cleanup_limits).await;
         });
 
         Self {
             limits,
             connections,
+            clients,
             cleanup_handle: Some(cleanup_handle),
         }
     }
@@ -264,7 +275,7 @@ impl ResourceManager {
         Self::with_limits(ResourceLimits::production())
     }
 
-    /// Register a new connection and check connection limits
+    /// Register a new connection as a client of its own and check connection limits
     ///
     /// # Errors
     /// Returns an error if the connection limit is exceeded.
@@ -272,20 +283,48 @@ impl ResourceManager {
         &self,
         client_id: String,
         client_addr: Option<SocketAddr>,
+    ) -> Result<(), ResourceError> {
+        let client_key = client_id.clone();
+        self.register_client_connection(client_id, client_key, client_addr)
+            .await
+    }
+
+    /// Register a new connection of the client `client_key` and check connection limits
+    ///
+    /// Requests on every connection of a client count toward the same rate limits.
+    ///
+    /// # Errors
+    /// Returns an error if the total or the client's connection limit is exceeded.
+    pub async fn register_client_connection(
+        &self,
+        client_id: String,
+        client_key: String,
+        client_addr: Option<SocketAddr>,
     ) -> Result<(), ResourceError> {
         let mut connections = self.connections.write().await;
 
-        // Check connection limit
+        // Check connection limits
         if connections.len() >= self.limits.max_connections {
             return Err(ResourceError::ConnectionLimitExceeded {
                 current: connections.len(),
                 max: self.limits.max_connections,
             });
         }
+        let client_connections = connections
+            .values()
+            .filter(|conn| conn.client_key == client_key)
+            .count();
+        if client_connections >= self.limits.max_connections_per_client {
+            return Err(ResourceError::ClientConnectionLimitExceeded {
+                client: client_key,
+                current: client_connections,
+                max: self.limits.max_connections_per_client,
+            });
+        }
 
         // Register the connection
-        let conn_info = ConnectionInfo::new(client_id.clone(), client_addr);
-        connections.insert(client_id.clone(), conn_info);
+        let conn_info = ConnectionInfo::new(client_id.clone(), client_key, client_addr);
+        connections.insert(client_id, conn_info);
 
         Ok(())
     }
@@ -301,14 +340,22 @@ impl ResourceManager {
     /// # Errors
     /// Returns an error if the client is unregistered or any rate limit is exceeded.
     pub async fn record_request(&self, client_id: &str) -> Result<(), ResourceError> {
-        let mut connections = self.connections.write().await;
+        let client_key = {
+            let mut connections = self.connections.write().await;
+            let Some(conn_info) = connections.get_mut(client_id) else {
+                warn!("Request from unregistered client: {client_id}");
+                return Err(ResourceError::ResourceUnavailable);
+            };
+            conn_info.update_activity();
+            conn_info.client_key.clone()
+        };
 
-        if let Some(conn_info) = connections.get_mut(client_id) {
-            conn_info.add_request_and_check_limits(&self.limits)
-        } else {
-            warn!("Request from unregistered client: {client_id}");
-            Err(ResourceError::ResourceUnavailable)
-        }
+        self.clients
+            .write()
+            .await
+            .entry(client_key)
+            .or_insert_with(RequestHistory::new)
+            .add_request_and_check_limits(&self.limits)
     }
 
     /// Get current connection count
@@ -316,23 +363,28 @@ impl ResourceManager {
         self.connections.read().await.len()
     }
 
+    /// Get the limits being enforced
+    #[must_use]
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.limits
+    }
+
     /// Get resource usage statistics
     pub async fn get_stats(&self) -> ResourceStats {
         let connections = self.connections.read().await;
+        let clients = self.clients.read().await;
         let now = Utc::now();
 
+        let active_connections = connections
+            .values()
+            .filter(|conn| !conn.is_timed_out(self.limits.connection_timeout_seconds))
+            .count();
+
         let mut total_requests_last_minute = 0;
         let mut total_requests_last_hour = 0;
-        let mut active_connections = 0;
-
-        for conn in connections.values() {
-            if !conn.is_timed_out(self.limits.connection_timeout_seconds) {
-                active_connections += 1;
-                total_requests_last_minute +=
-                    conn.request_history.count_requests_in_window(now, 60);
-                total_requests_last_hour +=
-                    conn.request_history.count_requests_in_window(now, 3600);
-            }
+        for history in clients.values() {
+            total_requests_last_minute += history.count_requests_in_window(now, 60);
+            total_requests_last_hour += history.count_requests_in_window(now, 3600);
         }
 
         ResourceStats {
@@ -349,6 +401,7 @@ impl ResourceManager {
     /// Background task to clean up timed-out connections
     async fn cleanup_task(
         connections: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
+        clients: Arc<RwLock<HashMap<String, RequestHistory>>>,
         limits: ResourceLimits,
     ) {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
@@ -373,6 +426,15 @@ impl ResourceManager {
             if removed_count > 0 {
                 debug!("Cleaned up {removed_count} timed-out connections");
             }
+
+            // Forget clients that are gone and out of every rate limit window
+            let now = Utc::now();
+            clients.write().await.retain(|client_key, history| {
+                connections_guard
+                    .values()
+                    .any(|conn| &conn.client_key == client_key)
+                    || history.count_requests_in_window(now, 3600) > 0
+            });
         }
     }
 }
@@ -476,6 +538,66 @@ mod tests {
         assert!(manager.record_request("client1").await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_client_connection_limiting() {
+        let limits = ResourceLimits {
+            max_connections_per_client: 2,
+            ..Default::default()
+        };
+        let manager = ResourceManager::with_limits(limits);
+
+        for conn in ["conn1", "conn2"] {
+            manager
+                .register_client_connection(conn.to_string(), "pid:1".to_string(), None)
+                .await
+                .unwrap();
+        }
+
+        // A third connection from the same client is rejected, another client's is not
+        assert!(matches!(
+            manager
+                .register_client_connection("conn3".to_string(), "pid:1".to_string(), None)
+                .await,
+            Err(ResourceError::ClientConnectionLimitExceeded { current: 2, .. })
+        ));
+        assert!(
+            manager
+                .register_client_connection("conn3".to_string(), "pid:2".to_string(), None)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spans_client_connections() {
+        let limits = ResourceLimits {
+            max_requests_per_second: 3,
+            ..Default::default()
+        };
+        let manager = ResourceManager::with_limits(limits);
+
+        for conn in ["conn1", "conn2"] {
+            manager
+                .register_client_connection(conn.to_string(), "pid:1".to_string(), None)
+                .await
+                .unwrap();
+        }
+        manager
+            .register_client_connection("conn3".to_string(), "pid:2".to_string(), None)
+            .await
+            .unwrap();
+
+        // Requests count toward the client however they are spread over its connections
+        for conn in ["conn1", "conn2", "conn1"] {
+            assert!(manager.record_request(conn).await.is_ok());
+        }
+        assert!(matches!(
+            manager.record_request("conn2").await,
+            Err(ResourceError::RateLimitExceeded { window: 1, .. })
+        ));
+        assert!(manager.record_request("conn3").await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_connection_timeout() {
         let limits = ResourceLimits {
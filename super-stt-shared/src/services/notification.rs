@@ -4,8 +4,8 @@ use chrono::Utc;
 use dashmap::DashMap;
 use log::{debug, warn};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 use tokio::task::JoinSet;
 use tokio::time::{Duration, interval, timeout};
@@ -22,9 +22,43 @@ pub struct Subscriber {
     pub created_at: chrono::DateTime<Utc>,
 }
 
+impl Subscriber {
+    /// Whether the subscriber asked for events of `event_type`
+    #[must_use]
+    pub fn wants(&self, event_type: &str) -> bool {
+        self.event_types.is_empty()
+            || self
+                .event_types
+                .iter()
+                .any(|pattern| event_type_matches(pattern, event_type))
+    }
+}
+
+/// Match an event type against a subscription pattern, where `*` stands for any
+/// run of characters; a pattern without `*` must equal the event type
+#[must_use]
+pub fn event_type_matches(pattern: &str, event_type: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == event_type;
+    };
+    let Some(mut remaining) = event_type.strip_prefix(prefix) else {
+        return false;
+    };
+    let (middle, suffix) = rest.rsplit_once('*').unwrap_or(("", rest));
+    // Each literal piece between wildcards is taken at its earliest occurrence
+    for piece in middle.split('*').filter(|piece| !piece.is_empty()) {
+        match remaining.find(piece) {
+            Some(index) => remaining = &remaining[index + piece.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(suffix)
+}
+
 pub struct NotificationManager {
     pub subscribers: Arc<DashMap<String, Subscriber>>,
-    event_history: Arc<DashMap<String, (NotificationEvent, chrono::DateTime<Utc>)>>,
+    /// Most recent events, oldest first, replayed to clients catching up
+    event_history: Arc<Mutex<VecDeque<NotificationEvent>>>,
     max_history_size: usize,
     max_subscribers: usize,
     cleanup_handle: Option<tokio::task::JoinHandle<()>>,
@@ -36,7 +70,7 @@ impl NotificationManager {
     pub fn new(max_history_size: usize, max_subscribers: usize) -> Self {
         Self {
             subscribers: Arc::new(DashMap::new()),
-            event_history: Arc::new(DashMap::new()),
+            event_history: Arc::new(Mutex::new(VecDeque::new())),
             max_history_size,
             max_subscribers,
             cleanup_handle: None,
@@ -57,9 +91,7 @@ impl NotificationManager {
 
     /// Start background cleanup task
     pub fn start_background_cleanup(&mut self) {
-        let event_history = Arc::clone(&self.event_history);
         let subscribers = Arc::clone(&self.subscribers);
-        let max_history_size = self.max_history_size;
 
         let handle = tokio::spawn(async move {
             let mut cleanup_interval = interval(Duration::from_secs(30)); // Cleanup every 30 seconds
@@ -67,25 +99,6 @@ impl NotificationManager {
             loop {
                 cleanup_interval.tick().await;
 
-                // Cleanup old events
-                if event_history.len() > max_history_size {
-                    let mut events_with_time: Vec<(String, chrono::DateTime<Utc>)> = event_history
-                        .iter()
-                        .map(|entry| (entry.key().clone(), entry.value().1))
-                        .collect();
-
-                    // Sort by timestamp (oldest first)
-                    events_with_time.sort_by(|a, b| a.1.cmp(&b.1));
-
-                    // Remove oldest events
-                    let to_remove = events_with_time.len().saturating_sub(max_history_size);
-                    for (key, _) in events_with_time.iter().take(to_remove) {
-                        event_history.remove(key);
-                    }
-
-                    debug!("Cleaned up {to_remove} old events");
-                }
-
                 // Cleanup disconnected subscribers
                 let mut to_remove = Vec::new();
                 for entry in subscribers.iter() {
@@ -163,11 +176,7 @@ impl NotificationManager {
             data,
         };
 
-        // Store in history with timestamp for cleanup
-        let event_id = Uuid::new_v4().to_string();
-        let stored_at = Utc::now();
-        self.event_history
-            .insert(event_id.clone(), (event.clone(), stored_at));
+        self.record_event(&event);
 
         // Collect eligible subscribers
         let eligible_subscribers: Vec<(String, broadcast::Sender<NotificationEvent>)> = self
@@ -175,11 +184,7 @@ impl NotificationManager {
             .iter()
             .filter_map(|entry| {
                 let subscriber = entry.value();
-                let should_send = subscriber.event_types.is_empty()
-                    || subscriber.event_types.contains(&event_type)
-                    || subscriber.event_types.contains(&"*".to_string());
-
-                if should_send {
+                if subscriber.wants(&event_type) {
                     Some((subscriber.id.clone(), subscriber.sender.clone()))
                 } else {
                     None
@@ -281,20 +286,12 @@ impl NotificationManager {
             data,
         };
 
-        // Store in history
-        let event_id = Uuid::new_v4().to_string();
-        let stored_at = Utc::now();
-        self.event_history
-            .insert(event_id, (event.clone(), stored_at));
+        self.record_event(&event);
 
         // Broadcast to relevant subscribers
         let mut delivered = 0;
         for subscriber in self.subscribers.iter() {
-            let should_send = subscriber.event_types.is_empty()
-                || subscriber.event_types.contains(&event_type.to_string())
-                || subscriber.event_types.contains(&"*".to_string());
-
-            if should_send {
+            if subscriber.wants(event_type) {
                 match subscriber.sender.send(event.clone()) {
                     Ok(_) => delivered += 1,
                     Err(_) => {
@@ -372,7 +369,7 @@ impl NotificationManager {
     #[must_use]
     pub fn get_broadcast_stats(&self) -> Value {
         let total_subscribers = self.subscribers.len();
-        let total_events = self.event_history.len();
+        let total_events = self.history().len();
 
         // Count active vs inactive subscribers
         let mut active_subscribers = 0;
@@ -393,6 +390,42 @@ impl NotificationManager {
         })
     }
 
+    fn history(&self) -> std::sync::MutexGuard<'_, VecDeque<NotificationEvent>> {
+        match self.event_history.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                warn!("Event history lock was poisoned, attempting recovery");
+                poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Keep `event` in the history, dropping the oldest once it is full
+    fn record_event(&self, event: &NotificationEvent) {
+        let mut history = self.history();
+        history.push_back(event.clone());
+        while history.len() > self.max_history_size {
+            history.pop_front();
+        }
+    }
+
+    /// Events newer than `since_timestamp` a subscriber missed, the latest `limit` of
+    /// them in the order they happened
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timestamp cannot be parsed.
+    pub fn events_to_replay(
+        &self,
+        since_timestamp: String,
+        event_types: Vec<String>,
+        limit: u32,
+    ) -> Result<Vec<NotificationEvent>> {
+        let mut events = self.get_recent_events(Some(since_timestamp), Some(event_types), limit)?;
+        events.reverse();
+        Ok(events)
+    }
+
     /// # Errors
     ///
     /// Returns an error if the timestamp cannot be parsed.
@@ -403,11 +436,7 @@ impl NotificationManager {
         limit: u32,
     ) -> Result<Vec<NotificationEvent>> {
         let limit = limit.min(1000) as usize;
-        let mut events: Vec<NotificationEvent> = self
-            .event_history
-            .iter()
-            .map(|entry| entry.value().0.clone())
-            .collect();
+        let mut events: Vec<NotificationEvent> = self.history().iter().cloned().collect();
 
         // Filter by timestamp if provided
         if let Some(since) = since_timestamp {
@@ -424,9 +453,12 @@ impl NotificationManager {
         // Filter by event types if provided
         if let Some(types) = event_types
             && !types.is_empty()
-            && !types.contains(&"*".to_string())
         {
-            events.retain(|event| types.contains(&event.event_type));
+            events.retain(|event| {
+                types
+                    .iter()
+                    .any(|pattern| event_type_matches(pattern, &event.event_type))
+            });
         }
 
         // Sort by timestamp (newest first)
@@ -458,7 +490,7 @@ impl NotificationManager {
             "total_subscribers": subscribers.len(),
             "subscribers": subscribers,
             "max_subscribers": self.max_subscribers,
-            "event_history_size": self.event_history.len(),
+            "event_history_size": self.history().len(),
             "max_history_size": self.max_history_size
         })
     }
@@ -471,12 +503,9 @@ impl NotificationManager {
     /// Check if there are any subscribers to a specific event type
     #[must_use]
     pub fn has_subscribers_for_event(&self, event_type: &str) -> bool {
-        self.subscribers.iter().any(|entry| {
-            let subscriber = entry.value();
-            subscriber.event_types.is_empty()
-                || subscriber.event_types.contains(&event_type.to_string())
-                || subscriber.event_types.contains(&"*".to_string())
-        })
+        self.subscribers
+            .iter()
+            .any(|entry| entry.value().wants(event_type))
     }
 
     pub fn cleanup_disconnected_subscribers(&self) {
@@ -503,6 +532,91 @@ impl NotificationManager {
         }
 
         self.subscribers.clear();
-        self.event_history.clear();
+        self.history().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_matches() {
+        assert!(event_type_matches("download_*", "download_progress"));
+        assert!(event_type_matches("download_*", "download_"));
+        assert!(!event_type_matches("download_*", "model_download_progress"));
+        assert!(event_type_matches("*", "recording_state_changed"));
+        assert!(event_type_matches("*_changed", "recording_state_changed"));
+        assert!(event_type_matches(
+            "model_*_*ed",
+            "model_download_completed"
+        ));
+        assert!(!event_type_matches("a*a", "a"));
+        // Without a wildcard the match is exact
+        assert!(event_type_matches(
+            "daemon_status_changed",
+            "daemon_status_changed"
+        ));
+        assert!(!event_type_matches(
+            "daemon_status",
+            "daemon_status_changed"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_subscription_receives_matching_events() {
+        let manager = NotificationManager::new(100, 10);
+        let (_, mut receiver) = manager
+            .subscribe(vec!["download_*".to_string()], HashMap::new())
+            .unwrap();
+
+        for event_type in ["recording_started", "download_progress"] {
+            manager
+                .broadcast_event(event_type.to_string(), "daemon".to_string(), Value::Null)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            receiver.recv().await.unwrap().event_type,
+            "download_progress"
+        );
+        assert!(receiver.try_recv().is_err());
+        assert!(manager.has_subscribers_for_event("download_completed"));
+        assert!(!manager.has_subscribers_for_event("recording_started"));
+    }
+
+    #[tokio::test]
+    async fn test_events_to_replay_are_the_latest_in_order() {
+        let manager = NotificationManager::new(3, 10);
+        let since = Utc::now().to_rfc3339();
+        for i in 0..5 {
+            manager
+                .broadcast_event(format!("download_{i}"), "daemon".to_string(), Value::Null)
+                .await
+                .unwrap();
+        }
+        manager
+            .broadcast_event(
+                "recording_started".to_string(),
+                "daemon".to_string(),
+                Value::Null,
+            )
+            .await
+            .unwrap();
+
+        // The history holds the last three events, the wildcard drops the recording one
+        let replayed: Vec<String> = manager
+            .events_to_replay(since, vec!["download_*".to_string()], 10)
+            .unwrap()
+            .into_iter()
+            .map(|event| event.event_type)
+            .collect();
+        assert_eq!(replayed, vec!["download_3", "download_4"]);
+
+        let limited = manager
+            .events_to_replay(Utc::now().to_rfc3339(), Vec::new(), 10)
+            .unwrap();
+        assert!(limited.is_empty());
     }
 }
@@ -2,3 +2,4 @@
 #[cfg(feature = "audio")]
 pub mod audio;
 pub mod logger;
+pub mod sanitize;
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Redaction of user-specific paths from messages shown to users
+
+/// Replace the home and runtime directories in `message` with `$HOME` and
+/// `$XDG_RUNTIME_DIR`, so usernames and user ids stay out of error messages
+#[must_use]
+pub fn sanitize_error(message: &str) -> String {
+    let home = std::env::var("HOME").ok();
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").ok();
+    redact_dirs(message, home.as_deref(), runtime_dir.as_deref())
+}
+
+fn redact_dirs(message: &str, home: Option<&str>, runtime_dir: Option<&str>) -> String {
+    let mut redacted = message.to_string();
+    // The runtime dir goes first in case it lies inside the home directory
+    for (dir, placeholder) in [(runtime_dir, "$XDG_RUNTIME_DIR"), (home, "$HOME")] {
+        // A root or empty directory would redact every path
+        if let Some(dir) = dir.map(|dir| dir.trim_end_matches('/'))
+            && !dir.is_empty()
+        {
+            redacted = redacted.replace(dir, placeholder);
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_home_and_runtime_dirs() {
+        let message =
+            "Failed to open /home/alice/.cache/super-stt/model.bin and /run/user/1000/stt";
+        assert_eq!(
+            redact_dirs(message, Some("/home/alice"), Some("/run/user/1000/")),
+            "Failed to open $HOME/.cache/super-stt/model.bin and $XDG_RUNTIME_DIR/stt"
+        );
+    }
+
+    #[test]
+    fn test_leaves_messages_alone_without_usable_dirs() {
+        let message = "Failed to open /var/lib/model.bin";
+        assert_eq!(redact_dirs(message, Some("/"), None), message);
+        assert_eq!(redact_dirs(message, None, Some("")), message);
+    }
+}
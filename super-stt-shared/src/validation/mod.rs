@@ -21,6 +21,9 @@ pub mod limits {
     /// Maximum number of event types in a subscription
     pub const MAX_EVENT_TYPES: usize = 100;
 
+    /// Maximum length of an event type pattern containing `*` wildcards
+    pub const MAX_EVENT_PATTERN_LENGTH: usize = 64;
+
     /// Maximum sample rate (Hz)
     pub const MAX_SAMPLE_RATE: u32 = 96_000;
 
@@ -35,13 +38,32 @@ pub mod limits {
 
     /// Maximum size of JSON data fields (bytes)
     pub const MAX_JSON_SIZE: usize = 1024 * 1024; // 1MB
+
+    /// Maximum number of text replacement rules
+    pub const MAX_REPLACEMENTS: usize = 256;
+
+    /// Maximum number of words the word filter hides
+    pub const MAX_FILTERED_WORDS: usize = 256;
+
+    /// Maximum size of one length-prefixed message on the daemon socket (bytes)
+    pub const MAX_FRAME_SIZE: usize = 100 * 1024 * 1024; // 100MB
+
+    /// Maximum tokens a model may generate for one transcription
+    pub const MAX_NEW_TOKENS: usize = 8192;
+
+    /// Maximum decoding temperature
+    pub const MAX_TEMPERATURE: f64 = 2.0;
 }
 
 /// Validation errors for better error reporting
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
-    #[error("String too long: {len} > {max} characters")]
-    StringTooLong { len: usize, max: usize },
+    #[error("String too long in field '{field}': {len} > {max} characters")]
+    StringTooLong {
+        field: String,
+        len: usize,
+        max: usize,
+    },
 
     #[error("Audio data too large: {samples} > {max} samples")]
     AudioTooLarge { samples: usize, max: usize },
@@ -66,6 +88,44 @@ pub enum ValidationError {
 
     #[error("Invalid character in field '{field}': contains control characters")]
     InvalidCharacters { field: String },
+
+    #[error("Invalid file path: {reason}")]
+    InvalidPath { reason: String },
+
+    #[error("Unsupported language code: '{code}'")]
+    UnsupportedLanguage { code: String },
+
+    #[error("Too many replacements: {count} > {max}")]
+    TooManyReplacements { count: usize, max: usize },
+
+    #[error("Invalid {field}: {value} (expected {expected})")]
+    OutOfRange {
+        field: String,
+        value: String,
+        expected: String,
+    },
+}
+
+impl ValidationError {
+    /// Name of the request field the error is about, `None` when it does not point at one
+    #[must_use]
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::StringTooLong { field, .. }
+            | Self::EmptyField { field }
+            | Self::InvalidCharacters { field }
+            | Self::OutOfRange { field, .. } => Some(field),
+            Self::AudioTooLarge { .. } => Some("audio_data"),
+            Self::InvalidSampleRate { .. } => Some("sample_rate"),
+            Self::TooManyEventTypes { .. } => Some("event_types"),
+            Self::InvalidLimit { .. } => Some("limit"),
+            Self::UnsupportedLanguage { .. } => Some("language"),
+            Self::JsonTooLarge { .. }
+            | Self::JsonTooDeep { .. }
+            | Self::InvalidPath { .. }
+            | Self::TooManyReplacements { .. } => None,
+        }
+    }
 }
 
 // Note: ValidationError implements std::error::Error via thiserror,
@@ -93,6 +153,7 @@ pub fn validate_string(
 ) -> Result<(), ValidationError> {
     if value.len() > max_length {
         return Err(ValidationError::StringTooLong {
+            field: field_name.to_string(),
             len: value.len(),
             max: max_length,
         });
@@ -208,9 +269,14 @@ pub fn validate_event_types(event_types: &[String]) -> Result<(), ValidationErro
         });
     }
 
-    // Validate each event type string
+    // Validate each event type string, keeping wildcard patterns short to match
     for event_type in event_types {
-        validate_string(event_type, "event_type", limits::MAX_NAME_LENGTH)?;
+        let max_length = if event_type.contains('*') {
+            limits::MAX_EVENT_PATTERN_LENGTH
+        } else {
+            limits::MAX_NAME_LENGTH
+        };
+        validate_string(event_type, "event_type", max_length)?;
     }
 
     Ok(())
@@ -354,6 +420,199 @@ pub fn get_secure_socket_path() -> std::path::PathBuf {
     }
 }
 
+/// Validate a client-supplied audio file path
+///
+/// The path must be absolute, free of traversal components and null bytes, and
+/// resolve to an existing regular file. Returns the canonicalized path.
+///
+/// # Errors
+/// Returns [`ValidationError::InvalidPath`] describing why the path was rejected,
+/// or any error returned by [`validate_string`].
+pub fn validate_file_path(path: &str) -> Result<std::path::PathBuf, ValidationError> {
+    validate_string(path, "path", limits::MAX_STRING_LENGTH)?;
+
+    let invalid = |reason: &str| ValidationError::InvalidPath {
+        reason: reason.to_string(),
+    };
+
+    if path.is_empty() {
+        return Err(invalid("path is empty"));
+    }
+
+    // Check for path traversal attempts and null bytes
+    if path.contains('\0') {
+        return Err(invalid("path contains null bytes"));
+    }
+    let path = std::path::Path::new(path);
+    if path
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        log::warn!("Potential path traversal in requested file path");
+        return Err(invalid("path traversal is not allowed"));
+    }
+    if !path.is_absolute() {
+        return Err(invalid("path must be absolute"));
+    }
+
+    // Resolve symlinks and ensure the target is a regular file
+    let canonical = path
+        .canonicalize()
+        .map_err(|_| invalid("file does not exist or is not accessible"))?;
+    if !canonical.is_file() {
+        return Err(invalid("path is not a regular file"));
+    }
+
+    Ok(canonical)
+}
+
+/// ISO 639-1 codes (plus a few Whisper-specific ones) accepted for transcription
+pub const LANGUAGE_CODES: &[&str] = &[
+    "en", "zh", "de", "es", "ru", "ko", "fr", "ja", "pt", "tr", "pl", "ca", "nl", "ar", "sv", "it",
+    "id", "hi", "fi", "vi", "he", "uk", "el", "ms", "cs", "ro", "da", "hu", "ta", "no", "th", "ur",
+    "hr", "bg", "lt", "la", "mi", "ml", "cy", "sk", "te", "fa", "lv", "bn", "sr", "az", "sl", "kn",
+    "et", "mk", "br", "eu", "is", "hy", "ne", "mn", "bs", "kk", "sq", "sw", "gl", "mr", "pa", "si",
+    "km", "sn", "yo", "so", "af", "oc", "ka", "be", "tg", "sd", "gu", "am", "yi", "lo", "uz", "fo",
+    "ht", "ps", "tk", "nn", "mt", "sa", "lb", "my", "bo", "tl", "mg", "as", "tt", "haw", "ln",
+    "ha", "ba", "jw", "su", "yue",
+];
+
+/// Validate a requested transcription language code
+///
+/// # Errors
+/// Returns [`ValidationError::UnsupportedLanguage`] when `code` is not in
+/// [`LANGUAGE_CODES`], or any error returned by [`validate_string`].
+pub fn validate_language(code: &str) -> Result<(), ValidationError> {
+    validate_language_supported(code, LANGUAGE_CODES)
+}
+
+/// Validate a language code against the languages a specific model supports
+///
+/// # Errors
+/// Returns [`ValidationError::UnsupportedLanguage`] when `code` is not in `supported`,
+/// or any error returned by [`validate_string`].
+pub fn validate_language_supported(code: &str, supported: &[&str]) -> Result<(), ValidationError> {
+    validate_string(code, "language", limits::MAX_NAME_LENGTH)?;
+
+    if supported.contains(&code) {
+        Ok(())
+    } else {
+        Err(ValidationError::UnsupportedLanguage {
+            code: code.to_string(),
+        })
+    }
+}
+
+/// Validate `(pattern, replacement)` text replacement rules
+///
+/// # Errors
+/// Returns [`ValidationError::TooManyReplacements`] if there are more than
+/// [`limits::MAX_REPLACEMENTS`] rules, [`ValidationError::EmptyField`] for an
+/// empty pattern, or any error returned by [`validate_string`].
+pub fn validate_replacements(replacements: &[(String, String)]) -> Result<(), ValidationError> {
+    if replacements.len() > limits::MAX_REPLACEMENTS {
+        return Err(ValidationError::TooManyReplacements {
+            count: replacements.len(),
+            max: limits::MAX_REPLACEMENTS,
+        });
+    }
+
+    for (pattern, replacement) in replacements {
+        // An empty pattern would match between every character
+        if pattern.is_empty() {
+            return Err(ValidationError::EmptyField {
+                field: "replacement pattern".to_string(),
+            });
+        }
+        validate_string(pattern, "replacement pattern", limits::MAX_STRING_LENGTH)?;
+        validate_string(replacement, "replacement", limits::MAX_STRING_LENGTH)?;
+    }
+
+    Ok(())
+}
+
+/// Validate the words hidden by the word filter
+///
+/// # Errors
+/// Returns [`ValidationError::OutOfRange`] if there are more than
+/// [`limits::MAX_FILTERED_WORDS`] words or one of them contains whitespace,
+/// [`ValidationError::EmptyField`] for an empty word, or any error returned by
+/// [`validate_string`].
+pub fn validate_filtered_words(words: &[String]) -> Result<(), ValidationError> {
+    if words.len() > limits::MAX_FILTERED_WORDS {
+        return Err(ValidationError::OutOfRange {
+            field: "word_filter.words".to_string(),
+            value: words.len().to_string(),
+            expected: format!("at most {} words", limits::MAX_FILTERED_WORDS),
+        });
+    }
+
+    for word in words {
+        if word.trim().is_empty() {
+            return Err(ValidationError::EmptyField {
+                field: "filtered word".to_string(),
+            });
+        }
+        // Matching is word by word, so a phrase could never match
+        if word.chars().any(char::is_whitespace) {
+            return Err(ValidationError::OutOfRange {
+                field: "filtered word".to_string(),
+                value: format!("'{word}'"),
+                expected: "a single word".to_string(),
+            });
+        }
+        validate_string(word, "filtered word", limits::MAX_NAME_LENGTH)?;
+    }
+
+    Ok(())
+}
+
+/// Validate decoding settings, each of which may be left unset
+///
+/// # Errors
+/// Returns [`ValidationError::OutOfRange`] if `max_new_tokens` is 0 or above
+/// [`limits::MAX_NEW_TOKENS`], `temperature` is outside
+/// `0.0..=`[`limits::MAX_TEMPERATURE`], or `top_p` is outside `0.0 < top_p <= 1.0`.
+pub fn validate_decoding(
+    max_new_tokens: Option<usize>,
+    temperature: Option<f64>,
+    top_p: Option<f64>,
+) -> Result<(), ValidationError> {
+    let out_of_range = |field: &str, value: String, expected: String| {
+        Err(ValidationError::OutOfRange {
+            field: field.to_string(),
+            value,
+            expected,
+        })
+    };
+
+    if let Some(tokens) = max_new_tokens
+        && !(1..=limits::MAX_NEW_TOKENS).contains(&tokens)
+    {
+        return out_of_range(
+            "max_new_tokens",
+            tokens.to_string(),
+            format!("1-{}", limits::MAX_NEW_TOKENS),
+        );
+    }
+    if let Some(temperature) = temperature
+        && !(0.0..=limits::MAX_TEMPERATURE).contains(&temperature)
+    {
+        return out_of_range(
+            "temperature",
+            temperature.to_string(),
+            format!("0.0-{:.1}", limits::MAX_TEMPERATURE),
+        );
+    }
+    if let Some(top_p) = top_p
+        && !(top_p > 0.0 && top_p <= 1.0)
+    {
+        return out_of_range("top_p", top_p.to_string(), "0.0 < top_p <= 1.0".to_string());
+    }
+
+    Ok(())
+}
+
 // Helper to check JSON nesting depth without defining items after statements
 fn check_depth(
     value: &Value,
@@ -459,6 +718,83 @@ mod tests {
         assert!(validate_command("cmd|ls").is_err());
     }
 
+    #[test]
+    fn test_validate_file_path() {
+        let file = std::env::temp_dir().join("super-stt-validate-file-path.wav");
+        std::fs::write(&file, b"RIFF").unwrap();
+        let file_str = file.to_string_lossy();
+
+        // Valid absolute path to an existing file
+        assert!(validate_file_path(&file_str).is_ok());
+
+        // Relative paths and traversal attempts
+        assert!(validate_file_path("audio.wav").is_err());
+        assert!(validate_file_path("/tmp/../etc/passwd").is_err());
+
+        // Missing files and directories
+        assert!(validate_file_path("/nonexistent/super-stt/audio.wav").is_err());
+        assert!(validate_file_path(&std::env::temp_dir().to_string_lossy()).is_err());
+
+        // Empty and null-byte paths
+        assert!(validate_file_path("").is_err());
+        assert!(validate_file_path("/tmp/audio\0.wav").is_err());
+
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[test]
+    fn test_validate_language() {
+        assert!(validate_language("en").is_ok());
+        assert!(validate_language("es").is_ok());
+        assert!(validate_language("yue").is_ok());
+
+        assert!(validate_language("").is_err());
+        assert!(validate_language("EN").is_err());
+        assert!(validate_language("english").is_err());
+        assert!(validate_language_supported("ja", &["en", "fr"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_replacements() {
+        let rule =
+            |pattern: &str, replacement: &str| (pattern.to_string(), replacement.to_string());
+
+        assert!(validate_replacements(&[rule("gonna", "going to"), rule("um ", "")]).is_ok());
+
+        assert!(validate_replacements(&[rule("", "x")]).is_err());
+        assert!(validate_replacements(&[rule("a\x00b", "c")]).is_err());
+        assert!(
+            validate_replacements(&vec![rule("a", "b"); limits::MAX_REPLACEMENTS + 1]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_filtered_words() {
+        let words = |words: &[&str]| words.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert!(validate_filtered_words(&words(&["darn", "heck"])).is_ok());
+
+        assert!(validate_filtered_words(&words(&[""])).is_err());
+        assert!(validate_filtered_words(&words(&["oh no"])).is_err());
+        assert!(
+            validate_filtered_words(&vec!["a".to_string(); limits::MAX_FILTERED_WORDS + 1])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_decoding() {
+        assert!(validate_decoding(None, None, None).is_ok());
+        assert!(validate_decoding(Some(1000), Some(0.0), Some(1.0)).is_ok());
+
+        assert!(validate_decoding(Some(0), None, None).is_err());
+        assert!(validate_decoding(Some(limits::MAX_NEW_TOKENS + 1), None, None).is_err());
+        assert!(validate_decoding(None, Some(-0.1), None).is_err());
+        assert!(validate_decoding(None, Some(f64::NAN), None).is_err());
+        let error = validate_decoding(None, None, Some(0.0)).unwrap_err();
+        assert_eq!(error.field(), Some("top_p"));
+    }
+
     #[test]
     fn test_generate_secure_client_id() {
         // Test that client IDs are unique
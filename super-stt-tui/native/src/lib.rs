@@ -1,52 +1,152 @@
 #![deny(clippy::all)]
 
+use log::{debug, info, warn};
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
 use napi_derive::napi;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::net::{UdpSocket, UnixStream};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use super_stt_shared::UdpAuth;
+use super_stt_shared::daemon::client::create_daemon_request;
 use super_stt_shared::models::protocol::{DaemonRequest, DaemonResponse};
+use super_stt_shared::validation::{Validate, get_secure_socket_path};
+use super_stt_shared::networking::udp_parsing::{
+  parse_audio_samples_from_udp, parse_frequency_bands_from_udp, parse_recording_state_from_udp,
+};
+use super_stt_shared::udp::{AUDIO_SAMPLES_PACKET, FREQUENCY_BANDS_PACKET, RECORDING_STATE_PACKET};
+
+/// Recording state packet (type 5)
+#[napi(object)]
+pub struct RecordingState {
+  pub is_recording: bool,
+  /// Milliseconds since the Unix epoch, exact as a JS number
+  pub timestamp_ms: f64,
+  /// Rate the input device records at, 0 when stopped or unknown
+  pub input_sample_rate: u32,
+}
+
+/// Frequency bands packet (type 6)
+#[napi(object)]
+pub struct FrequencyBands {
+  pub sample_rate: f64,
+  pub total_energy: f64,
+  pub bands: Vec<f64>,
+}
+
+/// Audio samples packet (type 4)
+#[napi(object)]
+pub struct AudioSamples {
+  pub sample_rate: f64,
+  pub channels: u32,
+  pub samples: Vec<f64>,
+}
+
+/// Delay before the first reconnect attempt, doubled after every failed one
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest delay between reconnect attempts unless set with `setMaxBackoff`
+const DEFAULT_MAX_BACKOFF_MS: u32 = 15_000;
+/// Quiet time after which the client pings the daemon, and after which an unanswered
+/// ping means the daemon restarted or went away
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a reconnect attempt waits for the daemon to answer the registration
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[napi]
 pub struct UdpClient {
   socket: Arc<Mutex<Option<Arc<UdpSocket>>>>,
   auth: UdpAuth,
   client_id: Arc<Mutex<Option<String>>>,
+  client_type: Arc<Mutex<Option<String>>>,
+  max_backoff_ms: Arc<AtomicU32>,
+  reconnecting: Arc<AtomicBool>,
+  on_reconnect: Arc<Mutex<Option<ThreadsafeFunction<String, ErrorStrategy::Fatal>>>>,
+  /// Daemon socket that commands go to unless a call names another
+  socket_path: String,
+}
+
+/// Send log output to stderr, at debug level when `debug` is set and otherwise as
+/// `RUST_LOG` says, so nothing is printed by default
+///
+/// The first client to be created decides; later ones cannot change the level.
+fn init_logging(debug: bool) {
+  let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("off"));
+  if debug {
+    builder.filter_level(log::LevelFilter::Debug);
+  }
+  let _ = builder.try_init();
 }
 
 #[napi]
 impl UdpClient {
   #[napi(constructor)]
-  pub fn new() -> napi::Result<Self> {
-    eprintln!("[DEBUG] Creating UdpAuth...");
+  pub fn new(debug: Option<bool>, socket_path: Option<String>) -> napi::Result<Self> {
+    init_logging(debug.unwrap_or(false));
+    debug!("Creating UdpAuth...");
     let auth = UdpAuth::new()
       .map_err(|e| napi::Error::from_reason(format!("Failed to create UDP auth: {}", e)))?;
 
-    eprintln!("[DEBUG] UdpAuth created successfully");
+    debug!("UdpAuth created successfully");
 
     Ok(Self {
       socket: Arc::new(Mutex::new(None)),
       auth,
       client_id: Arc::new(Mutex::new(None)),
+      client_type: Arc::new(Mutex::new(None)),
+      max_backoff_ms: Arc::new(AtomicU32::new(DEFAULT_MAX_BACKOFF_MS)),
+      reconnecting: Arc::new(AtomicBool::new(false)),
+      on_reconnect: Arc::new(Mutex::new(None)),
+      socket_path: socket_path
+        .unwrap_or_else(|| get_secure_socket_path().to_string_lossy().into_owned()),
     })
   }
 
   #[napi]
   pub async fn connect(&self, client_type: String) -> napi::Result<String> {
-    let socket = UdpSocket::bind("127.0.0.1:0")
+    let (socket, response) = self.register(&client_type).await?;
+    *self.client_id.lock().unwrap() = Some(response.clone());
+    *self.client_type.lock().unwrap() = Some(client_type);
+    *self.socket.lock().unwrap() = Some(Arc::new(socket));
+    Ok(response)
+  }
+
+  /// Cap the delay between reconnect attempts, in milliseconds
+  #[napi]
+  pub fn set_max_backoff(&self, max_backoff_ms: u32) {
+    self.max_backoff_ms.store(max_backoff_ms, Ordering::Relaxed);
+  }
+
+  /// Call `callback` with the new registration response whenever the client has
+  /// reconnected to a restarted daemon
+  #[napi]
+  pub fn on_reconnect(&self, env: Env, callback: JsFunction) -> napi::Result<()> {
+    let mut callback: ThreadsafeFunction<String, ErrorStrategy::Fatal> =
+      callback.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+    // The callback alone should not keep Node running
+    callback.unref(&env)?;
+    *self.on_reconnect.lock().unwrap() = Some(callback);
+    Ok(())
+  }
+
+  /// Bind a fresh socket and register it with the daemon as `client_type`
+  async fn register(&self, client_type: &str) -> napi::Result<(UdpSocket, String)> {
+    let daemon_addr = super_stt_shared::daemon_udp_addr();
+    let socket = UdpSocket::bind(super_stt_shared::client_bind_addr(daemon_addr))
       .await
       .map_err(|e| napi::Error::from_reason(format!("Failed to bind socket: {}", e)))?;
 
-    eprintln!("[DEBUG] Creating auth message for client_type: {}", client_type);
+    debug!("Creating auth message for client_type: {}", client_type);
     let registration_msg = self.auth
-      .create_auth_message(&client_type)
+      .create_auth_message(client_type)
       .map_err(|e| napi::Error::from_reason(format!("Failed to create auth message: {}", e)))?;
 
-    eprintln!("[DEBUG] Registration message: {}", registration_msg);
-    eprintln!("[DEBUG] Sending registration to 127.0.0.1:8765");
+    debug!("Registration message: {}", registration_msg);
+    debug!("Sending registration to {}", daemon_addr);
     socket
-      .send_to(registration_msg.as_bytes(), "127.0.0.1:8765")
+      .send_to(registration_msg.as_bytes(), daemon_addr)
       .await
       .map_err(|e| napi::Error::from_reason(format!("Failed to send registration: {}", e)))?;
 
@@ -57,12 +157,10 @@ impl UdpClient {
       .map_err(|e| napi::Error::from_reason(format!("Failed to receive response: {}", e)))?;
 
     let response = String::from_utf8_lossy(&buf[..len]).to_string();
-    eprintln!("[DEBUG] Received response: {}", response);
+    debug!("Received response: {}", response);
 
     if response.starts_with("REGISTERED:") {
-      *self.client_id.lock().unwrap() = Some(response.clone());
-      *self.socket.lock().unwrap() = Some(Arc::new(socket));
-      Ok(response)
+      Ok((socket, response))
     } else if response.starts_with("AUTH_FAILED") {
       Err(napi::Error::from_reason("Authentication failed - check UDP secret"))
     } else {
@@ -72,34 +170,161 @@ impl UdpClient {
 
   #[napi]
   pub async fn receive_packet(&self) -> napi::Result<Buffer> {
-    let socket = {
-      let guard = self.socket.lock().unwrap();
-      guard.as_ref()
-        .ok_or_else(|| napi::Error::from_reason("Not connected - call connect() first"))?
-        .clone()
-    };
+    Ok(self.next_packet().await?.into())
+  }
+
+  /// Wait for the next recording state packet, skipping packets of other types
+  #[napi]
+  pub async fn receive_recording_state(&self) -> napi::Result<RecordingState> {
+    let state = self
+      .receive_parsed(RECORDING_STATE_PACKET, parse_recording_state_from_udp)
+      .await?;
+    Ok(RecordingState {
+      is_recording: state.is_recording,
+      timestamp_ms: state.timestamp_ms as f64,
+      input_sample_rate: state.input_sample_rate,
+    })
+  }
 
+  /// Wait for the next frequency bands packet, skipping packets of other types
+  #[napi]
+  pub async fn receive_frequency_bands(&self) -> napi::Result<FrequencyBands> {
+    let bands = self
+      .receive_parsed(FREQUENCY_BANDS_PACKET, parse_frequency_bands_from_udp)
+      .await?;
+    Ok(FrequencyBands {
+      sample_rate: f64::from(bands.sample_rate),
+      total_energy: f64::from(bands.total_energy),
+      bands: bands.bands.into_iter().map(f64::from).collect(),
+    })
+  }
+
+  /// Wait for the next audio samples packet, skipping packets of other types
+  #[napi]
+  pub async fn receive_audio_samples(&self) -> napi::Result<AudioSamples> {
+    let audio = self
+      .receive_parsed(AUDIO_SAMPLES_PACKET, parse_audio_samples_from_udp)
+      .await?;
+    Ok(AudioSamples {
+      sample_rate: f64::from(audio.sample_rate),
+      channels: u32::from(audio.channels),
+      samples: audio.samples.into_iter().map(f64::from).collect(),
+    })
+  }
+
+  /// Receive packets until one of `packet_type` arrives and parse it with the shared parser
+  async fn receive_parsed<T>(
+    &self,
+    packet_type: u8,
+    parse: fn(&[u8]) -> std::result::Result<T, String>,
+  ) -> napi::Result<T> {
+    loop {
+      let packet = self.next_packet().await?;
+      if packet.first() == Some(&packet_type) {
+        return parse(&packet)
+          .map_err(|e| napi::Error::from_reason(format!("Failed to parse packet: {}", e)));
+      }
+    }
+  }
+
+  /// Receive the next packet, reconnecting when the daemon stops answering
+  async fn next_packet(&self) -> napi::Result<Vec<u8>> {
     let mut buf = vec![0u8; 8192];
-    let (len, _addr) = socket
-      .recv_from(&mut buf)
-      .await
-      .map_err(|e| napi::Error::from_reason(format!("Failed to receive packet: {}", e)))?;
+    let mut pinged = false;
+    loop {
+      let socket = self.current_socket()?;
+      let len = match tokio::time::timeout(SILENCE_TIMEOUT, socket.recv_from(&mut buf)).await {
+        Ok(Ok((len, _addr))) => len,
+        Ok(Err(e)) => {
+          warn!("Failed to receive packet, reconnecting: {}", e);
+          self.reconnect().await?;
+          continue;
+        }
+        // A registered client gets a PONG back, so a quiet daemon answers this
+        Err(_) if !pinged => {
+          pinged = true;
+          self.send_ping().await?;
+          continue;
+        }
+        Err(_) => {
+          warn!("Daemon stopped answering, reconnecting");
+          pinged = false;
+          self.reconnect().await?;
+          continue;
+        }
+      };
+      pinged = false;
+
+      // The daemon rotated its secret; re-register before the grace period ends
+      if len > 0 && buf[0] == super_stt_shared::udp::SECRET_ROTATED_PACKET {
+        let client_type = self.client_type.lock().unwrap().clone().unwrap_or_default();
+        let registration_msg = self.auth
+          .create_auth_message(&client_type)
+          .map_err(|e| napi::Error::from_reason(format!("Failed to create auth message: {}", e)))?;
+        socket
+          .send_to(registration_msg.as_bytes(), super_stt_shared::daemon_udp_addr())
+          .await
+          .map_err(|e| napi::Error::from_reason(format!("Failed to send registration: {}", e)))?;
+        continue;
+      }
+
+      buf.truncate(len);
+      return Ok(buf);
+    }
+  }
+
+  /// Register again with growing delays until the daemon answers
+  async fn reconnect(&self) -> napi::Result<()> {
+    let client_type = self.client_type.lock().unwrap().clone().unwrap_or_default();
+    self.reconnecting.store(true, Ordering::Relaxed);
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+      let registered = tokio::time::timeout(REGISTRATION_TIMEOUT, self.register(&client_type)).await;
+      match registered {
+        Ok(Ok((socket, response))) => {
+          let mut current = self.socket.lock().unwrap();
+          // disconnect() was called meanwhile
+          if current.is_none() {
+            self.reconnecting.store(false, Ordering::Relaxed);
+            return Err(napi::Error::from_reason("Not connected - call connect() first"));
+          }
+          *current = Some(Arc::new(socket));
+          drop(current);
+          *self.client_id.lock().unwrap() = Some(response.clone());
+          self.reconnecting.store(false, Ordering::Relaxed);
+          info!("Reconnected to daemon: {}", response);
+          if let Some(callback) = self.on_reconnect.lock().unwrap().as_ref() {
+            callback.call(response, ThreadsafeFunctionCallMode::NonBlocking);
+          }
+          return Ok(());
+        }
+        Ok(Err(e)) => debug!("Reconnect attempt failed: {}", e),
+        Err(_) => debug!("Reconnect attempt timed out"),
+      }
+
+      if self.socket.lock().unwrap().is_none() {
+        self.reconnecting.store(false, Ordering::Relaxed);
+        return Err(napi::Error::from_reason("Not connected - call connect() first"));
+      }
+      let max_backoff = Duration::from_millis(u64::from(self.max_backoff_ms.load(Ordering::Relaxed)));
+      tokio::time::sleep(backoff.min(max_backoff)).await;
+      backoff = (backoff * 2).min(max_backoff);
+    }
+  }
 
-    buf.truncate(len);
-    Ok(buf.into())
+  fn current_socket(&self) -> napi::Result<Arc<UdpSocket>> {
+    let guard = self.socket.lock().unwrap();
+    guard.as_ref()
+      .ok_or_else(|| napi::Error::from_reason("Not connected - call connect() first"))
+      .cloned()
   }
 
   #[napi]
   pub async fn send_ping(&self) -> napi::Result<()> {
-    let socket = {
-      let guard = self.socket.lock().unwrap();
-      guard.as_ref()
-        .ok_or_else(|| napi::Error::from_reason("Not connected - call connect() first"))?
-        .clone()
-    };
+    let socket = self.current_socket()?;
 
     socket
-      .send_to(b"PING", "127.0.0.1:8765")
+      .send_to(b"PING", super_stt_shared::daemon_udp_addr())
       .await
       .map_err(|e| napi::Error::from_reason(format!("Failed to send ping: {}", e)))?;
 
@@ -112,9 +337,10 @@ impl UdpClient {
     *self.client_id.lock().unwrap() = None;
   }
 
+  /// Whether the client is registered, false while it reconnects
   #[napi]
   pub fn is_connected(&self) -> bool {
-    self.socket.lock().unwrap().is_some()
+    self.socket.lock().unwrap().is_some() && !self.reconnecting.load(Ordering::Relaxed)
   }
 
   #[napi]
@@ -127,9 +353,7 @@ impl UdpClient {
     let client_id = self.get_client_id()
       .ok_or_else(|| napi::Error::from_reason("Not connected - no client ID"))?;
 
-    let socket_path = socket_path.unwrap_or_else(|| {
-      format!("/run/user/{}/stt/super-stt.sock", unsafe { libc::getuid() })
-    });
+    let socket_path = socket_path.unwrap_or_else(|| self.socket_path.clone());
 
     let request = DaemonRequest {
       command: "record".to_string(),
@@ -146,48 +370,73 @@ impl UdpClient {
       limit: None,
       event_type: None,
       enabled: None,
+      format: None,
     };
 
     let response = send_daemon_command(&socket_path, &request).await?;
     Ok(response.message.unwrap_or_else(|| "Recording started".to_string()))
   }
+
+  /// Send any daemon command with optional `data` and return the daemon's response
+  ///
+  /// The request is validated like the daemon would before anything is sent, and an
+  /// error response rejects with its message.
+  #[napi]
+  pub async fn send_command(
+    &self,
+    command: String,
+    data: Option<serde_json::Value>,
+    socket_path: Option<String>,
+  ) -> napi::Result<serde_json::Value> {
+    let client_id = self.get_client_id().unwrap_or_else(|| "super-stt-tui".to_string());
+    let mut request = create_daemon_request(&command, &client_id);
+    request.data = data;
+    request
+      .validate()
+      .map_err(|e| napi::Error::from_reason(format!("Invalid request: {}", e)))?;
+
+    let socket_path = socket_path.unwrap_or_else(|| self.socket_path.clone());
+    let response = send_daemon_command(&socket_path, &request).await?;
+    serde_json::to_value(response)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to convert response: {}", e)))
+  }
 }
 
 async fn send_daemon_command(socket_path: &str, request: &DaemonRequest) -> napi::Result<DaemonResponse> {
-  eprintln!("[DEBUG] Connecting to socket: {}", socket_path);
+  debug!("Connecting to socket: {}", socket_path);
   let mut stream = UnixStream::connect(socket_path)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to connect to daemon: {}", e)))?;
-  eprintln!("[DEBUG] Connected successfully");
+  debug!("Connected successfully");
 
   let request_data = serde_json::to_vec(request)
     .map_err(|e| napi::Error::from_reason(format!("Failed to serialize request: {}", e)))?;
-  eprintln!("[DEBUG] Request serialized: {} bytes", request_data.len());
-  eprintln!("[DEBUG] Request JSON: {}", String::from_utf8_lossy(&request_data));
+  debug!("Request serialized: {} bytes", request_data.len());
+  debug!("Request JSON: {}", String::from_utf8_lossy(&request_data));
 
   // Daemon protocol: 8-byte message size (u64 big-endian) + message content
   let message_size = request_data.len() as u64;
   let size_bytes = message_size.to_be_bytes();
 
   // Send size prefix
-  eprintln!("[DEBUG] Sending size prefix: {}", message_size);
+  debug!("Sending size prefix: {}", message_size);
   stream.write_all(&size_bytes)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to send message size: {}", e)))?;
 
   // Send message content
-  eprintln!("[DEBUG] Sending request data");
+  debug!("Sending request data");
   stream.write_all(&request_data)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to send request: {}", e)))?;
-  eprintln!("[DEBUG] Request sent, waiting for response size");
+  debug!("Request sent, waiting for response size");
 
   // Read response size
   let mut response_size_buf = [0u8; 8];
   stream.read_exact(&mut response_size_buf)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to read response size: {}", e)))?;
-  eprintln!("[DEBUG] Response size received");
+  debug!("Response size received");
 
   let response_size = u64::from_be_bytes(response_size_buf) as usize;
   if response_size > 100 * 1024 * 1024 {
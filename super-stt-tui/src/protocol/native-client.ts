@@ -27,6 +27,7 @@ export interface UdpClientEvents {
   disconnected: [];
   error: [error: Error];
   registered: [clientId: string];
+  reconnected: [clientId: string];
   recording_state: [state: RecordingState];
   frequency_bands: [bands: FrequencyBands];
   audio_samples: [samples: AudioSamples];
@@ -65,6 +66,11 @@ export class NativeUdpClient extends EventEmitter {
         this.emit('connected');
         this.emit('registered', clientId);
 
+        // The native client re-registers on its own after a daemon restart
+        this.client.onReconnect((registration: string) => {
+          this.emit('reconnected', registration.split(':')[1]);
+        });
+
         this.startKeepAlive();
         this.startReceiveLoop();
       } else {
@@ -178,6 +184,20 @@ export class NativeUdpClient extends EventEmitter {
     }
     return await this.client.sendRecordCommand(socketPath, writeMode);
   }
+
+  /**
+   * Send any daemon command, resolving to the daemon's response object
+   */
+  async sendCommand(
+    command: string,
+    data?: Record<string, unknown>,
+    socketPath?: string,
+  ): Promise<Record<string, unknown>> {
+    if (!this.client) {
+      throw new Error('Client not connected');
+    }
+    return await this.client.sendCommand(command, data, socketPath);
+  }
 }
 
 /**
@@ -1,16 +1,266 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use anyhow::Result;
-use cpal::Device;
+use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, FromSample, SizedSample, Stream, StreamConfig};
 use log::debug;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use super_stt_shared::audio_utils::ResampleQuality;
+use super_stt_shared::theme::AudioTheme;
+use super_stt_shared::utils::audio::resample;
 
 pub const WARMUP_TONE_DURATION_MS: u64 = 20;
 pub const WARMUP_TONE_FREQUENCY: f32 = 44000.0;
 pub const WARMUP_DELAY_AFTER_TONE_MS: u64 = 50;
 
+/// Peak amplitude of theme beeps at full volume
+pub const BEEP_AMPLITUDE: f32 = 0.3;
+
+/// Longest custom sound accepted, since the start sound delays every recording
+pub const MAX_CUSTOM_SOUND_SECS: f32 = 5.0;
+
+/// Moment of a recording a sound announces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    Start,
+    Stop,
+    /// A recording that ended without usable speech
+    Error,
+}
+
+/// WAV files played by the `Custom` audio theme, unset cues use Classic tones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomSoundPaths {
+    pub start: Option<PathBuf>,
+    pub stop: Option<PathBuf>,
+    pub error: Option<PathBuf>,
+}
+
+/// A decoded sound, downmixed to mono
+#[derive(Debug, Clone)]
+pub struct SoundClip {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+/// The custom theme's sounds, decoded once when the theme is selected
+#[derive(Debug, Default)]
+pub struct CustomSounds {
+    start: Option<SoundClip>,
+    stop: Option<SoundClip>,
+    error: Option<SoundClip>,
+}
+
+impl SoundClip {
+    #[must_use]
+    pub fn new(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            samples,
+            sample_rate,
+        }
+    }
+}
+
+impl CustomSounds {
+    /// Decode every configured file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first file that is missing, not a readable WAV
+    /// file, or longer than `MAX_CUSTOM_SOUND_SECS`.
+    pub fn load(paths: &CustomSoundPaths) -> Result<Self> {
+        let load = |path: &Option<PathBuf>| path.as_deref().map(load_sound).transpose();
+        Ok(Self {
+            start: load(&paths.start)?,
+            stop: load(&paths.stop)?,
+            error: load(&paths.error)?,
+        })
+    }
+
+    fn clip(&self, cue: Cue) -> Option<&SoundClip> {
+        match cue {
+            Cue::Start => self.start.as_ref(),
+            Cue::Stop => self.stop.as_ref(),
+            Cue::Error => self.error.as_ref(),
+        }
+    }
+}
+
+/// Decode a WAV file into a mono clip
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or decoded, or is too long.
+#[allow(clippy::cast_precision_loss)]
+pub fn load_sound(path: &Path) -> Result<SoundClip> {
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("Failed to open sound {}", path.display()))?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .with_context(|| format!("Failed to decode sound {}", path.display()))?;
+
+    let channels = usize::from(spec.channels.max(1));
+    let samples: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    let seconds = samples.len() as f32 / spec.sample_rate as f32;
+    if seconds > MAX_CUSTOM_SOUND_SECS {
+        return Err(anyhow::anyhow!(
+            "Sound {} is {seconds:.1}s long, at most {MAX_CUSTOM_SOUND_SECS}s is allowed",
+            path.display()
+        ));
+    }
+    Ok(SoundClip {
+        samples,
+        sample_rate: spec.sample_rate,
+    })
+}
+
+/// Play the sound `theme` has for `cue` at `volume` (0.0 to 1.0), waiting until it
+/// finishes
+///
+/// The `Custom` theme plays its clip for the cue when one is loaded. Only custom
+/// sounds cover `Cue::Error`; the preset themes stay quiet for it. Nothing is played
+/// at zero volume.
+///
+/// # Errors
+///
+/// Returns an error if no output device is available or playback fails.
+pub fn play_cue(
+    theme: AudioTheme,
+    cue: Cue,
+    custom: Option<&CustomSounds>,
+    volume: f32,
+) -> Result<()> {
+    if volume <= 0.0 {
+        return Ok(());
+    }
+    if theme == AudioTheme::Custom
+        && let Some(clip) = custom.and_then(|sounds| sounds.clip(cue))
+    {
+        return play_clip(clip, volume);
+    }
+    let (frequencies, duration, fade_in, fade_out) = match cue {
+        Cue::Start => theme.start_sound(),
+        Cue::Stop => theme.end_sound(),
+        Cue::Error => return Ok(()),
+    };
+    play_beep_sequence(&frequencies, duration, fade_in, fade_out, volume)
+}
+
+/// Play a decoded clip on the default output device, scaled by `volume`
+///
+/// # Errors
+///
+/// Returns an error if no output device is available, its sample format is not
+/// supported, or the stream cannot be played.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn play_clip(clip: &SoundClip, volume: f32) -> Result<()> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| anyhow::anyhow!("No output device available"))?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| anyhow::anyhow!("Failed to get output config: {}", e))?;
+    let sample_rate = config.sample_rate().0;
+    let mut samples = resample(
+        &clip.samples,
+        clip.sample_rate,
+        sample_rate,
+        ResampleQuality::Fast,
+    )?;
+    for sample in &mut samples {
+        *sample *= volume;
+    }
+    let samples = Arc::new(samples);
+    let duration = Duration::from_secs_f32(samples.len() as f32 / sample_rate as f32);
+
+    let finished = Arc::new(AtomicBool::new(false));
+    let stream_config = config.config();
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => {
+            build_clip_stream::<f32>(&device, &stream_config, samples, Arc::clone(&finished))
+        }
+        cpal::SampleFormat::I16 => {
+            build_clip_stream::<i16>(&device, &stream_config, samples, Arc::clone(&finished))
+        }
+        cpal::SampleFormat::I32 => {
+            build_clip_stream::<i32>(&device, &stream_config, samples, Arc::clone(&finished))
+        }
+        cpal::SampleFormat::U16 => {
+            build_clip_stream::<u16>(&device, &stream_config, samples, Arc::clone(&finished))
+        }
+        format => Err(anyhow::anyhow!(
+            "Unsupported sample format for sound playback: {format}"
+        )),
+    }?;
+    stream
+        .play()
+        .map_err(|e| anyhow::anyhow!("Failed to play sound: {}", e))?;
+
+    let timeout = duration + Duration::from_secs(2);
+    let started = std::time::Instant::now();
+    while !finished.load(Ordering::Relaxed) {
+        if started.elapsed() > timeout {
+            log::warn!("Sound playback timed out after {:?}", started.elapsed());
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    // Let the driver drain its buffer before the stream is dropped
+    std::thread::sleep(Duration::from_millis(150));
+    drop(stream);
+    Ok(())
+}
+
+fn build_clip_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    samples: Arc<Vec<f32>>,
+    finished: Arc<AtomicBool>,
+) -> Result<Stream>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = usize::from(config.channels);
+    let mut position = 0;
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            for frame in data.chunks_mut(channels) {
+                let value = samples.get(position).copied().unwrap_or(0.0);
+                if position >= samples.len() {
+                    finished.store(true, Ordering::Relaxed);
+                }
+                position += 1;
+                frame.fill(T::from_sample(value));
+            }
+        },
+        |err| log::warn!("Audio stream error: {err}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
 /// Play a short warm-up tone to initialize audio drivers.
 ///
 /// # Errors
@@ -21,14 +271,15 @@ pub fn play_warmup_tone() -> Result<()> {
     debug!("Playing warm-up tone to initialize audio drivers");
     let warmup_frequencies = [WARMUP_TONE_FREQUENCY];
     let warmup_duration = WARMUP_TONE_DURATION_MS;
-    if let Err(e) = play_beep_sequence(&warmup_frequencies, warmup_duration, 5, 5) {
+    if let Err(e) = play_beep_sequence(&warmup_frequencies, warmup_duration, 5, 5, 1.0) {
         debug!("Warm-up tone failed (usually fine): {e}");
     }
     std::thread::sleep(Duration::from_millis(WARMUP_DELAY_AFTER_TONE_MS));
     Ok(())
 }
 
-/// Play a sequence of beeps on a freshly initialized output device.
+/// Play a sequence of beeps on a freshly initialized output device, with `volume`
+/// scaling their amplitude.
 ///
 /// # Errors
 ///
@@ -45,6 +296,7 @@ pub fn play_beep_sequence(
     duration_ms: u64,
     fade_in_ms: u64,
     fade_out_ms: u64,
+    volume: f32,
 ) -> Result<()> {
     if frequencies.is_empty() {
         return Ok(());
@@ -80,6 +332,7 @@ pub fn play_beep_sequence(
     let finished = std::sync::Arc::new(AtomicBool::new(false));
     let finished_clone = finished.clone();
     let frequencies_clone = frequencies.to_vec();
+    let amplitude = BEEP_AMPLITUDE * volume.clamp(0.0, 1.0);
 
     let stream = match config.sample_format() {
         cpal::SampleFormat::F32 => device.build_output_stream(
@@ -140,7 +393,7 @@ pub fn play_beep_sequence(
                     };
 
                     // Generate sine wave with continuous phase and both fades
-                    let value = phase.sin() * 0.3 * fade_in_multiplier * fade_out_multiplier;
+                    let value = phase.sin() * amplitude * fade_in_multiplier * fade_out_multiplier;
 
                     // Update phase for next sample
                     phase += frequency * 2.0 * std::f32::consts::PI / sample_rate;
@@ -219,7 +472,8 @@ pub fn play_beep_sequence(
                         };
 
                         // Generate sine wave with continuous phase and both fades
-                        let value = phase.sin() * 0.3 * fade_in_multiplier * fade_out_multiplier;
+                        let value =
+                            phase.sin() * amplitude * fade_in_multiplier * fade_out_multiplier;
                         let sample_value = (value * f32::from(i16::MAX)) as i16;
 
                         // Update phase for next sample
@@ -386,13 +640,46 @@ mod tests {
     #[test]
     fn test_empty_frequencies() {
         // This should return Ok(()) without panicking
-        let result = play_beep_sequence(&[], 100, 10, 10);
+        let result = play_beep_sequence(&[], 100, 10, 10, 1.0);
         assert!(
             result.is_ok(),
             "Empty frequency array should not cause error"
         );
     }
 
+    #[test]
+    fn test_load_sound_downmixes_and_validates() {
+        let dir = std::env::temp_dir().join(format!("super-stt-sound-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("start.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..100 {
+            writer.write_sample(16384_i16).unwrap();
+            writer.write_sample(0_i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let clip = load_sound(&path).unwrap();
+        assert_eq!(clip.sample_rate, 8000);
+        assert_eq!(clip.samples.len(), 100);
+        assert!((clip.samples[0] - 0.25).abs() < 1e-6);
+
+        let paths = CustomSoundPaths {
+            start: Some(path),
+            stop: Some(dir.join("missing.wav")),
+            error: None,
+        };
+        let error = CustomSounds::load(&paths).unwrap_err().to_string();
+        assert!(error.contains("missing.wav"), "{error}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_warmup_tone_constants() {
         // Verify warmup tone constants are reasonable
@@ -1,8 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, SupportedStreamConfig};
+use cpal::{
+    Device, FromSample, I24, Sample, SampleFormat, SizedSample, Stream, StreamConfig,
+    SupportedStreamConfig, SupportedStreamConfigRange,
+};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -363,6 +366,255 @@ pub fn check_input_device_health() -> Result<AudioDeviceInfo> {
     })
 }
 
+/// List the names of all available audio input devices.
+///
+/// # Errors
+///
+/// Returns an error if the audio host cannot enumerate input devices.
+pub fn list_input_devices() -> Result<Vec<String>> {
+    let host = cpal::default_host();
+    let devices = host
+        .input_devices()
+        .map_err(|e| anyhow::anyhow!("Failed to enumerate input devices: {}", e))?;
+
+    Ok(devices.filter_map(|device| device.name().ok()).collect())
+}
+
+/// Find an input device by name, falling back to the default input device when
+/// no name is given or the named device is no longer available.
+///
+/// # Errors
+///
+/// Returns an error if neither the named device nor a default input device exists.
+pub fn find_input_device(name: Option<&str>) -> Result<Device> {
+    let host = cpal::default_host();
+
+    if let Some(name) = name {
+        let found = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == name)));
+        if let Some(device) = found {
+            return Ok(device);
+        }
+        log::warn!("Input device '{name}' not found, falling back to default input device");
+    }
+
+    host.default_input_device()
+        .ok_or_else(|| anyhow::anyhow!("No input device available"))
+}
+
+/// Rate the models take audio at; capturing at it natively skips resampling
+const MODEL_SAMPLE_RATE: u32 = 16000;
+
+/// Pick the input stream config recordings use on `device`
+///
+/// Any sample format and channel count the device offers is accepted, since
+/// `build_mono_input_stream` converts them; lossless formats are preferred.
+/// A non-zero `preferred_rate` is used when the device supports it natively,
+/// otherwise the rate is picked automatically.
+///
+/// # Errors
+///
+/// Returns an error if the device reports no usable input config.
+pub fn negotiate_input_config(
+    device: &Device,
+    preferred_rate: u32,
+) -> Result<SupportedStreamConfig> {
+    let config = choose_input_config(device.supported_input_configs()?.collect(), preferred_rate)?;
+    log_negotiated_config(&config);
+    Ok(config)
+}
+
+/// Pick the input config to open from the ranges a device supports, as described
+/// for [`negotiate_input_config`]
+fn choose_input_config(
+    mut supported_configs: Vec<SupportedStreamConfigRange>,
+    preferred_rate: u32,
+) -> Result<SupportedStreamConfig> {
+    supported_configs.retain(|config| format_rank(config.sample_format()).is_some());
+
+    // Prefer float, then the deepest integer formats, then fewer channels to downmix
+    supported_configs
+        .sort_by_key(|config| (format_rank(config.sample_format()), config.channels()));
+
+    if preferred_rate > 0 {
+        let native = supported_configs.iter().find(|config| {
+            (config.min_sample_rate().0..=config.max_sample_rate().0).contains(&preferred_rate)
+        });
+        if let Some(config) = native {
+            return Ok(config.with_sample_rate(cpal::SampleRate(preferred_rate)));
+        }
+        log::warn!(
+            "Input device cannot capture at the configured {preferred_rate}Hz, picking another rate"
+        );
+    }
+
+    // Find a config with reasonable sample rate (prefer 16kHz-48kHz range)
+    let optimal_config = supported_configs
+        .iter()
+        .find(|config| {
+            let max_rate = config.max_sample_rate().0;
+            let min_rate = config.min_sample_rate().0;
+            // Look for configs that support common sample rates
+            min_rate <= 48000 && max_rate >= 16000
+        })
+        .copied()
+        .or_else(|| supported_configs.into_iter().next())
+        .context("No supported input config")?;
+
+    // Use a reasonable sample rate instead of max
+    let target_rate = if optimal_config.max_sample_rate().0 >= 48000 {
+        cpal::SampleRate(48000)
+    } else if optimal_config.max_sample_rate().0 >= 44100 {
+        cpal::SampleRate(44100)
+    } else if optimal_config.max_sample_rate().0 >= 16000 {
+        cpal::SampleRate(16000)
+    } else {
+        optimal_config.max_sample_rate()
+    };
+
+    Ok(optimal_config.with_sample_rate(target_rate))
+}
+
+fn log_negotiated_config(config: &SupportedStreamConfig) {
+    let rate = config.sample_rate().0;
+    log::info!(
+        "Negotiated input format: {} {}ch at {rate}Hz ({})",
+        config.sample_format(),
+        config.channels(),
+        if rate == MODEL_SAMPLE_RATE {
+            "no resampling needed"
+        } else {
+            "resampled to 16kHz for the model"
+        }
+    );
+}
+
+/// Preference of an input sample format, `None` for formats that cannot be captured
+fn format_rank(format: SampleFormat) -> Option<u8> {
+    match format {
+        SampleFormat::F32 => Some(0),
+        SampleFormat::F64 => Some(1),
+        SampleFormat::I32 => Some(2),
+        SampleFormat::I24 => Some(3),
+        SampleFormat::I16 => Some(4),
+        SampleFormat::U16 => Some(5),
+        SampleFormat::I8 => Some(6),
+        SampleFormat::U8 => Some(7),
+        _ => None,
+    }
+}
+
+/// Open an input stream on `device` that hands `on_samples` mono f32 samples,
+/// whatever the negotiated sample format and channel count
+///
+/// # Errors
+///
+/// Returns an error if the format is unsupported or the stream cannot be built.
+pub fn build_mono_input_stream<F>(
+    device: &Device,
+    config: &SupportedStreamConfig,
+    on_samples: F,
+) -> Result<Stream>
+where
+    F: FnMut(&[f32]) + Send + 'static,
+{
+    let stream_config = config.config();
+    match config.sample_format() {
+        SampleFormat::F32 => build_mono_stream::<f32, F>(device, &stream_config, on_samples),
+        SampleFormat::F64 => build_mono_stream::<f64, F>(device, &stream_config, on_samples),
+        SampleFormat::I32 => build_mono_stream::<i32, F>(device, &stream_config, on_samples),
+        SampleFormat::I24 => build_mono_stream::<I24, F>(device, &stream_config, on_samples),
+        SampleFormat::I16 => build_mono_stream::<i16, F>(device, &stream_config, on_samples),
+        SampleFormat::U16 => build_mono_stream::<u16, F>(device, &stream_config, on_samples),
+        SampleFormat::I8 => build_mono_stream::<i8, F>(device, &stream_config, on_samples),
+        SampleFormat::U8 => build_mono_stream::<u8, F>(device, &stream_config, on_samples),
+        format => Err(anyhow::anyhow!("Unsupported sample format: {format}")),
+    }
+}
+
+fn build_mono_stream<T, F>(
+    device: &Device,
+    config: &StreamConfig,
+    mut on_samples: F,
+) -> Result<Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+    F: FnMut(&[f32]) + Send + 'static,
+{
+    let channels = usize::from(config.channels);
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            on_samples(&downmix_to_mono(data, channels));
+        },
+        |err| log::error!("Stream error: {err}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// Average interleaved frames of any sample type into mono f32
+#[allow(clippy::cast_precision_loss)]
+fn downmix_to_mono<T>(data: &[T], channels: usize) -> Vec<f32>
+where
+    T: Sample,
+    f32: FromSample<T>,
+{
+    let channels = channels.max(1);
+    data.chunks(channels)
+        .map(|frame| frame.iter().map(|&s| f32::from_sample(s)).sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Rate `input_device`, `None` for the default, records at once negotiated with
+/// `preferred_rate` as in [`negotiate_input_config`]
+///
+/// # Errors
+///
+/// Returns an error if the device cannot be found or offers no usable format.
+pub fn detect_input_sample_rate(input_device: Option<&str>, preferred_rate: u32) -> Result<u32> {
+    let device = find_input_device(input_device)?;
+    Ok(negotiate_input_config(&device, preferred_rate)?
+        .sample_rate()
+        .0)
+}
+
+/// Record `duration` of mono audio from `input_device`, `None` for the default,
+/// at `preferred_rate` when it supports it, blocking until it is captured
+///
+/// # Errors
+///
+/// Returns an error if the device cannot be opened or its stream fails to start.
+pub fn capture_clip(
+    input_device: Option<&str>,
+    preferred_rate: u32,
+    duration: Duration,
+) -> Result<(Vec<f32>, u32)> {
+    let device = find_input_device(input_device)?;
+    let config = negotiate_input_config(&device, preferred_rate)?;
+    let sample_rate = config.sample_rate().0;
+
+    let samples = Arc::new(Mutex::new(Vec::new()));
+    let callback_samples = Arc::clone(&samples);
+    let stream = build_mono_input_stream(&device, &config, move |mono_samples| {
+        if let Ok(mut samples) = callback_samples.lock() {
+            samples.extend_from_slice(mono_samples);
+        }
+    })?;
+    stream.play()?;
+    std::thread::sleep(duration);
+    drop(stream);
+
+    let samples = match samples.lock() {
+        Ok(mut guard) => std::mem::take(&mut *guard),
+        Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+    };
+    Ok((samples, sample_rate))
+}
+
 #[must_use]
 pub fn check_audio_permissions() -> bool {
     let host = cpal::default_host();
@@ -433,3 +685,64 @@ pub fn perform_audio_health_check(
 
     Ok(health_status)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downmix_converts_integer_formats() {
+        // Stereo 24-bit frames: full-scale positive with silence, then both at half scale
+        let frames = [
+            I24::new(8_388_607).unwrap(),
+            I24::new(0).unwrap(),
+            I24::new(-4_194_304).unwrap(),
+            I24::new(-4_194_304).unwrap(),
+        ];
+        let mono = downmix_to_mono(&frames, 2);
+        assert_eq!(mono.len(), 2);
+        assert!((mono[0] - 0.5).abs() < 1e-6);
+        assert!((mono[1] + 0.5).abs() < 1e-6);
+
+        // Unsigned 8-bit centers on 128
+        let mono = downmix_to_mono(&[128u8, 255, 0], 1);
+        assert!(mono[0].abs() < 1e-6);
+        assert!(mono[1] > 0.99 && mono[2] <= -1.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_capture_rate_preference() {
+        let ranges = || {
+            vec![
+                SupportedStreamConfigRange::new(
+                    2,
+                    cpal::SampleRate(8000),
+                    cpal::SampleRate(96000),
+                    cpal::SupportedBufferSize::Unknown,
+                    SampleFormat::I16,
+                ),
+                SupportedStreamConfigRange::new(
+                    2,
+                    cpal::SampleRate(44100),
+                    cpal::SampleRate(48000),
+                    cpal::SupportedBufferSize::Unknown,
+                    SampleFormat::F32,
+                ),
+            ]
+        };
+
+        // Automatic: the preferred format at a common rate, resampled for the model
+        let config = choose_input_config(ranges(), 0).unwrap();
+        assert_eq!(config.sample_format(), SampleFormat::F32);
+        assert_eq!(config.sample_rate().0, 48000);
+
+        // A rate the device captures natively wins over the sample format
+        let config = choose_input_config(ranges(), MODEL_SAMPLE_RATE).unwrap();
+        assert_eq!(config.sample_format(), SampleFormat::I16);
+        assert_eq!(config.sample_rate().0, MODEL_SAMPLE_RATE);
+
+        // A rate no range offers falls back to the automatic choice
+        let config = choose_input_config(ranges(), 192_000).unwrap();
+        assert_eq!(config.sample_rate().0, 48000);
+    }
+}
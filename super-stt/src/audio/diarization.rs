@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Speaker diarization, attributing each transcribed segment to one of the voices
+//! in a recording
+//!
+//! Every segment is summarized by the shape of its average log-mel spectrum, which
+//! follows the speaker's vocal tract but not their loudness. Segments are then
+//! merged bottom-up while the closest two clusters stay within `MERGE_DISTANCE_DB`.
+//! A segment is attributed as a whole, so a turn change inside one is not split.
+
+use rustfft::{FftPlanner, num_complex::Complex};
+use std::f32::consts::PI;
+use super_stt_shared::models::protocol::Segment;
+
+/// Analysis frame and hop, 32ms and 10ms at 16kHz
+const FRAME_SIZE: usize = 512;
+const HOP_SIZE: usize = 160;
+/// Mel bands of the spectral embedding, spanning the range voices differ in
+const MEL_BANDS: usize = 24;
+const MIN_HZ: f32 = 100.0;
+const MAX_HZ: f32 = 6000.0;
+/// Frames quieter than this are left out of an embedding so pauses do not blur it
+const SILENCE_FLOOR_RMS: f32 = 0.005;
+/// Voiced frames a segment needs before it gets an embedding of its own
+const MIN_FRAMES: usize = 10;
+/// RMS distance across bands, in dB, below which two clusters are the same speaker
+const MERGE_DISTANCE_DB: f32 = 4.0;
+
+/// Set `speaker_id` on every segment, numbering speakers from 0 in order of appearance
+///
+/// Segments too short or quiet to embed keep the speaker of the segment before them.
+pub fn assign_speakers(samples: &[f32], sample_rate: u32, segments: &mut [Segment]) {
+    let extractor = MelExtractor::new(sample_rate);
+    let embeddings: Vec<Option<Vec<f32>>> = segments
+        .iter()
+        .map(|segment| {
+            let range = sample_range(segment, sample_rate, samples.len());
+            extractor.embedding(&samples[range])
+        })
+        .collect();
+
+    let clusters = cluster(&embeddings);
+    let mut previous = 0;
+    for (segment, cluster) in segments.iter_mut().zip(clusters) {
+        segment.speaker_id = cluster.unwrap_or(previous);
+        previous = segment.speaker_id;
+    }
+    log::debug!(
+        "Diarization found {} speaker(s) in {} segments",
+        segments.iter().map(|s| s.speaker_id + 1).max().unwrap_or(0),
+        segments.len()
+    );
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn sample_range(segment: &Segment, sample_rate: u32, len: usize) -> std::ops::Range<usize> {
+    let to_index =
+        |seconds: f32| ((f64::from(seconds.max(0.0)) * f64::from(sample_rate)) as usize).min(len);
+    let start = to_index(segment.start);
+    start..to_index(segment.end).max(start)
+}
+
+/// Centroid-linkage clustering of the embeddings, `None` for missing ones
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn cluster(embeddings: &[Option<Vec<f32>>]) -> Vec<Option<u32>> {
+    // Each cluster: member indices and the sum of their embeddings
+    let mut clusters: Vec<(Vec<usize>, Vec<f32>)> = embeddings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, embedding)| Some((vec![i], embedding.clone()?)))
+        .collect();
+
+    loop {
+        let mut closest: Option<(usize, usize, f32)> = None;
+        for a in 0..clusters.len() {
+            for b in a + 1..clusters.len() {
+                let distance = centroid_distance(&clusters[a], &clusters[b]);
+                if closest.is_none_or(|(_, _, best)| distance < best) {
+                    closest = Some((a, b, distance));
+                }
+            }
+        }
+        match closest {
+            Some((a, b, distance)) if distance < MERGE_DISTANCE_DB => {
+                let (members, sum) = clusters.swap_remove(b);
+                clusters[a].0.extend(members);
+                for (total, value) in clusters[a].1.iter_mut().zip(sum) {
+                    *total += value;
+                }
+            }
+            _ => break,
+        }
+    }
+
+    // Number speakers by their first segment
+    clusters.sort_by_key(|(members, _)| members.iter().min().copied());
+    let mut speakers = vec![None; embeddings.len()];
+    for (speaker, (members, _)) in clusters.iter().enumerate() {
+        for &member in members {
+            speakers[member] = Some(speaker as u32);
+        }
+    }
+    speakers
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn centroid_distance(a: &(Vec<usize>, Vec<f32>), b: &(Vec<usize>, Vec<f32>)) -> f32 {
+    let (count_a, count_b) = (a.0.len() as f32, b.0.len() as f32);
+    let squared: f32 =
+        a.1.iter()
+            .zip(&b.1)
+            .map(|(x, y)| (x / count_a - y / count_b).powi(2))
+            .sum();
+    (squared / a.1.len() as f32).sqrt()
+}
+
+/// Log-mel spectra of audio at one sample rate
+struct MelExtractor {
+    window: Vec<f32>,
+    // Per mel band: first FFT bin and the triangular weights from it
+    filters: Vec<(usize, Vec<f32>)>,
+}
+
+impl MelExtractor {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn new(sample_rate: u32) -> Self {
+        let window = (0..FRAME_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FRAME_SIZE as f32).cos())
+            .collect();
+
+        let to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+        let to_hz = |mel: f32| 700.0 * (10.0_f32.powf(mel / 2595.0) - 1.0);
+        let max_hz = MAX_HZ.min(sample_rate as f32 / 2.0);
+        let (low, high) = (to_mel(MIN_HZ), to_mel(max_hz));
+        let bin_hz = sample_rate as f32 / FRAME_SIZE as f32;
+        let edges: Vec<f32> = (0..MEL_BANDS + 2)
+            .map(|i| to_hz(low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32) / bin_hz)
+            .collect();
+        let filters = edges
+            .windows(3)
+            .map(|edge| {
+                let first = edge[0].ceil() as usize;
+                let weights = (first..=edge[2].floor() as usize)
+                    .map(|bin| {
+                        let bin = bin as f32;
+                        if bin <= edge[1] {
+                            (bin - edge[0]) / (edge[1] - edge[0]).max(f32::EPSILON)
+                        } else {
+                            (edge[2] - bin) / (edge[2] - edge[1]).max(f32::EPSILON)
+                        }
+                    })
+                    .collect();
+                (first, weights)
+            })
+            .collect();
+
+        Self { window, filters }
+    }
+
+    /// Average log-mel spectrum of the voiced frames in `samples`, in dB relative to
+    /// its own mean so that loudness does not count
+    #[allow(clippy::cast_precision_loss)]
+    fn embedding(&self, samples: &[f32]) -> Option<Vec<f32>> {
+        if samples.len() < FRAME_SIZE {
+            return None;
+        }
+        let fft = FftPlanner::new().plan_fft_forward(FRAME_SIZE);
+        let mut buffer = Vec::with_capacity(FRAME_SIZE);
+        let mut sum = vec![0.0_f32; MEL_BANDS];
+        let mut frames = 0;
+        for frame in samples.windows(FRAME_SIZE).step_by(HOP_SIZE) {
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / FRAME_SIZE as f32).sqrt();
+            if rms < SILENCE_FLOOR_RMS {
+                continue;
+            }
+            buffer.clear();
+            buffer.extend(
+                frame
+                    .iter()
+                    .zip(&self.window)
+                    .map(|(sample, w)| Complex::new(sample * w, 0.0)),
+            );
+            fft.process(&mut buffer);
+            for (total, (first, weights)) in sum.iter_mut().zip(&self.filters) {
+                let energy: f32 = weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, weight)| weight * buffer[first + i].norm_sqr())
+                    .sum();
+                *total += 10.0 * (energy + 1e-10).log10();
+            }
+            frames += 1;
+        }
+        if frames < MIN_FRAMES {
+            return None;
+        }
+
+        let mean = sum.iter().sum::<f32>() / (MEL_BANDS * frames) as f32;
+        Some(sum.iter().map(|band| band / frames as f32 - mean).collect())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    /// A voice-like tone at `pitch_hz` whose harmonics peak around `formant_hz`
+    fn voice(seconds: f32, pitch_hz: f32, formant_hz: f32, level: f32) -> Vec<f32> {
+        (0..(seconds * 16000.0) as usize)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                (1..=(5000.0 / pitch_hz) as usize)
+                    .map(|h| {
+                        let freq = pitch_hz * h as f32;
+                        let gain = (-((freq - formant_hz) / 600.0).powi(2)).exp();
+                        level * gain * (2.0 * PI * freq * t).sin()
+                    })
+                    .sum::<f32>()
+            })
+            .collect()
+    }
+
+    fn segment(start: f32, end: f32) -> Segment {
+        Segment {
+            start,
+            end,
+            text: String::new(),
+            speaker_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_alternating_voices_get_their_own_speakers() {
+        let mut samples = voice(2.0, 120.0, 500.0, 0.1);
+        samples.extend(voice(2.0, 220.0, 2500.0, 0.1));
+        // The first voice again, louder
+        samples.extend(voice(2.0, 120.0, 500.0, 0.3));
+        let mut segments = vec![segment(0.0, 2.0), segment(2.0, 4.0), segment(4.0, 6.0)];
+
+        assign_speakers(&samples, 16000, &mut segments);
+        let speakers: Vec<u32> = segments.iter().map(|s| s.speaker_id).collect();
+        assert_eq!(speakers, vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_unembeddable_segment_keeps_previous_speaker() {
+        let mut samples = voice(2.0, 120.0, 500.0, 0.1);
+        samples.extend(vec![0.0; 16000]);
+        samples.extend(voice(2.0, 220.0, 2500.0, 0.1));
+        let mut segments = vec![segment(0.0, 2.0), segment(2.0, 3.0), segment(3.0, 5.0)];
+
+        assign_speakers(&samples, 16000, &mut segments);
+        let speakers: Vec<u32> = segments.iter().map(|s| s.speaker_id).collect();
+        assert_eq!(speakers, vec![0, 0, 1]);
+    }
+}
@@ -2,7 +2,11 @@
 
 pub mod beeper;
 pub mod device;
+#[cfg(feature = "diarization")]
+pub mod diarization;
+pub mod pre_roll;
 pub mod processing;
 pub mod recorder;
 pub mod state;
 pub mod streamer;
+pub mod vad;
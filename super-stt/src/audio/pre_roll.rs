@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Background capture of the most recent input audio, so recordings can start with
+//! the speech that came before they were requested
+//!
+//! The capture runs on its own thread because cpal streams cannot move between
+//! threads; it only downmixes into a ring buffer, without level detection or analysis.
+
+use crate::audio::device::{build_mono_input_stream, find_input_device, negotiate_input_config};
+use anyhow::Result;
+use cpal::traits::StreamTrait;
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Extra audio kept beyond the pre-roll, covering the start sound and device setup
+/// between a record request and its own capture
+const PRE_ROLL_SLACK_MS: u32 = 3000;
+/// How often the capture thread checks its stop flag
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Position in the captured stream from which a recording's pre-roll is taken
+#[derive(Debug, Clone, Copy)]
+pub struct PreRollMark(u64);
+
+/// Mono samples at the device rate, with the count ever written so marks stay valid
+/// as old samples drop out
+#[derive(Debug, Default)]
+struct Ring {
+    samples: VecDeque<f32>,
+    capacity: usize,
+    written: u64,
+    sample_rate: u32,
+}
+
+impl Ring {
+    fn push(&mut self, mono_samples: impl IntoIterator<Item = f32>) {
+        let before = self.samples.len();
+        self.samples.extend(mono_samples);
+        self.written += (self.samples.len() - before) as u64;
+        if self.samples.len() > self.capacity {
+            let excess = self.samples.len() - self.capacity;
+            self.samples.drain(..excess);
+        }
+    }
+
+    /// Samples written at or after `position`, as far as they are still held
+    fn since(&self, position: u64) -> Vec<f32> {
+        let oldest = self.written - self.samples.len() as u64;
+        let skip = usize::try_from(position.saturating_sub(oldest)).unwrap_or(usize::MAX);
+        self.samples.iter().skip(skip).copied().collect()
+    }
+}
+
+/// Continuously captured input audio that recordings take their lead-in from
+pub struct PreRollCapture {
+    ring: Arc<Mutex<Ring>>,
+    length_ms: u32,
+    stop: Arc<AtomicBool>,
+}
+
+impl PreRollCapture {
+    /// Start capturing from `input_device`, `None` for the default, at `capture_rate`
+    /// when it supports it, keeping at least `length_ms` of audio
+    ///
+    /// Failing to open the device only logs a warning; recordings then start without
+    /// pre-roll.
+    #[must_use]
+    pub fn start(input_device: Option<String>, capture_rate: u32, length_ms: u32) -> Self {
+        let ring = Arc::new(Mutex::new(Ring::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_ring = Arc::clone(&ring);
+        let thread_stop = Arc::clone(&stop);
+        let spawned = std::thread::Builder::new()
+            .name("pre-roll".to_string())
+            .spawn(move || {
+                if let Err(e) = run_capture(
+                    input_device.as_deref(),
+                    capture_rate,
+                    length_ms,
+                    &thread_ring,
+                    &thread_stop,
+                ) {
+                    warn!("Pre-roll capture unavailable, recordings start without it: {e}");
+                }
+            });
+        if let Err(e) = spawned {
+            warn!("Failed to start pre-roll capture thread: {e}");
+        }
+
+        Self {
+            ring,
+            length_ms,
+            stop,
+        }
+    }
+
+    /// Remember where the pre-roll of a recording requested now begins
+    #[must_use]
+    pub fn mark(&self) -> PreRollMark {
+        let ring = lock(&self.ring);
+        let length = u64::from(ring.sample_rate) * u64::from(self.length_ms) / 1000;
+        PreRollMark(ring.written.saturating_sub(length))
+    }
+
+    /// Audio captured from `mark` until now, with its sample rate
+    ///
+    /// Returns `None` if nothing has been captured.
+    #[must_use]
+    pub fn since(&self, mark: PreRollMark) -> Option<(Vec<f32>, u32)> {
+        let ring = lock(&self.ring);
+        let samples = ring.since(mark.0);
+        (!samples.is_empty()).then_some((samples, ring.sample_rate))
+    }
+}
+
+impl Drop for PreRollCapture {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn lock(ring: &Mutex<Ring>) -> std::sync::MutexGuard<'_, Ring> {
+    match ring.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            warn!("Pre-roll buffer lock was poisoned, attempting recovery");
+            poisoned.into_inner()
+        }
+    }
+}
+
+/// Keep the input stream open until `stop` is set
+fn run_capture(
+    input_device: Option<&str>,
+    capture_rate: u32,
+    length_ms: u32,
+    ring: &Arc<Mutex<Ring>>,
+    stop: &AtomicBool,
+) -> Result<()> {
+    let device = find_input_device(input_device)?;
+    let config = negotiate_input_config(&device, capture_rate)?;
+    let sample_rate = config.sample_rate().0;
+    {
+        let mut ring = lock(ring);
+        ring.sample_rate = sample_rate;
+        ring.capacity =
+            (u64::from(sample_rate) * u64::from(length_ms + PRE_ROLL_SLACK_MS) / 1000) as usize;
+    }
+
+    let callback_ring = Arc::clone(ring);
+    let stream = build_mono_input_stream(&device, &config, move |mono_samples| {
+        lock(&callback_ring).push(mono_samples.iter().copied());
+    })?;
+    stream.play()?;
+    info!("Pre-roll capture started ({length_ms}ms at {sample_rate}Hz)");
+
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(STOP_POLL_INTERVAL);
+    }
+    drop(stream);
+    info!("Pre-roll capture stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_keeps_marks_valid_as_it_wraps() {
+        let mut ring = Ring {
+            capacity: 4,
+            sample_rate: 1000,
+            ..Ring::default()
+        };
+        ring.push((0..3).map(|i| i as f32));
+        let mark = ring.written;
+        ring.push((3..6).map(|i| i as f32));
+
+        assert_eq!(ring.since(mark), vec![3.0, 4.0, 5.0]);
+        // Marks older than the ring fall back to everything it still holds
+        assert_eq!(ring.since(0), vec![2.0, 3.0, 4.0, 5.0]);
+        assert!(ring.since(ring.written).is_empty());
+    }
+}
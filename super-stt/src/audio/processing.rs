@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::audio::state::{GRACE_PERIOD, NO_SPEECH_TIMEOUT, RecordingState, SILENCE_TIMEOUT};
+use crate::audio::state::{GRACE_PERIOD, NO_SPEECH_TIMEOUT, RecordingState};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
@@ -42,7 +42,12 @@ pub fn process_audio_samples(
     }
 
     let current_threshold = state.get_speech_threshold();
-    let raw_speech_decision = rms > current_threshold;
+    // The VAD sees every sample to keep its frames aligned, and vetoes loud non-speech
+    let voiced = state.vad.as_ref().is_none_or(|vad| {
+        vad.push(mono_samples);
+        vad.is_speech()
+    });
+    let raw_speech_decision = rms > current_threshold && voiced;
 
     let recent_activity = state.speech_buffer.iter().rev().take(3).any(|&x| x);
     state.update_adaptive_levels(rms, recent_activity);
@@ -69,13 +74,21 @@ pub fn process_audio_samples(
                     state.silence_start = Some(Instant::now());
                 }
                 if let Some(silence_start) = state.silence_start
-                    && silence_start.elapsed() >= SILENCE_TIMEOUT
+                    && silence_start.elapsed() >= state.silence_timeout
                     && !state.stop_requested
                 {
-                    state.stop_requested = true;
+                    if state.continuous {
+                        // Hand the utterance off and wait for the next one
+                        state.utterance_ready = true;
+                        state.recording = false;
+                        state.silence_start = None;
+                    } else {
+                        state.stop_requested = true;
+                    }
                 }
             }
-        } else if let Some(recording_start) = state.recording_start
+        } else if !state.continuous
+            && let Some(recording_start) = state.recording_start
             && recording_start.elapsed() >= NO_SPEECH_TIMEOUT
             && !state.stop_requested
         {
@@ -95,37 +108,14 @@ pub fn process_audio_samples(
     let _ = level_tx.send(audio_level);
 }
 
-#[allow(clippy::cast_precision_loss)]
-pub fn process_audio_data_f32_with_streaming(
-    data: &[f32],
-    channels: usize,
-    buffer: &Arc<Mutex<VecDeque<f32>>>,
-    state: &Arc<Mutex<RecordingState>>,
-    level_tx: &broadcast::Sender<AudioLevel>,
-    samples_tx: &tokio::sync::mpsc::UnboundedSender<Vec<f32>>,
-) {
-    let mono_samples: Vec<f32> = data
-        .chunks(channels)
-        .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
-        .collect();
-    let _ = samples_tx.send(mono_samples.clone());
-    process_audio_samples(&mono_samples, buffer, state, level_tx);
-}
-
-#[allow(clippy::cast_precision_loss)]
-pub fn process_audio_data_i16_with_streaming(
-    data: &[i16],
-    channels: usize,
+/// Forward mono samples to the analysis task and process them for recording
+pub fn process_audio_data_with_streaming(
+    mono_samples: &[f32],
     buffer: &Arc<Mutex<VecDeque<f32>>>,
     state: &Arc<Mutex<RecordingState>>,
     level_tx: &broadcast::Sender<AudioLevel>,
     samples_tx: &tokio::sync::mpsc::UnboundedSender<Vec<f32>>,
 ) {
-    let samples: Vec<f32> = data.iter().map(|&s| f32::from(s) / 32768.0).collect();
-    let mono_samples: Vec<f32> = samples
-        .chunks(channels)
-        .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
-        .collect();
-    let _ = samples_tx.send(mono_samples.clone());
-    process_audio_samples(&mono_samples, buffer, state, level_tx);
+    let _ = samples_tx.send(mono_samples.to_vec());
+    process_audio_samples(mono_samples, buffer, state, level_tx);
 }
@@ -1,41 +1,188 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::audio::beeper;
+use crate::audio::beeper::{self, Cue, CustomSounds};
 use crate::audio::device::{
-    AudioDeviceCache, AudioHealthStatus, get_or_initialize_audio_device,
+    AudioDeviceCache, AudioHealthStatus, build_mono_input_stream, detect_input_sample_rate,
+    find_input_device, get_or_initialize_audio_device, negotiate_input_config,
     verify_audio_device_readiness,
 };
-use crate::audio::processing::{
-    process_audio_data_f32_with_streaming, process_audio_data_i16_with_streaming,
-};
-use crate::audio::state::RecordingState;
+use crate::audio::pre_roll::{PreRollCapture, PreRollMark};
+use crate::audio::processing::process_audio_data_with_streaming;
+use crate::audio::state::{MIN_SPEECH_THRESHOLD, RecordingState, SILENCE_TIMEOUT};
 use crate::audio::streamer::UdpAudioStreamer;
-use anyhow::{Context, Result};
-use cpal::traits::{DeviceTrait, HostTrait};
-use cpal::{Device, SampleFormat, Stream, StreamConfig};
+use crate::audio::vad::{SileroVad, VadMode, VadWorker, VoiceActivityDetector, speech_bounds};
+use anyhow::Result;
+use cpal::{Device, Stream, SupportedStreamConfig};
 use log::info;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use super_stt_shared::AudioAnalyzer;
 use super_stt_shared::audio_utils::ResampleQuality;
 use super_stt_shared::models::audio::AudioLevel;
+use super_stt_shared::models::protocol::SilenceOverride;
 use super_stt_shared::theme::AudioTheme;
 use super_stt_shared::utils::audio::resample;
+use super_stt_shared::{AudioAnalyzer, DEFAULT_FREQUENCY_BANDS, DEFAULT_MAX_HZ, DEFAULT_MIN_HZ};
 use tokio::sync::broadcast;
 use tokio::time;
 
 // Audio processing loop interval
 const AUDIO_LOOP_INTERVAL: Duration = Duration::from_millis(100);
 
+// Audio kept ahead of speech onset while waiting for the next utterance
+const CONTINUOUS_PRE_ROLL_SECS: usize = 1;
+
+/// Silence detection settings used to decide when a recording ends
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SilenceConfig {
+    /// Minimum RMS level treated as speech; the adaptive threshold never drops below it
+    pub threshold: f32,
+    /// Trailing silence after speech that ends the recording
+    pub min_silence_ms: u32,
+    /// Hard cap on the length of a single recording
+    pub max_duration_s: u32,
+    /// Whether speech boundaries come from energy alone or are confirmed by the VAD
+    pub vad_mode: VadMode,
+}
+
+impl Default for SilenceConfig {
+    #[allow(clippy::cast_possible_truncation)]
+    fn default() -> Self {
+        Self {
+            threshold: MIN_SPEECH_THRESHOLD,
+            min_silence_ms: SILENCE_TIMEOUT.as_millis() as u32,
+            max_duration_s: 60,
+            vad_mode: VadMode::default(),
+        }
+    }
+}
+
+impl SilenceConfig {
+    /// Apply per-request overrides on top of these settings
+    #[must_use]
+    pub fn with_override(self, silence: &SilenceOverride) -> Self {
+        Self {
+            threshold: silence.threshold.unwrap_or(self.threshold),
+            min_silence_ms: silence.min_silence_ms.unwrap_or(self.min_silence_ms),
+            max_duration_s: silence.max_duration_s.unwrap_or(self.max_duration_s),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn silence_timeout(&self) -> Duration {
+        Duration::from_millis(u64::from(self.min_silence_ms))
+    }
+
+    #[must_use]
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_secs(u64::from(self.max_duration_s))
+    }
+}
+
+/// Layout of the frequency bands broadcast to visualization clients
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrequencyBandsConfig {
+    /// Number of bands per analysis, at most `MAX_FREQUENCY_BANDS`
+    pub band_count: usize,
+    /// Lower edge of the analyzed range
+    pub min_hz: f32,
+    /// Upper edge of the analyzed range
+    pub max_hz: f32,
+}
+
+impl Default for FrequencyBandsConfig {
+    fn default() -> Self {
+        Self {
+            band_count: DEFAULT_FREQUENCY_BANDS,
+            min_hz: DEFAULT_MIN_HZ,
+            max_hz: DEFAULT_MAX_HZ,
+        }
+    }
+}
+
+impl FrequencyBandsConfig {
+    /// Build an analyzer for this layout, falling back to the defaults if it is invalid
+    fn analyzer(&self, sample_rate: f32, buffer_size: usize) -> AudioAnalyzer {
+        AudioAnalyzer::with_bands(
+            sample_rate,
+            buffer_size,
+            self.band_count,
+            self.min_hz,
+            self.max_hz,
+        )
+        .unwrap_or_else(|e| {
+            log::warn!("Invalid frequency band layout ({e}), using defaults");
+            AudioAnalyzer::new(sample_rate, buffer_size)
+        })
+    }
+}
+
+/// Error returned by a recording aborted through its cancel flag
+#[derive(Debug)]
+pub struct RecordingCancelled;
+
+impl std::fmt::Display for RecordingCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Recording cancelled")
+    }
+}
+
+impl std::error::Error for RecordingCancelled {}
+
+/// An open input stream and the task broadcasting its frequency analysis
+struct CaptureSession {
+    stream: Stream,
+    samples_tx: tokio::sync::mpsc::UnboundedSender<Vec<f32>>,
+    analysis_task: tokio::task::JoinHandle<()>,
+    device_sample_rate: u32,
+}
+
+impl CaptureSession {
+    /// Stop the input stream and wait for the analysis task to drain
+    async fn finish(self) {
+        drop(self.stream);
+
+        // Close the samples channel to stop the analysis task
+        drop(self.samples_tx);
+
+        // Wait for analysis task to finish
+        let _ = self.analysis_task.await;
+    }
+}
+
 pub struct DaemonAudioRecorder {
     pub sample_rate: u32,
     audio_buffer: Arc<Mutex<VecDeque<f32>>>,
     recording_state: Arc<Mutex<RecordingState>>,
     pub audio_level_tx: broadcast::Sender<AudioLevel>,
     audio_theme: AudioTheme,
+    silence_config: SilenceConfig,
+    frequency_bands: FrequencyBandsConfig,
+    // Preferred capture device, `None` for the system default
+    input_device: Option<String>,
+    // Rate to capture at when the device supports it, 0 to pick one automatically
+    capture_sample_rate: u32,
+    // Filter used to bring the capture down to the recorder's rate
+    resample_quality: ResampleQuality,
+    // Set from outside to abort `record_until_silence_with_streaming` without its audio
+    cancel: Option<Arc<AtomicBool>>,
+    // Background capture whose recent audio is prepended to recordings
+    pre_roll: Option<Arc<PreRollCapture>>,
+    // Confirms speech in `VadMode::Vad`, energy alone decides without it
+    vad_model: Option<SileroVad>,
+    // Decoded sounds played when the theme is `Custom`
+    custom_sounds: Option<Arc<CustomSounds>>,
+    // Gain applied to the theme's sounds, 0.0 when muted
+    theme_volume: f32,
     // Audio device initialization state
     audio_device_cache: Arc<Mutex<Option<AudioDeviceCache>>>,
+    // Milliseconds the latest resampling to the recorder's rate took
+    resample_ms: Arc<AtomicU64>,
 }
 
 impl DaemonAudioRecorder {
@@ -62,7 +209,18 @@ impl DaemonAudioRecorder {
             recording_state: Arc::new(Mutex::new(RecordingState::new())),
             audio_level_tx,
             audio_theme: theme,
+            silence_config: SilenceConfig::default(),
+            frequency_bands: FrequencyBandsConfig::default(),
+            input_device: None,
+            capture_sample_rate: 0,
+            resample_quality: ResampleQuality::default(),
+            cancel: None,
+            pre_roll: None,
+            vad_model: None,
+            custom_sounds: None,
+            theme_volume: 1.0,
             audio_device_cache: Arc::new(Mutex::new(None)),
+            resample_ms: Arc::new(AtomicU64::new(0)),
         };
 
         // Pre-warm audio system to prevent cold start issues
@@ -78,12 +236,74 @@ impl DaemonAudioRecorder {
         self.audio_theme = theme;
     }
 
+    /// Set the sounds played by the `Custom` theme
+    pub fn set_custom_sounds(&mut self, custom_sounds: Option<Arc<CustomSounds>>) {
+        self.custom_sounds = custom_sounds;
+    }
+
+    /// Set the gain applied to the theme's sounds, 0.0 to 1.0
+    pub fn set_theme_volume(&mut self, theme_volume: f32) {
+        self.theme_volume = theme_volume;
+    }
+
     /// Get current audio theme
     #[must_use]
     pub fn theme(&self) -> AudioTheme {
         self.audio_theme
     }
 
+    /// Change the silence detection settings used by subsequent recordings
+    pub fn set_silence_config(&mut self, silence_config: SilenceConfig) {
+        self.silence_config = silence_config;
+    }
+
+    /// Get current silence detection settings
+    #[must_use]
+    pub fn silence_config(&self) -> SilenceConfig {
+        self.silence_config
+    }
+
+    /// Change the frequency band layout used by subsequent recordings
+    pub fn set_frequency_bands(&mut self, frequency_bands: FrequencyBandsConfig) {
+        self.frequency_bands = frequency_bands;
+    }
+
+    /// Choose the capture device used by subsequent recordings, `None` for the default
+    pub fn set_input_device(&mut self, input_device: Option<String>) {
+        self.input_device = input_device;
+    }
+
+    /// Capture at `rate` when the device supports it natively, 0 to pick a rate automatically
+    pub fn set_capture_sample_rate(&mut self, rate: u32) {
+        self.capture_sample_rate = rate;
+    }
+
+    /// Choose the filter used to resample captured audio to the recorder's rate
+    pub fn set_resample_quality(&mut self, quality: ResampleQuality) {
+        self.resample_quality = quality;
+    }
+
+    /// Abort the next recording as soon as `cancel` is set
+    pub fn set_cancel_flag(&mut self, cancel: Arc<AtomicBool>) {
+        self.cancel = Some(cancel);
+    }
+
+    /// Start recordings with the audio `pre_roll` captured just before they were requested
+    pub fn set_pre_roll(&mut self, pre_roll: Arc<PreRollCapture>) {
+        self.pre_roll = Some(pre_roll);
+    }
+
+    /// Confirm speech boundaries with `model` when the silence config asks for the VAD
+    pub fn set_vad_model(&mut self, model: SileroVad) {
+        self.vad_model = Some(model);
+    }
+
+    /// Get the preferred capture device, if any
+    #[must_use]
+    pub fn input_device(&self) -> Option<&str> {
+        self.input_device.as_deref()
+    }
+
     /// Comprehensive audio system health check
     /// This verifies both input and output audio systems are functional
     /// Perform a health check on the audio system
@@ -131,102 +351,35 @@ impl DaemonAudioRecorder {
         preview_tx: Option<tokio::sync::mpsc::UnboundedSender<(Vec<f32>, u32)>>,
     ) -> Result<Vec<f32>> {
         info!("🎤 Starting audio recording with streaming...");
+        let pre_roll_mark = self.pre_roll_mark();
 
         // Play start sound and wait for it to complete
         self.play_start_sound_and_wait();
 
         // Clear previous recording
-        {
-            let mut buffer = match self.audio_buffer.lock() {
-                Ok(guard) => guard,
-                Err(poisoned) => {
-                    log::warn!("Audio buffer lock was poisoned, attempting recovery");
-                    poisoned.into_inner()
-                }
-            };
-            buffer.clear();
-
-            let mut state = match self.recording_state.lock() {
-                Ok(guard) => guard,
-                Err(poisoned) => {
-                    log::warn!("Recording state lock was poisoned, attempting recovery");
-                    poisoned.into_inner()
-                }
-            };
-            *state = RecordingState::new();
-            state.recording_start = Some(Instant::now());
-        }
-
-        // Set up audio stream
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
-
-        let config = self.get_optimal_config(&device)?;
-        let sample_format = config.sample_format();
-        let stream_config = config.config();
-
-        // Create channel for sending audio samples from callback to async task for frequency analysis
-        let (samples_tx, mut samples_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<f32>>();
-
-        // Start frequency analysis and broadcasting task (only when clients are listening)
-        let udp_streamer_clone = Arc::clone(&udp_streamer);
-        let device_sample_rate_u32 = stream_config.sample_rate.0;
-        let device_sample_rate = device_sample_rate_u32 as f32;
-        let analysis_task = tokio::spawn(async move {
-            let frequency_analyzer = AudioAnalyzer::new(device_sample_rate, 1024);
-
-            while let Some(samples) = samples_rx.recv().await {
-                // Only compute frequency bands if there are clients listening
-                if udp_streamer_clone.has_clients().await {
-                    let freq_data = frequency_analyzer.analyze(&samples);
-                    let frequency_bands = freq_data.bands;
-                    let total_energy = freq_data.total_energy;
-
-                    if let Err(e) = udp_streamer_clone
-                        .broadcast_frequency_bands(
-                            &frequency_bands,
-                            device_sample_rate,
-                            total_energy,
-                            0, // daemon client ID
-                        )
-                        .await
-                    {
-                        log::warn!("Failed to broadcast frequency bands: {e}");
-                    }
-                }
-
-                // Forward to real-time preview if requested
-                if let Some(ref tx) = preview_tx {
-                    // Ignore if receiver is dropped
-                    let _ = tx.send((samples.clone(), device_sample_rate_u32));
-                }
-            }
-        });
-
-        // Create audio stream with UDP streaming
-        let buffer_clone = self.audio_buffer.clone();
-        let state_clone = self.recording_state.clone();
-        let level_tx = self.audio_level_tx.clone();
+        self.reset_recording_state(false);
 
-        let stream = self.create_audio_stream_with_streaming(
-            &device,
-            &stream_config,
-            sample_format,
-            buffer_clone,
-            state_clone,
-            level_tx,
-            samples_tx.clone(),
-        )?;
+        let capture = self.start_capture(&udp_streamer, preview_tx)?;
+        let device_sample_rate = capture.device_sample_rate;
+        self.prepend_pre_roll(pre_roll_mark, device_sample_rate);
 
         // Wait for recording to complete with intelligent timeout
         let start_time = Instant::now();
         let mut timeout_occurred = false;
+        let mut cancelled = false;
 
         loop {
             time::sleep(AUDIO_LOOP_INTERVAL).await;
 
+            if self
+                .cancel
+                .as_ref()
+                .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+            {
+                cancelled = true;
+                break;
+            }
+
             let should_stop = {
                 let state = match self.recording_state.lock() {
                     Ok(guard) => guard,
@@ -259,27 +412,38 @@ impl DaemonAudioRecorder {
                 state.recording // Check if speech has been detected and recording started
             };
 
-            // If speech has been detected, rely on silence detection instead of timeout
-            // Only timeout if no speech has been detected at all
-            if !has_detected_speech && elapsed >= Duration::from_secs(60) {
-                log::warn!("⚠️ Recording timeout: No speech detected within 60 seconds");
-                timeout_occurred = true;
+            // If speech has been detected, rely on silence detection until the hard cap
+            let max_duration = self.silence_config.max_duration();
+            if elapsed >= max_duration {
+                if has_detected_speech {
+                    log::warn!(
+                        "⚠️ Recording reached the {}s limit, stopping",
+                        self.silence_config.max_duration_s
+                    );
+                } else {
+                    log::warn!(
+                        "⚠️ Recording timeout: No speech detected within {} seconds",
+                        self.silence_config.max_duration_s
+                    );
+                    timeout_occurred = true;
+                }
                 break;
             }
         }
 
-        drop(stream);
-
-        // Close the samples channel to stop the analysis task
-        drop(samples_tx);
+        capture.finish().await;
 
-        // Wait for analysis task to finish
-        let _ = analysis_task.await;
+        if cancelled {
+            log::info!("🎤 Recording cancelled, discarding audio");
+            return Err(RecordingCancelled.into());
+        }
 
         // Check if timeout occurred
         if timeout_occurred {
+            self.play_error_sound();
             return Err(anyhow::anyhow!(
-                "Timeout: No speech detected within 60 seconds"
+                "Timeout: No speech detected within {} seconds",
+                self.silence_config.max_duration_s
             ));
         }
 
@@ -300,19 +464,13 @@ impl DaemonAudioRecorder {
         if audio_data.is_empty() {
             return Err(anyhow::anyhow!("No audio recorded"));
         }
+        let Some(audio_data) = self.gate_speech(audio_data, device_sample_rate) else {
+            self.play_error_sound();
+            return Err(anyhow::anyhow!("No speech detected"));
+        };
 
         // Resample if needed
-        let device_sample_rate = stream_config.sample_rate.0;
-        let final_audio = if device_sample_rate == self.sample_rate {
-            audio_data
-        } else {
-            resample(
-                &audio_data,
-                device_sample_rate,
-                self.sample_rate,
-                ResampleQuality::Fast,
-            )?
-        };
+        let final_audio = self.resample_to_target(audio_data, device_sample_rate)?;
 
         log::info!("🎤 Recording completed: {} samples", final_audio.len());
 
@@ -322,44 +480,441 @@ impl DaemonAudioRecorder {
         Ok(final_audio)
     }
 
-    #[allow(clippy::unused_self)]
-    fn get_optimal_config(&self, device: &Device) -> Result<cpal::SupportedStreamConfig> {
-        let mut supported_configs: Vec<_> = device.supported_input_configs()?.collect();
-
-        // Sort by preference: F32, I16, I32, others
-        supported_configs.sort_by_key(|config| match config.sample_format() {
-            SampleFormat::F32 => 0,
-            SampleFormat::I16 => 1,
-            SampleFormat::I32 => 2,
-            SampleFormat::F64 => 3,
-            _ => 4,
-        });
+    /// Record until `stop` is set regardless of silence, and return everything captured
+    /// resampled to the recorder's sample rate
+    ///
+    /// The silence config's `max_duration` still caps the recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if device setup or resampling fails, or nothing was captured.
+    pub async fn record_until_stopped_with_streaming(
+        &mut self,
+        udp_streamer: Arc<UdpAudioStreamer>,
+        stop: Arc<AtomicBool>,
+    ) -> Result<Vec<f32>> {
+        info!("🎤 Starting push-to-talk recording with streaming...");
+        let pre_roll_mark = self.pre_roll_mark();
+
+        self.play_start_sound_and_wait();
+        // Continuous state only marks utterance boundaries, so silence never stops capture
+        self.reset_recording_state(true);
+
+        let capture = self.start_capture(&udp_streamer, None)?;
+        let device_sample_rate = capture.device_sample_rate;
+        self.prepend_pre_roll(pre_roll_mark, device_sample_rate);
+
+        let start_time = Instant::now();
+        let max_duration = self.silence_config.max_duration();
+        while !stop.load(Ordering::Relaxed) {
+            time::sleep(AUDIO_LOOP_INTERVAL).await;
+
+            if start_time.elapsed() >= max_duration {
+                log::warn!(
+                    "⚠️ Push-to-talk recording reached the {}s limit, stopping",
+                    self.silence_config.max_duration_s
+                );
+                break;
+            }
+        }
+
+        capture.finish().await;
+
+        let audio_data: Vec<f32> = {
+            let mut buffer = match self.audio_buffer.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    log::warn!(
+                        "Audio buffer lock was poisoned during extraction, attempting recovery"
+                    );
+                    poisoned.into_inner()
+                }
+            };
+            buffer.drain(..).collect()
+        };
+
+        if audio_data.is_empty() {
+            return Err(anyhow::anyhow!("No audio recorded"));
+        }
+
+        let final_audio = self.resample_to_target(audio_data, device_sample_rate)?;
+
+        log::info!(
+            "🎤 Push-to-talk recording stopped: {} samples",
+            final_audio.len()
+        );
+
+        self.play_end_sound();
+
+        Ok(final_audio)
+    }
+
+    /// Record across silences until `stop` is set, sending each utterance through
+    /// `utterance_tx` (resampled to the recorder's sample rate) as soon as silence ends it
+    ///
+    /// Speech still buffered when `stop` is set is flushed as a final utterance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if device setup or resampling fails.
+    pub async fn record_continuous_with_streaming(
+        &mut self,
+        udp_streamer: Arc<UdpAudioStreamer>,
+        stop: Arc<AtomicBool>,
+        utterance_tx: tokio::sync::mpsc::UnboundedSender<Vec<f32>>,
+    ) -> Result<()> {
+        info!("🎤 Starting continuous audio recording with streaming...");
+        let pre_roll_mark = self.pre_roll_mark();
+
+        self.play_start_sound_and_wait();
+        self.reset_recording_state(true);
+
+        let capture = self.start_capture(&udp_streamer, None)?;
+        let device_sample_rate = capture.device_sample_rate;
+        self.prepend_pre_roll(pre_roll_mark, device_sample_rate);
+        let pre_roll_samples = device_sample_rate as usize * CONTINUOUS_PRE_ROLL_SECS;
+
+        while !stop.load(Ordering::Relaxed) {
+            time::sleep(AUDIO_LOOP_INTERVAL).await;
+
+            let utterance = {
+                let mut buffer = match self.audio_buffer.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => {
+                        log::warn!(
+                            "Audio buffer lock was poisoned during continuous recording, attempting recovery"
+                        );
+                        poisoned.into_inner()
+                    }
+                };
+                let mut state = match self.recording_state.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => {
+                        log::warn!(
+                            "Recording state lock was poisoned during continuous recording, attempting recovery"
+                        );
+                        poisoned.into_inner()
+                    }
+                };
+
+                if state.utterance_ready {
+                    state.utterance_ready = false;
+                    Some(buffer.drain(..).collect::<Vec<f32>>())
+                } else {
+                    // Between utterances only keep a short lead-in so the buffer stays bounded
+                    if !state.recording && buffer.len() > pre_roll_samples {
+                        let excess = buffer.len() - pre_roll_samples;
+                        buffer.drain(..excess);
+                    }
+                    None
+                }
+            };
+
+            if let Some(audio) =
+                utterance.and_then(|audio| self.gate_speech(audio, device_sample_rate))
+            {
+                let audio = self.resample_to_target(audio, device_sample_rate)?;
+                if utterance_tx.send(audio).is_err() {
+                    log::warn!("Utterance receiver dropped, stopping continuous recording");
+                    break;
+                }
+            }
+        }
+
+        capture.finish().await;
+
+        // Flush speech captured since the last utterance boundary
+        let remaining = {
+            let mut buffer = match self.audio_buffer.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    log::warn!("Audio buffer lock was poisoned during flush, attempting recovery");
+                    poisoned.into_inner()
+                }
+            };
+            let state = match self.recording_state.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    log::warn!(
+                        "Recording state lock was poisoned during flush, attempting recovery"
+                    );
+                    poisoned.into_inner()
+                }
+            };
+            let audio: Vec<f32> = buffer.drain(..).collect();
+            (state.recording && !audio.is_empty()).then_some(audio)
+        };
+        if let Some(audio) = remaining.and_then(|audio| self.gate_speech(audio, device_sample_rate))
+        {
+            let audio = self.resample_to_target(audio, device_sample_rate)?;
+            let _ = utterance_tx.send(audio);
+        }
+
+        log::info!("🎤 Continuous recording stopped");
+
+        self.play_end_sound();
+
+        Ok(())
+    }
+
+    /// Capture from the input device without recording, streaming frequency bands and
+    /// raw samples to UDP clients until `stop` is set
+    ///
+    /// Captured audio is discarded as it arrives; the device is released on return.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if device setup fails.
+    #[allow(clippy::cast_precision_loss)]
+    pub async fn monitor_with_streaming(
+        &mut self,
+        udp_streamer: Arc<UdpAudioStreamer>,
+        stop: Arc<AtomicBool>,
+    ) -> Result<()> {
+        info!("🎚️ Starting audio level monitoring...");
+
+        self.reset_recording_state(false);
+
+        let (samples_tx, mut samples_rx) = tokio::sync::mpsc::unbounded_channel();
+        let capture = self.start_capture(&udp_streamer, Some(samples_tx))?;
+
+        while !stop.load(Ordering::Relaxed) {
+            tokio::select! {
+                Some((samples, sample_rate)) = samples_rx.recv() => {
+                    if udp_streamer.has_clients().await
+                        && let Err(e) = udp_streamer
+                            .broadcast_audio_samples(&samples, sample_rate as f32, 1, 0)
+                            .await
+                    {
+                        log::warn!("Failed to broadcast audio samples: {e}");
+                    }
+                }
+                () = time::sleep(AUDIO_LOOP_INTERVAL) => {}
+            }
+
+            // Nothing is transcribed while monitoring, so keep the buffer empty
+            match self.audio_buffer.lock() {
+                Ok(mut buffer) => buffer.clear(),
+                Err(poisoned) => {
+                    log::warn!(
+                        "Audio buffer lock was poisoned during monitoring, attempting recovery"
+                    );
+                    poisoned.into_inner().clear();
+                }
+            }
+        }
 
-        // Find a config with reasonable sample rate (prefer 16kHz-48kHz range)
-        let optimal_config = supported_configs
-            .iter()
-            .find(|config| {
-                let max_rate = config.max_sample_rate().0;
-                let min_rate = config.min_sample_rate().0;
-                // Look for configs that support common sample rates
-                min_rate <= 48000 && max_rate >= 16000
-            })
-            .copied()
-            .or_else(|| supported_configs.into_iter().next())
-            .context("No supported input config")?;
-
-        // Use a reasonable sample rate instead of max
-        let target_rate = if optimal_config.max_sample_rate().0 >= 48000 {
-            cpal::SampleRate(48000)
-        } else if optimal_config.max_sample_rate().0 >= 44100 {
-            cpal::SampleRate(44100)
-        } else if optimal_config.max_sample_rate().0 >= 16000 {
-            cpal::SampleRate(16000)
+        capture.finish().await;
+
+        log::info!("🎚️ Audio level monitoring stopped");
+
+        Ok(())
+    }
+
+    /// Clear the audio buffer and start a fresh recording state
+    fn reset_recording_state(&self, continuous: bool) {
+        let mut buffer = match self.audio_buffer.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                log::warn!("Audio buffer lock was poisoned, attempting recovery");
+                poisoned.into_inner()
+            }
+        };
+        buffer.clear();
+
+        let mut state = match self.recording_state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                log::warn!("Recording state lock was poisoned, attempting recovery");
+                poisoned.into_inner()
+            }
+        };
+        *state = RecordingState::with_silence_settings(
+            self.silence_config.silence_timeout(),
+            self.silence_config.threshold,
+        );
+        state.continuous = continuous;
+        state.recording_start = Some(Instant::now());
+    }
+
+    fn pre_roll_mark(&self) -> Option<PreRollMark> {
+        self.pre_roll.as_ref().map(|pre_roll| pre_roll.mark())
+    }
+
+    /// Put the pre-roll audio from `mark` until now ahead of the freshly started capture
+    ///
+    /// Covers the configured lead-in plus the start sound and device setup, which the
+    /// recording's own stream missed.
+    fn prepend_pre_roll(&self, mark: Option<PreRollMark>, device_sample_rate: u32) {
+        let Some((pre_roll, mark)) = self.pre_roll.as_ref().zip(mark) else {
+            return;
+        };
+        let Some((audio, sample_rate)) = pre_roll.since(mark) else {
+            return;
+        };
+        let audio = if sample_rate == device_sample_rate {
+            audio
         } else {
-            optimal_config.max_sample_rate()
+            match resample(
+                &audio,
+                sample_rate,
+                device_sample_rate,
+                self.resample_quality,
+            ) {
+                Ok(audio) => audio,
+                Err(e) => {
+                    log::warn!("Failed to resample pre-roll audio, skipping it: {e}");
+                    return;
+                }
+            }
+        };
+
+        let mut buffer = match self.audio_buffer.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                log::warn!("Audio buffer lock was poisoned during pre-roll, attempting recovery");
+                poisoned.into_inner()
+            }
+        };
+        log::debug!(
+            "Prepending {} pre-roll samples at {device_sample_rate}Hz",
+            audio.len()
+        );
+        for sample in audio.into_iter().rev() {
+            buffer.push_front(sample);
+        }
+    }
+
+    /// Open the selected input device and start streaming its samples into the buffer
+    #[allow(clippy::cast_precision_loss)]
+    fn start_capture(
+        &self,
+        udp_streamer: &Arc<UdpAudioStreamer>,
+        // Optional channel to forward live mono PCM samples and device sample rate
+        preview_tx: Option<tokio::sync::mpsc::UnboundedSender<(Vec<f32>, u32)>>,
+    ) -> Result<CaptureSession> {
+        // Set up audio stream
+        let device = find_input_device(self.input_device.as_deref())?;
+
+        let config = negotiate_input_config(&device, self.capture_sample_rate)?;
+        {
+            let mut state = match self.recording_state.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    log::warn!("Recording state lock was poisoned, attempting recovery");
+                    poisoned.into_inner()
+                }
+            };
+            // Replacing the previous capture's worker also stops its thread
+            state.vad = self.vad_model().and_then(|model| {
+                VoiceActivityDetector::new(model.clone(), config.sample_rate().0)
+                    .and_then(VadWorker::spawn)
+                    .inspect_err(|e| log::warn!("Failed to start the VAD: {e}"))
+                    .ok()
+            });
+        }
+
+        // Create channel for sending audio samples from callback to async task for frequency analysis
+        let (samples_tx, mut samples_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<f32>>();
+
+        // Start frequency analysis and broadcasting task (only when clients are listening)
+        let udp_streamer_clone = Arc::clone(udp_streamer);
+        let device_sample_rate_u32 = config.sample_rate().0;
+        let device_sample_rate = device_sample_rate_u32 as f32;
+        let frequency_analyzer = self.frequency_bands.analyzer(device_sample_rate, 1024);
+        let analysis_task = tokio::spawn(async move {
+            while let Some(samples) = samples_rx.recv().await {
+                // Only compute frequency bands if there are clients listening
+                if udp_streamer_clone.has_clients().await {
+                    let freq_data = frequency_analyzer.analyze(&samples);
+                    let frequency_bands = freq_data.bands;
+                    let total_energy = freq_data.total_energy;
+
+                    if let Err(e) = udp_streamer_clone
+                        .broadcast_frequency_bands(
+                            &frequency_bands,
+                            device_sample_rate,
+                            total_energy,
+                            0, // daemon client ID
+                        )
+                        .await
+                    {
+                        log::warn!("Failed to broadcast frequency bands: {e}");
+                    }
+                }
+
+                // Forward to real-time preview if requested
+                if let Some(ref tx) = preview_tx {
+                    // Ignore if receiver is dropped
+                    let _ = tx.send((samples.clone(), device_sample_rate_u32));
+                }
+            }
+        });
+
+        // Create audio stream with UDP streaming
+        let buffer_clone = self.audio_buffer.clone();
+        let state_clone = self.recording_state.clone();
+        let level_tx = self.audio_level_tx.clone();
+
+        let stream = self.create_audio_stream_with_streaming(
+            &device,
+            &config,
+            buffer_clone,
+            state_clone,
+            level_tx,
+            samples_tx.clone(),
+        )?;
+
+        Ok(CaptureSession {
+            stream,
+            samples_tx,
+            analysis_task,
+            device_sample_rate: device_sample_rate_u32,
+        })
+    }
+
+    /// Cut captured audio down to the speech the VAD finds in it, so non-speech never
+    /// costs a model pass
+    ///
+    /// Returns `None` if the VAD finds no speech; audio passes unchanged in energy mode
+    /// or without a VAD model.
+    fn gate_speech(&self, audio: Vec<f32>, device_sample_rate: u32) -> Option<Vec<f32>> {
+        let Some(model) = self.vad_model() else {
+            return Some(audio);
+        };
+        let Some(bounds) = speech_bounds(model, &audio, device_sample_rate) else {
+            log::info!(
+                "🎤 VAD found no speech in {} samples, dropping them",
+                audio.len()
+            );
+            return None;
         };
+        Some(audio[bounds].to_vec())
+    }
 
-        optimal_config.with_sample_rate(target_rate).pipe(Ok)
+    /// The VAD model, when the silence config asks for it and one was set
+    fn vad_model(&self) -> Option<&SileroVad> {
+        self.vad_model
+            .as_ref()
+            .filter(|_| self.silence_config.vad_mode == VadMode::Vad)
+    }
+
+    /// Resample captured audio from the device rate to the recorder's rate
+    fn resample_to_target(&self, audio: Vec<f32>, device_sample_rate: u32) -> Result<Vec<f32>> {
+        if device_sample_rate == self.sample_rate {
+            self.resample_ms.store(0, Ordering::Relaxed);
+            return Ok(audio);
+        }
+        let start = Instant::now();
+        let resampled = resample(
+            &audio,
+            device_sample_rate,
+            self.sample_rate,
+            self.resample_quality,
+        );
+        let elapsed_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.resample_ms.store(elapsed_ms, Ordering::Relaxed);
+        resampled
     }
 
     /// Play start recording sound using current theme and wait for it to complete
@@ -367,8 +922,12 @@ impl DaemonAudioRecorder {
         if self.audio_theme == AudioTheme::Silent {
             return;
         }
-        let (frequencies, duration, fade_in, fade_out) = self.audio_theme.start_sound();
-        if let Err(e) = beeper::play_beep_sequence(&frequencies, duration, fade_in, fade_out) {
+        if let Err(e) = beeper::play_cue(
+            self.audio_theme,
+            Cue::Start,
+            self.custom_sounds.as_deref(),
+            self.theme_volume,
+        ) {
             log::warn!("Failed to play start sound (audio permissions may be missing): {e}");
         }
     }
@@ -378,70 +937,49 @@ impl DaemonAudioRecorder {
         if self.audio_theme == AudioTheme::Silent {
             return;
         }
-        let (frequencies, duration, fade_in, fade_out) = self.audio_theme.end_sound();
+        self.play_cue_in_background(Cue::Stop, "end");
+    }
+
+    /// Play the sound for a recording that ended without speech, if the theme has one
+    fn play_error_sound(&self) {
+        if self.audio_theme == AudioTheme::Silent {
+            return;
+        }
+        self.play_cue_in_background(Cue::Error, "error");
+    }
+
+    fn play_cue_in_background(&self, cue: Cue, name: &'static str) {
+        let theme = self.audio_theme;
+        let custom_sounds = self.custom_sounds.clone();
+        let volume = self.theme_volume;
         std::thread::spawn(move || {
-            if let Err(e) = beeper::play_beep_sequence(&frequencies, duration, fade_in, fade_out) {
-                log::warn!("Failed to play end sound (audio permissions may be missing): {e}");
+            if let Err(e) = beeper::play_cue(theme, cue, custom_sounds.as_deref(), volume) {
+                log::warn!("Failed to play {name} sound (audio permissions may be missing): {e}");
             }
         });
     }
 
-    #[allow(clippy::too_many_arguments, clippy::unused_self)]
+    #[allow(clippy::unused_self)]
     fn create_audio_stream_with_streaming(
         &self,
         device: &Device,
-        config: &StreamConfig,
-        sample_format: SampleFormat,
+        config: &SupportedStreamConfig,
         buffer: Arc<Mutex<VecDeque<f32>>>,
         state: Arc<Mutex<RecordingState>>,
         level_tx: broadcast::Sender<AudioLevel>,
         samples_tx: tokio::sync::mpsc::UnboundedSender<Vec<f32>>,
     ) -> Result<Stream> {
-        let channels = config.channels as usize;
-
-        match sample_format {
-            SampleFormat::F32 => {
-                let stream = device.build_input_stream(
-                    config,
-                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        process_audio_data_f32_with_streaming(
-                            data,
-                            channels,
-                            &buffer,
-                            &state,
-                            &level_tx,
-                            &samples_tx,
-                        );
-                    },
-                    |err| log::error!("Stream error: {err}"),
-                    None,
-                )?;
-                Ok(stream)
-            }
-            SampleFormat::I16 => {
-                let stream = device.build_input_stream(
-                    config,
-                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        process_audio_data_i16_with_streaming(
-                            data,
-                            channels,
-                            &buffer,
-                            &state,
-                            &level_tx,
-                            &samples_tx,
-                        );
-                    },
-                    |err| log::error!("Stream error: {err}"),
-                    None,
-                )?;
-                Ok(stream)
-            }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported sample format: {:?}",
-                sample_format
-            )),
-        }
+        build_mono_input_stream(device, config, move |mono_samples| {
+            process_audio_data_with_streaming(
+                mono_samples,
+                &buffer,
+                &state,
+                &level_tx,
+                &samples_tx,
+            );
+        })
     }
+
     /// Detect the default input device's chosen sample rate using the same logic
     /// as the recording stream setup, so callers can preconfigure dependencies
     /// (e.g., real-time preview) with the correct rate.
@@ -451,12 +989,7 @@ impl DaemonAudioRecorder {
     ///
     /// Returns an error if no input device/config is available.
     pub fn detect_default_input_sample_rate(&self) -> Result<u32> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
-        let config = self.get_optimal_config(&device)?;
-        Ok(config.config().sample_rate.0)
+        detect_input_sample_rate(self.input_device.as_deref(), self.capture_sample_rate)
     }
 
     /// Prepare recorder for threaded operation - initializes any threaded state
@@ -507,18 +1040,11 @@ impl DaemonAudioRecorder {
     pub fn get_audio_buffer_ref(&self) -> Arc<Mutex<VecDeque<f32>>> {
         Arc::clone(&self.audio_buffer)
     }
-}
-trait PipeExt<T> {
-    fn pipe<F, U>(self, f: F) -> U
-    where
-        F: FnOnce(T) -> U;
-}
 
-impl<T> PipeExt<T> for T {
-    fn pipe<F, U>(self, f: F) -> U
-    where
-        F: FnOnce(T) -> U,
-    {
-        f(self)
+    /// Get a reference to how many milliseconds the latest resampling took, so
+    /// the time can be read after the recorder has moved into its task
+    #[must_use]
+    pub fn get_resample_ms_ref(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.resample_ms)
     }
 }
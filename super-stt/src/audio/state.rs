@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::audio::vad::VadWorker;
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
@@ -28,12 +29,20 @@ pub const ACTIVE_PERCENTILE: f32 = 0.25;
 pub const SPEECH_DETECTION_THRESHOLD: f32 = 0.2;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct RecordingState {
     pub recording: bool,
+    pub silence_timeout: Duration,
+    pub min_speech_threshold: f32,
+    /// Keep recording across silences, flagging each utterance boundary instead of stopping
+    pub continuous: bool,
+    pub utterance_ready: bool,
     pub silence_start: Option<Instant>,
     pub stop_requested: bool,
     pub speech_buffer: VecDeque<bool>,
     pub recording_start: Option<Instant>,
+    /// Confirms that loud frames are voiced speech, `None` in energy mode
+    pub vad: Option<VadWorker>,
 
     pub recent_levels: VecDeque<f32>,
     pub quiet_levels: VecDeque<f32>,
@@ -51,12 +60,24 @@ impl Default for RecordingState {
 impl RecordingState {
     #[must_use]
     pub fn new() -> Self {
+        Self::with_silence_settings(SILENCE_TIMEOUT, MIN_SPEECH_THRESHOLD)
+    }
+
+    /// Create a state that stops after `silence_timeout` of trailing silence and
+    /// never treats levels below `min_speech_threshold` as speech
+    #[must_use]
+    pub fn with_silence_settings(silence_timeout: Duration, min_speech_threshold: f32) -> Self {
         Self {
             recording: false,
+            silence_timeout,
+            min_speech_threshold,
+            continuous: false,
+            utterance_ready: false,
             silence_start: None,
             stop_requested: false,
             speech_buffer: VecDeque::with_capacity(SPEECH_BUFFER_SIZE),
             recording_start: None,
+            vad: None,
 
             recent_levels: VecDeque::with_capacity(RECENT_LEVELS_BUFFER_SIZE),
             quiet_levels: VecDeque::with_capacity(QUIET_LEVELS_BUFFER_SIZE),
@@ -127,7 +148,10 @@ impl RecordingState {
     pub fn get_speech_threshold(&self) -> f32 {
         let contrast = self.active_level - self.baseline_level;
         let threshold = self.baseline_level + (contrast * THRESHOLD_CONTRAST_FRACTION);
-        threshold.clamp(MIN_SPEECH_THRESHOLD, MAX_SPEECH_THRESHOLD)
+        threshold.clamp(
+            self.min_speech_threshold,
+            MAX_SPEECH_THRESHOLD.max(self.min_speech_threshold),
+        )
     }
 
     #[allow(clippy::cast_precision_loss)]
@@ -4,22 +4,28 @@ use anyhow::Result;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use super_stt_shared::UdpAuth;
+use super_stt_shared::auth::SECRET_GRACE_PERIOD;
 use super_stt_shared::daemon_state::RecordingStateData;
 use super_stt_shared::models::audio::{AudioSamplesData, FrequencyBandsData};
 use super_stt_shared::stt::STTData;
 use super_stt_shared::udp::{
     AUDIO_SAMPLES_PACKET, FINAL_STT_PACKET, FREQUENCY_BANDS_PACKET, MAX_PACKET_SIZE,
-    PARTIAL_STT_PACKET, PacketHeader, RECORDING_STATE_PACKET,
+    PARTIAL_STT_PACKET, PacketHeader, RECORDING_STATE_PACKET, SECRET_ROTATED_PACKET,
 };
 use tokio::net::UdpSocket;
 use tokio::sync::{RwLock, broadcast};
 use tokio::time::{Duration, Instant};
 
+/// How long a client may go without traffic before the cleanup task drops it
+const STALE_CLIENT_TIMEOUT: Duration = Duration::from_secs(300); // 5 minutes
+
 #[derive(Debug, Clone)]
 pub struct StreamClient {
     pub addr: SocketAddr,
     pub last_seen: Instant,
+    pub registered_at: Instant,
     pub client_type: String, // "cosmic", "web", etc.
 }
 
@@ -28,6 +34,21 @@ pub struct UdpAudioStreamer {
     clients: Arc<RwLock<HashMap<String, StreamClient>>>,
     next_client_id: Arc<RwLock<u32>>,
     auth: UdpAuth,
+    holds: Arc<AtomicUsize>,
+}
+
+/// Keeps registered clients from being dropped as stale while it is alive
+///
+/// Taken for operations like a model swap, during which the daemon sends no
+/// packets and clients would otherwise age past the stale timeout.
+pub struct ClientHold {
+    holds: Arc<AtomicUsize>,
+}
+
+impl Drop for ClientHold {
+    fn drop(&mut self) {
+        self.holds.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl UdpAudioStreamer {
@@ -35,8 +56,12 @@ impl UdpAudioStreamer {
     ///
     /// # Errors
     ///
-    /// Returns an error if binding the UDP socket fails.
+    /// Returns an error if `bind_addr` is not an IPv4 or IPv6 socket address literal
+    /// (e.g. `127.0.0.1:8765` or `[::1]:8765`), or binding the UDP socket fails.
     pub async fn new(bind_addr: &str) -> Result<Self> {
+        let bind_addr: SocketAddr = bind_addr
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid UDP bind address '{bind_addr}': {e}"))?;
         let socket = UdpSocket::bind(bind_addr).await?;
         log::info!("UDP Audio Streamer listening on {bind_addr}");
 
@@ -48,6 +73,7 @@ impl UdpAudioStreamer {
             clients: Arc::new(RwLock::new(HashMap::new())),
             next_client_id: Arc::new(RwLock::new(1)),
             auth,
+            holds: Arc::new(AtomicUsize::new(0)),
         })
     }
 
@@ -64,6 +90,7 @@ impl UdpAudioStreamer {
             StreamClient {
                 addr,
                 last_seen: Instant::now(),
+                registered_at: Instant::now(),
                 client_type,
             },
         );
@@ -95,8 +122,9 @@ impl UdpAudioStreamer {
         &self,
         is_recording: bool,
         source_client_id: u32,
+        input_sample_rate: u32,
     ) -> Result<()> {
-        let data = RecordingStateData::new(is_recording);
+        let data = RecordingStateData::new(is_recording, input_sample_rate);
         let data_bytes = data.to_bytes();
 
         let header = PacketHeader::new(
@@ -259,11 +287,20 @@ impl UdpAudioStreamer {
 
     /// Internal method to broadcast a packet to all registered clients
     async fn broadcast_packet(&self, packet: &[u8]) -> Result<()> {
-        let mut clients = self.clients.write().await;
+        Self::broadcast_packet_to(&self.socket, &self.clients, packet).await
+    }
+
+    /// Broadcast a packet to the given clients, dropping any that can no longer be reached
+    async fn broadcast_packet_to(
+        socket: &UdpSocket,
+        clients: &RwLock<HashMap<String, StreamClient>>,
+        packet: &[u8],
+    ) -> Result<()> {
+        let mut clients = clients.write().await;
         let mut failed_clients = Vec::new();
 
         for (client_id, client) in clients.iter_mut() {
-            match self.socket.send_to(packet, &client.addr).await {
+            match socket.send_to(packet, &client.addr).await {
                 Ok(_) => {
                     // Update last_seen to prevent stale client cleanup
                     client.last_seen = Instant::now();
@@ -285,9 +322,38 @@ impl UdpAudioStreamer {
         Ok(())
     }
 
+    /// Keep registered clients alive until the returned hold is dropped
+    #[must_use]
+    pub fn hold_clients(&self) -> ClientHold {
+        self.holds.fetch_add(1, Ordering::SeqCst);
+        ClientHold {
+            holds: Arc::clone(&self.holds),
+        }
+    }
+
+    /// Drop clients not seen within the stale timeout, or refresh them all while held
+    fn sweep_stale_clients(clients: &mut HashMap<String, StreamClient>, held: bool) {
+        let now = Instant::now();
+        if held {
+            for client in clients.values_mut() {
+                client.last_seen = now;
+            }
+            return;
+        }
+
+        clients.retain(|client_id, client| {
+            let keep = now.duration_since(client.last_seen) <= STALE_CLIENT_TIMEOUT;
+            if !keep {
+                log::info!("Removed stale client: {client_id}");
+            }
+            keep
+        });
+    }
+
     /// Start a cleanup task to remove stale clients
     pub fn start_cleanup_task(&self, shutdown_tx: &broadcast::Sender<()>) {
         let clients = Arc::clone(&self.clients);
+        let holds = Arc::clone(&self.holds);
         let mut shutdown_rx = shutdown_tx.subscribe();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(30));
@@ -295,19 +361,7 @@ impl UdpAudioStreamer {
                 tokio::select! {
                     _ = interval.tick() => {
                         let mut clients_guard = clients.write().await;
-                        let now = Instant::now();
-                        let stale_timeout = Duration::from_secs(300); // 5 minutes
-
-                        let stale_clients: Vec<String> = clients_guard
-                            .iter()
-                            .filter(|(_, client)| now.duration_since(client.last_seen) > stale_timeout)
-                            .map(|(id, _)| id.clone())
-                            .collect();
-
-                        for client_id in stale_clients {
-                            clients_guard.remove(&client_id);
-                            log::info!("Removed stale client: {client_id}");
-                        }
+                        Self::sweep_stale_clients(&mut clients_guard, holds.load(Ordering::SeqCst) > 0);
                     }
                     _ = shutdown_rx.recv() => {
                         log::info!("UDP cleanup task shutting down gracefully");
@@ -318,6 +372,57 @@ impl UdpAudioStreamer {
         });
     }
 
+    /// Start a task that rotates the UDP auth secret every rotation interval
+    ///
+    /// Registered clients are sent a `SECRET_ROTATED_PACKET` and must re-register
+    /// within the grace period; those that don't are dropped.
+    pub fn start_secret_rotation_task(&self, shutdown_tx: &broadcast::Sender<()>) {
+        let socket = Arc::clone(&self.socket);
+        let clients = Arc::clone(&self.clients);
+        let auth = self.auth.clone();
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let rotation_interval = auth.rotation_interval();
+            let mut interval =
+                tokio::time::interval_at(Instant::now() + rotation_interval, rotation_interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = auth.rotate() {
+                            log::error!("Failed to rotate UDP auth secret: {e}");
+                            continue;
+                        }
+                        let rotated_at = Instant::now();
+
+                        let packet = PacketHeader::new(SECRET_ROTATED_PACKET, 0, 0).to_bytes();
+                        if let Err(e) = Self::broadcast_packet_to(&socket, &clients, &packet).await {
+                            log::warn!("Failed to broadcast secret rotation notice: {e}");
+                        }
+
+                        // Give clients the grace period to re-register with the new secret
+                        tokio::select! {
+                            () = tokio::time::sleep(SECRET_GRACE_PERIOD) => {}
+                            _ = shutdown_rx.recv() => break,
+                        }
+
+                        let mut clients_guard = clients.write().await;
+                        clients_guard.retain(|client_id, client| {
+                            let keep = client.registered_at >= rotated_at;
+                            if !keep {
+                                log::info!("Dropped client that did not re-register after secret rotation: {client_id}");
+                            }
+                            keep
+                        });
+                    }
+                    _ = shutdown_rx.recv() => {
+                        log::info!("UDP secret rotation task shutting down gracefully");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
     /// Get current client count
     pub async fn client_count(&self) -> usize {
         self.clients.read().await.len()
@@ -354,7 +459,7 @@ impl UdpAudioStreamer {
                         match result {
                             Ok((len, addr)) => {
                                 if len >= 8 && &buf[0..8] == b"REGISTER" {
-                                    // Authenticated registration protocol: "REGISTER:client_type:secret"
+                                    // Authenticated registration protocol: "REGISTER:client_type:timestamp_ms:nonce:mac"
                                     let msg = String::from_utf8_lossy(&buf[0..len]);
                                     log::debug!("Received UDP registration from {addr}");
 
@@ -367,6 +472,7 @@ impl UdpAudioStreamer {
                                                 StreamClient {
                                                     addr,
                                                     last_seen: Instant::now(),
+                                                    registered_at: Instant::now(),
                                                     client_type,
                                                 },
                                             );
@@ -423,6 +529,15 @@ impl UdpAudioStreamer {
         Ok(())
     }
 
+    /// The current UDP auth secret
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret file cannot be read.
+    pub fn secret(&self) -> Result<String> {
+        self.auth.get_or_create_secret()
+    }
+
     /// Clean up authentication resources
     ///
     /// # Errors
@@ -431,7 +546,7 @@ impl UdpAudioStreamer {
         self.auth.cleanup()
     }
 
-    /// Get the local socket address for testing purposes
+    /// Get the local socket address
     ///
     /// # Errors
     /// Throws error if it fails to get local address
@@ -551,6 +666,42 @@ mod tests {
         assert_eq!(clients.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_clients_survive_model_swap() {
+        let streamer = UdpAudioStreamer::new("127.0.0.1:0").await.unwrap();
+        streamer
+            .register_client("127.0.0.1:12349".parse().unwrap(), "test1".to_string())
+            .await;
+        streamer
+            .register_client("127.0.0.1:12350".parse().unwrap(), "test2".to_string())
+            .await;
+        let before = streamer.client_count().await;
+
+        // A long model load sends no packets, so clients age past the timeout
+        let hold = streamer.hold_clients();
+        {
+            let mut clients = streamer.clients.write().await;
+            for client in clients.values_mut() {
+                client.last_seen = Instant::now() - Duration::from_secs(400);
+            }
+            UdpAudioStreamer::sweep_stale_clients(
+                &mut clients,
+                streamer.holds.load(Ordering::SeqCst) > 0,
+            );
+        }
+        drop(hold);
+
+        // The first sweep after the swap must not drop them either
+        {
+            let mut clients = streamer.clients.write().await;
+            UdpAudioStreamer::sweep_stale_clients(
+                &mut clients,
+                streamer.holds.load(Ordering::SeqCst) > 0,
+            );
+        }
+        assert_eq!(streamer.client_count().await, before);
+    }
+
     #[tokio::test]
     async fn test_broadcast_packet_updates_timestamps() {
         let streamer = UdpAudioStreamer::new("127.0.0.1:0").await.unwrap();
@@ -0,0 +1,428 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Voice activity detection with the Silero VAD model
+//!
+//! Energy thresholding trips on keyboard clacks and breath. Silero VAD is a small
+//! network trained to tell speech apart from everything else; it runs here through
+//! candle on the CPU, over 32ms chunks of 16kHz audio. Its weights are the official
+//! release converted to safetensors by `scripts/convert-silero-vad.py`.
+
+use anyhow::{Context, Result};
+use candle_core::{DType, Device, Module, Tensor};
+use candle_nn::{Conv1d, Conv1dConfig, VarBuilder, ops::sigmoid};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+/// Rate the model was trained at; audio is resampled to it
+const MODEL_SAMPLE_RATE: u32 = 16000;
+/// Samples of each chunk the model decides on
+const CHUNK_SAMPLES: usize = 512;
+/// Samples of the previous chunk the model sees ahead of each new one
+const CONTEXT_SAMPLES: usize = 64;
+/// Window and hop of the model's short-time Fourier transform
+const STFT_WINDOW: usize = 256;
+const STFT_HOP: usize = 128;
+/// Size of the LSTM state carried from chunk to chunk
+const HIDDEN_SIZE: usize = 128;
+/// Encoder convolutions as (input channels, output channels, stride)
+const ENCODER: [(usize, usize, usize); 4] =
+    [(129, 128, 1), (128, 64, 2), (64, 64, 2), (64, 128, 1)];
+/// Probability above which a chunk starts speech, and below which speech ends
+const SPEECH_THRESHOLD: f32 = 0.5;
+const SILENCE_THRESHOLD: f32 = 0.35;
+/// Audio kept around the detected speech when gating what reaches the model,
+/// so word onsets and endings survive
+const SPEECH_MARGIN_MS: u32 = 300;
+
+/// How recordings decide where speech starts and ends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadMode {
+    /// Adaptive energy threshold only
+    #[default]
+    Energy,
+    /// Energy threshold confirmed by voice activity detection
+    Vad,
+}
+
+/// Silero VAD v5 network for 16kHz audio; clones share the weights
+#[derive(Debug, Clone)]
+pub struct SileroVad {
+    // Real and imaginary STFT bases stacked along the output channels
+    stft_basis: Tensor,
+    encoder: Vec<Conv1d>,
+    weight_ih: Tensor,
+    weight_hh: Tensor,
+    // Sum of the LSTM cell's input and hidden biases
+    bias: Tensor,
+    output: Conv1d,
+}
+
+impl SileroVad {
+    /// Where the converted weights are looked for unless configured otherwise
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+                PathBuf::from(home).join(".local").join("share")
+            })
+            .join("super-stt")
+            .join("models")
+            .join("silero_vad.safetensors")
+    }
+
+    /// Load the weights converted to safetensors from `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or lacks a tensor of the model.
+    pub fn load(path: &Path) -> Result<Self> {
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[path], DType::F32, &Device::Cpu) }
+            .with_context(|| format!("Failed to load VAD weights from {}", path.display()))?;
+        Self::new(&vb).with_context(|| format!("Invalid VAD weights in {}", path.display()))
+    }
+
+    fn new(vb: &VarBuilder) -> Result<Self> {
+        let encoder = ENCODER
+            .iter()
+            .enumerate()
+            .map(|(i, &(input, output, stride))| {
+                let config = Conv1dConfig {
+                    padding: 1,
+                    stride,
+                    ..Conv1dConfig::default()
+                };
+                candle_nn::conv1d(
+                    input,
+                    output,
+                    3,
+                    config,
+                    vb.pp(format!("encoder.{i}.reparam_conv")),
+                )
+            })
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let rnn = vb.pp("decoder.rnn");
+        let gates = 4 * HIDDEN_SIZE;
+        Ok(Self {
+            stft_basis: vb.get(
+                (STFT_WINDOW + 2, 1, STFT_WINDOW),
+                "stft.forward_basis_buffer",
+            )?,
+            encoder,
+            weight_ih: rnn.get((gates, HIDDEN_SIZE), "weight_ih")?,
+            weight_hh: rnn.get((gates, HIDDEN_SIZE), "weight_hh")?,
+            bias: (rnn.get(gates, "bias_ih")? + rnn.get(gates, "bias_hh")?)?,
+            output: candle_nn::conv1d(
+                HIDDEN_SIZE,
+                1,
+                1,
+                Conv1dConfig::default(),
+                vb.pp("decoder.decoder.2"),
+            )?,
+        })
+    }
+
+    /// Zeroed LSTM state for the start of a stream
+    fn initial_state() -> Result<(Tensor, Tensor)> {
+        let zeros = Tensor::zeros((1, HIDDEN_SIZE), DType::F32, &Device::Cpu)?;
+        Ok((zeros.clone(), zeros))
+    }
+
+    /// Speech probability of the last `CHUNK_SAMPLES` of `input`, which starts with
+    /// `CONTEXT_SAMPLES` of the previous chunk, advancing the LSTM `state`
+    fn forward(&self, input: &[f32], state: &mut (Tensor, Tensor)) -> Result<f32> {
+        // Reflection padding on the right, as the model was trained with
+        let padded: Vec<f32> = input
+            .iter()
+            .copied()
+            .chain(input.iter().rev().skip(1).take(CONTEXT_SAMPLES).copied())
+            .collect();
+        let len = padded.len();
+        let x = Tensor::from_vec(padded, (1, 1, len), &Device::Cpu)?;
+
+        let spectrum = x.conv1d(&self.stft_basis, 0, STFT_HOP, 1, 1)?;
+        let bins = STFT_WINDOW / 2 + 1;
+        let real = spectrum.narrow(1, 0, bins)?;
+        let imag = spectrum.narrow(1, bins, bins)?;
+        let mut x = (real.sqr()? + imag.sqr()?)?.sqrt()?;
+        for conv in &self.encoder {
+            x = conv.forward(&x)?.relu()?;
+        }
+        let x = x.squeeze(2)?;
+
+        let (h, c) = &*state;
+        let gates = (x.matmul(&self.weight_ih.t()?)? + h.matmul(&self.weight_hh.t()?)?)?
+            .broadcast_add(&self.bias)?
+            .chunk(4, 1)?;
+        let c = ((sigmoid(&gates[1])? * c)? + (sigmoid(&gates[0])? * gates[2].tanh()?)?)?;
+        let h = (sigmoid(&gates[3])? * c.tanh()?)?;
+
+        let logit = self.output.forward(&h.relu()?.unsqueeze(2)?)?;
+        let probability = sigmoid(&logit)?.flatten_all()?.to_vec1::<f32>()?[0];
+        *state = (h, c);
+        Ok(probability)
+    }
+}
+
+/// Streaming detector fed with mono audio at the capture rate
+#[derive(Debug, Clone)]
+pub struct VoiceActivityDetector {
+    model: SileroVad,
+    state: (Tensor, Tensor),
+    // Capture samples per model sample, and where the next model sample falls
+    // between the previous capture sample and the next one
+    step: f64,
+    phase: f64,
+    previous: f32,
+    // Context of the previous chunk followed by the chunk being filled
+    chunk: Vec<f32>,
+    speech: bool,
+    failed: bool,
+}
+
+impl VoiceActivityDetector {
+    /// # Errors
+    ///
+    /// Returns an error if the model's initial state cannot be allocated.
+    pub fn new(model: SileroVad, sample_rate: u32) -> Result<Self> {
+        Ok(Self {
+            model,
+            state: SileroVad::initial_state()?,
+            step: f64::from(sample_rate.max(1)) / f64::from(MODEL_SAMPLE_RATE),
+            phase: 0.0,
+            previous: 0.0,
+            chunk: vec![0.0; CONTEXT_SAMPLES],
+            speech: false,
+            failed: false,
+        })
+    }
+
+    /// Feed `samples` and return whether the latest complete chunk was speech
+    pub fn is_speech(&mut self, samples: &[f32]) -> bool {
+        for &sample in samples {
+            self.push(sample);
+        }
+        self.speech
+    }
+
+    /// Feed one capture sample, returning the decision of the chunk it completed, if any
+    #[allow(clippy::cast_possible_truncation)]
+    fn push(&mut self, sample: f32) -> Option<bool> {
+        // Linear interpolation down to the model's rate
+        let mut decision = None;
+        while self.phase < 1.0 {
+            let resampled = self.previous + (sample - self.previous) * self.phase as f32;
+            self.chunk.push(resampled);
+            if self.chunk.len() == CONTEXT_SAMPLES + CHUNK_SAMPLES {
+                decision = Some(self.decide());
+            }
+            self.phase += self.step;
+        }
+        self.phase -= 1.0;
+        self.previous = sample;
+        decision
+    }
+
+    /// Run the model on the filled chunk, keeping its tail as the next context
+    ///
+    /// A failing model never vetoes speech, so recordings fall back to energy alone.
+    fn decide(&mut self) -> bool {
+        match self.model.forward(&self.chunk, &mut self.state) {
+            Ok(probability) if probability >= SPEECH_THRESHOLD => self.speech = true,
+            Ok(probability) if probability < SILENCE_THRESHOLD => self.speech = false,
+            Ok(_) => {}
+            Err(e) => {
+                if !self.failed {
+                    log::warn!("VAD failed, falling back to energy thresholding: {e}");
+                    self.failed = true;
+                }
+                self.speech = true;
+            }
+        }
+        self.chunk.drain(..CHUNK_SAMPLES);
+        self.speech
+    }
+
+    /// Capture samples covered by one chunk
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn chunk_len(&self) -> usize {
+        (CHUNK_SAMPLES as f64 * self.step).ceil() as usize
+    }
+}
+
+/// A detector running on its own thread, so the audio callback never waits on the model
+///
+/// The callback hands over its samples and reads the latest decision, which trails
+/// the audio by the chunk being filled and the time the model takes. The thread
+/// exits once the worker and its clones are dropped.
+#[derive(Debug, Clone)]
+pub struct VadWorker {
+    samples_tx: mpsc::Sender<Vec<f32>>,
+    speech: Arc<AtomicBool>,
+}
+
+impl VadWorker {
+    /// # Errors
+    ///
+    /// Returns an error if the thread cannot be spawned.
+    pub fn spawn(mut detector: VoiceActivityDetector) -> Result<Self> {
+        let (samples_tx, samples_rx) = mpsc::channel::<Vec<f32>>();
+        let speech = Arc::new(AtomicBool::new(detector.speech));
+        let decision = Arc::clone(&speech);
+        std::thread::Builder::new()
+            .name("vad".to_string())
+            .spawn(move || {
+                while let Ok(samples) = samples_rx.recv() {
+                    decision.store(detector.is_speech(&samples), Ordering::Relaxed);
+                }
+            })
+            .context("Failed to spawn the VAD thread")?;
+        Ok(Self { samples_tx, speech })
+    }
+
+    /// Queue `samples` for the detector
+    pub fn push(&self, samples: &[f32]) {
+        // The thread only stops once every sender is gone
+        let _ = self.samples_tx.send(samples.to_vec());
+    }
+
+    /// Whether the latest chunk the detector finished was speech
+    #[must_use]
+    pub fn is_speech(&self) -> bool {
+        self.speech.load(Ordering::Relaxed)
+    }
+}
+
+/// Range of `samples` from the first to the last speech chunk, widened by a margin,
+/// or `None` if no chunk holds speech
+#[must_use]
+pub fn speech_bounds(model: &SileroVad, samples: &[f32], sample_rate: u32) -> Option<Range<usize>> {
+    let mut detector = match VoiceActivityDetector::new(model.clone(), sample_rate) {
+        Ok(detector) => detector,
+        Err(e) => {
+            log::warn!("Failed to start the VAD: {e}");
+            return Some(0..samples.len());
+        }
+    };
+    let chunk_len = detector.chunk_len();
+    let mut first = None;
+    let mut last = 0;
+    for (i, &sample) in samples.iter().enumerate() {
+        if detector.push(sample) == Some(true) {
+            first.get_or_insert((i + 1).saturating_sub(chunk_len));
+            last = i + 1;
+        }
+    }
+
+    let margin = (sample_rate * SPEECH_MARGIN_MS / 1000) as usize;
+    first.map(|first| first.saturating_sub(margin)..(last + margin).min(samples.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vad_runs_a_chunk_per_32ms() {
+        // All-zero weights put every chunk at a probability of exactly one half
+        let model = SileroVad::new(&VarBuilder::zeros(DType::F32, &Device::Cpu)).unwrap();
+        let mut state = SileroVad::initial_state().unwrap();
+        let input = vec![0.1; CONTEXT_SAMPLES + CHUNK_SAMPLES];
+        assert!((model.forward(&input, &mut state).unwrap() - 0.5).abs() < f32::EPSILON);
+
+        let mut detector = VoiceActivityDetector::new(model.clone(), 48000).unwrap();
+        let decisions = (0..48000).filter_map(|_| detector.push(0.1)).count();
+        assert_eq!(decisions, 48000 / 3 / CHUNK_SAMPLES);
+
+        let bounds = speech_bounds(&model, &vec![0.1; 32000], 16000).expect("speech found");
+        assert_eq!(bounds, 0..32000);
+    }
+
+    /// Zeroed tensor of `shape` with the flat indices of `set` filled in
+    fn tensor(shape: &[usize], set: &[(usize, f32)]) -> Tensor {
+        let mut values = vec![0.0; shape.iter().product()];
+        for &(index, value) in set {
+            values[index] = value;
+        }
+        Tensor::from_vec(values, shape, &Device::Cpu).unwrap()
+    }
+
+    /// Zero weights except for a path that carries the magnitude of each chunk's
+    /// first sample through to the output, so the probability is near one for loud
+    /// chunks and near zero for silent ones
+    fn loudness_model() -> SileroVad {
+        let gates = 4 * HIDDEN_SIZE;
+        let mut tensors = std::collections::HashMap::new();
+        tensors.insert(
+            "stft.forward_basis_buffer".to_string(),
+            tensor(&[STFT_WINDOW + 2, 1, STFT_WINDOW], &[(0, 1.0)]),
+        );
+        for (i, &(input, output, _)) in ENCODER.iter().enumerate() {
+            // Centre tap from the first channel to the first channel
+            tensors.insert(
+                format!("encoder.{i}.reparam_conv.weight"),
+                tensor(&[output, input, 3], &[(1, 1.0)]),
+            );
+            tensors.insert(
+                format!("encoder.{i}.reparam_conv.bias"),
+                tensor(&[output], &[]),
+            );
+        }
+        // The first cell's candidate follows the input and its forget gate stays shut
+        tensors.insert(
+            "decoder.rnn.weight_ih".to_string(),
+            tensor(
+                &[gates, HIDDEN_SIZE],
+                &[(2 * HIDDEN_SIZE * HIDDEN_SIZE, 10.0)],
+            ),
+        );
+        tensors.insert(
+            "decoder.rnn.weight_hh".to_string(),
+            tensor(&[gates, HIDDEN_SIZE], &[]),
+        );
+        tensors.insert(
+            "decoder.rnn.bias_ih".to_string(),
+            tensor(&[gates], &[(HIDDEN_SIZE, -10.0)]),
+        );
+        tensors.insert("decoder.rnn.bias_hh".to_string(), tensor(&[gates], &[]));
+        tensors.insert(
+            "decoder.decoder.2.weight".to_string(),
+            tensor(&[1, HIDDEN_SIZE, 1], &[(0, 100.0)]),
+        );
+        tensors.insert(
+            "decoder.decoder.2.bias".to_string(),
+            tensor(&[1], &[(0, -10.0)]),
+        );
+        SileroVad::new(&VarBuilder::from_tensors(tensors, DType::F32, &Device::Cpu)).unwrap()
+    }
+
+    #[test]
+    fn test_speech_bounds_trims_silence() {
+        let model = loudness_model();
+
+        // One second of silence, one of tone, one of silence
+        let mut samples = vec![0.0; 16000];
+        samples.extend((0..16000).map(|i| if i % 2 == 0 { 0.5 } else { -0.5 }));
+        samples.extend(vec![0.0; 16000]);
+
+        let bounds = speech_bounds(&model, &samples, 16000).expect("speech found");
+        // The tone survives with its margin, give or take a chunk, and the rest goes
+        let margin = 16000 * SPEECH_MARGIN_MS as usize / 1000;
+        let slack = 2 * CHUNK_SAMPLES;
+        assert!(bounds.start <= 16000 - margin + slack, "{bounds:?}");
+        assert!(bounds.start >= 16000 - margin - slack, "{bounds:?}");
+        assert!(bounds.end >= 32000 + margin - slack, "{bounds:?}");
+        assert!(bounds.end <= 32000 + margin + slack, "{bounds:?}");
+
+        assert_eq!(speech_bounds(&model, &vec![0.0; 16000], 16000), None);
+    }
+}
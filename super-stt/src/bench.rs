@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Offline benchmark of a model on one audio file, for comparing models and devices
+//!
+//! The model is loaded in-process the way the daemon loads it, so no daemon needs to
+//! run. After one untimed warm-up pass the file is transcribed `runs` times; each run
+//! covers audio processing and inference, as a transcription request does.
+
+use crate::config::DaemonConfig;
+use crate::daemon::types::SuperSTTDaemon;
+use crate::input::audio::AudioProcessor;
+use crate::stt_models::TextRules;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use super_stt_shared::stt_model::STTModel;
+
+/// Timings of a benchmark, in milliseconds unless noted
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub model: STTModel,
+    /// Device the model ended up on, after any fallback to CPU
+    pub device: String,
+    pub audio_seconds: f64,
+    pub runs: usize,
+    pub load_ms: u64,
+    pub mean_ms: u64,
+    pub p95_ms: u64,
+    /// Audio seconds per second of processing; above 1.0 is faster than realtime
+    pub realtime_factor: f64,
+    pub words_per_second: f64,
+    pub transcription: String,
+}
+
+impl BenchReport {
+    /// Print the report as an aligned table
+    pub fn print_table(&self) {
+        let rows = [
+            ("Model", self.model.to_string()),
+            ("Device", self.device.clone()),
+            ("Audio", format!("{:.1}s", self.audio_seconds)),
+            ("Runs", self.runs.to_string()),
+            ("Load", format!("{}ms", self.load_ms)),
+            ("Mean latency", format!("{}ms", self.mean_ms)),
+            ("p95 latency", format!("{}ms", self.p95_ms)),
+            ("Realtime factor", format!("{:.2}x", self.realtime_factor)),
+            (
+                "Throughput",
+                format!("{:.1} words/s", self.words_per_second),
+            ),
+        ];
+        for (label, value) in rows {
+            println!("{label:<16} {value}");
+        }
+    }
+}
+
+/// Load `model` and time `runs` transcriptions of the audio file at `path`
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be decoded, the model is not downloaded or
+/// fails to load, or a transcription fails.
+#[allow(clippy::cast_precision_loss)]
+pub fn run(path: &Path, model: STTModel, device: &str, runs: usize) -> Result<BenchReport> {
+    let config = DaemonConfig::load();
+    let source = &config.transcription.model_source;
+    if crate::stt_models::download::downloaded_model_size(model, source).is_none() {
+        anyhow::bail!("{model} is not downloaded; select it in the daemon once to fetch it");
+    }
+
+    let samples = SuperSTTDaemon::decode_audio_file(path)
+        .with_context(|| format!("Failed to decode {}", path.display()))?;
+    let audio_seconds = samples.len() as f64 / 16000.0;
+    let processor = AudioProcessor::new()
+        .with_noise_suppression(config.audio.noise_suppression)
+        .with_trim_silence(config.audio.trim_silence)
+        .with_resample_quality(config.audio.resample_quality)
+        .with_agc_target_rms(config.audio.agc_target());
+
+    let load_started = Instant::now();
+    let mut instance =
+        SuperSTTDaemon::load_model_sync(model, source, device, config.transcription.decoding)?;
+    let load_time = load_started.elapsed();
+    let device = match instance.device() {
+        candle_core::Device::Cpu => "cpu",
+        candle_core::Device::Cuda(_) => "cuda",
+        candle_core::Device::Metal(_) => "metal",
+    };
+
+    let mut transcribe = || -> Result<String> {
+        let processed = processor.process_audio(&samples, 16000)?;
+        let (text, _) = instance.transcribe_audio_with_confidence(
+            &processed,
+            16000,
+            None,
+            &TextRules::default(),
+        )?;
+        Ok(text)
+    };
+    // The first pass pays for kernel compilation and allocations
+    let mut transcription = transcribe().context("Warm-up transcription failed")?;
+
+    let mut durations = Vec::with_capacity(runs);
+    for run in 1..=runs {
+        let started = Instant::now();
+        transcription = transcribe().with_context(|| format!("Run {run} failed"))?;
+        durations.push(started.elapsed());
+    }
+
+    let mean = durations.iter().sum::<Duration>() / u32::try_from(runs).unwrap_or(u32::MAX);
+    let words = transcription.split_whitespace().count();
+    let to_ms = |d: Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+    Ok(BenchReport {
+        model,
+        device: device.to_string(),
+        audio_seconds,
+        runs,
+        load_ms: to_ms(load_time),
+        mean_ms: to_ms(mean),
+        p95_ms: to_ms(percentile(&mut durations, 95)),
+        realtime_factor: audio_seconds / mean.as_secs_f64().max(f64::EPSILON),
+        words_per_second: words as f64 / mean.as_secs_f64().max(f64::EPSILON),
+        transcription,
+    })
+}
+
+/// Nearest-rank `percent`th percentile, zero when there are no durations
+fn percentile(durations: &mut [Duration], percent: usize) -> Duration {
+    if durations.is_empty() {
+        return Duration::ZERO;
+    }
+    durations.sort_unstable();
+    let rank = (durations.len() * percent).div_ceil(100).max(1);
+    durations[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_uses_nearest_rank() {
+        let mut durations: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        assert_eq!(percentile(&mut durations, 95), Duration::from_millis(19));
+        assert_eq!(percentile(&mut durations, 50), Duration::from_millis(10));
+        assert_eq!(
+            percentile(&mut [Duration::from_millis(7)], 95),
+            Duration::from_millis(7)
+        );
+        assert_eq!(percentile(&mut [], 95), Duration::ZERO);
+    }
+}
@@ -26,6 +26,7 @@ pub static DEFAULT_MODEL_STR: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(DEFAULT_MODEL.to_string().into_boxed_str()));
 
 #[must_use]
+#[allow(clippy::too_many_lines)]
 pub fn build() -> Command {
     command!()
     .about("🎙️ Super STT Daemon - Advanced Speech-to-text for Linux")
@@ -42,6 +43,102 @@ pub fn build() -> Command {
                 arg!(-w --write "Type the transcription directly into the active window")
                 .action(ArgAction::SetTrue)
             )
+            .arg(
+                arg!(--stream "Wait for the recording and print partial transcriptions as they arrive")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                arg!(--output <output> "Where to put the transcription; implies --write")
+                .value_parser(["type", "clipboard", "both"])
+                .conflicts_with("stream")
+            )
+            .arg(
+                arg!(--queue "Wait for a recording already in progress instead of failing")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stream")
+            )
+            .arg(
+                arg!(-s --socket <socket> "The daemon socket path")
+                .default_value(*DEFAULT_SOCKET_PATH_STR)
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::AnyPath)
+            )
+    )
+    .subcommand(
+        Command::new("transcribe")
+            .about("📄 Transcribe an audio file")
+            .long_about("Send a WAV or FLAC file to the running daemon and print the transcription using the loaded model.")
+            .arg(
+                arg!(<path> "Path to the audio file")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::FilePath)
+            )
+            .arg(
+                arg!(-s --socket <socket> "The daemon socket path")
+                .default_value(*DEFAULT_SOCKET_PATH_STR)
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::AnyPath)
+            )
+    )
+    .subcommand(
+        Command::new("detect-language")
+            .about("🌐 Detect the language spoken in an audio file")
+            .long_about("Run the first 30 seconds of a WAV or FLAC file through the loaded model's language identification and print the most likely languages.")
+            .arg(
+                arg!(<path> "Path to the audio file")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::FilePath)
+            )
+            .arg(
+                arg!(-s --socket <socket> "The daemon socket path")
+                .default_value(*DEFAULT_SOCKET_PATH_STR)
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::AnyPath)
+            )
+    )
+    .subcommand(
+        Command::new("bench")
+            .about("⏱️ Benchmark a model on an audio file")
+            .long_about("Load a model in this process, transcribe a WAV or FLAC file repeatedly and report mean and p95 latency, realtime factor and words per second. The daemon does not need to be running, but the model must be downloaded.")
+            .arg(
+                arg!(<path> "Path to the audio file")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::FilePath)
+            )
+            .arg(
+                arg!(-m --model <model> "The model to benchmark (defaults to the configured model)")
+                .value_parser(value_parser!(STTModel))
+            )
+            .arg(
+                arg!(--device <device> "Device to run the model on")
+                .default_value("cuda")
+                .value_parser(["cuda", "cpu"])
+            )
+            .arg(
+                arg!(-n --runs <runs> "Number of timed transcriptions")
+                .default_value("5")
+                .value_parser(value_parser!(u16).range(1..))
+            )
+    )
+    .subcommand(
+        Command::new("rm-model")
+            .about("🗑️ Delete a downloaded model")
+            .long_about("Remove a model's files from the download cache to free disk space. The model the daemon has loaded cannot be deleted.")
+            .arg(
+                arg!(<model> "The model to delete")
+                .value_parser(value_parser!(STTModel))
+            )
+            .arg(
+                arg!(-s --socket <socket> "The daemon socket path")
+                .default_value(*DEFAULT_SOCKET_PATH_STR)
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::AnyPath)
+            )
+    )
+    .subcommand(
+        Command::new("unload-model")
+            .about("💤 Free the memory held by the loaded model")
+            .long_about("Drop the loaded model while keeping the daemon running, releasing its RAM or VRAM. The next recording or transcription reloads it.")
             .arg(
                 arg!(-s --socket <socket> "The daemon socket path")
                 .default_value(*DEFAULT_SOCKET_PATH_STR)
@@ -59,6 +156,21 @@ pub fn build() -> Command {
             .about("📊 Get daemon status")
             .long_about("Get detailed status information from the daemon including model and device information.")
     )
+    .subcommand(
+        Command::new("health")
+            .about("🩺 Check daemon subsystems")
+            .long_about("Report whether the model is loaded, the UDP streamer is bound, D-Bus is connected and an input device is available, with the last error of each. Exits with 1 unless every subsystem is up, for use from watchdogs.")
+    )
+    .subcommand(
+        Command::new("preview-undo")
+            .about("↩️ Undo the last preview typing correction")
+            .long_about("Restore the preview text that the most recent model pass replaced during a recording. Bind this to a keyboard shortcut to revert bad rewrites as they happen.")
+    )
+    .subcommand(
+        Command::new("preview-redo")
+            .about("↪️ Redo the last undone preview typing correction")
+            .long_about("Reapply the preview update most recently reverted with preview-undo.")
+    )
     .arg(
         arg!(-m --model <model> "The model to use for transcription")
         .default_value(*DEFAULT_MODEL_STR)
@@ -84,15 +196,38 @@ pub fn build() -> Command {
         .default_value("false")
         .action(ArgAction::SetTrue)
     )
+    .arg(
+        arg!(--"log-format" <format> "Daemon log format")
+        .default_value("pretty")
+        .env("SUPER_STT_LOG_FORMAT")
+        .help("Choose log output: pretty (human readable lines) or json (one object per line, for log aggregators)")
+        .value_parser(["pretty", "json"])
+    )
+    .arg(
+        arg!(-c --config <path> "Config file to use instead of ~/.config/super-stt/daemon.toml")
+        .help("Config file to read and save settings to, so several daemons with their own --socket can run side by side")
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::FilePath)
+    )
     .arg(
         arg!(--"udp-port" <port> "UDP port for audio streaming")
         .default_value("8765")
         .value_parser(value_parser!(u16))
     )
+    .arg(
+        arg!(--"udp-bind-addr" <addr> "Address for the UDP audio streamer to bind to")
+        .help("IPv4 or IPv6 address for UDP audio streaming, e.g. 127.0.0.1 or ::1 (saved to the daemon config)")
+        .value_parser(value_parser!(std::net::IpAddr))
+    )
     .arg(
         arg!(--"audio-theme" <theme> "Audio feedback theme")
         .default_value("classic")
         .help("Choose audio feedback style: classic, gentle, minimal, scifi, musical, nature, retro, silent")
         .value_parser(["classic", "gentle", "minimal", "scifi", "musical", "nature", "retro", "silent"])
     )
+    .arg(
+        arg!(--json "Print the raw daemon response as JSON (status, health and ping) or the bench report")
+        .action(ArgAction::SetTrue)
+        .global(true)
+    )
 }
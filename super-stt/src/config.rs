@@ -1,57 +1,502 @@
 // SPDX-License-Identifier: GPL-3.0-only
-use log::{debug, error, warn};
+use crate::audio::beeper::CustomSoundPaths;
+use crate::audio::recorder::{FrequencyBandsConfig, SilenceConfig};
+use crate::daemon::idle::IdleAction;
+use crate::input::noise::NoiseSuppressionConfig;
+use crate::input::trim::TrimSilenceConfig;
+use crate::output::focus::FocusFilterConfig;
+use crate::output::keyboard::TypingBackend;
+use crate::output::preview::{FormattingConfig, MAX_COMMIT_PASSES};
+use crate::services::archive::ArchiveConfig;
+use crate::services::power::BatteryAction;
+use crate::stt_models::download::{DownloadLimits, ModelSource};
+use crate::stt_models::voxtral::model::DecodingConfig;
+use crate::stt_models::word_filter::WordFilterConfig;
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::OnceLock;
+use super_stt_shared::AudioAnalyzer;
+use super_stt_shared::audio_utils::ResampleQuality;
 use super_stt_shared::stt_model::STTModel;
 use super_stt_shared::theme::AudioTheme;
+use super_stt_shared::validation;
+
+/// Layout version written to new config files
+///
+/// Bump it when a field is renamed, moved or reshaped, and teach [`migrate`] to
+/// upgrade files of the previous version. Fields that only add a default need no bump.
+pub const CONFIG_VERSION: u32 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DaemonConfig {
+    #[serde(default)] // Files from before versioning have none, which counts as 0
+    pub version: u32,
     pub device: DeviceConfig,
     pub audio: AudioConfig,
     pub transcription: TranscriptionConfig,
+    #[serde(default)] // For backwards compatibility with existing configs
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub archive_recordings: ArchiveConfig,
+    #[serde(default)]
+    pub debug: DebugConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceConfig {
     pub preferred_device: String, // "cpu" or "cuda"
+    #[serde(default = "default_warmup_on_load")]
+    pub warmup_on_load: bool, // Prime each freshly loaded model before reporting it ready
+    #[serde(default)]
+    pub idle_timeout_minutes: u32, // Unload the model after this long without recordings, 0 never does
+    #[serde(default)]
+    pub idle_action: IdleAction, // "unload" keeps the daemon running to reload on demand, "exit" stops it
+    #[serde(default)]
+    pub battery_action: BatteryAction, // "cpu" moves a CUDA model to the CPU on battery, "unload" drops it, "keep" does nothing
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
     pub theme: AudioTheme,
+    #[serde(default)] // For backwards compatibility with existing configs
+    pub silence: SilenceConfig,
+    #[serde(default)]
+    pub frequency_bands: FrequencyBandsConfig,
+    #[serde(default)]
+    pub input_device: Option<String>, // Last selected capture device, `None` for the default
+    #[serde(default)]
+    pub capture_sample_rate: u32, // Rate to open the device at when it supports it, e.g. 16000 to skip resampling; 0 picks one
+    #[serde(default)]
+    pub resample_quality: ResampleQuality, // "fast" or "high", the windowed-sinc filter that costs more CPU but aliases less
+    #[serde(default = "default_record_queue_depth")]
+    pub record_queue_depth: usize, // Queued `record` requests allowed behind the active one, 0 rejects them
+    #[serde(default)]
+    pub noise_suppression: NoiseSuppressionConfig, // High-pass filter and spectral gate, off by default
+    #[serde(default)]
+    pub trim_silence: TrimSilenceConfig, // Silence cut from before and after the speech, on by default
+    #[serde(default)]
+    pub vad_model: Option<PathBuf>, // Silero VAD weights used when `silence.vad_mode` is "vad", `None` for the data directory
+    #[serde(default)]
+    pub agc_enabled: bool, // Automatic gain control for quiet microphones
+    #[serde(default = "default_agc_target_rms")]
+    pub agc_target_rms: f32, // Speech level the gain control aims for, 0.0 < target <= 1.0
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32, // Audio kept from before each recording starts, 0 stops the background capture
+    #[serde(default = "default_min_audio_ms")]
+    pub min_audio_ms: u32, // Shorter recordings are reported as no speech instead of transcribed
+    #[serde(default)]
+    pub custom_sounds: CustomSoundPaths, // WAV files played by the `custom` theme
+    #[serde(default = "default_theme_volume")]
+    pub theme_volume: f32, // Loudness of the theme's sounds, 0.0 to 1.0
+    #[serde(default)]
+    pub theme_muted: bool, // Silence the theme's sounds while keeping their volume
+}
+
+impl AudioConfig {
+    /// Target level of the automatic gain control, `None` when it is off
+    #[must_use]
+    pub fn agc_target(&self) -> Option<f32> {
+        self.agc_enabled.then_some(self.agc_target_rms)
+    }
+
+    /// Gain applied to the theme's sounds, 0.0 when they are muted
+    #[must_use]
+    pub fn theme_gain(&self) -> f32 {
+        if self.theme_muted {
+            0.0
+        } else {
+            self.theme_volume.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Check the noise suppression and silence trimming fields, as part of
+    /// [`DaemonConfig::validate`]
+    fn validate_processing(&self) -> Result<(), ConfigFieldError> {
+        let noise = &self.noise_suppression;
+        if !(0.0..1000.0).contains(&noise.high_pass_hz) {
+            return Err(ConfigFieldError::new(
+                "audio.noise_suppression.high_pass_hz",
+                "expected 0 <= high_pass_hz < 1000",
+            ));
+        }
+        if !(0.0..=40.0).contains(&noise.gate_threshold_db) {
+            return Err(ConfigFieldError::new(
+                "audio.noise_suppression.gate_threshold_db",
+                "expected 0 <= gate_threshold_db <= 40",
+            ));
+        }
+        if !(0.0..=60.0).contains(&noise.gate_reduction_db) {
+            return Err(ConfigFieldError::new(
+                "audio.noise_suppression.gate_reduction_db",
+                "expected 0 <= gate_reduction_db <= 60",
+            ));
+        }
+
+        let trim = &self.trim_silence;
+        if !(0.0..=0.5).contains(&trim.floor_rms) {
+            return Err(ConfigFieldError::new(
+                "audio.trim_silence.floor_rms",
+                "expected 0.0 <= floor_rms <= 0.5",
+            ));
+        }
+        if trim.padding_ms > 2000 {
+            return Err(ConfigFieldError::new(
+                "audio.trim_silence.padding_ms",
+                "expected padding_ms <= 2000",
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct TranscriptionConfig {
     pub preferred_model: STTModel,
     pub write_mode: bool, // Auto-type transcriptions
+    #[serde(default)]
+    pub typing_backend: TypingBackend, // "enigo", "xdotool" or "clipboard_paste"
     #[serde(default)] // For backwards compatibility with existing configs
     pub preview_typing_enabled: bool, // Beta feature: show preview while typing
+    #[serde(default)]
+    pub preview_commit_passes: usize, // Previews a word must stay unchanged in before it is typed, 0 types it right away
+    #[serde(default)]
+    pub voice_commands_enabled: bool, // Turn spoken "period", "new line", ... into keystrokes
+    #[serde(default)]
+    pub formatting: FormattingConfig, // Capitalization, final period, whitespace and trailing space, all on by default
+    #[serde(default)]
+    pub focus_filter: FocusFilterConfig, // App ids or WM_CLASS names typed into or never typed into
+    #[serde(default)]
+    pub diarization_enabled: bool, // Label timestamped segments by speaker, needs the diarization feature
+    #[serde(default = "default_max_file_size_mb")]
+    pub max_file_size_mb: u64, // Upper bound for files passed to `transcribe_file`
+    #[serde(default)]
+    pub replacements: Vec<(String, String)>, // `(pattern, replacement)` rules applied in order
+    #[serde(default)]
+    pub word_filter: WordFilterConfig, // Whole words masked or dropped after the replacements
+    #[serde(default)]
+    pub model_source: ModelSource, // "hf://", "file:///path" or a Hub mirror's base URL
+    #[serde(default)]
+    pub download_max_bandwidth_kbps: u32, // Combined model download rate in kbit/s, 0 for unlimited
+    #[serde(default = "default_download_parallel_files")]
+    pub download_parallel_files: usize, // Model files fetched at the same time
+    #[serde(default)]
+    pub decoding: DecodingConfig, // Voxtral token limit and sampling; Whisper ignores it
+}
+
+fn default_max_file_size_mb() -> u64 {
+    100
+}
+
+impl TranscriptionConfig {
+    /// Bandwidth and concurrency limits for model downloads
+    #[must_use]
+    pub fn download_limits(&self) -> DownloadLimits {
+        DownloadLimits {
+            max_bandwidth_kbps: self.download_max_bandwidth_kbps,
+            parallel_files: self.download_parallel_files,
+        }
+    }
+
+    /// Check the transcription fields, as part of [`DaemonConfig::validate`]
+    fn validate(&self) -> Result<(), ConfigFieldError> {
+        if self.max_file_size_mb == 0 {
+            return Err(ConfigFieldError::new(
+                "transcription.max_file_size_mb",
+                "must be greater than 0",
+            ));
+        }
+
+        if self.preview_commit_passes > MAX_COMMIT_PASSES {
+            return Err(ConfigFieldError::new(
+                "transcription.preview_commit_passes",
+                format!("expected preview_commit_passes <= {MAX_COMMIT_PASSES}"),
+            ));
+        }
+
+        validation::validate_replacements(&self.replacements)
+            .map_err(|e| ConfigFieldError::new("transcription.replacements", e.to_string()))?;
+        validation::validate_filtered_words(&self.word_filter.words)
+            .map_err(|e| ConfigFieldError::new("transcription.word_filter.words", e.to_string()))?;
+
+        let focus_filter = &self.focus_filter;
+        if focus_filter
+            .allow
+            .iter()
+            .chain(&focus_filter.block)
+            .any(|name| name.trim().is_empty())
+        {
+            return Err(ConfigFieldError::new(
+                "transcription.focus_filter",
+                "application names must not be empty",
+            ));
+        }
+
+        if let ModelSource::Local(root) = &self.model_source
+            && !root.is_dir()
+        {
+            return Err(ConfigFieldError::new(
+                "transcription.model_source",
+                format!("{} is not a directory", root.display()),
+            ));
+        }
+
+        if self.download_parallel_files == 0 {
+            return Err(ConfigFieldError::new(
+                "transcription.download_parallel_files",
+                "must be greater than 0",
+            ));
+        }
+
+        let decoding = &self.decoding;
+        validation::validate_decoding(
+            Some(decoding.max_new_tokens),
+            Some(decoding.temperature),
+            decoding.top_p,
+        )
+        .map_err(|e| {
+            let field = format!("transcription.decoding.{}", e.field().unwrap_or_default());
+            ConfigFieldError::new(&field, e.to_string())
+        })?;
+
+        Ok(())
+    }
+}
+
+fn default_download_parallel_files() -> usize {
+    1
+}
+
+fn default_record_queue_depth() -> usize {
+    4
+}
+
+fn default_theme_volume() -> f32 {
+    1.0
+}
+
+fn default_agc_target_rms() -> f32 {
+    0.1
+}
+
+fn default_pre_roll_ms() -> u32 {
+    300
 }
 
+fn default_min_audio_ms() -> u32 {
+    500
+}
+
+fn default_warmup_on_load() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    pub udp_bind_addr: IpAddr, // Address the UDP audio streamer binds to, IPv4 or IPv6
+    #[serde(default)]
+    pub websocket_bind_addr: Option<SocketAddr>, // WebSocket gateway address, off when unset
+    #[serde(default)]
+    pub http_bind_addr: Option<SocketAddr>, // OpenAI-compatible HTTP API address, off when unset
+    #[serde(default)]
+    pub metrics_bind_addr: Option<SocketAddr>, // Prometheus exporter address, off when unset
+    #[serde(default)]
+    pub api_key: Option<String>, // Bearer token of the WebSocket gateway and HTTP API, the UDP secret at startup when unset
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            udp_bind_addr: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            websocket_bind_addr: None,
+            http_bind_addr: None,
+            metrics_bind_addr: None,
+            api_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,     // Keep final transcriptions on disk, off unless opted in
+    pub max_entries: u32,  // Oldest transcriptions beyond this many are deleted
+    pub max_age_days: u32, // Transcriptions older than this are deleted, 0 keeps them all
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 1000,
+            max_age_days: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugConfig {
+    pub keep_last_audio: bool, // Hold the latest recording in memory for `get_last_audio`
+}
+
+/// A rejected config patch, naming the first offending field by its dotted path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+impl ConfigFieldError {
+    fn new(field: &str, reason: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for ConfigFieldError {}
+
 impl Default for DaemonConfig {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             device: DeviceConfig {
                 preferred_device: "cpu".to_string(), // Default to CPU for compatibility
+                warmup_on_load: default_warmup_on_load(),
+                idle_timeout_minutes: 0,
+                idle_action: IdleAction::default(),
+                battery_action: BatteryAction::default(),
             },
             audio: AudioConfig {
                 theme: AudioTheme::default(),
+                silence: SilenceConfig::default(),
+                frequency_bands: FrequencyBandsConfig::default(),
+                input_device: None,
+                capture_sample_rate: 0,
+                resample_quality: ResampleQuality::default(),
+                record_queue_depth: default_record_queue_depth(),
+                noise_suppression: NoiseSuppressionConfig::default(),
+                trim_silence: TrimSilenceConfig::default(),
+                vad_model: None,
+                agc_enabled: false,
+                agc_target_rms: default_agc_target_rms(),
+                pre_roll_ms: default_pre_roll_ms(),
+                min_audio_ms: default_min_audio_ms(),
+                custom_sounds: CustomSoundPaths::default(),
+                theme_volume: default_theme_volume(),
+                theme_muted: false,
             },
             transcription: TranscriptionConfig {
                 preferred_model: STTModel::default(),
-                write_mode: false,             // Default to not auto-typing
+                write_mode: false, // Default to not auto-typing
+                typing_backend: TypingBackend::default(),
                 preview_typing_enabled: false, // Default to disabled (beta feature)
+                preview_commit_passes: 0,
+                voice_commands_enabled: false,
+                formatting: FormattingConfig::default(),
+                focus_filter: FocusFilterConfig::default(),
+                diarization_enabled: false,
+                max_file_size_mb: default_max_file_size_mb(),
+                replacements: Vec::new(),
+                word_filter: WordFilterConfig::default(),
+                model_source: ModelSource::default(),
+                download_max_bandwidth_kbps: 0,
+                download_parallel_files: default_download_parallel_files(),
+                decoding: DecodingConfig::default(),
             },
+            network: NetworkConfig::default(),
+            history: HistoryConfig::default(),
+            archive_recordings: ArchiveConfig::default(),
+            debug: DebugConfig::default(),
+        }
+    }
+}
+
+/// Why a config file could not be read as the current version
+#[derive(Debug)]
+enum VersionedParseError {
+    /// Written by a newer daemon, with the version it has
+    Future(u32),
+    Invalid(String),
+}
+
+/// Upgrade a config table of `from`, an older version, to the current layout
+///
+/// Steps that rename or reshape fields go first, oldest version first. Fields the
+/// file lacks are then taken from the defaults, and each one filled in is logged.
+fn migrate(table: toml::Table, from: u32) -> toml::Table {
+    info!("Upgrading config from version {from} to {CONFIG_VERSION}");
+    // Version 0 files predate the version field; no field has been reshaped since
+
+    let mut merged =
+        toml::Table::try_from(DaemonConfig::default()).expect("default config serializes");
+    let mut added = Vec::new();
+    fill_defaults(&mut merged, table, "", &mut added);
+    merged.insert("version".to_string(), CONFIG_VERSION.into());
+    for field in added {
+        info!("Config upgrade added {field} with its default value");
+    }
+    merged
+}
+
+/// Write the values of `table` over `defaults`, recording the paths of defaults that
+/// `table` does not set
+fn fill_defaults(
+    defaults: &mut toml::Table,
+    mut table: toml::Table,
+    prefix: &str,
+    added: &mut Vec<String>,
+) {
+    for (key, default) in defaults.iter_mut() {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (default, table.remove(key)) {
+            (toml::Value::Table(defaults), Some(toml::Value::Table(table))) => {
+                fill_defaults(defaults, table, &path, added);
+            }
+            (default, Some(value)) => *default = value,
+            (_, None) if key == "version" => {}
+            (_, None) => added.push(path),
         }
     }
+    // Keys without a default, like options that are unset by default, are kept as written
+    defaults.extend(table);
 }
 
+/// Config file given with `--config`, read and written instead of the default one
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 impl DaemonConfig {
+    /// Use the config file at `path` instead of `~/.config/super-stt/daemon.toml`
+    ///
+    /// Call before the config is first loaded; only the first call has an effect.
+    pub fn set_config_path(path: PathBuf) {
+        let _ = CONFIG_PATH_OVERRIDE.set(path);
+    }
+
     /// Get the config file path
     fn get_config_path() -> PathBuf {
+        if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+            return path.clone();
+        }
         let config_dir = dirs::config_dir()
             .unwrap_or_else(|| {
                 let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
@@ -63,25 +508,104 @@ impl DaemonConfig {
     }
 
     /// Load configuration from disk
+    ///
+    /// A file from an older version is upgraded and saved back. One from a newer
+    /// version is moved aside to `daemon.toml.v<version>.bak`, and the defaults are used.
     #[must_use]
     pub fn load() -> Self {
         let config_path = Self::get_config_path();
+        let Ok(content) = fs::read_to_string(&config_path) else {
+            return Self::default();
+        };
 
-        match fs::read_to_string(&config_path) {
-            Ok(content) => match toml::from_str::<DaemonConfig>(&content) {
-                Ok(config) => config,
-                Err(e) => {
-                    warn!(
-                        "Failed to parse config file {}: {e}. Using defaults.",
+        match Self::parse_versioned(&content) {
+            Ok((config, false)) => config,
+            Ok((config, true)) => {
+                match config.save() {
+                    Ok(()) => info!("Saved upgraded config to {}", config_path.display()),
+                    Err(e) => warn!("Failed to save upgraded config: {e}"),
+                }
+                config
+            }
+            Err(VersionedParseError::Future(version)) => {
+                let backup = config_path.with_extension(format!("toml.v{version}.bak"));
+                match fs::rename(&config_path, &backup) {
+                    Ok(()) => warn!(
+                        "Config file {} is from a newer version ({version}), moved it to {} and using defaults",
+                        config_path.display(),
+                        backup.display()
+                    ),
+                    Err(e) => warn!(
+                        "Config file {} is from a newer version ({version}) and could not be backed up: {e}. Using defaults.",
                         config_path.display()
-                    );
-                    Self::default()
+                    ),
                 }
-            },
-            Err(_) => Self::default(),
+                Self::default()
+            }
+            Err(VersionedParseError::Invalid(e)) => {
+                warn!(
+                    "Failed to parse config file {}: {e}. Using defaults.",
+                    config_path.display()
+                );
+                Self::default()
+            }
         }
     }
 
+    /// Parse a config file of any version up to [`CONFIG_VERSION`], also returning
+    /// whether it had to be upgraded
+    fn parse_versioned(content: &str) -> Result<(Self, bool), VersionedParseError> {
+        let table: toml::Table =
+            toml::from_str(content).map_err(|e| VersionedParseError::Invalid(e.to_string()))?;
+        let version = match table.get("version") {
+            None => 0,
+            Some(toml::Value::Integer(version)) => u32::try_from(*version)
+                .map_err(|_| VersionedParseError::Invalid(format!("invalid version {version}")))?,
+            Some(other) => {
+                return Err(VersionedParseError::Invalid(format!(
+                    "invalid version {other}"
+                )));
+            }
+        };
+
+        if version > CONFIG_VERSION {
+            return Err(VersionedParseError::Future(version));
+        }
+        let upgraded = version < CONFIG_VERSION;
+        let table = if upgraded {
+            migrate(table, version)
+        } else {
+            table
+        };
+        let config = table
+            .try_into()
+            .map_err(|e: toml::de::Error| VersionedParseError::Invalid(e.to_string()))?;
+        Ok((config, upgraded))
+    }
+
+    /// Read and validate the config file, for reloading it into a running daemon
+    ///
+    /// Unlike [`Self::load`], a file that is missing or invalid is an error rather than
+    /// the defaults, so a typo does not reset every setting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or holds an invalid value.
+    pub fn read_validated() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_path = Self::get_config_path();
+        let content = fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read {}: {e}", config_path.display()))?;
+        let (config, _) = match Self::parse_versioned(&content) {
+            Ok(parsed) => parsed,
+            Err(VersionedParseError::Future(version)) => {
+                return Err(format!("config version {version} is newer than this daemon").into());
+            }
+            Err(VersionedParseError::Invalid(e)) => return Err(e.into()),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
     /// Save configuration to disk
     ///
     /// # Errors
@@ -103,6 +627,155 @@ impl DaemonConfig {
         Ok(())
     }
 
+    /// Build a new config from this one with `patch` merged on top
+    ///
+    /// The patch mirrors the serialized config layout and may contain any subset of its
+    /// fields. Nothing is applied unless every field in the patch is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first field that is unknown, has the wrong type or holds an invalid value.
+    pub fn with_patch(&self, patch: &Value) -> Result<Self, ConfigFieldError> {
+        let current = serde_json::to_value(self)
+            .map_err(|e| ConfigFieldError::new("config", e.to_string()))?;
+
+        let mut leaves = Vec::new();
+        collect_patch_leaves(&current, patch, "", &mut leaves)?;
+
+        // Apply fields one at a time first so a type error can be pinned to its field
+        for (path, value) in &leaves {
+            let mut candidate = current.clone();
+            set_path(&mut candidate, path, value.clone());
+            if let Err(e) = serde_json::from_value::<Self>(candidate) {
+                return Err(ConfigFieldError::new(path, e.to_string()));
+            }
+        }
+
+        let mut merged = current;
+        for (path, value) in leaves {
+            set_path(&mut merged, &path, value);
+        }
+        let config: Self = serde_json::from_value(merged)
+            .map_err(|e| ConfigFieldError::new("config", e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check field values that are well-typed but out of range
+    ///
+    /// # Errors
+    ///
+    /// Returns the first field holding an invalid value.
+    pub fn validate(&self) -> Result<(), ConfigFieldError> {
+        if self.version != CONFIG_VERSION {
+            return Err(ConfigFieldError::new(
+                "version",
+                format!("expected {CONFIG_VERSION}"),
+            ));
+        }
+
+        if !matches!(self.device.preferred_device.as_str(), "cpu" | "cuda") {
+            return Err(ConfigFieldError::new(
+                "device.preferred_device",
+                "expected \"cpu\" or \"cuda\"",
+            ));
+        }
+
+        let silence = &self.audio.silence;
+        if !(silence.threshold > 0.0 && silence.threshold <= 1.0) {
+            return Err(ConfigFieldError::new(
+                "audio.silence.threshold",
+                "expected 0.0 < threshold <= 1.0",
+            ));
+        }
+        if silence.min_silence_ms == 0 {
+            return Err(ConfigFieldError::new(
+                "audio.silence.min_silence_ms",
+                "must be greater than 0",
+            ));
+        }
+        if silence.max_duration_s == 0 {
+            return Err(ConfigFieldError::new(
+                "audio.silence.max_duration_s",
+                "must be greater than 0",
+            ));
+        }
+
+        if self.audio.input_device.is_some() {
+            validation::validate_required_string(
+                &self.audio.input_device,
+                "audio.input_device",
+                validation::limits::MAX_NAME_LENGTH,
+            )
+            .map_err(|e| ConfigFieldError::new("audio.input_device", e.to_string()))?;
+        }
+
+        if !(self.audio.agc_target_rms > 0.0 && self.audio.agc_target_rms <= 1.0) {
+            return Err(ConfigFieldError::new(
+                "audio.agc_target_rms",
+                "expected 0.0 < agc_target_rms <= 1.0",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.audio.theme_volume) {
+            return Err(ConfigFieldError::new(
+                "audio.theme_volume",
+                "expected 0.0 <= theme_volume <= 1.0",
+            ));
+        }
+
+        if self.audio.pre_roll_ms > 2000 {
+            return Err(ConfigFieldError::new(
+                "audio.pre_roll_ms",
+                "expected pre_roll_ms <= 2000",
+            ));
+        }
+
+        let rate = self.audio.capture_sample_rate;
+        if rate != 0 && !(8000..=192_000).contains(&rate) {
+            return Err(ConfigFieldError::new(
+                "audio.capture_sample_rate",
+                "expected 0 or 8000 <= capture_sample_rate <= 192000",
+            ));
+        }
+
+        if self.audio.min_audio_ms > 5000 {
+            return Err(ConfigFieldError::new(
+                "audio.min_audio_ms",
+                "expected min_audio_ms <= 5000",
+            ));
+        }
+
+        self.audio.validate_processing()?;
+
+        let bands = &self.audio.frequency_bands;
+        AudioAnalyzer::validate_bands(bands.band_count, bands.min_hz, bands.max_hz)
+            .map_err(|e| ConfigFieldError::new("audio.frequency_bands", e))?;
+
+        self.transcription.validate()?;
+
+        if self.history.max_entries == 0 {
+            return Err(ConfigFieldError::new(
+                "history.max_entries",
+                "must be greater than 0",
+            ));
+        }
+
+        if self
+            .archive_recordings
+            .directory
+            .as_ref()
+            .is_some_and(|directory| !directory.is_absolute())
+        {
+            return Err(ConfigFieldError::new(
+                "archive_recordings.directory",
+                "must be an absolute path",
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Update preferred device and save to disk
     pub fn update_preferred_device(&mut self, device: String) {
         self.device.preferred_device = device;
@@ -119,6 +792,14 @@ impl DaemonConfig {
         }
     }
 
+    /// Update the selected input device and save to disk
+    pub fn update_input_device(&mut self, input_device: Option<String>) {
+        self.audio.input_device = input_device;
+        if let Err(e) = self.save() {
+            error!("Failed to save config after input device update: {e}");
+        }
+    }
+
     /// Update preferred model and save to disk
     pub fn update_preferred_model(&mut self, model: STTModel) {
         self.transcription.preferred_model = model;
@@ -135,3 +816,243 @@ impl DaemonConfig {
         }
     }
 }
+
+/// Flatten `patch` into dotted paths, rejecting keys that `current` does not have
+fn collect_patch_leaves(
+    current: &Value,
+    patch: &Value,
+    prefix: &str,
+    leaves: &mut Vec<(String, Value)>,
+) -> Result<(), ConfigFieldError> {
+    let Value::Object(patch) = patch else {
+        return Err(ConfigFieldError::new(
+            if prefix.is_empty() { "config" } else { prefix },
+            "expected an object",
+        ));
+    };
+
+    for (key, value) in patch {
+        let field = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match current.get(key) {
+            None => return Err(ConfigFieldError::new(&field, "unknown field")),
+            Some(existing @ Value::Object(_)) => {
+                collect_patch_leaves(existing, value, &field, leaves)?;
+            }
+            Some(_) => leaves.push((field, value.clone())),
+        }
+    }
+    Ok(())
+}
+
+/// Replace the value at a dotted path produced by `collect_patch_leaves`
+fn set_path(target: &mut Value, path: &str, value: Value) {
+    let mut slot = target;
+    for key in path.split('.') {
+        slot = &mut slot[key];
+    }
+    *slot = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_patch_applies_nested_fields() {
+        let config = DaemonConfig::default();
+        let patched = config
+            .with_patch(&serde_json::json!({
+                "audio": { "silence": { "min_silence_ms": 1500 }, "resample_quality": "high" },
+                "transcription": { "write_mode": true, "typing_backend": "clipboard_paste" }
+            }))
+            .unwrap();
+
+        assert_eq!(patched.audio.silence.min_silence_ms, 1500);
+        assert_eq!(
+            patched.audio.silence.max_duration_s,
+            config.audio.silence.max_duration_s
+        );
+        assert_eq!(patched.audio.resample_quality, ResampleQuality::HighQuality);
+        assert!(patched.transcription.write_mode);
+        assert_eq!(
+            patched.transcription.typing_backend,
+            TypingBackend::ClipboardPaste
+        );
+    }
+
+    #[test]
+    fn test_with_patch_reports_offending_field() {
+        let config = DaemonConfig::default();
+
+        let err = config
+            .with_patch(&serde_json::json!({ "audio": { "theme": "loud" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.theme");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "audio": { "volume": 3 } }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.volume");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "transcription": { "typing_backend": "ydotool" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.typing_backend");
+
+        let err = config
+            .with_patch(&serde_json::json!({
+                "transcription": { "write_mode": true, "max_file_size_mb": 0 }
+            }))
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.max_file_size_mb");
+
+        let err = config
+            .with_patch(&serde_json::json!({
+                "audio": { "frequency_bands": { "band_count": 4096 } }
+            }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.frequency_bands");
+
+        let err = config
+            .with_patch(&serde_json::json!({
+                "transcription": { "replacements": [["", "the"]] }
+            }))
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.replacements");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "audio": { "theme_volume": 1.5 } }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.theme_volume");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "audio": { "capture_sample_rate": 100 } }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.capture_sample_rate");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "audio": { "resample_quality": "best" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.resample_quality");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "device": { "idle_action": "sleep" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "device.idle_action");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "device": { "battery_action": "gpu" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "device.battery_action");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "audio": { "min_audio_ms": 60000 } }))
+            .unwrap_err();
+        assert_eq!(err.field, "audio.min_audio_ms");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "transcription": { "model_source": "file:///no/such/model/dir" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.model_source");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "transcription": { "download_parallel_files": 0 } }))
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.download_parallel_files");
+
+        let err = config
+            .with_patch(
+                &serde_json::json!({ "transcription": { "decoding": { "temperature": 5.0 } } }),
+            )
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.decoding.temperature");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "transcription": { "word_filter": { "words": ["two words"] } } }))
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.word_filter.words");
+
+        let err = config
+            .with_patch(
+                &serde_json::json!({ "transcription": { "focus_filter": { "allow": [" "] } } }),
+            )
+            .unwrap_err();
+        assert_eq!(err.field, "transcription.focus_filter");
+
+        let err = config
+            .with_patch(&serde_json::json!({ "archive_recordings": { "directory": "recordings" } }))
+            .unwrap_err();
+        assert_eq!(err.field, "archive_recordings.directory");
+    }
+
+    #[test]
+    fn test_theme_gain_honors_mute() {
+        let mut config = DaemonConfig::default()
+            .with_patch(&serde_json::json!({ "audio": { "theme_volume": 0.4 } }))
+            .unwrap();
+        assert!((config.audio.theme_gain() - 0.4).abs() < f32::EPSILON);
+
+        config.audio.theme_muted = true;
+        assert!(config.audio.theme_gain().abs() < f32::EPSILON);
+        assert!((config.audio.theme_volume - 0.4).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_replacements_round_trip_through_toml() {
+        let mut config = DaemonConfig::default();
+        config.transcription.replacements = vec![
+            ("gonna".to_string(), "going to".to_string()),
+            ("teh".to_string(), "the".to_string()),
+        ];
+
+        let loaded: DaemonConfig =
+            toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(
+            loaded.transcription.replacements,
+            config.transcription.replacements
+        );
+    }
+
+    #[test]
+    fn test_unversioned_config_is_upgraded() {
+        // An early config file: no version and no device section
+        let content = r#"
+            [audio]
+            theme = "Gentle"
+
+            [transcription]
+            preferred_model = "WhisperBase"
+            write_mode = true
+        "#;
+        assert!(toml::from_str::<DaemonConfig>(content).is_err());
+
+        let (config, upgraded) = DaemonConfig::parse_versioned(content).unwrap();
+        assert!(upgraded);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.audio.theme, AudioTheme::Gentle);
+        assert_eq!(config.transcription.preferred_model, STTModel::WhisperBase);
+        assert!(config.transcription.write_mode);
+        assert_eq!(config.device.preferred_device, "cpu");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_current_and_future_config_versions() {
+        let current = toml::to_string_pretty(&DaemonConfig::default()).unwrap();
+        let (_, upgraded) = DaemonConfig::parse_versioned(&current).unwrap();
+        assert!(!upgraded);
+
+        let future = current.replace(
+            &format!("version = {CONFIG_VERSION}"),
+            &format!("version = {}", CONFIG_VERSION + 1),
+        );
+        assert!(matches!(
+            DaemonConfig::parse_versioned(&future),
+            Err(VersionedParseError::Future(version)) if version == CONFIG_VERSION + 1
+        ));
+    }
+}
@@ -1,15 +1,42 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::daemon::types::SuperSTTDaemon;
+use crate::daemon::core::parse_command;
+use crate::daemon::types::{DEFAULT_RECORDING_CLIENT, SuperSTTDaemon};
+use crate::output::preview::Typer;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use log::{error, warn};
 use std::collections::HashMap;
-use super_stt_shared::models::protocol::{DaemonRequest, DaemonResponse};
-use super_stt_shared::validation::Validate;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use super_stt_shared::daemon::client::{FrameError, read_frame};
+use super_stt_shared::models::protocol::{
+    Command, DaemonRequest, DaemonResponse, HEARTBEAT_INTERVAL, NotificationEvent,
+};
+use super_stt_shared::validation::limits;
+use tokio::io::AsyncWriteExt;
 use tokio::net::UnixStream;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Instant;
+
+/// A client connection that responses can be written to
+pub(crate) trait ResponseSink: Send {
+    fn send_response(
+        &mut self,
+        response: &DaemonResponse,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+impl ResponseSink for UnixStream {
+    /// Write `response` as a length-prefixed JSON frame
+    async fn send_response(&mut self, response: &DaemonResponse) -> Result<()> {
+        let response_data = serde_json::to_vec(response)?;
+        let size = response_data.len() as u64;
+
+        self.write_all(&size.to_be_bytes()).await?;
+        self.write_all(&response_data).await?;
+
+        Ok(())
+    }
+}
 
 /// Track active client connections
 #[derive(Debug, Clone)]
@@ -21,7 +48,59 @@ pub struct ClientConnection {
 pub type ClientConnectionsMap =
     std::sync::Arc<tokio::sync::RwLock<HashMap<String, ClientConnection>>>;
 
+/// Events a subscribe request asks to catch up on, from its `since_timestamp`
+pub(crate) struct Replay {
+    since_timestamp: String,
+    event_types: Vec<String>,
+    limit: u32,
+}
+
+impl Replay {
+    /// `None` when the request streams only future events
+    pub(crate) fn from_request(request: &DaemonRequest) -> Option<Self> {
+        Some(Self {
+            since_timestamp: request.since_timestamp.clone()?,
+            event_types: request.event_types.clone().unwrap_or_default(),
+            limit: request.limit.unwrap_or(100),
+        })
+    }
+
+    /// Whether a live event was already part of the replay that ended at `replayed_until`
+    pub(crate) fn already_sent(event: &NotificationEvent, replayed_until: Option<&str>) -> bool {
+        replayed_until.is_some_and(|until| event.timestamp.as_str() <= until)
+    }
+}
+
 impl SuperSTTDaemon {
+    /// Buffered events a new subscriber missed, oldest first
+    ///
+    /// Call it after taking the subscriber's receiver so no event falls between the
+    /// two; live events up to the last one returned are then duplicates.
+    pub(crate) fn missed_events(&self, replay: Option<Replay>) -> Vec<NotificationEvent> {
+        let Some(replay) = replay else {
+            return Vec::new();
+        };
+        match self.notification_manager.events_to_replay(
+            replay.since_timestamp,
+            replay.event_types,
+            replay.limit,
+        ) {
+            Ok(events) => events,
+            Err(e) => {
+                warn!("Failed to replay missed events: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Whether `provided` is the bearer token of the WebSocket gateway and HTTP API
+    ///
+    /// The comparison takes the same time however much of `provided` is right.
+    #[must_use]
+    pub fn verify_api_token(&self, provided: &str) -> bool {
+        super_stt_shared::auth::secrets_match(&self.api_token, provided)
+    }
+
     /// Handle client connection
     ///
     /// # Errors
@@ -30,11 +109,16 @@ impl SuperSTTDaemon {
     pub async fn handle_client(&self, mut stream: UnixStream) -> Result<()> {
         // Generate a unique client ID for this connection
         let client_id = format!("conn_{}", uuid::Uuid::new_v4());
+        // Connections from one process share its connection and rate limits
+        let client_key = match stream.peer_cred().map(|cred| cred.pid()) {
+            Ok(Some(pid)) => format!("pid:{pid}"),
+            _ => client_id.clone(),
+        };
 
         // Register the connection with resource manager
         if let Err(e) = self
             .resource_manager
-            .register_connection(client_id.clone(), None)
+            .register_client_connection(client_id.clone(), client_key, None)
             .await
         {
             warn!("Connection rejected due to resource limits: {e}");
@@ -44,35 +128,24 @@ impl SuperSTTDaemon {
         }
 
         loop {
-            // Read message size (8 bytes, big endian)
-            let mut size_buf = [0u8; 8];
-            match stream.read_exact(&mut size_buf).await {
-                Ok(_) => {}
-                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            // Read the size-prefixed message, refusing oversized ones before allocating
+            let message_buf = match read_frame(&mut stream, limits::MAX_FRAME_SIZE).await {
+                Ok(Some(message_buf)) => message_buf,
+                Ok(None) => break,
+                Err(e @ FrameError::TooLarge { .. }) => {
+                    warn!("Rejecting request: {e}");
+                    // The unread body leaves the stream out of step, so close it after replying
+                    let response = DaemonResponse::error("Request too large");
+                    if let Err(e) = self.send_response(&mut stream, &response).await {
+                        warn!("Failed to send error response: {e}");
+                    }
                     break;
                 }
-                Err(e) => {
-                    warn!("Failed to read message size: {e}");
+                Err(FrameError::Io(e)) => {
+                    warn!("Failed to read message: {e}");
                     break;
                 }
-            }
-
-            let Ok(message_size) = usize::try_from(u64::from_be_bytes(size_buf)) else {
-                warn!("Invalid message size received");
-                break;
             };
-            if message_size > 100 * 1024 * 1024 {
-                // 100MB limit
-                warn!("Message too large: {message_size} bytes");
-                break;
-            }
-
-            // Read message data
-            let mut message_buf = vec![0u8; message_size];
-            if let Err(e) = stream.read_exact(&mut message_buf).await {
-                warn!("Failed to read message data: {e}");
-                break;
-            }
 
             // Parse request
             let request: DaemonRequest = match serde_json::from_slice(&message_buf) {
@@ -87,16 +160,6 @@ impl SuperSTTDaemon {
                 }
             };
 
-            // Validate request
-            if let Err(e) = request.validate() {
-                warn!("Request validation failed: {e}");
-                let response = DaemonResponse::error(&format!("Request validation failed: {e}"));
-                if let Err(e) = self.send_response(&mut stream, &response).await {
-                    warn!("Failed to send validation error response: {e}");
-                }
-                continue;
-            }
-
             // Check rate limits
             if let Err(e) = self.resource_manager.record_request(&client_id).await {
                 warn!("Rate limit exceeded for client {client_id}: {e}");
@@ -112,15 +175,23 @@ impl SuperSTTDaemon {
                 request.command.as_str(),
                 "subscribe" | "get_events" | "get_subscriber_info"
             ) {
-                // Clean up the connection from resource manager before transferring to persistent handler
-                self.resource_manager
-                    .unregister_connection(&client_id)
-                    .await;
-
-                if let Err(e) = self.handle_persistent_client(stream, request).await {
+                // The connection stays registered, counting toward the client's limits,
+                // until the persistent handler is done with it
+                if let Err(e) = self
+                    .handle_persistent_client(stream, request, &client_id)
+                    .await
+                {
                     error!("Error in persistent client handler: {e}");
                 }
-                return Ok(());
+                break;
+            }
+
+            // Streamed recordings keep the connection for partial frames
+            if request.command == "record_stream" {
+                if let Err(e) = self.handle_record_stream(stream, request).await {
+                    error!("Error in record stream handler: {e}");
+                }
+                break;
             }
 
             // Handle regular commands with stream access for authentication
@@ -140,7 +211,9 @@ impl SuperSTTDaemon {
     }
 
     /// Handle persistent client connections (for subscriptions and events)
-    /// Handle persistent client connections (for subscriptions and events)
+    ///
+    /// Requests read on the connection count against the rate limits of
+    /// `connection_id`, the connection's registration with the resource manager.
     ///
     /// # Errors
     ///
@@ -149,7 +222,10 @@ impl SuperSTTDaemon {
         &self,
         mut stream: UnixStream,
         initial_request: DaemonRequest,
+        connection_id: &str,
     ) -> Result<()> {
+        let replay = Replay::from_request(&initial_request);
+
         // Handle initial request
         let initial_response = self.handle_command(initial_request).await;
         self.send_response(&mut stream, &initial_response).await?;
@@ -165,11 +241,37 @@ impl SuperSTTDaemon {
                 let mut receiver = subscriber.sender.subscribe();
                 drop(subscriber); // Release the lock
 
+                // Catch up on what the client missed before streaming live events
+                let missed = self.missed_events(replay);
+                let replayed_until = missed.last().map(|event| event.timestamp.clone());
+                for event in &missed {
+                    let event_json = serde_json::to_vec(event)?;
+                    let size = event_json.len() as u64;
+                    stream.write_all(&size.to_be_bytes()).await?;
+                    stream.write_all(&event_json).await?;
+                }
+
+                let mut heartbeat = tokio::time::interval_at(
+                    Instant::now() + HEARTBEAT_INTERVAL,
+                    HEARTBEAT_INTERVAL,
+                );
                 loop {
                     tokio::select! {
+                        // Keep an idle stream visibly alive
+                        _ = heartbeat.tick() => {
+                            let event_json = serde_json::to_vec(&NotificationEvent::heartbeat())?;
+                            let size = event_json.len() as u64;
+
+                            if stream.write_all(&size.to_be_bytes()).await.is_err() ||
+                               stream.write_all(&event_json).await.is_err() {
+                                break;
+                            }
+                        }
+
                         // Receive notification events
                         event_result = receiver.recv() => {
                             match event_result {
+                                Ok(event) if Replay::already_sent(&event, replayed_until.as_deref()) => {}
                                 Ok(event) => {
                                     let event_json = serde_json::to_vec(&event)?;
                                     let size = event_json.len() as u64;
@@ -190,28 +292,22 @@ impl SuperSTTDaemon {
 
                         // Handle additional requests from client
                         read_result = async {
-                            let mut size_buf = [0u8; 8];
-                            stream.read_exact(&mut size_buf).await.map_err(|e| anyhow::anyhow!(e))?;
-                            let message_size = usize::try_from(u64::from_be_bytes(size_buf))
-                                .map_err(|e| anyhow::anyhow!(e))?;
-                            let mut message_buf = vec![0u8; message_size];
-                            stream.read_exact(&mut message_buf).await.map_err(|e| anyhow::anyhow!(e))?;
+                            let message_buf = read_frame(&mut stream, limits::MAX_FRAME_SIZE)
+                                .await?
+                                .ok_or_else(|| anyhow::anyhow!("Client disconnected"))?;
                             serde_json::from_slice::<DaemonRequest>(&message_buf).map_err(|e| anyhow::anyhow!(e))
                         } => {
                             match read_result {
                                 Ok(request) => {
-                                    // Validate persistent client requests too
-                                    if let Err(e) = request.validate() {
-                                        warn!("Persistent client request validation failed: {e}");
-                                        let response = DaemonResponse::error(&format!("Request validation failed: {e}"));
-                                        if self.send_response(&mut stream, &response).await.is_err() {
-                                            break;
-                                        }
-                                    } else {
-                                        let response = self.handle_command(request).await;
-                                        if self.send_response(&mut stream, &response).await.is_err() {
-                                            break;
+                                    let response = match self.resource_manager.record_request(connection_id).await {
+                                        Ok(()) => self.handle_command(request).await,
+                                        Err(e) => {
+                                            warn!("Rate limit exceeded for client {connection_id}: {e}");
+                                            DaemonResponse::error(&format!("Rate limit exceeded: {e}"))
                                         }
+                                    };
+                                    if self.send_response(&mut stream, &response).await.is_err() {
+                                        break;
                                     }
                                 }
                                 Err(_) => break, // Client disconnected
@@ -231,6 +327,105 @@ impl SuperSTTDaemon {
         Ok(())
     }
 
+    /// Handle a `record_stream` request by writing a frame for every partial
+    /// transcription followed by a terminal frame with `is_final` set
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the terminal frame to the stream fails.
+    pub async fn handle_record_stream(
+        &self,
+        mut stream: UnixStream,
+        request: DaemonRequest,
+    ) -> Result<()> {
+        self.stream_recording(request, &mut stream).await
+    }
+
+    /// Run a `record_stream` request, sending each partial transcription and then
+    /// the terminal frame to `sink`; shared by the Unix socket and the WebSocket gateway
+    ///
+    /// The recording carries on if a partial frame cannot be sent, but nothing
+    /// more is sent to that client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sending the terminal frame fails.
+    pub(crate) async fn stream_recording(
+        &self,
+        request: DaemonRequest,
+        sink: &mut impl ResponseSink,
+    ) -> Result<()> {
+        let client_id = request
+            .client_id
+            .clone()
+            .unwrap_or_else(|| DEFAULT_RECORDING_CLIENT.to_string());
+        let (write_mode, silence, language, device_name, replacements, word_filter) =
+            match parse_command(request) {
+                Ok(Command::RecordStream {
+                    write_mode,
+                    silence,
+                    language,
+                    device_name,
+                    replacements,
+                    word_filter,
+                }) => (
+                    write_mode,
+                    silence,
+                    language,
+                    device_name,
+                    replacements,
+                    word_filter,
+                ),
+                Ok(_) => unreachable!("stream_recording called for another command"),
+                Err(response) => {
+                    return sink.send_response(&response.with_is_final(true)).await;
+                }
+            };
+
+        let (partial_tx, mut partial_rx) = mpsc::unbounded_channel::<(String, f32)>();
+        let mut typer = Typer::default();
+        let recording = self.handle_record_internal(
+            &mut typer,
+            &client_id,
+            write_mode,
+            silence,
+            language,
+            device_name,
+            replacements,
+            word_filter,
+            false,
+            Some(partial_tx),
+        );
+        tokio::pin!(recording);
+
+        // Keep recording even if the client goes away, but stop writing to it
+        let mut client_connected = true;
+        let response = loop {
+            tokio::select! {
+                response = &mut recording => break response,
+                Some((text, confidence)) = partial_rx.recv() => {
+                    if !client_connected {
+                        continue;
+                    }
+                    let frame = DaemonResponse::success()
+                        .with_transcription(text)
+                        .with_confidence(confidence)
+                        .with_is_final(false);
+                    if let Err(e) = sink.send_response(&frame).await {
+                        warn!("Record stream client disconnected: {e}");
+                        client_connected = false;
+                    }
+                }
+            }
+        };
+
+        if client_connected {
+            sink.send_response(&response.with_is_final(true)).await?;
+        }
+
+        Ok(())
+    }
+
     /// Send response to client
     ///
     /// # Errors
@@ -241,13 +436,7 @@ impl SuperSTTDaemon {
         stream: &mut UnixStream,
         response: &DaemonResponse,
     ) -> Result<()> {
-        let response_data = serde_json::to_vec(response)?;
-        let size = response_data.len() as u64;
-
-        stream.write_all(&size.to_be_bytes()).await?;
-        stream.write_all(&response_data).await?;
-
-        Ok(())
+        ResponseSink::send_response(stream, response).await
     }
 
     /// Update client connection timestamp
@@ -316,15 +505,19 @@ impl SuperSTTDaemon {
         true
     }
 
-    /// Broadcast recording state change to all clients
-    pub async fn broadcast_recording_state_change(&self, is_recording: bool) {
+    /// Broadcast a recording session's state change to all clients
+    ///
+    /// `source_id` identifies the session, so listeners can follow concurrent recordings.
+    pub async fn broadcast_recording_state_change(
+        &self,
+        is_recording: bool,
+        source_id: u32,
+        input_sample_rate: u32,
+    ) {
         // Broadcast recording state via UDP to applet
         if let Err(e) = &self
             .udp_streamer
-            .broadcast_recording_state(
-                is_recording,
-                0, // daemon client ID
-            )
+            .broadcast_recording_state(is_recording, source_id, input_sample_rate)
             .await
         {
             warn!("Failed to broadcast recording state via UDP: {e}");
@@ -1,29 +1,99 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::{daemon::types::SuperSTTDaemon, output::preview::Typer};
+use crate::{
+    daemon::types::{DEFAULT_RECORDING_CLIENT, RecordRequest, SuperSTTDaemon},
+    output::preview::{PreviewEdit, Typer},
+};
 use super_stt_shared::models::protocol::{Command, DaemonRequest, DaemonResponse};
+use super_stt_shared::validation::Validate;
+
+/// Validate `request` and turn it into a [`Command`]
+///
+/// Every transport dispatches through here, so this is the one place requests are
+/// validated; malformed ones are rejected before anything is recorded for them.
+pub(crate) fn parse_command(request: DaemonRequest) -> Result<Command, Box<DaemonResponse>> {
+    if let Err(e) = request.validate() {
+        log::warn!("Request validation failed: {e}");
+        return Err(Box::new(DaemonResponse::invalid_request(&e)));
+    }
+    Command::try_from(request).map_err(|e| Box::new(DaemonResponse::error(&e)))
+}
 
 impl SuperSTTDaemon {
     /// Main command handler - routes commands to appropriate handlers
+    #[allow(clippy::too_many_lines)]
     pub async fn handle_command(&self, request: DaemonRequest) -> DaemonResponse {
-        // Track connection if client_id is present
-        if let Some(client_id) = &request.client_id {
-            self.update_client_connection(client_id.clone()).await;
-        }
-
-        let command = match Command::try_from(request) {
+        let client_id = request.client_id.clone();
+        let command = match parse_command(request) {
             Ok(cmd) => cmd,
-            Err(e) => return DaemonResponse::error(&e),
+            Err(response) => return *response,
         };
 
+        // Track connection if client_id is present
+        if let Some(client_id) = client_id {
+            self.update_client_connection(client_id).await;
+        }
+
         match command {
             Command::Transcribe {
                 audio_data,
                 sample_rate,
                 client_id,
+                language,
+                replacements,
+                word_filter,
+                decoding,
+                timings,
             } => {
-                self.handle_transcribe(audio_data, sample_rate, client_id)
-                    .await
+                self.handle_transcribe(
+                    audio_data,
+                    sample_rate,
+                    client_id,
+                    language,
+                    replacements,
+                    word_filter,
+                    decoding,
+                    timings,
+                )
+                .await
+            }
+            Command::TranscribeTimestamped {
+                audio_data,
+                sample_rate,
+                client_id,
+                format,
+                language,
+                replacements,
+                word_filter,
+            } => {
+                self.handle_transcribe_timestamped(
+                    audio_data,
+                    sample_rate,
+                    client_id,
+                    format,
+                    language,
+                    replacements,
+                    word_filter,
+                )
+                .await
+            }
+            Command::TranscribeFile {
+                path,
+                client_id,
+                language,
+                replacements,
+                word_filter,
+                decoding,
+            } => {
+                self.handle_transcribe_file(
+                    path,
+                    client_id,
+                    language,
+                    replacements,
+                    word_filter,
+                    decoding,
+                )
+                .await
             }
             Command::Subscribe {
                 event_types,
@@ -38,6 +108,10 @@ impl SuperSTTDaemon {
                 limit,
             } => self.handle_get_events(since_timestamp, event_types, limit),
             Command::GetSubscriberInfo => self.handle_get_subscriber_info(),
+            Command::GetHistory {
+                since_timestamp,
+                limit,
+            } => self.handle_get_history(since_timestamp, limit).await,
             Command::Notify {
                 event_type,
                 client_id,
@@ -45,6 +119,7 @@ impl SuperSTTDaemon {
             } => self.handle_notify(event_type, client_id, data).await,
             Command::Ping { client_id } => self.handle_ping(client_id).await,
             Command::Status => self.handle_status().await,
+            Command::Health => self.handle_health().await,
             Command::StartRealTimeTranscription {
                 client_id,
                 sample_rate,
@@ -61,24 +136,128 @@ impl SuperSTTDaemon {
                 self.handle_realtime_audio(client_id, audio_data, sample_rate)
                     .await
             }
-            Command::Record { write_mode } => {
+            Command::Record {
+                client_id,
+                write_mode,
+                silence,
+                language,
+                device_name,
+                replacements,
+                word_filter,
+                output,
+                queue,
+                dry_run,
+                timings,
+            } => {
+                let request = RecordRequest {
+                    client_id: client_id.unwrap_or_else(|| DEFAULT_RECORDING_CLIENT.to_string()),
+                    write_mode,
+                    silence,
+                    language,
+                    device_name,
+                    replacements,
+                    word_filter,
+                    output,
+                    dry_run,
+                    timings,
+                };
+                if queue {
+                    self.handle_queued_record(request).await
+                } else {
+                    self.handle_record_request(request).await
+                }
+            }
+            Command::RecordStart {
+                client_id,
+                write_mode,
+                silence,
+                language,
+                device_name,
+                replacements,
+                word_filter,
+                output,
+            } => {
+                let request = RecordRequest {
+                    client_id: client_id.clone(),
+                    write_mode,
+                    silence,
+                    language,
+                    device_name,
+                    replacements,
+                    word_filter,
+                    output,
+                    dry_run: false,
+                    timings: false,
+                };
+                self.handle_record_start(client_id, request).await
+            }
+            Command::RecordStop { client_id } => self.handle_record_stop(&client_id).await,
+            Command::RecordContinuous {
+                client_id,
+                write_mode,
+                silence,
+                language,
+                device_name,
+                replacements,
+                word_filter,
+            } => {
                 let mut typer = Typer::default();
-                self.handle_record_internal(&mut typer, write_mode).await
+                let client_id = client_id.unwrap_or_else(|| DEFAULT_RECORDING_CLIENT.to_string());
+                self.handle_record_continuous(
+                    &mut typer,
+                    &client_id,
+                    write_mode,
+                    silence,
+                    language,
+                    device_name,
+                    replacements,
+                    word_filter,
+                )
+                .await
+            }
+            Command::RecordStream { .. } => DaemonResponse::error(
+                "record_stream must be sent as the first request on a connection",
+            ),
+            Command::Stop => self.handle_stop().await,
+            Command::CancelRecording { client_id } => {
+                self.handle_cancel_recording(client_id.as_deref()).await
+            }
+            Command::StartMonitor => self.handle_start_monitor().await,
+            Command::StopMonitor => self.handle_stop_monitor().await,
+            Command::ListAudioDevices => self.handle_list_audio_devices().await,
+            Command::DetectLanguage {
+                audio_data,
+                sample_rate,
+                path,
+                client_id,
+            } => {
+                self.handle_detect_language(audio_data, sample_rate, path, client_id)
+                    .await
             }
-            Command::SetAudioTheme { theme } => self.handle_set_audio_theme(theme),
+            Command::SetAudioTheme { theme } => self.handle_set_audio_theme(theme).await,
             Command::GetAudioTheme => self.handle_get_audio_theme(),
             Command::TestAudioTheme => self.handle_test_audio_theme().await,
+            Command::TestAudioInput { playback } => self.handle_test_audio_input(playback).await,
             Command::SetModel { model } => self.handle_set_model(model).await,
             Command::GetModel => self.handle_get_model().await,
             Command::ListModels => self.handle_list_models(),
+            Command::ListModelsDetailed => self.handle_list_models_detailed().await,
+            Command::ListLanguages => self.handle_list_languages().await,
+            Command::DeleteModel { model } => self.handle_delete_model(model).await,
+            Command::UnloadModel => self.handle_unload_model().await,
             Command::SetDevice { device } => self.handle_set_device(device).await,
             Command::GetDevice => self.handle_get_device().await,
             Command::GetConfig => self.handle_get_config().await,
+            Command::SetConfig { patch } => self.handle_set_config(&patch).await,
             Command::CancelDownload => self.handle_cancel_download(),
             Command::GetDownloadStatus => self.handle_get_download_status(),
             Command::ListAudioThemes => self.handle_list_audio_themes(),
             Command::SetPreviewTyping { enabled } => self.handle_set_preview_typing(enabled).await,
             Command::GetPreviewTyping => self.handle_get_preview_typing(),
+            Command::PreviewUndo => self.handle_preview_edit(PreviewEdit::Undo).await,
+            Command::PreviewRedo => self.handle_preview_edit(PreviewEdit::Redo).await,
+            Command::Warmup => self.handle_warmup().await,
+            Command::GetLastAudio { format } => self.handle_get_last_audio(format).await,
         }
     }
 
@@ -89,9 +268,10 @@ impl SuperSTTDaemon {
         sample_rate: Option<u32>,
         language: Option<String>,
     ) -> DaemonResponse {
+        let rules = self.resolve_text_rules(None, None).await;
         match self
             .realtime_manager
-            .start_session(client_id.clone(), sample_rate, language)
+            .start_session(client_id.clone(), sample_rate, language, rules)
             .await
         {
             Ok(_receiver) => {
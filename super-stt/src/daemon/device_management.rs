@@ -8,11 +8,19 @@ use super_stt_shared::models::protocol::DaemonResponse;
 impl SuperSTTDaemon {
     /// Handle set device command - switch between CPU and CUDA
     pub async fn handle_set_device(&self, device: String) -> DaemonResponse {
-        self.handle_set_device_impl(device).await
+        self.handle_set_device_impl(device, true).await
+    }
+
+    /// Switch devices like [`Self::handle_set_device`] for this session only,
+    /// leaving the saved device preference as it is
+    pub async fn switch_device_for_session(&self, device: String) -> DaemonResponse {
+        self.handle_set_device_impl(device, false).await
     }
 
     /// Internal implementation split from the public API for readability
-    async fn handle_set_device_impl(&self, device: String) -> DaemonResponse {
+    ///
+    /// With `persist` off, the switch changes the devices in memory but never the config.
+    async fn handle_set_device_impl(&self, device: String, persist: bool) -> DaemonResponse {
         info!("Device switch requested: {device}");
 
         // Perform all validation checks
@@ -36,18 +44,30 @@ impl SuperSTTDaemon {
             .load_model_with_target_device(&model_to_reload, &device)
             .await
         {
+            // A CUDA build on a machine without a working GPU loads on CPU instead
+            Ok(model_instance) if device == "cuda" && !model_instance.device().is_cuda() => {
+                self.handle_cuda_fallback(model_instance, &model_to_reload, persist)
+                    .await
+            }
             Ok(model_instance) => {
                 self.handle_device_switch_success(
                     model_instance,
                     &device,
                     &model_to_reload,
                     &current_preferred,
+                    persist,
                 )
                 .await
             }
             Err(e) => {
-                self.handle_device_switch_failure(e, &device, &model_to_reload, &current_preferred)
-                    .await
+                self.handle_device_switch_failure(
+                    e,
+                    &device,
+                    &model_to_reload,
+                    &current_preferred,
+                    persist,
+                )
+                .await
             }
         }
     }
@@ -82,14 +102,11 @@ impl SuperSTTDaemon {
         }
 
         // Security check: prevent device switching during active recording
-        {
-            let is_recording_guard = self.is_recording.read().await;
-            if *is_recording_guard {
-                warn!("Device switch rejected - recording in progress");
-                return Some(DaemonResponse::error(
-                    "Cannot switch devices during active recording. Please wait for recording to complete.",
-                ));
-            }
+        if self.is_recording().await {
+            warn!("Device switch rejected - recording in progress");
+            return Some(DaemonResponse::error(
+                "Cannot switch devices during active recording. Please wait for recording to complete.",
+            ));
         }
 
         // Security check: prevent device switching during real-time transcription
@@ -179,6 +196,7 @@ impl SuperSTTDaemon {
         device: &str,
         model_to_reload: &super_stt_shared::stt_model::STTModel,
         previous_device: &str,
+        persist: bool,
     ) -> DaemonResponse {
         let model_name = match &model_instance {
             STTModelInstance::Whisper(_) => "Whisper",
@@ -208,22 +226,17 @@ impl SuperSTTDaemon {
         }
 
         // Update the config with new device preference and save to disk
-        {
-            let mut config_guard = self.config.write().await;
-            config_guard.update_preferred_device(device.to_string());
+        if persist {
+            self.config
+                .write()
+                .await
+                .update_preferred_device(device.to_string());
+            if let Err(e) = self.broadcast_config_change().await {
+                warn!("Failed to broadcast config change after device switch: {e}");
+            }
         }
 
-        // Broadcast config change event
-        if let Err(e) = self.broadcast_config_change().await {
-            warn!("Failed to broadcast config change after device switch: {e}");
-        }
-
-        let success_message = if actual_device != device && device == "cuda" {
-            "Device switch requested to CUDA, but fell back to CPU due to CUDA unavailability"
-                .to_string()
-        } else {
-            format!("Successfully switched to {actual_device} device")
-        };
+        let success_message = format!("Successfully switched to {actual_device} device");
 
         info!("Device switch completed: {previous_device} -> {device} (actual: {actual_device})");
 
@@ -247,6 +260,8 @@ impl SuperSTTDaemon {
             warn!("Failed to broadcast device switch success: {e}");
         }
 
+        self.warm_up_after_load().await;
+
         // Broadcast ready status with new device
         if let Err(e) = self
             .notification_manager
@@ -273,6 +288,81 @@ impl SuperSTTDaemon {
             .with_message(success_message)
     }
 
+    /// Keep a model that fell back to CPU when CUDA was requested, and report the failure
+    ///
+    /// The CUDA preference is kept so a restart on a working GPU picks it up, while
+    /// the actual device reflects where the model really is.
+    async fn handle_cuda_fallback(
+        &self,
+        model_instance: STTModelInstance,
+        model_to_reload: &super_stt_shared::stt_model::STTModel,
+        persist: bool,
+    ) -> DaemonResponse {
+        let actual_device = match model_instance.device() {
+            candle_core::Device::Metal(_) => "metal",
+            _ => "cpu",
+        };
+        let message = format!(
+            "CUDA is unavailable on this machine, so {model_to_reload} was loaded on {actual_device} instead"
+        );
+        warn!("{message}");
+
+        *self.model.write().await = Some(model_instance);
+        *self.preferred_device.write().await = "cuda".to_string();
+        *self.actual_device.write().await = actual_device.to_string();
+        if persist {
+            self.config
+                .write()
+                .await
+                .update_preferred_device("cuda".to_string());
+            if let Err(e) = self.broadcast_config_change().await {
+                warn!("Failed to broadcast config change after CUDA fallback: {e}");
+            }
+        }
+
+        if let Err(e) = self
+            .notification_manager
+            .broadcast_event(
+                "daemon_status_changed".to_string(),
+                "daemon".to_string(),
+                serde_json::json!({
+                    "status": "device_switch_error",
+                    "error": message,
+                    "failed_device": "cuda",
+                    "actual_device": actual_device,
+                    "model": model_to_reload.to_string(),
+                    "timestamp": Utc::now().to_rfc3339()
+                }),
+            )
+            .await
+        {
+            warn!("Failed to broadcast CUDA fallback: {e}");
+        }
+
+        self.warm_up_after_load().await;
+
+        if let Err(e) = self
+            .notification_manager
+            .broadcast_event(
+                "daemon_status_changed".to_string(),
+                "daemon".to_string(),
+                serde_json::json!({
+                    "status": "ready",
+                    "model_loaded": true,
+                    "preferred_device": "cuda",
+                    "actual_device": actual_device,
+                    "model_name": model_to_reload.to_string(),
+                    "timestamp": Utc::now().to_rfc3339()
+                }),
+            )
+            .await
+        {
+            warn!("Failed to broadcast ready status after CUDA fallback: {e}");
+        }
+
+        DaemonResponse::error(&message)
+    }
+
     /// Handle failed device switch with recovery attempt
     async fn handle_device_switch_failure(
         &self,
@@ -280,6 +370,7 @@ impl SuperSTTDaemon {
         device: &str,
         model_to_reload: &super_stt_shared::stt_model::STTModel,
         previous_device: &str,
+        persist: bool,
     ) -> DaemonResponse {
         error!("Failed to reload model on new device: {error}");
 
@@ -326,14 +417,14 @@ impl SuperSTTDaemon {
                 }
 
                 // Update the config to revert to previous device
-                {
-                    let mut config_guard = self.config.write().await;
-                    config_guard.update_preferred_device(previous_device.to_string());
-                }
-
-                // Broadcast config change event for recovery
-                if let Err(e) = self.broadcast_config_change().await {
-                    warn!("Failed to broadcast config change after device recovery: {e}");
+                if persist {
+                    self.config
+                        .write()
+                        .await
+                        .update_preferred_device(previous_device.to_string());
+                    if let Err(e) = self.broadcast_config_change().await {
+                        warn!("Failed to broadcast config change after device recovery: {e}");
+                    }
                 }
 
                 warn!(
@@ -1,14 +1,28 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::config::DaemonConfig;
 use crate::daemon::types::SuperSTTDaemon;
+use crate::services::archive;
 use chrono::Utc;
 use log::{error, info, warn};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 use strum::VariantArray;
-use super_stt_shared::models::protocol::DaemonResponse;
+use super_stt_shared::audio_utils::{ResampleQuality, resample};
+use super_stt_shared::models::protocol::{
+    DaemonResponse, DecodingOverride, HealthStatus, InputTestResult, LastAudioFormat,
+    WordFilterOverride,
+};
 use super_stt_shared::stt_model::STTModel;
 use super_stt_shared::theme::AudioTheme;
+use super_stt_shared::validation;
+
+/// Length of the clip recorded by `test_audio_input`
+const INPUT_TEST_DURATION: Duration = Duration::from_secs(2);
+/// Peak below which a test clip holds no signal at all, as from a muted microphone
+const INPUT_TEST_SILENT_PEAK: f32 = 1e-3;
 
 impl SuperSTTDaemon {
     /// Handle ping command - test connectivity and connection status
@@ -31,6 +45,10 @@ impl SuperSTTDaemon {
         let model_guard = self.model.read().await;
         let model_type_guard = self.model_type.read().await;
 
+        let gpu_memory = model_guard
+            .as_ref()
+            .and_then(|model| cuda_memory_mb(model.device()));
+
         let (device, model_loaded) = match model_guard.as_ref() {
             Some(model) => {
                 let device_str = match model.device() {
@@ -55,10 +73,282 @@ impl SuperSTTDaemon {
         if let Some(model) = model {
             response = response.with_current_model(*model);
         }
+        if let Some((used_mb, total_mb)) = gpu_memory {
+            response = response.with_gpu_memory_mb(used_mb, total_mb);
+        }
+        if let Some(rss_mb) = process_rss_mb() {
+            response = response.with_model_memory_mb(rss_mb);
+        }
+        let input_sample_rate = self
+            .input_sample_rate
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if input_sample_rate > 0 {
+            response = response.with_input_sample_rate(input_sample_rate);
+        }
 
         response
     }
 
+    /// Handle health command - report which subsystems are up and their last errors
+    pub async fn handle_health(&self) -> DaemonResponse {
+        let model_loaded = self.model.read().await.is_some();
+        let udp_streamer_bound = self.udp_streamer.local_addr().is_ok();
+        let dbus_connected = self.dbus_manager.is_some();
+
+        // Probed on request so a device plugged in after startup counts
+        let input_device = self.config.read().await.audio.input_device.clone();
+        let input_device_available =
+            match crate::audio::device::find_input_device(input_device.as_deref()) {
+                Ok(_) => true,
+                Err(e) => {
+                    self.record_subsystem_error("input_device", &e.to_string())
+                        .await;
+                    false
+                }
+            };
+
+        let health = HealthStatus {
+            model_loaded,
+            udp_streamer_bound,
+            dbus_connected,
+            input_device_available,
+            connections: self.resource_manager.connection_count().await,
+            max_connections: self.resource_manager.limits().max_connections,
+            last_errors: self.last_errors.read().await.clone(),
+        };
+        let message = if health.is_healthy() {
+            "healthy"
+        } else {
+            "degraded"
+        };
+        DaemonResponse::success()
+            .with_message(message.to_string())
+            .with_model_loaded(model_loaded)
+            .with_health(health)
+    }
+
+    /// Remember the latest failure of a subsystem for the health command
+    pub async fn record_subsystem_error(&self, subsystem: &str, error: &str) {
+        self.last_errors
+            .write()
+            .await
+            .insert(subsystem.to_string(), error.to_string());
+    }
+
+    /// Handle transcribe file command - decode an audio file and transcribe it
+    /// with the currently loaded model
+    pub async fn handle_transcribe_file(
+        &self,
+        path: String,
+        client_id: String,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        decoding: Option<DecodingOverride>,
+    ) -> DaemonResponse {
+        info!("Processing file transcription request from client: {client_id}");
+
+        let file_path = match self.resolve_audio_file(&path).await {
+            Ok(p) => p,
+            Err(response) => return response,
+        };
+
+        let label = file_path.display().to_string();
+        self.transcribe_audio(
+            move || Self::decode_audio_file(&file_path),
+            label,
+            language,
+            replacements,
+            word_filter,
+            decoding,
+        )
+        .await
+    }
+
+    /// Transcribe an uploaded audio file held in memory, for the HTTP API
+    pub async fn handle_transcribe_bytes(
+        &self,
+        data: Vec<u8>,
+        client_id: String,
+        language: Option<String>,
+    ) -> DaemonResponse {
+        info!(
+            "Processing upload transcription request from client: {client_id} ({} bytes)",
+            data.len()
+        );
+
+        let max_bytes = self.config.read().await.transcription.max_file_size_mb * 1024 * 1024;
+        if data.len() as u64 > max_bytes {
+            return DaemonResponse::error(&format!(
+                "File too large: {} bytes (max {max_bytes} bytes)",
+                data.len()
+            ));
+        }
+
+        let label = format!("upload from {client_id}");
+        self.transcribe_audio(
+            move || Self::decode_audio_bytes(data),
+            label,
+            language,
+            None,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Transcribe an upload that was already decoded to 16kHz mono samples
+    #[cfg(feature = "http")]
+    pub(crate) async fn handle_transcribe_samples(
+        &self,
+        samples: Vec<f32>,
+        client_id: String,
+        language: Option<String>,
+    ) -> DaemonResponse {
+        let label = format!("upload from {client_id}");
+        self.transcribe_audio(move || Ok(samples), label, language, None, None, None)
+            .await
+    }
+
+    /// Decode audio with `decode` and transcribe it with the currently loaded model
+    async fn transcribe_audio(
+        &self,
+        decode: impl FnOnce() -> anyhow::Result<Vec<f32>> + Send + 'static,
+        label: String,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        decoding: Option<DecodingOverride>,
+    ) -> DaemonResponse {
+        if let Err(e) = self.ensure_model_loaded().await {
+            return DaemonResponse::error(&e.to_string());
+        }
+        if let Err(e) = self.validate_language_for_model(language.as_deref()).await {
+            warn!("Language validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid language: {e}"));
+        }
+
+        let rules = self.resolve_text_rules(replacements, word_filter).await;
+        let requested_language = language.clone();
+        let model_clone = Arc::clone(&self.model);
+        let audio_processor = Arc::clone(&self.audio_processor);
+
+        // Decoding, resampling and inference are all CPU bound
+        let result =
+            tokio::task::spawn_blocking(move || -> anyhow::Result<(String, f32, usize)> {
+                let start_time = std::time::Instant::now();
+                let samples = decode()?;
+                let processed_audio = audio_processor.process_audio(&samples, 16000)?;
+
+                let mut model_guard = model_clone.blocking_write();
+                let Some(model) = model_guard.as_mut() else {
+                    anyhow::bail!("Model not loaded");
+                };
+                let (text, confidence) = model.with_decoding(decoding.as_ref(), |model| {
+                    model.transcribe_audio_with_confidence(
+                        &processed_audio,
+                        16000,
+                        language.as_deref(),
+                        &rules,
+                    )
+                })?;
+                let duration = start_time.elapsed();
+                info!("Transcribed {label} in {duration:?}: '{text}'");
+                crate::services::metrics::record_transcription(duration);
+                Ok((text, confidence, samples.len()))
+            })
+            .await;
+
+        match result {
+            Ok(Ok((transcription, confidence, samples))) => {
+                self.save_history(&transcription, samples, 16000).await;
+                let output = self
+                    .transcription_output(
+                        &transcription,
+                        requested_language,
+                        confidence,
+                        samples,
+                        16000,
+                    )
+                    .await;
+                DaemonResponse::success()
+                    .with_transcription(transcription)
+                    .with_confidence(confidence)
+                    .with_result(output)
+            }
+            Ok(Err(e)) => {
+                warn!("File transcription failed: {e}");
+                crate::daemon::transcription::failure_response("File transcription failed", &e)
+            }
+            Err(e) => {
+                error!("File transcription task failed: {e}");
+                DaemonResponse::error(&format!("Task execution failed: {e}"))
+            }
+        }
+    }
+
+    /// Validate a client-supplied audio file path and enforce the configured size limit
+    ///
+    /// Returns the canonical path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error response ready to send back if the path is invalid or the file is too large.
+    pub async fn resolve_audio_file(
+        &self,
+        path: &str,
+    ) -> Result<std::path::PathBuf, DaemonResponse> {
+        let file_path = validation::validate_file_path(path).map_err(|e| {
+            warn!("Rejected audio file path: {e}");
+            DaemonResponse::error(&e.to_string())
+        })?;
+
+        let max_bytes = self.config.read().await.transcription.max_file_size_mb * 1024 * 1024;
+        match tokio::fs::metadata(&file_path).await {
+            Ok(metadata) if metadata.len() > max_bytes => {
+                warn!(
+                    "Rejected {}: {} bytes exceeds limit of {max_bytes} bytes",
+                    file_path.display(),
+                    metadata.len()
+                );
+                Err(DaemonResponse::error(&format!(
+                    "File too large: {} bytes (max {max_bytes} bytes)",
+                    metadata.len()
+                )))
+            }
+            Ok(_) => Ok(file_path),
+            Err(e) => Err(DaemonResponse::error(&format!(
+                "Failed to read file metadata: {e}"
+            ))),
+        }
+    }
+
+    /// Decode an audio file to mono samples at 16kHz
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded, is empty, or resampling fails.
+    pub fn decode_audio_file(path: &std::path::Path) -> anyhow::Result<Vec<f32>> {
+        let (samples, sample_rate) = crate::stt_models::voxtral::audio::pcm_decode(path)?;
+        if samples.is_empty() {
+            anyhow::bail!("Audio file contains no samples");
+        }
+        resample(&samples, sample_rate, 16000, ResampleQuality::Fast)
+    }
+
+    /// Decode an in-memory audio file to mono samples at 16kHz
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded, is empty, or resampling fails.
+    pub fn decode_audio_bytes(data: Vec<u8>) -> anyhow::Result<Vec<f32>> {
+        let (samples, sample_rate) = crate::stt_models::voxtral::audio::pcm_decode_bytes(data)?;
+        if samples.is_empty() {
+            anyhow::bail!("Audio contains no samples");
+        }
+        resample(&samples, sample_rate, 16000, ResampleQuality::Fast)
+    }
+
     /// Handle notify command - broadcast events to subscribers
     #[allow(clippy::cast_possible_truncation)]
     pub async fn handle_notify(
@@ -87,6 +377,11 @@ impl SuperSTTDaemon {
                             .get("audio_level")
                             .and_then(Value::as_f64)
                             .unwrap_or(0.0) as f32,
+                        input_sample_rate: data
+                            .get("input_sample_rate")
+                            .and_then(Value::as_u64)
+                            .and_then(|rate| u32::try_from(rate).ok())
+                            .unwrap_or(0),
                     };
 
                     if let Err(e) = dbus_manager.emit_listening_started(event).await {
@@ -160,7 +455,8 @@ impl SuperSTTDaemon {
         }
     }
 
-    /// Handle subscribe command - subscribe to event types
+    /// Handle subscribe command - subscribe to event types, which may use `*` wildcards
+    /// such as `download_*`
     #[must_use]
     pub fn handle_subscribe(
         &self,
@@ -209,6 +505,30 @@ impl SuperSTTDaemon {
         }
     }
 
+    /// Handle get history command - page through past final transcriptions, newest first
+    pub async fn handle_get_history(
+        &self,
+        since_timestamp: Option<String>,
+        limit: u32,
+    ) -> DaemonResponse {
+        let Some(history) = self.history.as_ref().map(Arc::clone) else {
+            return DaemonResponse::error("Transcription history is unavailable");
+        };
+
+        let result =
+            tokio::task::spawn_blocking(move || history.recent(since_timestamp.as_deref(), limit))
+                .await;
+
+        match result {
+            Ok(Ok(entries)) => DaemonResponse::success().with_history(entries),
+            Ok(Err(e)) => DaemonResponse::error(&e.to_string()),
+            Err(e) => {
+                error!("History task failed: {e}");
+                DaemonResponse::error(&format!("Task execution failed: {e}"))
+            }
+        }
+    }
+
     /// Handle get subscriber info command
     #[must_use]
     pub fn handle_get_subscriber_info(&self) -> DaemonResponse {
@@ -229,9 +549,173 @@ impl SuperSTTDaemon {
             }
         };
 
+        let response = DaemonResponse::success()
+            .with_daemon_config(config_json)
+            .with_message("Daemon configuration retrieved successfully".to_string());
+        // The port comes from the command line, not the config file; report the bound
+        // address so clients can find a daemon streaming elsewhere
+        match self.udp_streamer.local_addr() {
+            Ok(addr) => response.with_udp_addr(addr),
+            Err(_) => response,
+        }
+    }
+
+    /// Handle set config command - validate a config patch and apply it all at once
+    ///
+    /// Model and compute device changes go through `set_model`/`set_device`, which
+    /// handle downloads and reloads, so a patch may only restate their current values.
+    pub async fn handle_set_config(&self, patch: &Value) -> DaemonResponse {
+        let (new_config, pre_roll_changed) = {
+            let mut config = self.config.write().await;
+            let new_config = match config.with_patch(patch) {
+                Ok(new_config) => new_config,
+                Err(e) => {
+                    warn!("Rejected config patch: {e}");
+                    return DaemonResponse::error(&format!(
+                        "Invalid config field {}: {}",
+                        e.field, e.reason
+                    ));
+                }
+            };
+
+            if new_config.transcription.preferred_model != config.transcription.preferred_model {
+                return DaemonResponse::error(
+                    "Invalid config field transcription.preferred_model: use set_model to switch models",
+                );
+            }
+            if new_config.device.preferred_device != config.device.preferred_device {
+                return DaemonResponse::error(
+                    "Invalid config field device.preferred_device: use set_device to switch devices",
+                );
+            }
+
+            let archive = &new_config.archive_recordings;
+            if archive.enabled
+                && *archive != config.archive_recordings
+                && let Err(e) = archive::check_writable(&archive.directory())
+            {
+                warn!("Rejected recording archive directory: {e:#}");
+                return DaemonResponse::error(
+                    "Invalid config field archive_recordings.directory: not writable",
+                );
+            }
+
+            let pre_roll_changed = new_config.audio.pre_roll_ms != config.audio.pre_roll_ms
+                || new_config.audio.input_device != config.audio.input_device
+                || new_config.audio.capture_sample_rate != config.audio.capture_sample_rate;
+            *config = new_config.clone();
+            (new_config, pre_roll_changed)
+        };
+
+        self.apply_live_config(&new_config, pre_roll_changed).await;
+
+        // Broadcast config change (this saves the config to disk)
+        if let Err(e) = self.broadcast_config_change().await {
+            error!("Failed to persist config update: {e}");
+            return DaemonResponse::error(&format!("Failed to save config: {e}"));
+        }
+
+        let config_json = match serde_json::to_value(&new_config) {
+            Ok(value) => value,
+            Err(e) => {
+                error!("Failed to serialize daemon config: {e}");
+                return DaemonResponse::error(&format!("Failed to serialize config: {e}"));
+            }
+        };
+
+        info!("Daemon configuration updated");
         DaemonResponse::success()
             .with_daemon_config(config_json)
-            .with_message("Daemon configuration retrieved successfully".to_string())
+            .with_message("Daemon configuration updated successfully".to_string())
+    }
+
+    /// Re-read the config file and apply it, as on SIGHUP
+    ///
+    /// The model and compute device stay as they are, since switching them goes through
+    /// `set_model`/`set_device`, and so do the network services, which bind at startup.
+    /// A file that fails to load leaves the running config untouched.
+    pub async fn reload_config(&self) {
+        let mut loaded = match DaemonConfig::read_validated() {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!("Config reload failed, keeping the current settings: {e}");
+                return;
+            }
+        };
+
+        let pre_roll_changed = {
+            let mut config = self.config.write().await;
+            if loaded.transcription.preferred_model != config.transcription.preferred_model
+                || loaded.device.preferred_device != config.device.preferred_device
+            {
+                info!(
+                    "Config reload keeps the loaded model and device; use set_model or set_device to switch"
+                );
+            }
+            loaded.transcription.preferred_model = config.transcription.preferred_model;
+            loaded
+                .device
+                .preferred_device
+                .clone_from(&config.device.preferred_device);
+            loaded.network = config.network.clone();
+
+            let pre_roll_changed = loaded.audio.pre_roll_ms != config.audio.pre_roll_ms
+                || loaded.audio.input_device != config.audio.input_device
+                || loaded.audio.capture_sample_rate != config.audio.capture_sample_rate;
+            *config = loaded.clone();
+            pre_roll_changed
+        };
+        self.apply_live_config(&loaded, pre_roll_changed).await;
+        if loaded.archive_recordings.enabled
+            && let Err(e) = archive::check_writable(&loaded.archive_recordings.directory())
+        {
+            warn!("Recording archive unavailable, recordings will not be saved: {e:#}");
+            self.record_subsystem_error("archive", &format!("{e:#}"))
+                .await;
+        }
+        info!("Daemon configuration reloaded");
+
+        if let Err(e) = self
+            .notification_manager
+            .broadcast_event(
+                "daemon_status_changed".to_string(),
+                "daemon".to_string(),
+                serde_json::json!({
+                    "status": "config_reloaded",
+                    "timestamp": Utc::now().to_rfc3339()
+                }),
+            )
+            .await
+        {
+            warn!("Failed to broadcast config reload: {e}");
+        }
+    }
+
+    /// Bring runtime state in line with a config that was just put in place
+    async fn apply_live_config(&self, config: &DaemonConfig, pre_roll_changed: bool) {
+        self.apply_audio_theme(config.audio.theme, &config.audio.custom_sounds);
+        self.audio_processor
+            .set_noise_suppression(config.audio.noise_suppression);
+        self.audio_processor
+            .set_trim_silence(config.audio.trim_silence);
+        self.audio_processor
+            .set_resample_quality(config.audio.resample_quality);
+        self.audio_processor
+            .set_agc_target_rms(config.audio.agc_target());
+        if pre_roll_changed {
+            self.restart_pre_roll().await;
+        }
+        if !config.debug.keep_last_audio {
+            *self.last_audio.write().await = None;
+        }
+        self.preview_typing_enabled.store(
+            config.transcription.preview_typing_enabled,
+            std::sync::atomic::Ordering::Relaxed,
+        );
+        // Waits for a transcription in progress, which keeps its settings
+        if let Some(model) = self.model.write().await.as_mut() {
+            model.set_decoding(config.transcription.decoding);
+        }
     }
 
     /// Handle list all available models command
@@ -262,6 +746,116 @@ impl SuperSTTDaemon {
             .with_message("Available audio themes listed successfully".to_string())
     }
 
+    /// Handle list audio devices command - return available input devices and the selected one
+    pub async fn handle_list_audio_devices(&self) -> DaemonResponse {
+        let devices = match crate::audio::device::list_input_devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                warn!("Failed to list audio input devices: {e}");
+                return DaemonResponse::error(&format!("Failed to list audio devices: {e}"));
+            }
+        };
+        info!(
+            "Audio input devices requested, returning {} devices",
+            devices.len()
+        );
+
+        let mut response = DaemonResponse::success()
+            .with_audio_devices(devices)
+            .with_message("Audio input devices listed successfully".to_string());
+        if let Some(input_device) = self.config.read().await.audio.input_device.clone() {
+            response = response.with_input_device(input_device);
+        }
+        response
+    }
+
+    /// Handle test audio input command - record a short clip, report its levels and
+    /// whether it holds speech, and optionally play it back
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub async fn handle_test_audio_input(&self, playback: bool) -> DaemonResponse {
+        if !self.recording_sessions.read().await.is_empty() {
+            return DaemonResponse::error("Cannot test audio input while a recording is running");
+        }
+
+        let (input_device, capture_rate) = {
+            let config = self.config.read().await;
+            (
+                config.audio.input_device.clone(),
+                config.audio.capture_sample_rate,
+            )
+        };
+        info!(
+            "Testing audio input on {}",
+            input_device.as_deref().unwrap_or("the default device")
+        );
+        let captured = tokio::task::spawn_blocking(move || {
+            crate::audio::device::capture_clip(
+                input_device.as_deref(),
+                capture_rate,
+                INPUT_TEST_DURATION,
+            )
+        })
+        .await;
+        let (samples, sample_rate) = match captured {
+            Ok(Ok((samples, sample_rate))) => {
+                self.input_sample_rate
+                    .store(sample_rate, std::sync::atomic::Ordering::Relaxed);
+                (samples, sample_rate)
+            }
+            Ok(Err(e)) => {
+                warn!("Audio input test failed: {e}");
+                self.record_subsystem_error("input_device", &e.to_string())
+                    .await;
+                return DaemonResponse::error(&format!("Failed to record from input device: {e}"));
+            }
+            Err(e) => {
+                error!("Audio input test task failed: {e}");
+                return DaemonResponse::error("Audio input test failed");
+            }
+        };
+
+        let peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+        let rms = if samples.is_empty() {
+            0.0
+        } else {
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+        let speech_detected = match self.load_vad_model().await {
+            Some(model) => {
+                crate::audio::vad::speech_bounds(&model, &samples, sample_rate).is_some()
+            }
+            None => rms > self.config.read().await.audio.silence.threshold,
+        };
+        let result = InputTestResult {
+            peak,
+            rms,
+            speech_detected,
+            duration_ms: (samples.len() as u64 * 1000) / u64::from(sample_rate.max(1)),
+        };
+        info!("Audio input test: peak {peak:.4}, RMS {rms:.4}, speech {speech_detected}");
+
+        if playback && !samples.is_empty() {
+            let clip = crate::audio::beeper::SoundClip::new(samples, sample_rate);
+            let played =
+                tokio::task::spawn_blocking(move || crate::audio::beeper::play_clip(&clip, 1.0))
+                    .await;
+            if let Ok(Err(e)) = played {
+                warn!("Failed to play back audio input test: {e}");
+            }
+        }
+
+        let message = if peak < INPUT_TEST_SILENT_PEAK {
+            "No signal from the input device, it may be muted or disconnected"
+        } else if speech_detected {
+            "Input device is working and picked up speech"
+        } else {
+            "Input device is working, but no speech was detected"
+        };
+        DaemonResponse::success()
+            .with_message(message.to_string())
+            .with_input_test(result)
+    }
+
     /// Handle set preview typing command - enable or disable preview typing
     #[must_use]
     pub async fn handle_set_preview_typing(&self, enabled: bool) -> DaemonResponse {
@@ -341,4 +935,101 @@ impl SuperSTTDaemon {
             DaemonResponse::success().with_message("No download in progress".to_string())
         }
     }
+
+    /// Handle get last audio command - return the latest recording for debugging,
+    /// as samples or as a WAV file that `transcribe_file` accepts
+    pub async fn handle_get_last_audio(&self, format: LastAudioFormat) -> DaemonResponse {
+        if !self.config.read().await.debug.keep_last_audio {
+            return DaemonResponse::error(
+                "Recordings are not kept, turn on debug.keep_last_audio in the config",
+            );
+        }
+        let Some(audio) = self.last_audio.read().await.clone() else {
+            return DaemonResponse::error("No recording has been kept yet");
+        };
+
+        match format {
+            LastAudioFormat::Samples => DaemonResponse::success().with_audio_data(audio),
+            LastAudioFormat::Wav => {
+                match tokio::task::spawn_blocking(move || write_last_audio_wav(&audio)).await {
+                    Ok(Ok(path)) => {
+                        DaemonResponse::success().with_audio_path(path.display().to_string())
+                    }
+                    Ok(Err(e)) => {
+                        error!("Failed to write the last recording: {e}");
+                        DaemonResponse::error(&format!("Failed to write WAV file: {e}"))
+                    }
+                    Err(e) => {
+                        error!("WAV writing task failed: {e}");
+                        DaemonResponse::error(&format!("Task execution failed: {e}"))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write 16kHz samples to `last_audio.wav` in the cache directory, replacing
+/// the previous one
+///
+/// Samples are stored as 32-bit floats so decoding the file gives them back unchanged.
+fn write_last_audio_wav(audio: &[f32]) -> anyhow::Result<std::path::PathBuf> {
+    let dir = dirs::cache_dir()
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            std::path::PathBuf::from(home).join(".cache")
+        })
+        .join("super-stt");
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join("last_audio.wav");
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec)?;
+    for &sample in audio {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(path)
+}
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Used and total memory of the CUDA device, in MB
+#[cfg(feature = "cuda")]
+fn cuda_memory_mb(device: &candle_core::Device) -> Option<(u64, u64)> {
+    use candle_core::cuda_backend::cudarc::driver::result;
+
+    let candle_core::Device::Cuda(cuda_device) = device else {
+        return None;
+    };
+    // The driver reports memory for whichever context is current on this thread
+    cuda_device.cuda_stream().context().bind_to_thread().ok()?;
+    let (free, total) = result::mem_get_info().ok()?;
+    let (free, total) = (u64::try_from(free).ok()?, u64::try_from(total).ok()?);
+    Some(((total - free) / BYTES_PER_MB, total / BYTES_PER_MB))
+}
+
+/// GPU memory is only available with the `cuda` feature
+#[cfg(not(feature = "cuda"))]
+fn cuda_memory_mb(_device: &candle_core::Device) -> Option<(u64, u64)> {
+    None
+}
+
+/// Resident memory of this process from `/proc/self/status`, in MB
+fn process_rss_mb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024 / BYTES_PER_MB)
 }
@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Unloading the model to free memory, on request or once the daemon has gone unused
+//! for `idle_timeout_minutes`
+//!
+//! After an unload the daemon stays up without a model, unless the idle action is to
+//! exit, and the next recording or transcription reloads it through the model switch path.
+
+use crate::daemon::types::SuperSTTDaemon;
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use super_stt_shared::stt_model::STTModel;
+
+/// How often the idle monitor compares the last activity with the timeout
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What the daemon does once its model has been unloaded for inactivity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleAction {
+    /// Keep running without a model and reload it on the next request
+    #[default]
+    Unload,
+    /// Shut the daemon down
+    Exit,
+}
+
+impl SuperSTTDaemon {
+    /// Note that the model is in use, postponing the idle timeout
+    pub async fn touch_activity(&self) {
+        *self.last_activity.write().await = Instant::now();
+    }
+
+    /// Reload the model if it was unloaded, and count as activity
+    ///
+    /// Concurrent callers wait for the same reload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reloading the model fails; the next call tries again.
+    pub async fn ensure_model_loaded(&self) -> Result<()> {
+        self.touch_activity().await;
+        let mut idle_unloaded = self.idle_unloaded.lock().await;
+        if !*idle_unloaded {
+            return Ok(());
+        }
+
+        let model = self.config.read().await.transcription.preferred_model;
+        info!("Reloading {model} after unload");
+        let response = self.handle_set_model(model).await;
+        if response.status != "success" {
+            anyhow::bail!(
+                "Failed to reload model: {}",
+                response.message.unwrap_or_default()
+            );
+        }
+        *idle_unloaded = false;
+        self.touch_activity().await;
+        Ok(())
+    }
+
+    /// Periodically unload the model once `idle_timeout_minutes` pass without activity
+    pub(crate) fn spawn_idle_monitor(&self) {
+        let daemon = self.clone();
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    () = tokio::time::sleep(IDLE_CHECK_INTERVAL) => {}
+                    _ = shutdown_rx.recv() => break,
+                }
+
+                let (timeout_minutes, action) = {
+                    let config = daemon.config.read().await;
+                    (
+                        config.device.idle_timeout_minutes,
+                        config.device.idle_action,
+                    )
+                };
+                if timeout_minutes == 0 {
+                    continue;
+                }
+                let timeout = Duration::from_secs(u64::from(timeout_minutes) * 60);
+                if daemon.last_activity.read().await.elapsed() < timeout {
+                    continue;
+                }
+
+                if daemon.unload_model("idle").await.is_ok() && action == IdleAction::Exit {
+                    info!("Idle for {timeout_minutes} minutes, shutting down");
+                    let _ = daemon.shutdown_tx.send(());
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Drop the loaded model, to be reloaded by the next request that needs it
+    ///
+    /// # Errors
+    ///
+    /// Returns why the model was left alone: it is in use or not loaded.
+    pub(crate) async fn unload_model(&self, reason: &str) -> Result<STTModel, &'static str> {
+        let mut idle_unloaded = self.idle_unloaded.lock().await;
+        if self.is_recording().await
+            || !self.realtime_manager.get_active_sessions().await.is_empty()
+        {
+            return Err("Cannot unload the model while it is in use");
+        }
+        if self.download_manager.get_current_download().is_some() {
+            return Err("Cannot unload the model while a model is being loaded");
+        }
+        if *idle_unloaded || self.model.read().await.is_none() {
+            return Err("No model is loaded");
+        }
+        let model = self.model_type.write().await.take();
+        *self.model.write().await = None;
+        *idle_unloaded = true;
+        // The configured model is the one the next request reloads
+        let model = match model {
+            Some(model) => model,
+            None => self.config.read().await.transcription.preferred_model,
+        };
+        info!("Unloaded {model} ({reason})");
+
+        if let Err(e) = self
+            .notification_manager
+            .broadcast_event(
+                "daemon_status_changed".to_string(),
+                "daemon".to_string(),
+                serde_json::json!({
+                    "status": "model_unloaded",
+                    "model_loaded": false,
+                    "model_name": model.to_string(),
+                    "reason": reason,
+                    "timestamp": Utc::now().to_rfc3339()
+                }),
+            )
+            .await
+        {
+            warn!("Failed to broadcast model unloaded status: {e}");
+        }
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DaemonConfig;
+    use crate::stt_models::download::ModelSource;
+    use candle_core::{DType, Device};
+    use candle_nn::{VarBuilder, VarMap};
+    use candle_transformers::models::whisper::{Config, model::Whisper};
+    use std::path::Path;
+
+    /// Write a Whisper checkpoint with random weights, small enough to load in a test,
+    /// where a local model source at `root` looks for `model`
+    fn write_tiny_whisper(root: &Path, model: STTModel) -> Result<()> {
+        let dir = root.join(model.model_and_revision().0);
+        std::fs::create_dir_all(&dir)?;
+
+        let config = serde_json::json!({
+            "num_mel_bins": 80,
+            "max_source_positions": 1500,
+            "d_model": 8,
+            "encoder_attention_heads": 1,
+            "encoder_layers": 1,
+            "vocab_size": 5,
+            "max_target_positions": 8,
+            "decoder_attention_heads": 1,
+            "decoder_layers": 1
+        });
+        std::fs::write(dir.join("config.json"), config.to_string())?;
+
+        let tokenizer = serde_json::json!({
+            "version": "1.0",
+            "added_tokens": [],
+            "model": {
+                "type": "WordLevel",
+                "vocab": {
+                    "<|endoftext|>": 0,
+                    "<|startoftranscript|>": 1,
+                    "<|transcribe|>": 2,
+                    "<|notimestamps|>": 3,
+                    "hello": 4
+                },
+                "unk_token": "<|endoftext|>"
+            }
+        });
+        std::fs::write(dir.join("tokenizer.json"), tokenizer.to_string())?;
+
+        let weights = VarMap::new();
+        let vb = VarBuilder::from_varmap(&weights, DType::F32, &Device::Cpu);
+        Whisper::load(&vb, serde_json::from_value::<Config>(config)?)?;
+        weights.save(dir.join("model.safetensors"))?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unloaded_model_reloads_on_demand() {
+        let root = std::env::temp_dir().join(format!("super-stt-idle-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let model = STTModel::WhisperTinyEn;
+        write_tiny_whisper(&root, model).unwrap();
+        // Model switches save the config, which must not land on the user's
+        DaemonConfig::set_config_path(root.join("daemon.toml"));
+
+        let mut config = DaemonConfig::default();
+        config.transcription.model_source = ModelSource::Local(root.clone());
+        config.transcription.preferred_model = model;
+        config.device.preferred_device = "cpu".to_string();
+        let daemon = SuperSTTDaemon::for_tests(config).await;
+        assert_eq!(daemon.handle_set_model(model).await.status, "success");
+
+        assert_eq!(daemon.unload_model("test").await, Ok(model));
+        assert!(daemon.model.read().await.is_none());
+        assert_eq!(daemon.unload_model("test").await, Err("No model is loaded"));
+        // Still the model the next request loads, so it is kept on disk
+        assert_eq!(daemon.handle_delete_model(model).await.status, "error");
+
+        daemon.ensure_model_loaded().await.unwrap();
+        assert!(daemon.model.read().await.is_some());
+        assert_eq!(*daemon.model_type.read().await, Some(model));
+        assert!(!*daemon.idle_unloaded.lock().await);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -5,6 +5,7 @@ pub mod client_management;
 pub mod core;
 pub mod device_management;
 pub mod handlers;
+pub mod idle;
 pub mod model_management;
 pub mod recording;
 pub mod theme_handlers;
@@ -2,14 +2,21 @@
 
 use crate::daemon::types::{STTModelInstance, SuperSTTDaemon};
 use crate::download_progress::DownloadProgressTracker;
-use crate::stt_models::{voxtral::VoxtralModel, whisper::WhisperModel};
+use crate::stt_models::TextRules;
+use crate::stt_models::download::ModelSource;
+use crate::stt_models::voxtral::{VoxtralModel, model::DecodingConfig};
+use crate::stt_models::whisper::WhisperModel;
 use anyhow::Result;
 use chrono::Utc;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use std::sync::Arc;
-use super_stt_shared::models::protocol::DaemonResponse;
+use strum::VariantArray;
+use super_stt_shared::models::protocol::{DaemonResponse, ModelInfo};
 use super_stt_shared::stt_model::STTModel;
 
+// One second of silence at 16kHz, enough to touch every stage of the model
+const WARMUP_SAMPLES: usize = 16000;
+
 impl SuperSTTDaemon {
     /// Load model with explicit target device (used during device switching)
     ///
@@ -24,13 +31,21 @@ impl SuperSTTDaemon {
     ) -> Result<STTModelInstance> {
         let stt_model_copy = *stt_model;
         let target_device_copy = target_device.to_string();
+        let (source, decoding) = {
+            let config = self.config.read().await;
+            (
+                config.transcription.model_source.clone(),
+                config.transcription.decoding,
+            )
+        };
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         info!("Loading model with target device: {target_device}");
 
         // Load model in a single blocking task with cancellation support
+        let load_started = std::time::Instant::now();
         let load_handle = tokio::task::spawn_blocking(move || {
-            Self::load_model_sync(stt_model_copy, &target_device_copy)
+            Self::load_model_sync(stt_model_copy, &source, &target_device_copy, decoding)
         });
 
         // Wait for either model loading completion, shutdown signal, or timeout (60 seconds)
@@ -48,6 +63,8 @@ impl SuperSTTDaemon {
             }
         }?;
 
+        crate::services::metrics::record_model_load(load_started.elapsed());
+
         // Update actual device based on what was loaded
         let actual_device_str = match model_result.device() {
             candle_core::Device::Cpu => "cpu",
@@ -97,6 +114,111 @@ impl SuperSTTDaemon {
         }
     }
 
+    /// Handle list languages command - report the language codes the loaded model accepts
+    ///
+    /// English-only models report an empty list, as there is no language to pick.
+    pub async fn handle_list_languages(&self) -> DaemonResponse {
+        let model_guard = self.model.read().await;
+        let Some(model) = model_guard.as_ref() else {
+            warn!("Languages requested but no model is loaded");
+            return DaemonResponse::error("No model is currently loaded");
+        };
+
+        let languages: Vec<String> = if model.is_multilingual() {
+            model
+                .supported_languages()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        info!(
+            "Supported languages requested, returning {} languages",
+            languages.len()
+        );
+
+        DaemonResponse::success()
+            .with_languages(languages)
+            .with_message("Supported languages listed successfully".to_string())
+    }
+
+    /// Handle list models detailed command - report which models are on disk and which is loaded
+    pub async fn handle_list_models_detailed(&self) -> DaemonResponse {
+        let source = self.config.read().await.transcription.model_source.clone();
+        let loaded = *self.model_type.read().await;
+        let models: Vec<ModelInfo> = STTModel::VARIANTS
+            .iter()
+            .map(|&model| {
+                let size = crate::stt_models::download::downloaded_model_size(model, &source);
+                ModelInfo {
+                    model,
+                    downloaded: size.is_some(),
+                    size_bytes: size.unwrap_or(0),
+                    loaded: loaded == Some(model),
+                }
+            })
+            .collect();
+        let downloaded = models.iter().filter(|info| info.downloaded).count();
+        info!(
+            "Model details requested, {downloaded} of {} models downloaded",
+            models.len()
+        );
+
+        DaemonResponse::success()
+            .with_model_details(models)
+            .with_message("Available models listed successfully".to_string())
+    }
+
+    /// Handle delete model command - remove a downloaded model to reclaim disk space
+    pub async fn handle_delete_model(&self, model: STTModel) -> DaemonResponse {
+        // An unloaded model is still the one the next request reloads
+        let reloads = *self.idle_unloaded.lock().await
+            && self.config.read().await.transcription.preferred_model == model;
+        if reloads || *self.model_type.read().await == Some(model) {
+            return DaemonResponse::error(&format!(
+                "Cannot delete {model} while it is the daemon's model. Switch to another model first."
+            ));
+        }
+        // The download may be for this very model, or a switch may be about to load it
+        if self.download_manager.get_current_download().is_some() {
+            return DaemonResponse::error("Cannot delete models while a model is being loaded");
+        }
+        if let ModelSource::Local(root) = &self.config.read().await.transcription.model_source {
+            return DaemonResponse::error(&format!(
+                "Models are loaded from {}, which the daemon does not manage",
+                root.display()
+            ));
+        }
+
+        match crate::stt_models::download::delete_cached_model(model).await {
+            Ok(freed_bytes) => {
+                #[allow(clippy::cast_precision_loss)]
+                let freed_mb = freed_bytes as f64 / (1024.0 * 1024.0);
+                DaemonResponse::success()
+                    .with_freed_bytes(freed_bytes)
+                    .with_message(format!("Deleted {model}, freed {freed_mb:.1} MB"))
+            }
+            Err(e) => {
+                warn!("Failed to delete model {model}: {e}");
+                DaemonResponse::error(&format!("Failed to delete model: {e}"))
+            }
+        }
+    }
+
+    /// Handle unload model command - free the model's memory until the next
+    /// transcription reloads it
+    pub async fn handle_unload_model(&self) -> DaemonResponse {
+        match self.unload_model("requested").await {
+            Ok(model) => DaemonResponse::success()
+                .with_model_loaded(false)
+                .with_message(format!(
+                    "Unloaded {model}, it reloads on the next transcription"
+                )),
+            Err(reason) => DaemonResponse::error(reason),
+        }
+    }
+
     /// Handle set model command - switch to a different model
     pub async fn handle_set_model(&self, model: STTModel) -> DaemonResponse {
         self.handle_set_model_impl(model).await
@@ -114,6 +236,8 @@ impl SuperSTTDaemon {
             tracker.cancel();
             return *resp;
         }
+        // Only the model slot is replaced; UDP clients stay registered throughout
+        let _client_hold = self.udp_streamer.hold_clients();
         self.unload_current_model().await;
         let start_time = std::time::Instant::now();
         match self
@@ -126,6 +250,7 @@ impl SuperSTTDaemon {
             }
             Err(e) => {
                 error!("Model switch failed: {e}");
+                self.record_subsystem_error("model", &e.to_string()).await;
                 self.download_manager.clear_download();
                 DaemonResponse::error(&format!("Model switch failed: {e}"))
             }
@@ -135,7 +260,7 @@ impl SuperSTTDaemon {
 
 impl SuperSTTDaemon {
     async fn preflight_model_switch(&self, model: STTModel) -> Option<DaemonResponse> {
-        if *self.is_recording.read().await {
+        if self.is_recording().await {
             warn!("Model switch rejected - recording in progress");
             return Some(DaemonResponse::error(
                 "Cannot switch models during active recording. Please wait for recording to complete.",
@@ -167,6 +292,65 @@ impl SuperSTTDaemon {
         None
     }
 
+    /// Handle warmup command - prime the loaded model so the next transcription is fast
+    pub async fn handle_warmup(&self) -> DaemonResponse {
+        if self.is_recording().await {
+            return DaemonResponse::error("Cannot warm up the model while recording");
+        }
+
+        match self.warm_up_model().await {
+            Ok(elapsed) => {
+                let warmup_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+                DaemonResponse::success()
+                    .with_warmup_ms(warmup_ms)
+                    .with_device(self.actual_device.read().await.clone())
+                    .with_message(format!("Model warmed up in {warmup_ms}ms"))
+            }
+            Err(e) => DaemonResponse::error(&format!("Warmup failed: {e}")),
+        }
+    }
+
+    /// Run a short silent buffer through the loaded model so CUDA kernels and
+    /// caches are ready before the first real transcription
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no model is loaded or the warmup task fails.
+    pub async fn warm_up_model(&self) -> Result<std::time::Duration> {
+        let model = Arc::clone(&self.model);
+        tokio::task::spawn_blocking(move || {
+            let start_time = std::time::Instant::now();
+            let mut model_guard = model.blocking_write();
+            let Some(model) = model_guard.as_mut() else {
+                anyhow::bail!("Model not loaded");
+            };
+
+            // Silence rarely decodes to text, and only the timing matters here
+            if let Err(e) = model.transcribe_audio(
+                &vec![0.0; WARMUP_SAMPLES],
+                16000,
+                None,
+                &TextRules::default(),
+            ) {
+                debug!("Warmup transcription returned an error: {e}");
+            }
+            let elapsed = start_time.elapsed();
+            info!("Model warmed up in {elapsed:?}");
+            Ok(elapsed)
+        })
+        .await?
+    }
+
+    /// Warm up a freshly loaded model when `warmup_on_load` is enabled
+    pub async fn warm_up_after_load(&self) {
+        if !self.config.read().await.device.warmup_on_load {
+            return;
+        }
+        if let Err(e) = self.warm_up_model().await {
+            warn!("Model warmup after load failed: {e}");
+        }
+    }
+
     pub async fn broadcast_model_loading_status(&self, model: STTModel) {
         if let Err(e) = self
             .notification_manager
@@ -220,7 +404,12 @@ impl SuperSTTDaemon {
 
     /// Synchronous model loading function that handles device preference and fallback
     /// This is the core blocking operation that should be run in `spawn_blocking`
-    fn load_model_sync(model: STTModel, preferred_device: &str) -> Result<STTModelInstance> {
+    pub(crate) fn load_model_sync(
+        model: STTModel,
+        source: &ModelSource,
+        preferred_device: &str,
+        decoding: DecodingConfig,
+    ) -> Result<STTModelInstance> {
         let force_cpu = preferred_device == "cpu";
         info!("Loading model with device preference: {preferred_device} (force_cpu={force_cpu})");
 
@@ -228,16 +417,18 @@ impl SuperSTTDaemon {
         let initial_result = match model {
             STTModel::VoxtralSmall | STTModel::VoxtralMini => {
                 info!("Loading Voxtral model...");
-                VoxtralModel::new(&model, force_cpu).map(|m| STTModelInstance::Voxtral(Box::new(m)))
+                VoxtralModel::new(&model, source, force_cpu)
+                    .map(|m| STTModelInstance::Voxtral(Box::new(m)))
             }
             _ => {
                 info!("Loading Whisper model...");
-                WhisperModel::new(&model, force_cpu).map(|m| STTModelInstance::Whisper(Box::new(m)))
+                WhisperModel::new(&model, source, force_cpu)
+                    .map(|m| STTModelInstance::Whisper(Box::new(m)))
             }
         };
 
         // Handle CUDA fallback if needed
-        match initial_result {
+        let result = match initial_result {
             Ok(model_instance) => Ok(model_instance),
             Err(e) if !force_cpu => {
                 // If CUDA failed, try CPU fallback
@@ -245,10 +436,10 @@ impl SuperSTTDaemon {
 
                 match model {
                     STTModel::VoxtralSmall | STTModel::VoxtralMini => {
-                        VoxtralModel::new(&model, true)
+                        VoxtralModel::new(&model, source, true)
                             .map(|m| STTModelInstance::Voxtral(Box::new(m)))
                     }
-                    _ => WhisperModel::new(&model, true)
+                    _ => WhisperModel::new(&model, source, true)
                         .map(|m| STTModelInstance::Whisper(Box::new(m))),
                 }
                 .map_err(|cpu_e| {
@@ -260,7 +451,12 @@ impl SuperSTTDaemon {
                 error!("Model loading failed: {e}");
                 Err(e)
             }
-        }
+        };
+
+        result.map(|mut model_instance| {
+            model_instance.set_decoding(decoding);
+            model_instance
+        })
     }
 
     /// Download and load a model.
@@ -273,7 +469,16 @@ impl SuperSTTDaemon {
         tracker: Arc<DownloadProgressTracker>,
         start_time: std::time::Instant,
     ) -> anyhow::Result<STTModelInstance> {
-        crate::stt_models::download::with_progress(&model, Arc::clone(&tracker)).await?;
+        let (source, limits, decoding) = {
+            let config = self.config.read().await;
+            (
+                config.transcription.model_source.clone(),
+                config.transcription.download_limits(),
+                config.transcription.decoding,
+            )
+        };
+        crate::stt_models::download::with_progress(&model, &source, limits, Arc::clone(&tracker))
+            .await?;
         if tracker.is_cancelled() {
             anyhow::bail!("Model loading was cancelled");
         }
@@ -284,9 +489,10 @@ impl SuperSTTDaemon {
         let preferred_device = self.preferred_device.read().await.clone();
         let preferred_device_clone = preferred_device.clone();
         let instance = tokio::task::spawn_blocking(move || {
-            let result = Self::load_model_sync(model, &preferred_device_clone);
+            let result = Self::load_model_sync(model, &source, &preferred_device_clone, decoding);
             let duration = start_time.elapsed();
             info!("Model loading completed in {duration:?}");
+            crate::services::metrics::record_model_load(duration);
             result
         })
         .await??;
@@ -338,6 +544,7 @@ impl SuperSTTDaemon {
         if let Err(e) = self.broadcast_config_change().await {
             warn!("Failed to broadcast config change after model switch: {e}");
         }
+        self.warm_up_after_load().await;
         let _ = self
             .notification_manager
             .broadcast_event(
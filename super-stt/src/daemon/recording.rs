@@ -1,64 +1,486 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::daemon::types::SuperSTTDaemon;
-use crate::services::dbus::ListeningEvent;
-use crate::{audio::recorder::DaemonAudioRecorder, output::preview::Typer};
+use crate::audio::pre_roll::PreRollCapture;
+use crate::audio::recorder::{RecordingCancelled, SilenceConfig};
+use crate::audio::vad::{SileroVad, VadMode};
+use crate::daemon::transcription::failure_response;
+use crate::daemon::types::{
+    DBUS_RECORDING_CLIENT, DEFAULT_RECORDING_CLIENT, PushToTalkSession, RecordRequest,
+    RecordingSession, SuperSTTDaemon,
+};
+use crate::services::dbus::{DBusRequest, ListeningEvent};
+use crate::{
+    audio::recorder::DaemonAudioRecorder,
+    output::preview::{PreviewEdit, Typer},
+    stt_models::{TextRules, voxtral::model::MelGenerationFailed},
+};
 use anyhow::{Context, Result};
 use chrono::Utc;
 use log::{debug, error, info, warn};
 use std::sync::Arc;
-use super_stt_shared::models::protocol::DaemonResponse;
+use super_stt_shared::audio_utils::ResampleQuality;
+use super_stt_shared::models::protocol::{
+    DaemonResponse, SilenceOverride, TranscriptionTimings, WordFilterOverride,
+};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::time::Instant;
 
 // Removed PreviewContext - no longer needed with simplified architecture
 
+// How long `stop_monitor` waits for the monitor to release the input device
+const MONITOR_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+// How often the record queue checks whether the active recording has finished
+const RECORD_QUEUE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+// Five minutes at 16kHz, the longest recording `get_last_audio` keeps
+const MAX_LAST_AUDIO_SAMPLES: usize = 16000 * 300;
+
 impl SuperSTTDaemon {
     /// Handle record command - direct recording in daemon (legacy method)
     pub async fn handle_record(&self, typer: &mut Typer, write_mode: bool) -> DaemonResponse {
-        self.handle_record_internal(typer, write_mode).await
+        self.handle_record_internal(
+            typer,
+            DEFAULT_RECORDING_CLIENT,
+            write_mode,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+        )
+        .await
+    }
+
+    /// Whether any client is recording
+    pub async fn is_recording(&self) -> bool {
+        !self.recording_sessions.read().await.is_empty()
+    }
+
+    /// Whether `client_id` has a recording in progress
+    pub async fn is_client_recording(&self, client_id: &str) -> bool {
+        self.recording_sessions.read().await.contains_key(client_id)
     }
 
     /// Internal record handling implementation
+    ///
+    /// When `partial_tx` is set, every preview transcription is also sent
+    /// through it so `record_stream` clients can follow along. With `timings`
+    /// the response says where the time went.
+    #[allow(clippy::too_many_arguments)]
     pub async fn handle_record_internal(
         &self,
         typer: &mut Typer,
+        client_id: &str,
         write_mode: bool,
+        silence: Option<SilenceOverride>,
+        language: Option<String>,
+        device_name: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        timings: bool,
+        partial_tx: Option<UnboundedSender<(String, f32)>>,
     ) -> DaemonResponse {
-        // Check if already recording - prevent multiple simultaneous recordings
+        // Each client records one thing at a time; other clients are unaffected
+        if self.is_client_recording(client_id).await {
+            warn!("Recording request from {client_id} rejected - already recording");
+            return DaemonResponse::error(
+                "Recording already in progress. Please wait for current recording to complete.",
+            );
+        }
+
+        // Reject unsupported languages before the user starts speaking
+        if let Err(e) = self.validate_language_for_model(language.as_deref()).await {
+            warn!("Language validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid language: {e}"));
+        }
+
+        if let Some(device_name) = device_name
+            && let Err(e) = self.select_input_device(device_name).await
         {
-            let is_recording_guard = self.is_recording.read().await;
-            if *is_recording_guard {
-                warn!("Recording request rejected - already recording");
-                return DaemonResponse::error(
-                    "Recording already in progress. Please wait for current recording to complete.",
-                );
-            }
+            warn!("Input device selection failed: {e}");
+            return DaemonResponse::error(&format!("Invalid audio device: {e}"));
         }
 
+        let silence_config = self.resolve_silence_config(silence).await;
+        let rules = self.resolve_text_rules(replacements, word_filter).await;
+
         // Wait for recording to complete and return the transcription
-        match self.record_and_transcribe(typer, write_mode).await {
-            Ok(transcription) => {
-                if transcription.trim().is_empty() {
+        match self
+            .record_and_transcribe(
+                typer,
+                client_id,
+                write_mode,
+                silence_config,
+                language.as_deref(),
+                &rules,
+                partial_tx.as_ref(),
+            )
+            .await
+        {
+            Ok((transcription, confidence, breakdown, samples)) => {
+                debug!("Recording timings: {breakdown:?}");
+                let output = self
+                    .transcription_output(&transcription, language, confidence, samples, 16000)
+                    .await;
+                let response = if transcription.trim().is_empty() {
                     info!("🎤 Recording completed - No speech detected");
                     DaemonResponse::success()
                         .with_message("Recording completed - No speech detected".to_string())
                         .with_transcription(String::new())
+                        .with_confidence(0.0)
+                        .with_result(output)
                 } else {
                     info!("🎤 Recording completed: '{transcription}'");
 
-                    DaemonResponse::success()
+                    let response = DaemonResponse::success()
                         .with_message("Recording completed successfully".to_string())
                         .with_transcription(transcription)
+                        .with_confidence(confidence)
+                        .with_result(output);
+                    match typer.final_output() {
+                        Some(output) if write_mode => response.with_output(output),
+                        _ => response,
+                    }
+                };
+                if timings {
+                    response.with_timings(breakdown)
+                } else {
+                    response
                 }
             }
+            Err(e) if e.is::<RecordingCancelled>() => {
+                info!("🎤 Recording cancelled");
+                DaemonResponse::success().with_message("Recording cancelled".to_string())
+            }
             Err(e) => {
                 error!("🎤 Recording failed: {e}");
-                DaemonResponse::error(&format!("Recording failed: {e}"))
+                failure_response("Recording failed", &e)
             }
         }
     }
 
-    /// Record audio directly in daemon and transcribe
+    /// Handle a `record` request with a fresh typer for its output mode
+    ///
+    /// A dry run records and transcribes as usual but never writes the result.
+    pub async fn handle_record_request(&self, request: RecordRequest) -> DaemonResponse {
+        let mut typer = Typer::default();
+        typer.set_output(request.output.unwrap_or_default());
+        if request.dry_run {
+            info!(
+                "Dry run from {}, the result will not be written",
+                request.client_id
+            );
+        }
+        self.handle_record_internal(
+            &mut typer,
+            &request.client_id,
+            request.write_mode && !request.dry_run,
+            request.silence,
+            request.language,
+            request.device_name,
+            request.replacements,
+            request.word_filter,
+            request.timings || request.dry_run,
+            None,
+        )
+        .await
+    }
+
+    /// Handle `record_start` command - begin a push-to-talk recording owned by `client_id`
+    ///
+    /// Capture runs until the same client sends `record_stop`; silence does not end it.
+    pub async fn handle_record_start(
+        &self,
+        client_id: String,
+        request: RecordRequest,
+    ) -> DaemonResponse {
+        if self.is_client_recording(&client_id).await {
+            warn!("Push-to-talk request from {client_id} rejected - already recording");
+            return DaemonResponse::error(
+                "Recording already in progress. Please wait for current recording to complete.",
+            );
+        }
+
+        if let Err(e) = self
+            .validate_language_for_model(request.language.as_deref())
+            .await
+        {
+            warn!("Language validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid language: {e}"));
+        }
+
+        if let Some(device_name) = request.device_name
+            && let Err(e) = self.select_input_device(device_name).await
+        {
+            warn!("Input device selection failed: {e}");
+            return DaemonResponse::error(&format!("Invalid audio device: {e}"));
+        }
+
+        let silence_config = self.resolve_silence_config(request.silence).await;
+        let rules = self
+            .resolve_text_rules(request.replacements, request.word_filter)
+            .await;
+
+        let (mut recorder, source_id) = match self
+            .setup_recording_session(&client_id, request.write_mode, silence_config, None, None)
+            .await
+        {
+            Ok(started) => started,
+            Err(e) => {
+                error!("🎤 Push-to-talk recording failed to start: {e}");
+                return DaemonResponse::error(&format!("Recording failed: {e}"));
+            }
+        };
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let recorder_handle = tokio::spawn({
+            let udp_streamer = Arc::clone(&self.udp_streamer);
+            let stop = Arc::clone(&stop);
+            async move {
+                recorder
+                    .record_until_stopped_with_streaming(udp_streamer, stop)
+                    .await
+            }
+        });
+
+        self.push_to_talk.lock().await.insert(
+            client_id.clone(),
+            PushToTalkSession {
+                source_id,
+                write_mode: request.write_mode,
+                language: request.language,
+                rules,
+                output: request.output,
+                stop,
+                recorder: recorder_handle,
+            },
+        );
+
+        info!("🎤 Push-to-talk recording started for {client_id}");
+        DaemonResponse::success().with_message("Recording started".to_string())
+    }
+
+    /// Handle `record_stop` command - end `client_id`'s push-to-talk recording and
+    /// return its transcription
+    pub async fn handle_record_stop(&self, client_id: &str) -> DaemonResponse {
+        let Some(session) = self.push_to_talk.lock().await.remove(client_id) else {
+            return DaemonResponse::error("No push-to-talk recording in progress");
+        };
+
+        let mut typer = Typer::default();
+        typer.set_output(session.output.unwrap_or_default());
+        self.configure_typer(&mut typer).await;
+        let write_mode = session.write_mode;
+
+        let result = self.finish_push_to_talk(&mut typer, session).await;
+        self.finalize_recording_session(
+            client_id,
+            result
+                .as_ref()
+                .ok()
+                .map(|(text, confidence, _)| (text.as_str(), *confidence)),
+            result.as_ref().ok().map(|(_, _, audio)| audio.as_slice()),
+        )
+        .await;
+
+        match result {
+            Ok((transcription, confidence, _)) => {
+                info!("🎤 Push-to-talk recording completed: '{transcription}'");
+                let response = DaemonResponse::success()
+                    .with_message("Recording completed successfully".to_string())
+                    .with_transcription(transcription)
+                    .with_confidence(confidence);
+                match typer.final_output() {
+                    Some(output) if write_mode => response.with_output(output),
+                    _ => response,
+                }
+            }
+            Err(e) => {
+                error!("🎤 Push-to-talk recording failed: {e}");
+                failure_response("Recording failed", &e)
+            }
+        }
+    }
+
+    /// Stop a push-to-talk capture, then transcribe, type and broadcast what it recorded,
+    /// returning the processed 16kHz audio with the transcription
+    async fn finish_push_to_talk(
+        &self,
+        typer: &mut Typer,
+        session: PushToTalkSession,
+    ) -> Result<(String, f32, Vec<f32>)> {
+        session
+            .stop
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        let audio_data = session.recorder.await??;
+
+        let (transcription, confidence, _, processed_audio) = self
+            .transcribe_with_spinner(
+                &audio_data,
+                session.write_mode,
+                session.language.as_deref(),
+                &session.rules,
+            )
+            .await?;
+
+        if session.write_mode {
+            typer.process_final_text(&transcription);
+        }
+        if let Err(e) = self
+            .udp_streamer
+            .broadcast_final_stt(transcription.clone(), confidence, session.source_id)
+            .await
+        {
+            warn!("Failed to broadcast final STT: {}", e);
+        }
+
+        Ok((transcription, confidence, processed_audio))
+    }
+
+    /// Handle a `record` request that may wait for its client's active recording
+    ///
+    /// Starts right away when the client is not recording. Otherwise the request joins the
+    /// record queue and the response carries its position; the result is
+    /// announced through events once the queued recording runs.
+    pub async fn handle_queued_record(&self, request: RecordRequest) -> DaemonResponse {
+        let max_depth = self.config.read().await.audio.record_queue_depth;
+        let mut queue = self.record_queue.lock().await;
+
+        let claimed = queue.claimed.as_deref() == Some(request.client_id.as_str());
+        if queue.pending.is_empty()
+            && !claimed
+            && !self.is_client_recording(&request.client_id).await
+        {
+            drop(queue);
+            return self.handle_record_request(request).await;
+        }
+        if queue.pending.len() >= max_depth {
+            warn!("Recording request rejected - record queue is full ({max_depth})");
+            return DaemonResponse::error(&format!(
+                "Recording already in progress and the record queue is full ({max_depth} pending)."
+            ));
+        }
+
+        queue.pending.push_back(request);
+        let position = queue.pending.len();
+        if !queue.draining {
+            queue.draining = true;
+            self.spawn_record_queue_drain();
+        }
+        info!("🎤 Recording request queued at position {position}");
+
+        DaemonResponse::success()
+            .with_message(format!("Recording queued at position {position}"))
+            .with_queue_position(position)
+    }
+
+    /// Serve the recording methods of the D-Bus interface
+    ///
+    /// A start begins a push-to-talk recording, as the socket `record_start` command
+    /// does, and a stop ends it through `record_stop`, so the audio is transcribed and
+    /// typed. Neither holds the method call open until the transcription is done.
+    pub(crate) fn spawn_dbus_requests(&self, mut requests: UnboundedReceiver<DBusRequest>) {
+        let daemon = self.clone();
+        tokio::spawn(async move {
+            while let Some(request) = requests.recv().await {
+                match request {
+                    // Awaited here so a stop right after it finds the recording
+                    DBusRequest::StartRecording { write_mode } => {
+                        let request = RecordRequest {
+                            client_id: DBUS_RECORDING_CLIENT.to_string(),
+                            write_mode,
+                            silence: None,
+                            language: None,
+                            device_name: None,
+                            replacements: None,
+                            word_filter: None,
+                            output: None,
+                            dry_run: false,
+                            timings: false,
+                        };
+                        let response = daemon
+                            .handle_record_start(DBUS_RECORDING_CLIENT.to_string(), request)
+                            .await;
+                        log_dbus_failure(response);
+                    }
+                    DBusRequest::StopRecording => {
+                        let daemon = daemon.clone();
+                        tokio::spawn(async move {
+                            log_dbus_failure(
+                                daemon.handle_record_stop(DBUS_RECORDING_CLIENT).await,
+                            );
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    /// Run queued recordings one after another until the queue is empty
+    fn spawn_record_queue_drain(&self) {
+        let daemon = self.clone();
+        tokio::spawn(async move {
+            loop {
+                // The front client is checked and claimed under the queue lock, so a
+                // request arriving meanwhile queues behind it instead of taking its turn
+                let next = loop {
+                    let mut queue = daemon.record_queue.lock().await;
+                    let Some(front) = queue.pending.front() else {
+                        queue.draining = false;
+                        break None;
+                    };
+                    if daemon.is_client_recording(&front.client_id).await {
+                        drop(queue);
+                        tokio::time::sleep(RECORD_QUEUE_POLL_INTERVAL).await;
+                        continue;
+                    }
+                    let request = queue.pending.pop_front();
+                    queue.claimed = request.as_ref().map(|r| r.client_id.clone());
+                    break request.map(|request| (request, queue.pending.len()));
+                };
+                let Some((request, remaining)) = next else {
+                    break;
+                };
+
+                info!("🎤 Starting queued recording ({remaining} still queued)");
+                let _ = daemon
+                    .notification_manager
+                    .broadcast_event(
+                        "queued_recording_started".to_string(),
+                        "daemon".to_string(),
+                        serde_json::json!({
+                            "write_mode": request.write_mode,
+                            "remaining": remaining,
+                            "timestamp": Utc::now().to_rfc3339()
+                        }),
+                    )
+                    .await;
+
+                let response = daemon.handle_record_request(request).await;
+                daemon.record_queue.lock().await.claimed = None;
+                let _ = daemon
+                    .notification_manager
+                    .broadcast_event(
+                        "queued_recording_completed".to_string(),
+                        "daemon".to_string(),
+                        serde_json::json!({
+                            "status": response.status,
+                            "transcription": response.transcription,
+                            "message": response.message,
+                            "timestamp": Utc::now().to_rfc3339()
+                        }),
+                    )
+                    .await;
+            }
+        });
+    }
+
+    /// Record audio directly in daemon and transcribe, returning the text, the
+    /// model's confidence in it, where the time went and the number of 16kHz
+    /// samples recorded
     ///
     /// # Errors
     ///
@@ -68,35 +490,45 @@ impl SuperSTTDaemon {
     /// # Panics
     ///
     /// Panics if internal locks (e.g., audio theme or buffers) are poisoned.
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
     pub async fn record_and_transcribe(
         &self,
         typer: &mut Typer,
+        client_id: &str,
         write_mode: bool,
-    ) -> Result<String> {
+        silence_config: SilenceConfig,
+        language: Option<&str>,
+        rules: &TextRules,
+        partial_tx: Option<&UnboundedSender<(String, f32)>>,
+    ) -> Result<(String, f32, TranscriptionTimings, usize)> {
         info!("Starting direct audio recording in daemon with simplified architecture");
+        self.configure_typer(typer).await;
 
         // Set up recording state and create recorder
-        let mut recorder = self.setup_recording_session(write_mode).await?;
+        let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (mut recorder, source_id) = self
+            .setup_recording_session(
+                client_id,
+                write_mode,
+                silence_config,
+                Some(Arc::clone(&cancel)),
+                None,
+            )
+            .await?;
+        recorder.set_cancel_flag(Arc::clone(&cancel));
 
         // Get model processing interval from current model type
-        let model_processing_interval = {
-            let model_type_guard = self.model_type.read().await;
-            if let Some(model_type) = model_type_guard.as_ref() {
-                model_type.get_processing_interval()
-            } else {
-                // Default interval if no model loaded
-                std::time::Duration::from_millis(2000)
-            }
-        };
+        let model_processing_interval = self.model_processing_interval().await;
 
         let actually_typed = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
 
         // Get a reference to the recorder's internal audio buffer for direct preview access
         let preview_buffer = recorder.get_audio_buffer_ref();
+        let resample_ms = recorder.get_resample_ms_ref();
 
         // Detect the actual device sample rate for correct buffer calculations
         let device_sample_rate = recorder.detect_default_input_sample_rate().unwrap_or(16000); // fallback to 16kHz if detection fails
+        let resample_quality = self.config.read().await.audio.resample_quality;
 
         // Start the recorder in its own thread
         let recorder_handle = tokio::spawn({
@@ -108,64 +540,54 @@ impl SuperSTTDaemon {
             }
         });
 
+        // Let `preview_undo`/`preview_redo` reach this typer while it is on screen
+        let (edit_tx, mut edit_rx) = tokio::sync::mpsc::unbounded_channel();
+        if write_mode {
+            *self.preview_edits.write().await = Some(edit_tx);
+        }
+
         let start_time = Instant::now();
+        let mut backpressure = false;
 
         // Main transcription loop - process audio chunks while recording
         loop {
             debug!("Starting transcription loop");
+            // Stretch the interval on slow devices so previews don't pile up
+            let preview_interval = self.adaptive_preview_interval(model_processing_interval);
             debug!(
                 "Model processing interval: {:?}",
-                model_processing_interval.as_millis()
+                preview_interval.as_millis()
             );
+            if preview_interval > model_processing_interval && !backpressure {
+                info!(
+                    "Preview transcription is slower than the {}ms interval, waiting {}ms between passes",
+                    model_processing_interval.as_millis(),
+                    preview_interval.as_millis()
+                );
+            } else if preview_interval == model_processing_interval && backpressure {
+                info!(
+                    "Preview transcription caught up, back to the {}ms interval",
+                    model_processing_interval.as_millis()
+                );
+            }
+            backpressure = preview_interval > model_processing_interval;
+
             // Sleep until model processing interval has been reached
-            tokio::time::sleep(model_processing_interval).await;
+            tokio::time::sleep(preview_interval).await;
 
             // Check if recorder is still active
-            if recorder_handle.is_finished() {
+            if recorder_handle.is_finished() || cancel.load(std::sync::atomic::Ordering::Relaxed) {
                 break;
             }
 
-            // Get last 10 seconds of audio data directly from buffer for preview
-            debug!("About to get 10 secs from buffer");
-            let audio_data = {
-                let buffer_guard = match preview_buffer.lock() {
-                    Ok(guard) => guard,
-                    Err(poisoned) => {
-                        debug!("Buffer lock poisoned, recovering");
-                        poisoned.into_inner()
-                    }
-                };
-
-                let total_samples = buffer_guard.len();
-
-                if total_samples == 0 {
-                    Vec::new()
-                } else {
-                    // For preview, get the most recent audio (last 3-5 seconds is usually enough)
-                    // Using 5 seconds at the actual device sample rate
-                    let samples_for_preview =
-                        std::cmp::min(total_samples, device_sample_rate as usize * 5);
-                    let start_idx = total_samples - samples_for_preview;
-
-                    let samples: Vec<f32> = buffer_guard.range(start_idx..).copied().collect();
-                    debug!(
-                        "Extracted {} samples for preview (from idx {} to {})",
-                        samples.len(),
-                        start_idx,
-                        total_samples
-                    );
-
-                    // Basic audio validation - check if we have reasonable audio levels
-                    let max_amplitude = samples.iter().map(|&x| x.abs()).fold(0.0, f32::max);
-
-                    if max_amplitude < 0.001 {
-                        debug!("Audio appears to be mostly silence, skipping transcription");
-                        Vec::new()
-                    } else {
-                        samples
-                    }
+            while let Ok(edit) = edit_rx.try_recv() {
+                if let Ok(mut actually_typed_guard) = actually_typed.lock() {
+                    typer.apply_edit(edit, &mut actually_typed_guard);
                 }
-            };
+            }
+
+            // Get the most recent audio directly from buffer for preview
+            let audio_data = Self::recent_preview_audio(&preview_buffer, device_sample_rate);
 
             debug!("Got {} audio samples for preview", audio_data.len());
 
@@ -174,32 +596,15 @@ impl SuperSTTDaemon {
                 .preview_typing_enabled
                 .load(std::sync::atomic::Ordering::Relaxed);
 
-            if !audio_data.is_empty() && write_mode && preview_enabled {
+            // Streaming clients need partials even when nothing is typed
+            let type_preview = write_mode && preview_enabled && typer.output().types();
+
+            if !audio_data.is_empty() && (type_preview || partial_tx.is_some()) {
                 // Resample to 16kHz if needed (same as final recording does)
-                let resampled_audio = if device_sample_rate == 16000 {
-                    debug!("No resampling needed, device already at 16kHz");
-                    audio_data
-                } else {
-                    debug!("Resampling from {device_sample_rate}Hz to 16kHz for preview");
-                    match super_stt_shared::utils::audio::resample(
-                        &audio_data,
-                        device_sample_rate,
-                        16000,
-                        super_stt_shared::audio_utils::ResampleQuality::Fast,
-                    ) {
-                        Ok(resampled) => {
-                            debug!(
-                                "Resampled {} samples to {} samples",
-                                audio_data.len(),
-                                resampled.len()
-                            );
-                            resampled
-                        }
-                        Err(e) => {
-                            warn!("Failed to resample preview audio: {e}");
-                            continue; // Skip this preview iteration
-                        }
-                    }
+                let Some(resampled_audio) =
+                    Self::resample_for_preview(audio_data, device_sample_rate, resample_quality)
+                else {
+                    continue; // Skip this preview iteration
                 };
 
                 // Transcribe resampled audio data using current model
@@ -207,18 +612,33 @@ impl SuperSTTDaemon {
                     "Starting preview transcription with {} samples",
                     resampled_audio.len()
                 );
-                if let Ok(text) = self.transcribe_audio_chunk(&resampled_audio).await
+                let transcribe_start = Instant::now();
+                let result = self
+                    .transcribe_audio_chunk(&resampled_audio, language, rules)
+                    .await;
+                self.record_preview_latency(transcribe_start.elapsed());
+
+                if let Ok((text, confidence)) = result
                     && !text.trim().is_empty()
                 {
                     info!(
                         "Updating preview with text: '{}'",
                         text.chars().take(30).collect::<String>()
                     );
-                    if let Ok(mut actually_typed_guard) = actually_typed.lock() {
+                    if type_preview && let Ok(mut actually_typed_guard) = actually_typed.lock() {
                         typer.update_preview(&text, &mut actually_typed_guard);
                     }
 
-                    if let Err(e) = self.udp_streamer.broadcast_partial_stt(text.clone(), 1.0, 0).await {
+                    if let Some(partial_tx) = partial_tx {
+                        // The receiver is gone once the client disconnects
+                        let _ = partial_tx.send((text.clone(), confidence));
+                    }
+
+                    if let Err(e) = self
+                        .udp_streamer
+                        .broadcast_partial_stt(text.clone(), confidence, source_id)
+                        .await
+                    {
                         warn!("Failed to broadcast partial STT: {}", e);
                     }
                 }
@@ -228,17 +648,21 @@ impl SuperSTTDaemon {
                 debug!("No audio data available for preview yet");
             }
 
-            // Prevent infinite loops with a reasonable timeout
-            if start_time.elapsed() > std::time::Duration::from_secs(60) {
+            // Prevent infinite loops by honouring the recording's duration cap
+            if start_time.elapsed() > silence_config.max_duration() {
                 warn!("Recording timeout reached, stopping preview loop");
                 break;
             }
         }
 
+        if write_mode {
+            *self.preview_edits.write().await = None;
+        }
         info!("Step 1 complete: Preview has finished");
 
-        // Wait for recorder to finish and get full audio data
-        let full_audio_data = recorder_handle.await??;
+        // Wait for recorder to finish; a cancelled recorder stops without its audio
+        let recording_result = recorder_handle.await?;
+        let record_ms = u64::try_from(start_time.elapsed().as_millis()).unwrap_or(u64::MAX);
 
         // Clear preview after recording is done (only if preview typing was enabled)
         if write_mode {
@@ -262,11 +686,26 @@ impl SuperSTTDaemon {
         }
         info!("Step 2 complete: Preview has been cleared");
 
+        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            self.finalize_recording_session(client_id, None, None).await;
+            return Err(RecordingCancelled.into());
+        }
+        let full_audio_data = recording_result?;
+
         // STEP 3: Loader start + STEP 4: GPU final transcription + STEP 5: Loader end
         info!("Step 3-5: Starting loader, running GPU final transcription, stopping loader");
-        let transcription_result = self
-            .transcribe_with_spinner(typer, &full_audio_data, write_mode)
-            .await?;
+        let (transcription_result, confidence, mut timings, processed_audio) = match self
+            .transcribe_with_spinner(&full_audio_data, write_mode, language, rules)
+            .await
+        {
+            Ok(transcribed) => transcribed,
+            Err(e) => {
+                self.finalize_recording_session(client_id, None, None).await;
+                return Err(e);
+            }
+        };
+        timings.record_ms = record_ms;
+        timings.resample_ms = resample_ms.load(std::sync::atomic::Ordering::Relaxed);
         info!("Step 3-5 complete: Final GPU transcription finished");
 
         // STEP 6: Type final transcript and broadcast to UDP clients
@@ -274,7 +713,11 @@ impl SuperSTTDaemon {
             typer.process_final_text(&transcription_result);
         }
 
-        if let Err(e) = self.udp_streamer.broadcast_final_stt(transcription_result.clone(), 1.0, 0).await {
+        if let Err(e) = self
+            .udp_streamer
+            .broadcast_final_stt(transcription_result.clone(), confidence, source_id)
+            .await
+        {
             warn!("Failed to broadcast final STT: {}", e);
         }
 
@@ -282,8 +725,9 @@ impl SuperSTTDaemon {
 
         // Finalize recording session
         self.finalize_recording_session(
-            &transcription_result,
-            &std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            client_id,
+            Some((&transcription_result, confidence)),
+            Some(&processed_audio),
         )
         .await;
 
@@ -291,11 +735,406 @@ impl SuperSTTDaemon {
             "🎯 Perfect sequence completed: GPU preview finish → clear → loader → GPU final → type final"
         );
 
-        Ok(transcription_result)
+        Ok((
+            transcription_result,
+            confidence,
+            timings,
+            full_audio_data.len(),
+        ))
+    }
+
+    /// Handle `record_continuous` command - keep dictating across silences until `stop`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn handle_record_continuous(
+        &self,
+        typer: &mut Typer,
+        client_id: &str,
+        write_mode: bool,
+        silence: Option<SilenceOverride>,
+        language: Option<String>,
+        device_name: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+    ) -> DaemonResponse {
+        if self.is_client_recording(client_id).await {
+            warn!("Continuous recording request from {client_id} rejected - already recording");
+            return DaemonResponse::error(
+                "Recording already in progress. Please wait for current recording to complete.",
+            );
+        }
+
+        // Reject unsupported languages before the user starts speaking
+        if let Err(e) = self.validate_language_for_model(language.as_deref()).await {
+            warn!("Language validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid language: {e}"));
+        }
+
+        if let Some(device_name) = device_name
+            && let Err(e) = self.select_input_device(device_name).await
+        {
+            warn!("Input device selection failed: {e}");
+            return DaemonResponse::error(&format!("Invalid audio device: {e}"));
+        }
+
+        let silence_config = self.resolve_silence_config(silence).await;
+        let rules = self.resolve_text_rules(replacements, word_filter).await;
+
+        match self
+            .record_continuous_and_transcribe(
+                typer,
+                client_id,
+                write_mode,
+                silence_config,
+                language.as_deref(),
+                &rules,
+            )
+            .await
+        {
+            Ok(transcription) => {
+                info!("🎤 Continuous recording stopped: '{transcription}'");
+                DaemonResponse::success()
+                    .with_message("Continuous recording stopped".to_string())
+                    .with_transcription(transcription)
+            }
+            Err(e) => {
+                error!("🎤 Continuous recording failed: {e}");
+                DaemonResponse::error(&format!("Continuous recording failed: {e}"))
+            }
+        }
+    }
+
+    /// Handle stop command - end the active continuous recording
+    pub async fn handle_stop(&self) -> DaemonResponse {
+        let sessions = self.recording_sessions.read().await;
+        if let Some(stop) = sessions.values().find_map(|session| session.stop.as_ref()) {
+            info!("Stopping continuous recording");
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            DaemonResponse::success().with_message("Stopping continuous recording".to_string())
+        } else {
+            DaemonResponse::error("No continuous recording in progress")
+        }
+    }
+
+    /// Handle `cancel_recording` command - abort recordings without transcribing them
+    ///
+    /// Only `client_id`'s recordings are cancelled when it is set, otherwise all of them.
+    pub async fn handle_cancel_recording(&self, client_id: Option<&str>) -> DaemonResponse {
+        let owned_by = |owner: &str| client_id.is_none_or(|client_id| client_id == owner);
+
+        let mut signalled = false;
+        for (owner, session) in self.recording_sessions.read().await.iter() {
+            if owned_by(owner)
+                && let Some(cancel) = session.cancel.as_ref()
+            {
+                info!("Cancelling recording of {owner}");
+                cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+                signalled = true;
+            }
+        }
+
+        // A push-to-talk recording is discarded here since nobody waits on it
+        let push_to_talk: Vec<(String, PushToTalkSession)> = {
+            let mut sessions = self.push_to_talk.lock().await;
+            let owners: Vec<String> = sessions.keys().filter(|o| owned_by(o)).cloned().collect();
+            owners
+                .into_iter()
+                .filter_map(|owner| sessions.remove(&owner).map(|session| (owner, session)))
+                .collect()
+        };
+        if !signalled && push_to_talk.is_empty() {
+            return DaemonResponse::error("No recording in progress");
+        }
+        for (owner, session) in push_to_talk {
+            info!("Cancelling push-to-talk recording of {owner}");
+            session
+                .stop
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+            let _ = session.recorder.await;
+            self.finalize_recording_session(&owner, None, None).await;
+        }
+
+        if signalled {
+            DaemonResponse::success().with_message("Cancelling recording".to_string())
+        } else {
+            DaemonResponse::success().with_message("Recording cancelled".to_string())
+        }
     }
 
-    /// Transcribe a chunk of audio data for preview
-    async fn transcribe_audio_chunk(&self, audio_data: &[f32]) -> Result<String> {
+    /// Handle `preview_undo`/`preview_redo` commands - correct the active recording's preview
+    ///
+    /// The edit is applied before the next preview pass.
+    pub async fn handle_preview_edit(&self, edit: PreviewEdit) -> DaemonResponse {
+        let preview_edits = self.preview_edits.read().await;
+        if let Some(edit_tx) = preview_edits.as_ref()
+            && edit_tx.send(edit).is_ok()
+        {
+            info!("Queued preview {edit:?}");
+            DaemonResponse::success().with_message(format!("Preview {edit:?} queued"))
+        } else {
+            DaemonResponse::error("No recording is typing a preview")
+        }
+    }
+
+    /// Handle `start_monitor` command - stream input levels over UDP without recording
+    pub async fn handle_start_monitor(&self) -> DaemonResponse {
+        if self.is_recording().await {
+            warn!("Monitor request rejected - recording in progress");
+            return DaemonResponse::error("Cannot start monitoring while recording");
+        }
+
+        let mut handle_guard = self.audio_monitoring_handle.write().await;
+        if handle_guard
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return DaemonResponse::success()
+                .with_message("Audio monitoring already running".to_string());
+        }
+
+        let mut recorder = match DaemonAudioRecorder::new_with_theme(self.get_audio_theme()) {
+            Ok(recorder) => recorder,
+            Err(e) => {
+                error!("Failed to create audio recorder for monitoring: {e}");
+                return DaemonResponse::error(&format!("Failed to start monitoring: {e}"));
+            }
+        };
+        {
+            let config = self.config.read().await;
+            recorder.set_frequency_bands(config.audio.frequency_bands);
+            recorder.set_input_device(config.audio.input_device.clone());
+            recorder.set_capture_sample_rate(config.audio.capture_sample_rate);
+        }
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        *self.monitor_stop.write().await = Some(Arc::clone(&stop));
+
+        let udp_streamer = Arc::clone(&self.udp_streamer);
+        *handle_guard = Some(tokio::spawn(async move {
+            if let Err(e) = recorder.monitor_with_streaming(udp_streamer, stop).await {
+                error!("Audio monitoring failed: {e}");
+            }
+        }));
+
+        info!("Audio monitoring started");
+        DaemonResponse::success().with_message("Audio monitoring started".to_string())
+    }
+
+    /// Handle `stop_monitor` command - release the input device held by the level monitor
+    pub async fn handle_stop_monitor(&self) -> DaemonResponse {
+        if self.stop_monitor().await {
+            DaemonResponse::success().with_message("Audio monitoring stopped".to_string())
+        } else {
+            DaemonResponse::error("No audio monitoring in progress")
+        }
+    }
+
+    /// Stop the level monitor and wait for it to close the input device
+    ///
+    /// Returns whether a monitor was running.
+    async fn stop_monitor(&self) -> bool {
+        if let Some(stop) = self.monitor_stop.write().await.take() {
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+
+        let Some(mut handle) = self.audio_monitoring_handle.write().await.take() else {
+            return false;
+        };
+        if handle.is_finished() {
+            return false;
+        }
+
+        // The monitor loop checks its stop flag every tick; abort if it is stuck
+        if tokio::time::timeout(MONITOR_STOP_TIMEOUT, &mut handle)
+            .await
+            .is_err()
+        {
+            warn!("Audio monitoring did not stop in time, aborting");
+            handle.abort();
+        }
+        info!("Audio monitoring stopped");
+        true
+    }
+
+    /// Record continuously, transcribing each utterance as silence ends it, and
+    /// return all utterances joined once the recording is stopped
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if recording setup, audio processing or transcription fails.
+    pub async fn record_continuous_and_transcribe(
+        &self,
+        typer: &mut Typer,
+        client_id: &str,
+        write_mode: bool,
+        silence_config: SilenceConfig,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<String> {
+        info!("Starting continuous audio recording in daemon");
+        self.configure_typer(typer).await;
+
+        // Published with the session, so `stop` only ever sees a recording that started
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (recorder, source_id) = self
+            .setup_recording_session(
+                client_id,
+                write_mode,
+                silence_config,
+                None,
+                Some(Arc::clone(&stop)),
+            )
+            .await?;
+
+        let result = self
+            .run_continuous_session(
+                typer,
+                source_id,
+                write_mode,
+                language,
+                rules,
+                recorder,
+                Arc::clone(&stop),
+            )
+            .await;
+
+        // Make sure the recorder winds down even if transcription bailed out early
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+
+        self.finalize_recording_session(
+            client_id, // The utterances were each finalized and archived on their own
+            None, None,
+        )
+        .await;
+
+        result
+    }
+
+    /// Drive a continuous recording: preview the current utterance and finalize each
+    /// completed one until the recorder reports it has stopped
+    #[allow(clippy::too_many_arguments)]
+    async fn run_continuous_session(
+        &self,
+        typer: &mut Typer,
+        source_id: u32,
+        write_mode: bool,
+        language: Option<&str>,
+        rules: &TextRules,
+        mut recorder: DaemonAudioRecorder,
+        stop: Arc<std::sync::atomic::AtomicBool>,
+    ) -> Result<String> {
+        let model_processing_interval = self.model_processing_interval().await;
+        let preview_buffer = recorder.get_audio_buffer_ref();
+        let device_sample_rate = recorder.detect_default_input_sample_rate().unwrap_or(16000); // fallback to 16kHz if detection fails
+        let resample_quality = self.config.read().await.audio.resample_quality;
+
+        let (utterance_tx, mut utterance_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<f32>>();
+        let recorder_handle = tokio::spawn({
+            let udp_streamer = Arc::clone(&self.udp_streamer);
+            async move {
+                recorder
+                    .record_continuous_with_streaming(udp_streamer, stop, utterance_tx)
+                    .await
+            }
+        });
+
+        let actually_typed = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+        let mut preview_interval = tokio::time::interval(model_processing_interval);
+        let mut utterances: Vec<String> = Vec::new();
+
+        loop {
+            tokio::select! {
+                utterance = utterance_rx.recv() => {
+                    // The channel closes once the recorder has stopped and flushed
+                    let Some(audio_data) = utterance else {
+                        break;
+                    };
+
+                    if write_mode && let Ok(mut actually_typed_guard) = actually_typed.lock() {
+                        typer.clear_preview(&mut actually_typed_guard);
+                    }
+
+                    let (text, confidence, _, processed_audio) = self
+                        .transcribe_with_spinner(
+                            &audio_data,
+                            write_mode,
+                            language,
+                            rules,
+                        )
+                        .await?;
+                    let text = text.trim();
+                    if text.is_empty() {
+                        debug!("Utterance produced no transcription");
+                        continue;
+                    }
+                    self.archive_recording(&processed_audio, text, confidence).await;
+
+                    info!("🎤 Utterance transcribed: '{text}'");
+                    if write_mode {
+                        typer.process_final_text(text);
+                    }
+                    if let Err(e) = self
+                        .udp_streamer
+                        .broadcast_final_stt(text.to_string(), confidence, source_id)
+                        .await
+                    {
+                        warn!("Failed to broadcast final STT: {e}");
+                    }
+                    utterances.push(text.to_string());
+                }
+                _ = preview_interval.tick() => {
+                    let preview_typing = write_mode
+                        && self
+                            .preview_typing_enabled
+                            .load(std::sync::atomic::Ordering::Relaxed);
+                    if !preview_typing && !self.udp_streamer.has_clients().await {
+                        continue;
+                    }
+
+                    let audio_data =
+                        Self::recent_preview_audio(&preview_buffer, device_sample_rate);
+                    if audio_data.is_empty() {
+                        continue;
+                    }
+                    let Some(resampled_audio) =
+                        Self::resample_for_preview(audio_data, device_sample_rate, resample_quality)
+                    else {
+                        continue;
+                    };
+
+                    if let Ok((text, confidence)) = self
+                    .transcribe_audio_chunk(&resampled_audio, language, rules)
+                    .await
+                        && !text.trim().is_empty()
+                    {
+                        if preview_typing
+                            && let Ok(mut actually_typed_guard) = actually_typed.lock()
+                        {
+                            typer.update_preview(&text, &mut actually_typed_guard);
+                        }
+                        if let Err(e) = self
+                            .udp_streamer
+                            .broadcast_partial_stt(text, confidence, source_id)
+                            .await
+                        {
+                            warn!("Failed to broadcast partial STT: {e}");
+                        }
+                    }
+                }
+            }
+        }
+
+        recorder_handle.await??;
+
+        Ok(utterances.join(" "))
+    }
+
+    /// Transcribe a chunk of audio data for preview, with the model's confidence
+    async fn transcribe_audio_chunk(
+        &self,
+        audio_data: &[f32],
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<(String, f32)> {
         debug!(
             "Processing {} samples for preview transcription",
             audio_data.len()
@@ -304,7 +1143,7 @@ impl SuperSTTDaemon {
         // Basic validation of audio data
         if audio_data.is_empty() {
             debug!("Audio data is empty, skipping transcription");
-            return Ok(String::new());
+            return Ok((String::new(), 0.0));
         }
 
         // Check audio length - need at least 1 second of audio for decent transcription
@@ -313,7 +1152,7 @@ impl SuperSTTDaemon {
                 "Audio data too short ({} samples), skipping transcription",
                 audio_data.len()
             );
-            return Ok(String::new());
+            return Ok((String::new(), 0.0));
         }
 
         // Process audio
@@ -329,24 +1168,31 @@ impl SuperSTTDaemon {
 
         // Clone the model Arc for the blocking task
         let model_clone = Arc::clone(&self.model);
+        let language = language.map(str::to_string);
+        let rules = rules.clone();
 
         // Run transcription in a blocking task to avoid blocking the async runtime
-        let result = tokio::task::spawn_blocking(move || {
+        let result = tokio::task::spawn_blocking(move || -> Result<(String, f32)> {
             // Get exclusive write access to the model
             let mut model_guard = model_clone.blocking_write();
 
             if let Some(model) = model_guard.as_mut() {
-                match model.transcribe_audio(&processed_audio, 16000) {
-                    Ok(text) => Ok(text) as Result<String>,
+                match model.transcribe_audio_with_confidence(
+                    &processed_audio,
+                    16000,
+                    language.as_deref(),
+                    &rules,
+                ) {
+                    Ok(result) => Ok(result),
                     Err(e) => {
                         // For preview transcription errors, return empty string instead of failing
                         warn!("Preview transcription failed, continuing: {e}");
-                        Ok(String::new()) as Result<String>
+                        Ok((String::new(), 0.0))
                     }
                 }
             } else {
                 warn!("Model not loaded for preview transcription");
-                Ok(String::new()) as Result<String>
+                Ok((String::new(), 0.0))
             }
         })
         .await
@@ -355,45 +1201,351 @@ impl SuperSTTDaemon {
         Ok(result)
     }
 
-    /// Set up recording state and create audio recorder
-    async fn setup_recording_session(&self, write_mode: bool) -> Result<DaemonAudioRecorder> {
-        // Double-check recording state and set atomically
-        {
-            let mut is_recording_guard = self.is_recording.write().await;
-            if *is_recording_guard {
-                error!("Recording already in progress - rejecting duplicate request");
+    /// Get the most recent few seconds of captured audio for a preview pass
+    ///
+    /// Returns an empty buffer when nothing has been captured yet or the audio is silent.
+    fn recent_preview_audio(
+        preview_buffer: &std::sync::Mutex<std::collections::VecDeque<f32>>,
+        device_sample_rate: u32,
+    ) -> Vec<f32> {
+        let buffer_guard = match preview_buffer.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                debug!("Buffer lock poisoned, recovering");
+                poisoned.into_inner()
+            }
+        };
+
+        let total_samples = buffer_guard.len();
+
+        if total_samples == 0 {
+            return Vec::new();
+        }
+
+        // For preview, get the most recent audio (last 3-5 seconds is usually enough)
+        // Using 5 seconds at the actual device sample rate
+        let samples_for_preview = std::cmp::min(total_samples, device_sample_rate as usize * 5);
+        let start_idx = total_samples - samples_for_preview;
+
+        let samples: Vec<f32> = buffer_guard.range(start_idx..).copied().collect();
+        debug!(
+            "Extracted {} samples for preview (from idx {} to {})",
+            samples.len(),
+            start_idx,
+            total_samples
+        );
+
+        // Basic audio validation - check if we have reasonable audio levels
+        let max_amplitude = samples.iter().map(|&x| x.abs()).fold(0.0, f32::max);
+
+        if max_amplitude < 0.001 {
+            debug!("Audio appears to be mostly silence, skipping transcription");
+            Vec::new()
+        } else {
+            samples
+        }
+    }
+
+    /// Hold on to a recording's processed audio, as the model receives it, for
+    /// `get_last_audio` while `debug.keep_last_audio` is on
+    ///
+    /// Recordings over [`MAX_LAST_AUDIO_SAMPLES`] are not kept, so the samples fit in
+    /// one socket message.
+    async fn keep_last_audio(&self, audio_data: &[f32]) {
+        if !self.config.read().await.debug.keep_last_audio {
+            return;
+        }
+        let kept = if audio_data.len() > MAX_LAST_AUDIO_SAMPLES {
+            warn!(
+                "Recording of {} samples is too long to keep for get_last_audio",
+                audio_data.len()
+            );
+            None
+        } else {
+            Some(audio_data.to_vec())
+        };
+        *self.last_audio.write().await = kept;
+    }
+
+    /// Resample preview audio to 16kHz, returning `None` if resampling fails
+    fn resample_for_preview(
+        audio_data: Vec<f32>,
+        device_sample_rate: u32,
+        quality: ResampleQuality,
+    ) -> Option<Vec<f32>> {
+        if device_sample_rate == 16000 {
+            debug!("No resampling needed, device already at 16kHz");
+            return Some(audio_data);
+        }
+
+        debug!("Resampling from {device_sample_rate}Hz to 16kHz for preview");
+        match super_stt_shared::utils::audio::resample(
+            &audio_data,
+            device_sample_rate,
+            16000,
+            quality,
+        ) {
+            Ok(resampled) => {
+                debug!(
+                    "Resampled {} samples to {} samples",
+                    audio_data.len(),
+                    resampled.len()
+                );
+                Some(resampled)
+            }
+            Err(e) => {
+                warn!("Failed to resample preview audio: {e}");
+                None
+            }
+        }
+    }
+
+    /// Get the preview processing interval for the current model
+    async fn model_processing_interval(&self) -> std::time::Duration {
+        let model_type_guard = self.model_type.read().await;
+        if let Some(model_type) = model_type_guard.as_ref() {
+            model_type.get_processing_interval()
+        } else {
+            // Default interval if no model loaded
+            std::time::Duration::from_millis(2000)
+        }
+    }
+
+    /// Fold one preview transcription's wall-clock time into the moving average
+    fn record_preview_latency(&self, elapsed: std::time::Duration) {
+        let sample = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        let previous = self
+            .preview_latency_ms
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let average = if previous == 0 {
+            sample
+        } else {
+            previous.saturating_mul(3).saturating_add(sample) / 4
+        };
+        self.preview_latency_ms
+            .store(average, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Time to wait between preview passes: the model's interval, extended to the
+    /// average transcription time when the device can't keep up with it
+    fn adaptive_preview_interval(&self, base: std::time::Duration) -> std::time::Duration {
+        let average = std::time::Duration::from_millis(
+            self.preview_latency_ms
+                .load(std::sync::atomic::Ordering::Relaxed),
+        );
+        base.max(average)
+    }
+
+    /// Apply any per-request silence overrides on top of the configured defaults
+    async fn resolve_silence_config(&self, silence: Option<SilenceOverride>) -> SilenceConfig {
+        let config = self.config.read().await;
+        match silence {
+            Some(ref overrides) => config.audio.silence.with_override(overrides),
+            None => config.audio.silence,
+        }
+    }
+
+    /// Load the configured Silero VAD weights, or `None` with a warning when they are
+    /// missing, leaving speech detection to energy alone
+    pub(crate) async fn load_vad_model(&self) -> Option<SileroVad> {
+        let path = self
+            .config
+            .read()
+            .await
+            .audio
+            .vad_model
+            .clone()
+            .unwrap_or_else(SileroVad::default_path);
+        match SileroVad::load(&path) {
+            Ok(model) => Some(model),
+            Err(e) => {
+                warn!("VAD unavailable, using energy thresholding only: {e:#}");
+                None
+            }
+        }
+    }
+
+    /// Apply the configured typing options to a recording's typer
+    async fn configure_typer(&self, typer: &mut Typer) {
+        let config = self.config.read().await;
+        typer.set_voice_commands(config.transcription.voice_commands_enabled);
+        typer.set_typing_backend(config.transcription.typing_backend);
+        typer.set_commit_passes(config.transcription.preview_commit_passes);
+        typer.set_formatting(config.transcription.formatting);
+        typer.set_focus_filter(config.transcription.focus_filter.clone());
+    }
+
+    /// Remember the capture device for this and future recordings
+    ///
+    /// The name must match a currently available input device; it is persisted so
+    /// the choice survives daemon restarts.
+    async fn select_input_device(&self, device_name: String) -> Result<()> {
+        let available = crate::audio::device::list_input_devices()?;
+        if !available.contains(&device_name) {
+            return Err(anyhow::anyhow!("no input device named '{device_name}'"));
+        }
+
+        let mut config = self.config.write().await;
+        if config.audio.input_device.as_deref() != Some(device_name.as_str()) {
+            info!("Selecting input device '{device_name}'");
+            config.update_input_device(Some(device_name));
+            drop(config);
+            self.restart_pre_roll().await;
+        }
+        Ok(())
+    }
+
+    /// Restart the pre-roll capture on the configured device and length
+    pub async fn restart_pre_roll(&self) {
+        let (input_device, capture_rate, pre_roll_ms) = {
+            let config = self.config.read().await;
+            (
+                config.audio.input_device.clone(),
+                config.audio.capture_sample_rate,
+                config.audio.pre_roll_ms,
+            )
+        };
+        // Dropping the old capture stops its thread
+        *self.pre_roll.write().await = (pre_roll_ms > 0).then(|| {
+            Arc::new(PreRollCapture::start(
+                input_device,
+                capture_rate,
+                pre_roll_ms,
+            ))
+        });
+    }
+
+    /// Register `client_id`'s recording session and create its audio recorder
+    ///
+    /// Returns the recorder with the UDP source id tagging the session's packets.
+    /// `cancel` is the flag `cancel_recording` sets, for recordings that honour it, and
+    /// `stop` the flag `stop` sets to end a continuous recording.
+    async fn setup_recording_session(
+        &self,
+        client_id: &str,
+        write_mode: bool,
+        silence_config: SilenceConfig,
+        cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
+        stop: Option<Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<(DaemonAudioRecorder, u32)> {
+        // Before the session is registered, as a model reload refuses to run during one
+        self.ensure_model_loaded().await?;
+
+        // Double-check the client's recording state and register the session atomically
+        let source_id = {
+            let mut sessions = self.recording_sessions.write().await;
+            if sessions.contains_key(client_id) {
+                error!(
+                    "Recording already in progress for {client_id} - rejecting duplicate request"
+                );
                 return Err(anyhow::anyhow!("Recording already in progress"));
             }
-            // Set recording state to true atomically
-            *is_recording_guard = true;
+            // `stop` has no client_id, so only one continuous recording runs at a time
+            if stop.is_some() && sessions.values().any(|session| session.stop.is_some()) {
+                return Err(anyhow::anyhow!("Continuous recording already in progress"));
+            }
+            let source_id = self
+                .next_recording_source
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            sessions.insert(
+                client_id.to_string(),
+                RecordingSession {
+                    source_id,
+                    write_mode,
+                    cancel,
+                    stop,
+                    started_at: std::time::Instant::now(),
+                },
+            );
+            source_id
+        };
+
+        // Recording streams its own levels, so hand the input device over to it
+        if self.stop_monitor().await {
+            info!("Stopped audio monitoring for recording");
         }
 
+        // Negotiated the way the recorder will, so the events carry the rate it records at
+        let (input_device, capture_rate) = {
+            let config = self.config.read().await;
+            (
+                config.audio.input_device.clone(),
+                config.audio.capture_sample_rate,
+            )
+        };
+        let input_sample_rate = match crate::audio::device::detect_input_sample_rate(
+            input_device.as_deref(),
+            capture_rate,
+        ) {
+            Ok(rate) => {
+                info!("Input device negotiated at {rate}Hz");
+                self.input_sample_rate
+                    .store(rate, std::sync::atomic::Ordering::Relaxed);
+                rate
+            }
+            Err(e) => {
+                warn!("Failed to detect input sample rate: {e}");
+                0
+            }
+        };
+
         // Emit UDP recording state change
-        self.broadcast_recording_state_change(true).await;
+        self.broadcast_recording_state_change(true, source_id, input_sample_rate)
+            .await;
 
         // Emit D-Bus listening started event
-        self.emit_listening_started_dbus(write_mode).await;
+        self.emit_listening_started_dbus(client_id, write_mode, input_sample_rate)
+            .await;
 
         // Create audio recorder with current theme
         let current_theme = self.get_audio_theme();
-        let mut recorder = DaemonAudioRecorder::new_with_theme(current_theme)
-            .context("Failed to create audio recorder")?;
+        let mut recorder = match DaemonAudioRecorder::new_with_theme(current_theme) {
+            Ok(recorder) => recorder,
+            Err(e) => {
+                self.finalize_recording_session(client_id, None, None).await;
+                return Err(e.context("Failed to create audio recorder"));
+            }
+        };
+        recorder.set_silence_config(silence_config);
+        if silence_config.vad_mode == VadMode::Vad
+            && let Some(model) = self.load_vad_model().await
+        {
+            recorder.set_vad_model(model);
+        }
+        {
+            let config = self.config.read().await;
+            recorder.set_frequency_bands(config.audio.frequency_bands);
+            recorder.set_input_device(config.audio.input_device.clone());
+            recorder.set_capture_sample_rate(config.audio.capture_sample_rate);
+            recorder.set_resample_quality(config.audio.resample_quality);
+            recorder.set_theme_volume(config.audio.theme_gain());
+        }
+        if let Some(pre_roll) = self.pre_roll.read().await.as_ref() {
+            recorder.set_pre_roll(Arc::clone(pre_roll));
+        }
+        recorder.set_custom_sounds(self.get_custom_sounds());
 
         // Initialize the recorder for threaded operation
         recorder.prepare_for_threaded_recording();
 
-        Ok(recorder)
+        Ok((recorder, source_id))
     }
 
     /// Emit D-Bus listening started event
-    async fn emit_listening_started_dbus(&self, write_mode: bool) {
+    async fn emit_listening_started_dbus(
+        &self,
+        client_id: &str,
+        write_mode: bool,
+        input_sample_rate: u32,
+    ) {
         if let Some(ref dbus_manager) = self.dbus_manager {
             let event = ListeningEvent {
-                client_id: "daemon_recorder".to_string(),
+                client_id: client_id.to_string(),
                 timestamp: Utc::now().to_rfc3339(),
                 write_mode,
                 timeout_seconds: 0,
                 audio_level: 0.0,
+                input_sample_rate,
             };
 
             if let Err(e) = dbus_manager.emit_listening_started(event).await {
@@ -416,13 +1568,15 @@ impl SuperSTTDaemon {
             .await
     }
 
-    /// Transcribe audio with spinner if needed
+    /// Transcribe audio with spinner if needed, timing the processing and the
+    /// transcription, and return the processed audio the model was given
     async fn transcribe_with_spinner(
         &self,
-        _typer: &mut Typer,
         audio_data: &[f32],
         _write_mode: bool,
-    ) -> Result<String> {
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<(String, f32, TranscriptionTimings, Vec<f32>)> {
         // If we'll type the result, show a simple spinner by typing characters and backspacing
         // This indicates work while transcription runs.
         let mut spinner_handle: Option<tokio::task::JoinHandle<()>> = None;
@@ -433,15 +1587,44 @@ impl SuperSTTDaemon {
         // TODO: Implement proper loader that doesn't conflict with final typing
 
         // Process audio
+        let process_start = Instant::now();
         let processed_audio = self
             .audio_processor
             .process_audio(audio_data, 16000)
             .context("Failed to process audio")?;
+        let mut timings = TranscriptionTimings {
+            process_ms: u64::try_from(process_start.elapsed().as_millis()).unwrap_or(u64::MAX),
+            ..TranscriptionTimings::default()
+        };
+        self.keep_last_audio(&processed_audio).await;
+
+        // Clips this short are a tap of the shortcut or a click, and the models
+        // tend to invent words for them rather than return nothing. Measured
+        // after processing, so the silence around a click does not count.
+        let min_audio_ms = self.config.read().await.audio.min_audio_ms;
+        let audio_ms = processed_audio.len() as u64 * 1000 / 16000;
+        if audio_ms < u64::from(min_audio_ms) {
+            info!("Recording of {audio_ms}ms is shorter than {min_audio_ms}ms, no speech detected");
+            return Ok((String::new(), 0.0, timings, processed_audio));
+        }
 
         // Transcribe the audio
+        let transcribe_start = Instant::now();
         let transcription_result = {
             // Clone the model Arc for the blocking task
             let model_clone = Arc::clone(&self.model);
+            let model_audio = processed_audio.clone();
+            let language = language.map(str::to_string);
+            let rules = rules.clone();
+            // What a one-off CPU copy of the model needs if the GPU fails
+            let model_type = *self.model_type.read().await;
+            let (source, decoding) = {
+                let config = self.config.read().await;
+                (
+                    config.transcription.model_source.clone(),
+                    config.transcription.decoding,
+                )
+            };
 
             // Run transcription in a blocking task to avoid blocking the async runtime
             tokio::task::spawn_blocking(move || {
@@ -449,24 +1632,57 @@ impl SuperSTTDaemon {
 
                 // Get exclusive write access to the model
                 let mut model_guard = model_clone.blocking_write();
+                let Some(model) = model_guard.as_mut() else {
+                    error!("Model not loaded");
+                    return Err(anyhow::anyhow!("Model not loaded"));
+                };
+                let mut result = model.transcribe_audio_with_confidence(
+                    &model_audio,
+                    16000,
+                    language.as_deref(),
+                    &rules,
+                );
+                let gpu_failed =
+                    model.device().is_cuda() && result.as_ref().is_err_and(is_device_error);
+                // Other requests can use the model while the CPU copy loads
+                drop(model_guard);
+
+                if gpu_failed
+                    && let Err(e) = &result
+                    && let Some(model_type) = model_type
+                {
+                    warn!(
+                        "Transcription failed on the GPU, retrying on a CPU copy of {model_type} \
+                         that is loaded for this transcription only: {e}"
+                    );
+                    result = Self::load_model_sync(model_type, &source, "cpu", decoding)
+                        .and_then(|mut cpu_model| {
+                            cpu_model.transcribe_audio_with_confidence(
+                                &model_audio,
+                                16000,
+                                language.as_deref(),
+                                &rules,
+                            )
+                        });
+                }
 
-                if let Some(model) = model_guard.as_mut() {
-                    match model.transcribe_audio(&processed_audio, 16000) {
-                        Ok(text) => {
-                            let duration = start_time.elapsed();
-                            info!("Transcription completed in {duration:?}: '{text}'");
-                            Ok(text)
-                        }
-                        Err(e) => {
-                            // For transcription errors (like Voxtral mel generation issues),
-                            // return empty string instead of failing the entire request
-                            warn!("Transcription failed, returning empty result: {e}");
-                            Ok(String::new())
-                        }
+                match result {
+                    Ok((text, confidence)) => {
+                        let duration = start_time.elapsed();
+                        info!(
+                            "Transcription completed in {duration:?} (confidence {confidence:.2}): '{text}'"
+                        );
+                        crate::services::metrics::record_transcription(duration);
+                        Ok((text, confidence))
+                    }
+                    // Reported as its own error, rather than an empty result
+                    Err(e) if e.is::<MelGenerationFailed>() => Err(e),
+                    Err(e) => {
+                        // For other transcription errors, return empty string
+                        // instead of failing the entire request
+                        warn!("Transcription failed, returning empty result: {e}");
+                        Ok((String::new(), 0.0))
                     }
-                } else {
-                    error!("Model not loaded");
-                    Err(anyhow::anyhow!("Model not loaded"))
                 }
             })
             .await
@@ -482,26 +1698,40 @@ impl SuperSTTDaemon {
             }
         }
 
-        transcription_result
+        timings.transcribe_ms =
+            u64::try_from(transcribe_start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        if let Ok((text, _)) = &transcription_result {
+            self.save_history(text, audio_data.len(), 16000).await;
+        }
+
+        transcription_result.map(|(text, confidence)| (text, confidence, timings, processed_audio))
     }
 
-    /// Finalize recording session and emit events
+    /// End `client_id`'s recording session and emit events, including the final
+    /// transcription and its confidence when there is one
     async fn finalize_recording_session(
         &self,
-        _transcription_result: &str,
-        _preview_typed_count: &std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        client_id: &str,
+        transcription: Option<(&str, f32)>,
+        audio: Option<&[f32]>,
     ) {
         // Reset recording state
-        {
-            let mut is_recording_guard = self.is_recording.write().await;
-            *is_recording_guard = false;
+        let Some(session) = self.recording_sessions.write().await.remove(client_id) else {
+            return;
+        };
+        self.touch_activity().await;
+        self.broadcast_recording_state_change(false, session.source_id, 0)
+            .await;
+
+        if let Some(((text, confidence), audio)) = transcription.zip(audio) {
+            self.archive_recording(audio, text, confidence).await;
         }
-        self.broadcast_recording_state_change(false).await;
 
         // Emit D-Bus listening stopped event
         if let Some(ref dbus_manager) = self.dbus_manager {
             let event = crate::services::dbus::ListeningStoppedEvent {
-                client_id: "daemon_recorder".to_string(),
+                client_id: client_id.to_string(),
                 timestamp: Utc::now().to_rfc3339(),
                 transcription_success: true, // We only call this on success
                 error: String::new(),
@@ -510,6 +1740,129 @@ impl SuperSTTDaemon {
             if let Err(e) = dbus_manager.emit_listening_stopped(event).await {
                 warn!("Failed to emit D-Bus listening_stopped signal: {e}");
             }
+
+            if let Some((text, confidence)) = transcription
+                && !text.is_empty()
+            {
+                let event = crate::services::dbus::TranscriptionCompletedEvent {
+                    client_id: client_id.to_string(),
+                    timestamp: Utc::now().to_rfc3339(),
+                    transcription: text.to_string(),
+                    duration_ms: u64::try_from(session.started_at.elapsed().as_millis())
+                        .unwrap_or(u64::MAX),
+                    confidence: f64::from(confidence),
+                };
+                if let Err(e) = dbus_manager.emit_transcription_completed(event).await {
+                    warn!("Failed to emit D-Bus transcription_completed signal: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Log a D-Bus recording request the daemon turned down
+fn log_dbus_failure(response: DaemonResponse) {
+    if response.status != "success" {
+        warn!(
+            "D-Bus recording request failed: {}",
+            response.message.unwrap_or_default()
+        );
+    }
+}
+
+/// Whether a transcription failed in the GPU backend, such as by running out of the
+/// GPU's memory, rather than because of the audio or the model
+fn is_device_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause
+            .downcast_ref::<candle_core::Error>()
+            .is_some_and(is_backend_error)
+    })
+}
+
+/// Whether `e` came from the CUDA or Metal backend, under any context candle added
+fn is_backend_error(e: &candle_core::Error) -> bool {
+    match e {
+        candle_core::Error::Cuda(_) | candle_core::Error::Metal(_) => true,
+        candle_core::Error::Context { inner, .. }
+        | candle_core::Error::WithPath { inner, .. }
+        | candle_core::Error::WithBacktrace { inner, .. } => is_backend_error(inner),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DaemonConfig;
+
+    #[test]
+    fn test_is_device_error() {
+        let cuda = candle_core::Error::Cuda(Box::new(std::io::Error::other(
+            "DriverError(CUDA_ERROR_OUT_OF_MEMORY, \"out of memory\")",
+        )))
+        .context("conv1d");
+        let wrapped = anyhow::Error::from(cuda).context("Final transcription failed");
+        assert!(is_device_error(&wrapped));
+
+        // Running out of host memory is no reason to move to the CPU
+        let host = candle_core::Error::Msg("out of memory".to_string());
+        let wrapped = anyhow::Error::from(host).context("Final transcription failed");
+        assert!(!is_device_error(&wrapped));
+
+        let shape = candle_core::Error::Msg("unexpected rank, expected: 2, got: 3".to_string());
+        let wrapped = anyhow::Error::from(shape).context("Final transcription failed");
+        assert!(!is_device_error(&wrapped));
+    }
+
+    #[tokio::test]
+    #[allow(clippy::cast_precision_loss)]
+    async fn test_min_audio_gate_measures_processed_audio() {
+        let mut config = DaemonConfig::default();
+        config.audio.min_audio_ms = 1000;
+        let daemon = SuperSTTDaemon::for_tests(config).await;
+        let rules = TextRules::default();
+
+        let tone = |i: usize| (i as f32 * 440.0 * std::f32::consts::TAU / 16000.0).sin() * 0.5;
+
+        // Two seconds long, but only a click once the silence around it is trimmed
+        let mut click = vec![0.0; 32000];
+        for (i, sample) in click.iter_mut().enumerate().skip(16000).take(800) {
+            *sample = tone(i);
+        }
+        let (text, ..) = daemon
+            .transcribe_with_spinner(&click, false, None, &rules)
+            .await
+            .unwrap();
+        assert!(text.is_empty());
+
+        // Two seconds of sound passes the gate and reaches the (missing) model
+        let sound: Vec<f32> = (0..32000).map(tone).collect();
+        let result = daemon
+            .transcribe_with_spinner(&sound, false, None, &rules)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    #[allow(clippy::cast_precision_loss)]
+    async fn test_keeps_processed_audio() {
+        let mut config = DaemonConfig::default();
+        config.debug.keep_last_audio = true;
+        let daemon = SuperSTTDaemon::for_tests(config).await;
+
+        // A second of speech-like tone between two seconds of silence
+        let mut audio = vec![0.0; 48000];
+        for (i, sample) in audio.iter_mut().enumerate().skip(16000).take(16000) {
+            *sample = (i as f32 * 440.0 * std::f32::consts::TAU / 16000.0).sin() * 0.5;
         }
+        let _ = daemon
+            .transcribe_with_spinner(&audio, false, None, &TextRules::default())
+            .await;
+
+        // The silence was trimmed before the model would have seen it
+        let kept = daemon.last_audio.read().await.clone().unwrap();
+        assert!(kept.len() < 32000, "kept {} samples", kept.len());
+        assert!(kept.len() >= 16000, "kept {} samples", kept.len());
     }
 }
@@ -1,41 +1,36 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::audio::beeper::play_beep_sequence;
+use crate::audio::beeper::{Cue, play_cue};
 use crate::daemon::types::SuperSTTDaemon;
 use log::{error, info};
-use std::sync::Arc;
 use super_stt_shared::models::protocol::DaemonResponse;
 use super_stt_shared::theme::AudioTheme;
 
 impl SuperSTTDaemon {
     /// Handle set audio theme command
-    #[must_use]
-    pub fn handle_set_audio_theme(&self, theme_str: String) -> DaemonResponse {
-        let theme = theme_str.parse::<AudioTheme>().unwrap_or_default();
-        self.set_audio_theme(theme);
-
-        // Update the config with new audio theme and save to disk
-        // Note: This needs to be done in an async context, but this method is sync
-        // We'll spawn a task to handle the config update and broadcast
-        let config_clone = Arc::clone(&self.config);
-        let notification_manager = Arc::clone(&self.notification_manager);
-        tokio::spawn(async move {
-            let mut config_guard = config_clone.write().await;
-            config_guard.update_audio_theme(theme);
-            drop(config_guard);
+    ///
+    /// Selecting `custom` loads its sounds from the config; if they cannot be loaded
+    /// the default theme is selected instead, and reported back.
+    pub async fn handle_set_audio_theme(&self, theme_str: String) -> DaemonResponse {
+        let requested = theme_str.parse::<AudioTheme>().unwrap_or_default();
+        let paths = self.config.read().await.audio.custom_sounds.clone();
+        self.apply_audio_theme(requested, &paths);
+        let theme = self.get_audio_theme();
 
-            // Broadcast config change event
-            if let Err(e) =
-                SuperSTTDaemon::broadcast_config_change_static(&notification_manager, &config_clone)
-                    .await
-            {
-                log::warn!("Failed to broadcast config change after audio theme change: {e}");
-            }
-        });
+        // Update the config with the theme in effect and save to disk
+        self.config.write().await.update_audio_theme(theme);
+        if let Err(e) = self.broadcast_config_change().await {
+            log::warn!("Failed to broadcast config change after audio theme change: {e}");
+        }
 
+        let message = if theme == requested {
+            format!("Audio theme set to: {theme}")
+        } else {
+            format!("Audio theme {requested} could not be loaded, set to: {theme}")
+        };
         DaemonResponse::success()
-            .with_message(format!("Audio theme set to: {theme}",))
-            .with_audio_theme(theme_str)
+            .with_message(message)
+            .with_audio_theme(theme.to_string())
     }
 
     /// Handle get audio theme command
@@ -52,6 +47,13 @@ impl SuperSTTDaemon {
         let current_theme = self.get_audio_theme();
         let theme_name = format!("{current_theme:?}").to_lowercase();
 
+        let volume = self.config.read().await.audio.theme_gain();
+        if volume <= 0.0 {
+            info!("Testing audio theme: {theme_name} (muted - no sounds played)");
+            return DaemonResponse::success()
+                .with_message("Audio theme sounds are muted - no sounds played".to_string());
+        }
+
         // Skip playing sounds for Silent theme
         if current_theme == AudioTheme::Silent {
             info!("Testing audio theme: {theme_name} (silent - no sounds played)");
@@ -60,31 +62,19 @@ impl SuperSTTDaemon {
             );
         }
 
-        // Play both start and end sounds to test the theme
-        let (start_frequencies, start_duration, start_fade_in, start_fade_out) =
-            current_theme.start_sound();
-        let (end_frequencies, end_duration, end_fade_in, end_fade_out) = current_theme.end_sound();
-
-        info!("Testing audio theme: {theme_name}");
-        info!("Start frequencies: {start_frequencies:?}, duration: {start_duration}ms");
-        info!("End frequencies: {end_frequencies:?}, duration: {end_duration}ms");
+        info!("Testing audio theme: {theme_name} at volume {volume:.2}");
+        let custom_sounds = self.get_custom_sounds();
 
         // Test with start sound first
         info!("Playing start sound...");
-        match play_beep_sequence(
-            &start_frequencies,
-            start_duration,
-            start_fade_in,
-            start_fade_out,
-        ) {
+        match play_cue(current_theme, Cue::Start, custom_sounds.as_deref(), volume) {
             Ok(()) => {
                 info!("Start sound completed successfully");
 
                 // Test end sound as well
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
                 info!("Playing end sound...");
-                match play_beep_sequence(&end_frequencies, end_duration, end_fade_in, end_fade_out)
-                {
+                match play_cue(current_theme, Cue::Stop, custom_sounds.as_deref(), volume) {
                     Ok(()) => {
                         info!("End sound completed successfully");
                         DaemonResponse::success()
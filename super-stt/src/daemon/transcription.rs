@@ -1,19 +1,38 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::daemon::types::SuperSTTDaemon;
+use crate::services::archive::{self, ArchiveEntry};
+use crate::stt_models::TextRules;
+use crate::stt_models::voxtral::model::MelGenerationFailed;
 use chrono::Utc;
 use log::{debug, error, info, warn};
-use std::sync::Arc;
-use super_stt_shared::models::protocol::DaemonResponse;
+use std::sync::{Arc, Once};
+use super_stt_shared::models::protocol::{
+    DaemonResponse, DecodingOverride, HistoryEntry, MEL_GENERATION_FAILED, TranscriptFormat,
+    TranscriptionOutput, TranscriptionTimings, WordFilterOverride,
+};
 use super_stt_shared::utils::audio::validate_audio;
+use super_stt_shared::validation::{self, ValidationError};
+
+// Number of ranked alternatives returned by language detection
+const LANGUAGE_CANDIDATES: usize = 3;
 
 impl SuperSTTDaemon {
     /// Handle transcribe command
-    #[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::too_many_lines,
+        clippy::too_many_arguments
+    )]
     pub async fn handle_transcribe(
         &self,
         audio_data: Vec<f32>,
         sample_rate: u32,
         client_id: String,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+        decoding: Option<DecodingOverride>,
+        timings: bool,
     ) -> DaemonResponse {
         info!("Processing transcription request from client: {client_id}");
 
@@ -23,6 +42,14 @@ impl SuperSTTDaemon {
             return DaemonResponse::error(&format!("Invalid audio data: {e}"));
         }
 
+        if let Err(e) = self.ensure_model_loaded().await {
+            return DaemonResponse::error(&e.to_string());
+        }
+        if let Err(e) = self.validate_language_for_model(language.as_deref()).await {
+            warn!("Language validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid language: {e}"));
+        }
+
         debug!("Audio validation completed");
 
         // Calculate audio level for visualization
@@ -109,13 +136,20 @@ impl SuperSTTDaemon {
         }
 
         // Process audio
-        let processed_audio = match self.audio_processor.process_audio(&audio_data, sample_rate) {
-            Ok(p) => p,
+        let process_start = std::time::Instant::now();
+        let (processed_audio, resample_time) = match self
+            .audio_processor
+            .process_audio_detailed(&audio_data, sample_rate)
+        {
+            Ok(p) => (p.samples, p.resample_time),
             Err(e) => {
                 warn!("Failed to process audio: {e}");
                 return DaemonResponse::error(&format!("Failed to process audio: {e}"));
             }
         };
+        let process_time = process_start.elapsed().saturating_sub(resample_time);
+
+        let rules = self.resolve_text_rules(replacements, word_filter).await;
 
         // Clone the model Arc for the blocking task
         let model_clone = Arc::clone(&self.model);
@@ -128,18 +162,26 @@ impl SuperSTTDaemon {
             let mut model_guard = model_clone.blocking_write();
 
             if let Some(model) = model_guard.as_mut() {
-                match model.transcribe_audio(&processed_audio, 16000) {
-                    Ok(text) => {
+                match model.with_decoding(decoding.as_ref(), |model| {
+                    model.transcribe_audio_with_confidence(
+                        &processed_audio,
+                        16000,
+                        language.as_deref(),
+                        &rules,
+                    )
+                }) {
+                    Ok((text, confidence)) => {
                         let duration = start_time.elapsed();
                         info!("Transcription completed in {duration:?}: '{text}'");
-                        Ok((text, duration))
+                        crate::services::metrics::record_transcription(duration);
+                        Ok((text, confidence, duration))
                     }
                     Err(e) => {
                         // For transcription errors (like Voxtral mel generation issues),
                         // return empty string instead of failing the entire request
                         warn!("Transcription failed, returning empty result: {e}");
                         let duration = start_time.elapsed();
-                        Ok((String::new(), duration))
+                        Ok((String::new(), 0.0, duration))
                     }
                 }
             } else {
@@ -151,7 +193,10 @@ impl SuperSTTDaemon {
 
         // Handle the result of the blocking task
         match transcription_result {
-            Ok(Ok((transcription, duration))) => {
+            Ok(Ok((transcription, confidence, duration))) => {
+                self.save_history(&transcription, audio_data.len(), sample_rate)
+                    .await;
+
                 // Broadcast transcription completed event
                 let _ = self
                     .notification_manager
@@ -173,6 +218,7 @@ impl SuperSTTDaemon {
                         timestamp: Utc::now().to_rfc3339(),
                         transcription: transcription.clone(),
                         duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+                        confidence: f64::from(confidence),
                     };
 
                     if let Err(e) = dbus_manager.emit_transcription_completed(event).await {
@@ -184,7 +230,21 @@ impl SuperSTTDaemon {
                     }
                 }
 
-                DaemonResponse::success().with_transcription(transcription)
+                let response = DaemonResponse::success()
+                    .with_transcription(transcription)
+                    .with_confidence(confidence);
+                if timings {
+                    let to_ms =
+                        |d: std::time::Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+                    response.with_timings(TranscriptionTimings {
+                        record_ms: 0,
+                        resample_ms: to_ms(resample_time),
+                        process_ms: to_ms(process_time),
+                        transcribe_ms: to_ms(duration),
+                    })
+                } else {
+                    response
+                }
             }
             Ok(Err(e)) => {
                 // Transcription error
@@ -199,7 +259,7 @@ impl SuperSTTDaemon {
                         }),
                     )
                     .await;
-                DaemonResponse::error(&format!("Transcription failed: {e}"))
+                failure_response("Transcription failed", &e)
             }
             Err(e) => {
                 // Task join error
@@ -219,4 +279,342 @@ impl SuperSTTDaemon {
             }
         }
     }
+
+    /// Handle transcribe timestamped command - transcribe audio and return timed segments,
+    /// optionally rendered as SRT or VTT subtitles
+    #[allow(clippy::too_many_arguments)]
+    pub async fn handle_transcribe_timestamped(
+        &self,
+        audio_data: Vec<f32>,
+        sample_rate: u32,
+        client_id: String,
+        format: TranscriptFormat,
+        language: Option<String>,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+    ) -> DaemonResponse {
+        info!("Processing timestamped transcription request from client: {client_id}");
+
+        if let Err(e) = validate_audio(&audio_data, sample_rate) {
+            warn!("Audio validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid audio data: {e}"));
+        }
+
+        if let Err(e) = self.ensure_model_loaded().await {
+            return DaemonResponse::error(&e.to_string());
+        }
+        if let Err(e) = self.validate_language_for_model(language.as_deref()).await {
+            warn!("Language validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid language: {e}"));
+        }
+
+        let (processed_audio, trimmed_secs) = match self
+            .audio_processor
+            .process_audio_detailed(&audio_data, sample_rate)
+        {
+            Ok(p) => (p.samples, p.trimmed_secs),
+            Err(e) => {
+                warn!("Failed to process audio: {e}");
+                return DaemonResponse::error(&format!("Failed to process audio: {e}"));
+            }
+        };
+
+        let rules = self.resolve_text_rules(replacements, word_filter).await;
+        let diarize = self.config.read().await.transcription.diarization_enabled;
+        #[cfg(not(feature = "diarization"))]
+        if diarize {
+            warn!("Diarization is enabled, but this build lacks the diarization feature");
+        }
+        let model_clone = Arc::clone(&self.model);
+        let result = tokio::task::spawn_blocking(move || {
+            let mut model_guard = model_clone.blocking_write();
+            let Some(model) = model_guard.as_mut() else {
+                error!("Model not loaded");
+                return Err(anyhow::anyhow!("Model not loaded"));
+            };
+            // Subtitles built from a single whole-utterance fallback would be misleading
+            if format != TranscriptFormat::Text && !model.supports_timestamps() {
+                return Err(anyhow::anyhow!(
+                    "Timestamps are unavailable for the loaded model; use format \"text\" or switch to a Whisper model"
+                ));
+            }
+            let (transcription, mut segments) = model.transcribe_audio_with_timestamps(
+                &processed_audio,
+                16000,
+                language.as_deref(),
+                &rules,
+            )?;
+            #[cfg(feature = "diarization")]
+            if diarize {
+                crate::audio::diarization::assign_speakers(&processed_audio, 16000, &mut segments);
+            }
+            // Segment times are into the trimmed audio; put them back on the input's clock
+            for segment in &mut segments {
+                segment.start += trimmed_secs;
+                segment.end += trimmed_secs;
+            }
+            Ok((transcription, segments))
+        })
+        .await;
+
+        match result {
+            Ok(Ok((transcription, segments))) => {
+                debug!(
+                    "Timestamped transcription produced {} segments for client: {client_id}",
+                    segments.len()
+                );
+                let transcription = match format {
+                    TranscriptFormat::Text => transcription,
+                    TranscriptFormat::Srt | TranscriptFormat::Vtt => {
+                        crate::output::subtitle::format_segments(&segments, format)
+                    }
+                };
+                DaemonResponse::success()
+                    .with_transcription(transcription)
+                    .with_segments(segments)
+            }
+            Ok(Err(e)) => {
+                warn!("Timestamped transcription failed: {e}");
+                DaemonResponse::error(&format!("Transcription failed: {e}"))
+            }
+            Err(e) => {
+                error!("Transcription task failed: {e}");
+                DaemonResponse::error(&format!("Task execution failed: {e}"))
+            }
+        }
+    }
+
+    /// Handle detect language command - rank the languages spoken in the first
+    /// 30 seconds of a clip or audio file
+    pub async fn handle_detect_language(
+        &self,
+        audio_data: Option<Vec<f32>>,
+        sample_rate: u32,
+        path: Option<String>,
+        client_id: String,
+    ) -> DaemonResponse {
+        info!("Processing language detection request from client: {client_id}");
+
+        if let Err(e) = self.ensure_model_loaded().await {
+            return DaemonResponse::error(&e.to_string());
+        }
+
+        if let Some(model) = self.model.read().await.as_ref()
+            && !model.supports_language_detection()
+        {
+            return DaemonResponse::error(
+                "Language detection is not supported by the loaded model",
+            );
+        }
+
+        let file_path = match path {
+            Some(path) => match self.resolve_audio_file(&path).await {
+                Ok(p) => Some(p),
+                Err(response) => return response,
+            },
+            None => None,
+        };
+        if let Some(ref audio_data) = audio_data
+            && let Err(e) = validate_audio(audio_data, sample_rate)
+        {
+            warn!("Audio validation failed: {e}");
+            return DaemonResponse::error(&format!("Invalid audio data: {e}"));
+        }
+
+        let model_clone = Arc::clone(&self.model);
+        let audio_processor = Arc::clone(&self.audio_processor);
+        let result = tokio::task::spawn_blocking(move || {
+            let (samples, sample_rate) = match file_path {
+                Some(file_path) => (Self::decode_audio_file(&file_path)?, 16000),
+                None => (audio_data.unwrap_or_default(), sample_rate),
+            };
+            let processed_audio = audio_processor.process_audio(&samples, sample_rate)?;
+
+            let mut model_guard = model_clone.blocking_write();
+            let Some(model) = model_guard.as_mut() else {
+                error!("Model not loaded");
+                return Err(anyhow::anyhow!("Model not loaded"));
+            };
+            model.detect_language(&processed_audio, 16000, LANGUAGE_CANDIDATES)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(candidates)) => {
+                let Some(top) = candidates.first() else {
+                    return DaemonResponse::error("No language could be detected");
+                };
+                info!(
+                    "Detected language '{}' ({:.2}) for client: {client_id}",
+                    top.language, top.confidence
+                );
+                DaemonResponse::success()
+                    .with_message(format!("Detected language: {}", top.language))
+                    .with_language_candidates(candidates)
+            }
+            Ok(Err(e)) => {
+                warn!("Language detection failed: {e}");
+                DaemonResponse::error(&format!("Language detection failed: {e}"))
+            }
+            Err(e) => {
+                error!("Language detection task failed: {e}");
+                DaemonResponse::error(&format!("Task execution failed: {e}"))
+            }
+        }
+    }
+
+    /// Text rules for a request; per-request replacement rules take the place of the
+    /// configured ones, and a word filter override is applied on top of the configured filter
+    pub async fn resolve_text_rules(
+        &self,
+        replacements: Option<Vec<(String, String)>>,
+        word_filter: Option<WordFilterOverride>,
+    ) -> TextRules {
+        let config = self.config.read().await;
+        TextRules {
+            replacements: replacements.unwrap_or_else(|| config.transcription.replacements.clone()),
+            word_filter: match word_filter {
+                Some(ref overrides) => config.transcription.word_filter.with_override(overrides),
+                None => config.transcription.word_filter.clone(),
+            },
+        }
+    }
+
+    /// The `result` of a final transcription of `samples` audio samples, naming the
+    /// model and device it ran on
+    pub async fn transcription_output(
+        &self,
+        text: &str,
+        language: Option<String>,
+        confidence: f32,
+        samples: usize,
+        sample_rate: u32,
+    ) -> TranscriptionOutput {
+        TranscriptionOutput {
+            text: text.to_string(),
+            language,
+            confidence,
+            duration_ms: duration_ms(samples, sample_rate),
+            model: self
+                .model_type
+                .read()
+                .await
+                .map(|model| model.to_string())
+                .unwrap_or_default(),
+            device: self.actual_device.read().await.clone(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Add a final transcription of `samples` audio samples to the history, if enabled
+    pub async fn save_history(&self, text: &str, samples: usize, sample_rate: u32) {
+        let Some(history) = self.history.as_ref().map(Arc::clone) else {
+            return;
+        };
+        let retention = self.config.read().await.history.clone();
+        if !retention.enabled || text.trim().is_empty() {
+            return;
+        }
+
+        let entry = HistoryEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            text: text.to_string(),
+            model: self
+                .model_type
+                .read()
+                .await
+                .map(|model| model.to_string())
+                .unwrap_or_default(),
+            device: self.actual_device.read().await.clone(),
+            duration_ms: duration_ms(samples, sample_rate),
+        };
+
+        match tokio::task::spawn_blocking(move || history.record(&entry, &retention)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Failed to save transcription history: {e}"),
+            Err(e) => warn!("History task failed: {e}"),
+        }
+    }
+
+    /// Save a recording's 16kHz audio and its transcription to the archive, if enabled
+    pub async fn archive_recording(&self, audio: &[f32], text: &str, confidence: f32) {
+        let config = self.config.read().await.archive_recordings.clone();
+        if !config.enabled {
+            return;
+        }
+
+        let entry = ArchiveEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            transcription: text.to_string(),
+            confidence,
+            model: self
+                .model_type
+                .read()
+                .await
+                .map(|model| model.to_string())
+                .unwrap_or_default(),
+            device: self.actual_device.read().await.clone(),
+            duration_ms: duration_ms(audio.len(), 16000),
+        };
+        let audio = audio.to_vec();
+
+        match tokio::task::spawn_blocking(move || archive::save(&config, &audio, &entry)).await {
+            Ok(Ok(path)) => debug!("Archived recording to {}", path.display()),
+            Ok(Err(e)) => {
+                warn!("Failed to archive recording: {e:#}");
+                self.record_subsystem_error("archive", &format!("{e:#}"))
+                    .await;
+            }
+            Err(e) => warn!("Archive task failed: {e}"),
+        }
+    }
+
+    /// Check that the loaded model can transcribe the requested language
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::UnsupportedLanguage`] if the loaded model does not
+    /// support `language`. A missing model is reported later by the transcription itself.
+    pub async fn validate_language_for_model(
+        &self,
+        language: Option<&str>,
+    ) -> Result<(), ValidationError> {
+        let Some(language) = language else {
+            return Ok(());
+        };
+        match self.model.read().await.as_ref() {
+            Some(model) => {
+                validation::validate_language_supported(language, model.supported_languages())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Logs the advice for Voxtral's mel generation failure once per daemon run
+static MEL_HINT: Once = Once::new();
+
+/// Error response for a failed transcription, flagging Voxtral's known mel generation
+/// failure with [`MEL_GENERATION_FAILED`] so clients can tell it from other errors
+pub(crate) fn failure_response(context: &str, e: &anyhow::Error) -> DaemonResponse {
+    let response = DaemonResponse::error(&format!("{context}: {e}"));
+    if !e.is::<MelGenerationFailed>() {
+        return response;
+    }
+
+    MEL_HINT.call_once(|| {
+        warn!(
+            "Voxtral produced invalid mel features, a known issue with Candle's mel spectrogram; \
+             if it keeps happening, switch to a Whisper model"
+        );
+    });
+    response.with_error_code(MEL_GENERATION_FAILED)
+}
+
+/// Length in milliseconds of `samples` audio samples at `sample_rate`
+fn duration_ms(samples: usize, sample_rate: u32) -> u64 {
+    u64::try_from(samples)
+        .unwrap_or(u64::MAX)
+        .saturating_mul(1000)
+        / u64::from(sample_rate.max(1))
 }
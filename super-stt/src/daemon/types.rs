@@ -1,23 +1,35 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use crate::audio::beeper::{CustomSoundPaths, CustomSounds};
+use crate::audio::pre_roll::PreRollCapture;
 use crate::audio::streamer::UdpAudioStreamer;
 use crate::config::DaemonConfig;
 use crate::daemon::auth::ProcessAuth;
 use crate::download_progress::DownloadStateManager;
 use crate::input::audio::AudioProcessor;
+use crate::output::preview::PreviewEdit;
+use crate::services::archive;
 use crate::services::dbus::DBusManager;
+use crate::services::history::HistoryStore;
 use crate::services::transcription::RealTimeTranscriptionManager;
-use crate::stt_models::{voxtral::VoxtralModel, whisper::WhisperModel};
+use crate::stt_models::TextRules;
+use crate::stt_models::voxtral::{VoxtralModel, model::DecodingConfig};
+use crate::stt_models::whisper::WhisperModel;
 use anyhow::{Context, Result};
 use log::{info, warn};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use super_stt_shared::NotificationManager;
+use super_stt_shared::models::protocol::{
+    DecodingOverride, LanguageCandidate, OutputMode, Segment, SilenceOverride, WordFilterOverride,
+};
 use super_stt_shared::resource_management::ResourceManager;
 use super_stt_shared::stt_model::STTModel;
 use super_stt_shared::theme::AudioTheme;
 use tokio::net::UnixListener;
 use tokio::sync::broadcast;
+use tokio::sync::mpsc::UnboundedSender;
 
 use super::client_management::ClientConnectionsMap;
 
@@ -27,6 +39,65 @@ pub enum DeviceOverride {
     Cuda,
 }
 
+/// Client a recording is filed under when its request names none
+pub const DEFAULT_RECORDING_CLIENT: &str = "daemon_recorder";
+
+/// Client recordings started through the D-Bus methods are filed under
+pub const DBUS_RECORDING_CLIENT: &str = "dbus";
+
+/// A recording in progress, owned by the client that started it
+#[derive(Clone)]
+pub struct RecordingSession {
+    // Tags this session's UDP packets so listeners can tell concurrent recordings apart
+    pub source_id: u32,
+    pub write_mode: bool,
+    // Set by `cancel_recording` to discard the recording, for recordings that honour it
+    pub cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
+    // Set by `stop` to end a continuous recording
+    pub stop: Option<Arc<std::sync::atomic::AtomicBool>>,
+    // When the recording began, for the duration reported once it ends
+    pub started_at: std::time::Instant,
+}
+
+/// Options of a `record` request, kept so the request can wait in the record queue
+pub struct RecordRequest {
+    pub client_id: String,
+    pub write_mode: bool,
+    pub silence: Option<SilenceOverride>,
+    pub language: Option<String>,
+    pub device_name: Option<String>,
+    pub replacements: Option<Vec<(String, String)>>,
+    pub word_filter: Option<WordFilterOverride>,
+    pub output: Option<OutputMode>,
+    /// Run the whole pipeline but type nothing, for measuring latency
+    pub dry_run: bool,
+    /// Report where the time went in the response
+    pub timings: bool,
+}
+
+/// A push-to-talk recording started by `record_start`, waiting for its `record_stop`
+pub struct PushToTalkSession {
+    pub source_id: u32,
+    pub write_mode: bool,
+    pub language: Option<String>,
+    pub rules: TextRules,
+    pub output: Option<OutputMode>,
+    // Ends the capture; the recorder task then returns the recorded audio
+    pub stop: Arc<std::sync::atomic::AtomicBool>,
+    pub recorder: tokio::task::JoinHandle<Result<Vec<f32>>>,
+}
+
+/// `record` requests waiting for their client's active recording to finish, oldest first
+#[derive(Default)]
+pub struct RecordQueue {
+    pub pending: VecDeque<RecordRequest>,
+    // Whether a task is already working through `pending`
+    pub draining: bool,
+    // Client whose dequeued request is running, counted as recording until it finishes
+    // so no other request can start in the gap before its session is registered
+    pub claimed: Option<String>,
+}
+
 /// Enum to hold different STT model types
 pub enum STTModelInstance {
     Whisper(Box<WhisperModel>),
@@ -34,15 +105,185 @@ pub enum STTModelInstance {
 }
 
 impl STTModelInstance {
-    /// Transcribe audio using the loaded model
+    /// Transcribe audio using the loaded model, in `language` when one is given,
+    /// applying the user's text `rules`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying model fails to transcribe or does not
+    /// support the requested language.
+    pub fn transcribe_audio(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<String> {
+        match self {
+            STTModelInstance::Whisper(model) => {
+                model.transcribe_audio(audio_data, sample_rate, language, rules)
+            }
+            STTModelInstance::Voxtral(model) => {
+                model.transcribe_audio(audio_data, sample_rate, language, rules)
+            }
+        }
+    }
+
+    /// Use `decoding` for later transcriptions; Whisper keeps its own decoding
+    pub fn set_decoding(&mut self, decoding: DecodingConfig) {
+        if let STTModelInstance::Voxtral(model) = self {
+            model.set_decoding(decoding);
+        }
+    }
+
+    /// Run `f` with `overrides` applied to the decoding settings, restoring them afterwards
+    pub fn with_decoding<T>(
+        &mut self,
+        overrides: Option<&DecodingOverride>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let previous = match (&mut *self, overrides) {
+            (STTModelInstance::Voxtral(model), Some(overrides)) => {
+                let previous = model.decoding();
+                model.set_decoding(previous.with_override(overrides));
+                Some(previous)
+            }
+            _ => None,
+        };
+        let result = f(self);
+        if let Some(previous) = previous {
+            self.set_decoding(previous);
+        }
+        result
+    }
+
+    /// Languages the loaded model can transcribe
+    #[must_use]
+    pub fn supported_languages(&self) -> &'static [&'static str] {
+        match self {
+            STTModelInstance::Whisper(model) => model.supported_languages(),
+            STTModelInstance::Voxtral(_) => crate::stt_models::voxtral::model::SUPPORTED_LANGUAGES,
+        }
+    }
+
+    /// Whether the model can transcribe languages other than English
+    #[must_use]
+    pub fn is_multilingual(&self) -> bool {
+        match self {
+            STTModelInstance::Whisper(model) => model.is_multilingual(),
+            STTModelInstance::Voxtral(_) => true,
+        }
+    }
+
+    /// Whether the model aligns its output to the audio
+    #[must_use]
+    pub fn supports_timestamps(&self) -> bool {
+        matches!(self, STTModelInstance::Whisper(_))
+    }
+
+    /// Transcribe audio and return the text with the model's confidence in it
+    ///
+    /// Confidence is the geometric mean of the decoded token probabilities, in
+    /// `0.0..=1.0`. Models that cannot score their output report 0.0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying model fails to transcribe or does not
+    /// support the requested language.
+    pub fn transcribe_audio_with_confidence(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<(String, f32)> {
+        match self {
+            STTModelInstance::Whisper(model) => {
+                let result = model.transcribe(audio_data, sample_rate, language, rules, false)?;
+                Ok((result.text, result.confidence))
+            }
+            STTModelInstance::Voxtral(model) => {
+                let result =
+                    model.transcribe_audio_with_tokens(audio_data, sample_rate, language, rules)?;
+                Ok((result.text, result.confidence))
+            }
+        }
+    }
+
+    /// Transcribe audio and return the text with timed segments
+    ///
+    /// Models that cannot align their output fall back to a single segment
+    /// spanning the whole utterance, so callers always receive valid timings.
     ///
     /// # Errors
     ///
     /// Returns an error if the underlying model fails to transcribe.
-    pub fn transcribe_audio(&mut self, audio_data: &[f32], sample_rate: u32) -> Result<String> {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn transcribe_audio_with_timestamps(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<(String, Vec<Segment>)> {
+        let (text, segments) = match self {
+            STTModelInstance::Whisper(model) => {
+                let result = model.transcribe_audio_with_timestamps(
+                    audio_data,
+                    sample_rate,
+                    language,
+                    rules,
+                )?;
+                (result.text, result.segments)
+            }
+            STTModelInstance::Voxtral(model) => {
+                let result =
+                    model.transcribe_audio_with_tokens(audio_data, sample_rate, language, rules)?;
+                (result.text, result.segments)
+            }
+        };
+
+        let segments = match segments {
+            Some(segments) if !segments.is_empty() => segments,
+            _ if text.is_empty() => Vec::new(),
+            _ => vec![Segment {
+                start: 0.0,
+                end: audio_data.len() as f32 / sample_rate as f32,
+                text: text.clone(),
+                speaker_id: 0,
+            }],
+        };
+
+        Ok((text, segments))
+    }
+
+    /// Whether the model has a language identification head
+    #[must_use]
+    pub fn supports_language_detection(&self) -> bool {
+        match self {
+            STTModelInstance::Whisper(model) => model.is_multilingual(),
+            STTModelInstance::Voxtral(_) => false,
+        }
+    }
+
+    /// Rank the most likely spoken languages, best first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the model cannot identify languages or detection fails.
+    pub fn detect_language(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        top_n: usize,
+    ) -> Result<Vec<LanguageCandidate>> {
         match self {
-            STTModelInstance::Whisper(model) => model.transcribe_audio(audio_data, sample_rate),
-            STTModelInstance::Voxtral(model) => model.transcribe_audio(audio_data, sample_rate),
+            STTModelInstance::Whisper(model) => {
+                model.detect_language(audio_data, sample_rate, top_n)
+            }
+            STTModelInstance::Voxtral(_) => Err(anyhow::anyhow!(
+                "Language detection is not available for Voxtral models"
+            )),
         }
     }
 
@@ -65,11 +306,30 @@ pub struct SuperSTTDaemon {
     pub audio_processor: Arc<AudioProcessor>,
     pub shutdown_tx: broadcast::Sender<()>,
     pub dbus_manager: Option<Arc<DBusManager>>,
+    // Durable record of final transcriptions, if the database could be opened
+    pub history: Option<Arc<HistoryStore>>,
     pub realtime_manager: Arc<RealTimeTranscriptionManager>,
     pub udp_streamer: Arc<UdpAudioStreamer>,
+    // Bearer token of the WebSocket gateway and HTTP API, fixed for the daemon's lifetime
+    pub api_token: Arc<str>,
     pub audio_theme: Arc<RwLock<AudioTheme>>,
-    pub is_recording: Arc<tokio::sync::RwLock<bool>>,
+    // Decoded sounds of the custom audio theme, while it is selected
+    pub custom_sounds: Arc<RwLock<Option<Arc<CustomSounds>>>>,
+    // Recordings in progress, keyed by the client that started them
+    pub recording_sessions: Arc<tokio::sync::RwLock<HashMap<String, RecordingSession>>>,
+    // UDP source id handed to the next recording session
+    pub next_recording_source: Arc<std::sync::atomic::AtomicU32>,
+    // Record requests that opted to wait for their client's active recording
+    pub record_queue: Arc<tokio::sync::Mutex<RecordQueue>>,
+    // Push-to-talk recordings awaiting `record_stop`, keyed by the client that started them
+    pub push_to_talk: Arc<tokio::sync::Mutex<HashMap<String, PushToTalkSession>>>,
     pub audio_monitoring_handle: Arc<tokio::sync::RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    // Stop flag for the active level monitor, if any
+    pub monitor_stop: Arc<tokio::sync::RwLock<Option<Arc<std::sync::atomic::AtomicBool>>>>,
+    // Background capture recordings take their lead-in from, off when `pre_roll_ms` is 0
+    pub pre_roll: Arc<tokio::sync::RwLock<Option<Arc<PreRollCapture>>>>,
+    // Rate the input device was negotiated at for the latest recording, 0 before the first
+    pub input_sample_rate: Arc<std::sync::atomic::AtomicU32>,
     pub download_manager: Arc<DownloadStateManager>,
     // Device management
     pub preferred_device: Arc<tokio::sync::RwLock<String>>, // "cpu" or "cuda"
@@ -84,6 +344,18 @@ pub struct SuperSTTDaemon {
     pub resource_manager: Arc<ResourceManager>,
     // Preview typing setting (beta feature)
     pub preview_typing_enabled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    // Undo/redo requests for the active recording's preview typer, if any
+    pub preview_edits: Arc<tokio::sync::RwLock<Option<UnboundedSender<PreviewEdit>>>>,
+    // Moving average of preview transcription time in ms (0 until the first pass)
+    pub preview_latency_ms: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    // Most recent failure per subsystem, reported by the health command
+    pub last_errors: Arc<tokio::sync::RwLock<BTreeMap<String, String>>>,
+    // When the model was last used, for the idle timeout
+    pub last_activity: Arc<tokio::sync::RwLock<std::time::Instant>>,
+    // Whether the idle timeout unloaded the model, held while it reloads
+    pub idle_unloaded: Arc<tokio::sync::Mutex<bool>>,
+    // Latest recording's processed 16kHz audio, kept for `get_last_audio` while `debug.keep_last_audio` is on
+    pub last_audio: Arc<tokio::sync::RwLock<Option<Vec<f32>>>>,
     // Mutex to prevent GPU processing during typing operations
 }
 
@@ -94,11 +366,13 @@ impl SuperSTTDaemon {
     ///
     /// Returns an error if initializing subsystems (like UDP streamer) fails
     /// or if model loading fails.
+    #[allow(clippy::too_many_lines)]
     pub async fn new(
         socket_path: PathBuf,
         stt_model_override: Option<STTModel>,
         device_override: Option<DeviceOverride>,
         udp_port: u16,
+        udp_bind_override: Option<IpAddr>,
         audio_theme_override: Option<AudioTheme>,
     ) -> Result<Self> {
         info!("Initializing Super STT Daemon...");
@@ -110,6 +384,7 @@ impl SuperSTTDaemon {
             &mut config,
             stt_model_override,
             device_override,
+            udp_bind_override,
             audio_theme_override,
         );
         if config_changed {
@@ -123,7 +398,13 @@ impl SuperSTTDaemon {
         // Initialize components
         let (shutdown_tx, _) = broadcast::channel(1);
         let notification_manager = Arc::new(NotificationManager::new(1000, 100)); // max 1000 events, 100 subscribers
-        let audio_processor = Arc::new(AudioProcessor::new());
+        let audio_processor = Arc::new(
+            AudioProcessor::new()
+                .with_noise_suppression(config.audio.noise_suppression)
+                .with_trim_silence(config.audio.trim_silence)
+                .with_resample_quality(config.audio.resample_quality)
+                .with_agc_target_rms(config.audio.agc_target()),
+        );
 
         // Initialize model storage
         let model = Arc::new(tokio::sync::RwLock::new(None));
@@ -138,14 +419,28 @@ impl SuperSTTDaemon {
             Arc::clone(&notification_manager),
             Arc::clone(&audio_processor),
         ));
-        let udp_bind_addr = format!("127.0.0.1:{udp_port}");
+        let udp_bind_addr = SocketAddr::new(config.network.udp_bind_addr, udp_port);
+        if !udp_bind_addr.ip().is_loopback() {
+            warn!("UDP audio streamer bound to non-loopback address {udp_bind_addr}");
+        }
+        let mut last_errors = BTreeMap::new();
         let udp_streamer = {
-            let streamer = Arc::new(UdpAudioStreamer::new(&udp_bind_addr).await?);
-            info!("UDP audio streamer initialized on port {udp_port}");
+            let streamer = Arc::new(UdpAudioStreamer::new(&udp_bind_addr.to_string()).await?);
+            info!("UDP audio streamer initialized on {udp_bind_addr}");
             streamer.start_cleanup_task(&shutdown_tx);
-            let _ = streamer.start_registration_listener(&shutdown_tx).await;
+            streamer.start_secret_rotation_task(&shutdown_tx);
+            if let Err(e) = streamer.start_registration_listener(&shutdown_tx).await {
+                warn!("UDP registration listener failed to start: {e}");
+                last_errors.insert("udp_streamer".to_string(), e.to_string());
+            }
             streamer
         };
+        // The UDP secret rotates hourly, so unless a key is configured the network
+        // services take a copy of it from startup that rotation leaves alone
+        let api_token: Arc<str> = match config.network.api_key.clone() {
+            Some(key) => key.into(),
+            None => udp_streamer.secret()?.into(),
+        };
 
         let download_manager = Arc::new(DownloadStateManager::new());
 
@@ -164,10 +459,26 @@ impl SuperSTTDaemon {
             Ok(mgr) => Some(Arc::new(mgr)),
             Err(e) => {
                 warn!("D-Bus initialization failed (this is normal on some systems): {e}");
+                last_errors.insert("dbus".to_string(), e.to_string());
                 None
             }
         };
 
+        let history = match HistoryStore::open(&HistoryStore::default_path()) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                warn!("Transcription history unavailable: {e}");
+                None
+            }
+        };
+
+        if config.archive_recordings.enabled
+            && let Err(e) = archive::check_writable(&config.archive_recordings.directory())
+        {
+            warn!("Recording archive unavailable, recordings will not be saved: {e:#}");
+            last_errors.insert("archive".to_string(), format!("{e:#}"));
+        }
+
         // Initialize device state based on config
         let preferred_device = config.device.preferred_device.clone();
         let actual_device = preferred_device.clone(); // Will be updated when model loads
@@ -186,11 +497,21 @@ impl SuperSTTDaemon {
             audio_processor,
             shutdown_tx,
             dbus_manager,
+            history,
             realtime_manager,
             udp_streamer,
+            api_token,
             audio_theme: Arc::new(RwLock::new(config.audio.theme)),
-            is_recording: Arc::new(tokio::sync::RwLock::new(false)),
+            custom_sounds: Arc::new(RwLock::new(None)),
+            recording_sessions: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            // 0 is the daemon's own source id, used for packets outside any session
+            next_recording_source: Arc::new(std::sync::atomic::AtomicU32::new(1)),
+            record_queue: Arc::new(tokio::sync::Mutex::new(RecordQueue::default())),
+            push_to_talk: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
             audio_monitoring_handle: Arc::new(tokio::sync::RwLock::new(None)),
+            monitor_stop: Arc::new(tokio::sync::RwLock::new(None)),
+            pre_roll: Arc::new(tokio::sync::RwLock::new(None)),
+            input_sample_rate: Arc::new(std::sync::atomic::AtomicU32::new(0)),
             download_manager,
             preferred_device: Arc::new(tokio::sync::RwLock::new(preferred_device)),
             actual_device: Arc::new(tokio::sync::RwLock::new(actual_device)),
@@ -201,6 +522,12 @@ impl SuperSTTDaemon {
             preview_typing_enabled: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(
                 preview_typing_enabled,
             )),
+            preview_edits: Arc::new(tokio::sync::RwLock::new(None)),
+            preview_latency_ms: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            last_errors: Arc::new(tokio::sync::RwLock::new(last_errors)),
+            last_activity: Arc::new(tokio::sync::RwLock::new(std::time::Instant::now())),
+            idle_unloaded: Arc::new(tokio::sync::Mutex::new(false)),
+            last_audio: Arc::new(tokio::sync::RwLock::new(None)),
         };
 
         // Apply temporary device override for current session (not saved to config)
@@ -232,6 +559,7 @@ impl SuperSTTDaemon {
         config: &mut DaemonConfig,
         stt_model_override: Option<STTModel>,
         device_override: Option<DeviceOverride>,
+        udp_bind_override: Option<IpAddr>,
         audio_theme_override: Option<AudioTheme>,
     ) -> bool {
         let mut changed = false;
@@ -250,6 +578,16 @@ impl SuperSTTDaemon {
                 changed = true;
             }
         }
+        if let Some(addr) = udp_bind_override
+            && config.network.udp_bind_addr != addr
+        {
+            info!(
+                "CLI override: UDP bind address {} -> {addr}",
+                config.network.udp_bind_addr
+            );
+            config.network.udp_bind_addr = addr;
+            changed = true;
+        }
         if let Some(theme) = audio_theme_override
             && config.audio.theme != theme
         {
@@ -325,6 +663,7 @@ impl SuperSTTDaemon {
         info!("{model_name} model loaded successfully");
         *daemon.model.write().await = Some(instance);
         *daemon.model_type.write().await = Some(model_to_load);
+        daemon.warm_up_after_load().await;
 
         // Broadcast ready status
         if let Err(e) = daemon
@@ -346,6 +685,39 @@ impl SuperSTTDaemon {
         Ok(())
     }
 
+    /// Start the optional TCP services that have an address configured
+    async fn spawn_network_services(&self) {
+        let network = self.config.read().await.network.clone();
+        if let Some(addr) = network.websocket_bind_addr {
+            #[cfg(feature = "websocket")]
+            if let Err(e) = crate::services::websocket::spawn(self.clone(), addr).await {
+                log::error!("Failed to start WebSocket gateway: {e}");
+            }
+            #[cfg(not(feature = "websocket"))]
+            warn!(
+                "WebSocket gateway at {addr} is configured, but this build lacks the websocket feature"
+            );
+        }
+        if let Some(addr) = network.http_bind_addr {
+            #[cfg(feature = "http")]
+            if let Err(e) = crate::services::http::spawn(self.clone(), addr).await {
+                log::error!("Failed to start HTTP API: {e}");
+            }
+            #[cfg(not(feature = "http"))]
+            warn!("HTTP API at {addr} is configured, but this build lacks the http feature");
+        }
+        if let Some(addr) = network.metrics_bind_addr {
+            #[cfg(feature = "metrics")]
+            if let Err(e) = crate::services::metrics::spawn(self.clone(), addr).await {
+                log::error!("Failed to start metrics exporter: {e}");
+            }
+            #[cfg(not(feature = "metrics"))]
+            warn!(
+                "Metrics exporter at {addr} is configured, but this build lacks the metrics feature"
+            );
+        }
+    }
+
     /// Start the daemon and listen for connections
     ///
     /// # Errors
@@ -402,6 +774,19 @@ impl SuperSTTDaemon {
 
         info!("Daemon listening on socket: {}", self.socket_path.display());
 
+        self.spawn_network_services().await;
+        {
+            let config = self.config.read().await;
+            self.apply_audio_theme(config.audio.theme, &config.audio.custom_sounds);
+        }
+        self.restart_pre_roll().await;
+        if let Some(requests) = self.dbus_manager.as_ref().and_then(|m| m.take_requests()) {
+            self.spawn_dbus_requests(requests);
+        }
+        self.spawn_idle_monitor();
+        crate::services::power::spawn(self.clone());
+        crate::services::systemd::notify_ready();
+
         // Set up shutdown receiver
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
@@ -460,6 +845,48 @@ impl SuperSTTDaemon {
         }
     }
 
+    /// Select `theme`, loading the custom sounds from `paths` when it is `Custom`
+    ///
+    /// Sounds that are missing or cannot be decoded fall back to the default theme
+    /// with a warning.
+    pub fn apply_audio_theme(&self, theme: AudioTheme, paths: &CustomSoundPaths) {
+        let (theme, sounds) = if theme == AudioTheme::Custom {
+            match CustomSounds::load(paths) {
+                Ok(sounds) => (theme, Some(Arc::new(sounds))),
+                Err(e) => {
+                    warn!(
+                        "Custom audio theme unavailable, using {} instead: {e:#}",
+                        AudioTheme::default()
+                    );
+                    (AudioTheme::default(), None)
+                }
+            }
+        } else {
+            (theme, None)
+        };
+
+        match self.custom_sounds.write() {
+            Ok(mut guard) => *guard = sounds,
+            Err(poisoned) => {
+                log::warn!("Custom sounds lock was poisoned, attempting recovery");
+                *poisoned.into_inner() = sounds;
+            }
+        }
+        self.set_audio_theme(theme);
+    }
+
+    /// Get the decoded sounds of the custom audio theme, if it is selected
+    #[must_use]
+    pub fn get_custom_sounds(&self) -> Option<Arc<CustomSounds>> {
+        match self.custom_sounds.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                log::warn!("Custom sounds lock was poisoned, returning current value");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
     /// Get the current audio theme
     ///
     /// If the lock is poisoned, logs a warning and returns the default theme.
@@ -523,3 +950,62 @@ impl SuperSTTDaemon {
         Ok(())
     }
 }
+
+#[cfg(test)]
+impl SuperSTTDaemon {
+    /// A daemon on `config` without a model, D-Bus or history, streaming on a free UDP port
+    pub(crate) async fn for_tests(config: DaemonConfig) -> Self {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let notification_manager = Arc::new(NotificationManager::new(1000, 100));
+        let audio_processor = Arc::new(AudioProcessor::new());
+        let model = Arc::new(tokio::sync::RwLock::new(None));
+        let model_type = Arc::new(tokio::sync::RwLock::new(None));
+        let realtime_manager = Arc::new(RealTimeTranscriptionManager::new(
+            Arc::clone(&model),
+            Arc::clone(&model_type),
+            Arc::clone(&notification_manager),
+            Arc::clone(&audio_processor),
+        ));
+        let udp_streamer = Arc::new(UdpAudioStreamer::new("127.0.0.1:0").await.unwrap());
+        let api_token = udp_streamer.secret().unwrap().into();
+        let preferred_device = config.device.preferred_device.clone();
+
+        SuperSTTDaemon {
+            socket_path: std::env::temp_dir().join("super-stt-test.sock"),
+            model,
+            model_type,
+            notification_manager,
+            audio_processor,
+            shutdown_tx,
+            dbus_manager: None,
+            history: None,
+            realtime_manager,
+            udp_streamer,
+            api_token,
+            audio_theme: Arc::new(RwLock::new(config.audio.theme)),
+            custom_sounds: Arc::new(RwLock::new(None)),
+            recording_sessions: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            next_recording_source: Arc::new(std::sync::atomic::AtomicU32::new(1)),
+            record_queue: Arc::new(tokio::sync::Mutex::new(RecordQueue::default())),
+            push_to_talk: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            audio_monitoring_handle: Arc::new(tokio::sync::RwLock::new(None)),
+            monitor_stop: Arc::new(tokio::sync::RwLock::new(None)),
+            pre_roll: Arc::new(tokio::sync::RwLock::new(None)),
+            input_sample_rate: Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            download_manager: Arc::new(DownloadStateManager::new()),
+            preferred_device: Arc::new(tokio::sync::RwLock::new(preferred_device.clone())),
+            actual_device: Arc::new(tokio::sync::RwLock::new(preferred_device)),
+            config: Arc::new(tokio::sync::RwLock::new(config)),
+            active_connections: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            process_auth: ProcessAuth::new(),
+            resource_manager: Arc::new(ResourceManager::development()),
+            preview_typing_enabled: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            preview_edits: Arc::new(tokio::sync::RwLock::new(None)),
+            preview_latency_ms: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            last_errors: Arc::new(tokio::sync::RwLock::new(BTreeMap::new())),
+            last_activity: Arc::new(tokio::sync::RwLock::new(std::time::Instant::now())),
+            idle_unloaded: Arc::new(tokio::sync::Mutex::new(false)),
+            last_audio: Arc::new(tokio::sync::RwLock::new(None)),
+        }
+    }
+}
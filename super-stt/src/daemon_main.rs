@@ -8,8 +8,10 @@
 use crate::cli;
 use crate::config::DaemonConfig;
 use crate::daemon::types::{DeviceOverride, SuperSTTDaemon};
+use crate::services::systemd;
 use anyhow::{Context, Result};
-use log::{error, info};
+use log::{error, info, warn};
+use std::io::Write;
 use std::path::PathBuf;
 use super_stt_shared::stt_model::STTModel;
 use super_stt_shared::theme::AudioTheme;
@@ -23,6 +25,7 @@ use super_stt_shared::theme::AudioTheme;
 /// # Panics
 ///
 /// Panics if the daemon fails to initialize.
+#[allow(clippy::too_many_lines)]
 pub async fn run() -> Result<()> {
     let matches = cli::build().get_matches();
 
@@ -31,17 +34,80 @@ pub async fn run() -> Result<()> {
         return handle_record_command(record_matches).await;
     }
 
+    // Check if transcribe subcommand was used
+    if let Some(transcribe_matches) = matches.subcommand_matches("transcribe") {
+        return handle_transcribe_command(transcribe_matches).await;
+    }
+
+    // Check if detect-language subcommand was used
+    if let Some(detect_matches) = matches.subcommand_matches("detect-language") {
+        return handle_detect_language_command(detect_matches).await;
+    }
+
+    // Check if bench subcommand was used
+    if let Some(bench_matches) = matches.subcommand_matches("bench") {
+        return handle_bench_command(bench_matches);
+    }
+
+    // Check if rm-model subcommand was used
+    if let Some(rm_model_matches) = matches.subcommand_matches("rm-model") {
+        return handle_rm_model_command(rm_model_matches).await;
+    }
+
+    // Check if unload-model subcommand was used
+    if let Some(unload_model_matches) = matches.subcommand_matches("unload-model") {
+        return handle_unload_model_command(unload_model_matches).await;
+    }
+
     // Check if ping subcommand was used
-    if matches.subcommand_matches("ping").is_some() {
-        return handle_ping_command(&matches).await;
+    if let Some(ping_matches) = matches.subcommand_matches("ping") {
+        return handle_ping_command(&matches, ping_matches.get_flag("json")).await;
     }
 
     // Check if status subcommand was used
-    if matches.subcommand_matches("status").is_some() {
-        return handle_status_command(&matches).await;
+    if let Some(status_matches) = matches.subcommand_matches("status") {
+        return handle_status_command(&matches, status_matches.get_flag("json")).await;
+    }
+
+    // Check if health subcommand was used
+    if let Some(health_matches) = matches.subcommand_matches("health") {
+        return handle_health_command(&matches, health_matches.get_flag("json")).await;
+    }
+
+    // Check if preview-undo or preview-redo subcommand was used
+    if matches.subcommand_matches("preview-undo").is_some() {
+        return handle_preview_edit_command(&matches, "preview_undo").await;
+    }
+    if matches.subcommand_matches("preview-redo").is_some() {
+        return handle_preview_edit_command(&matches, "preview_redo").await;
     }
 
     // Standard daemon mode
+    let config_path = matches.get_one::<PathBuf>("config").cloned();
+    if let Some(path) = &config_path {
+        if path.is_dir() {
+            anyhow::bail!("--config {} is a directory, not a file", path.display());
+        }
+        // Settings are saved back to it, which must not depend on the working directory
+        DaemonConfig::set_config_path(std::path::absolute(path)?);
+    }
+
+    // Initialize logging - respect RUST_LOG env var, fallback to verbose flag.
+    // This comes first so that upgrading an old config file is logged
+    let verbose = matches.get_flag("verbose");
+    let mut logger = env_logger::Builder::from_default_env();
+    if std::env::var("RUST_LOG").is_err() {
+        logger.filter_level(if verbose {
+            log::LevelFilter::Debug
+        } else {
+            log::LevelFilter::Info
+        });
+    }
+    if matches.get_one::<String>("log-format").map(String::as_str) == Some("json") {
+        logger.format(write_json_record);
+    }
+    logger.init();
+
     // Load saved configuration first
     let config = DaemonConfig::load();
 
@@ -54,8 +120,10 @@ pub async fn run() -> Result<()> {
 
     let device = matches.get_one::<String>("device").unwrap();
     let force_cpu = device == "cpu";
-    let verbose = matches.get_flag("verbose");
     let udp_port = matches.get_one::<u16>("udp-port").copied().unwrap();
+    let udp_bind_override = matches
+        .get_one::<std::net::IpAddr>("udp-bind-addr")
+        .copied();
     let socket_path = matches
         .get_one::<PathBuf>("socket")
         .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
@@ -68,21 +136,17 @@ pub async fn run() -> Result<()> {
             config.audio.theme
         };
 
-    // Initialize logging - respect RUST_LOG env var, fallback to verbose flag
-    if std::env::var("RUST_LOG").is_ok() {
-        env_logger::init();
-    } else {
-        let log_level = if verbose {
-            log::LevelFilter::Debug
+    info!("Starting Super STT Daemon");
+    if let Some(path) = &config_path {
+        if path.exists() {
+            info!("Config file: {}", path.display());
         } else {
-            log::LevelFilter::Info
-        };
-        env_logger::Builder::from_default_env()
-            .filter_level(log_level)
-            .init();
+            warn!(
+                "Config file {} does not exist, using defaults",
+                path.display()
+            );
+        }
     }
-
-    info!("Starting Super STT Daemon");
     info!("Socket path: {}", socket_path.display());
     info!("Model: {model}");
     info!("Device: {device}");
@@ -116,16 +180,20 @@ pub async fn run() -> Result<()> {
         None
     };
 
+    // Model downloads can take a while; show why startup is not done yet
+    systemd::notify_status("Loading model");
     let daemon = SuperSTTDaemon::new(
         socket_path.clone(),
         model_override,
         device_override,
         udp_port,
+        udp_bind_override,
         audio_theme_override,
     )
     .await?;
 
     info!("Daemon initialized successfully");
+    systemd::spawn_watchdog(&daemon.shutdown_tx);
 
     // Set up Ctrl+C handler
     let shutdown_tx = daemon.shutdown_tx.clone();
@@ -137,36 +205,78 @@ pub async fn run() -> Result<()> {
         let _ = shutdown_tx.send(());
     });
 
+    // SIGHUP re-reads the config file, so settings change without a restart
+    let reload_daemon = daemon.clone();
+    tokio::spawn(async move {
+        let mut hangups =
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+                Ok(hangups) => hangups,
+                Err(e) => {
+                    warn!("Failed to listen for SIGHUP, config reloads are unavailable: {e}");
+                    return;
+                }
+            };
+        while hangups.recv().await.is_some() {
+            info!("Received SIGHUP, reloading config...");
+            reload_daemon.reload_config().await;
+        }
+    });
+
     // Start the daemon and wait for it to complete
-    daemon.start().await?;
+    let result = daemon.start().await;
+    systemd::notify_stopping();
+    result?;
 
     info!("Daemon stopped gracefully");
 
     Ok(())
 }
 
+/// Write a log record as a single-line JSON object, for log aggregators
+fn write_json_record(
+    buf: &mut env_logger::fmt::Formatter,
+    record: &log::Record,
+) -> std::io::Result<()> {
+    let line = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+    });
+    writeln!(buf, "{line}")
+}
+
+/// Log to stderr for a CLI subcommand, at `default_level` unless `RUST_LOG` is set
+fn init_cli_logger(default_level: log::LevelFilter) {
+    let mut logger = env_logger::Builder::from_default_env();
+    if std::env::var("RUST_LOG").is_err() {
+        logger.filter_level(default_level);
+    }
+    logger.init();
+}
+
 /// Handle the record subcommand - direct recording mode
 async fn handle_record_command(matches: &clap::ArgMatches) -> Result<()> {
-    let write_mode = matches.get_flag("write");
+    let output = matches.get_one::<String>("output").map(String::as_str);
+    // Choosing an output only makes sense when the transcription is written
+    let write_mode = matches.get_flag("write") || output.is_some();
+    let stream = matches.get_flag("stream");
+    let queue = matches.get_flag("queue");
     let socket_path = matches
         .get_one::<PathBuf>("socket")
         .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
 
-    // Initialize logging for recording mode - respect RUST_LOG env var
-    if std::env::var("RUST_LOG").is_ok() {
-        env_logger::init();
-    } else {
-        env_logger::Builder::from_default_env()
-            .filter_level(log::LevelFilter::Info)
-            .init();
-    }
+    init_cli_logger(log::LevelFilter::Info);
 
     info!("Super STT Direct Recording Mode");
 
     // Try to connect to existing daemon first
     if socket_path.exists() {
         info!("Found existing daemon, sending record request...");
-        return send_record_request_to_daemon(socket_path, write_mode).await;
+        if stream {
+            return send_record_stream_request_to_daemon(socket_path, write_mode).await;
+        }
+        return send_record_request_to_daemon(socket_path, write_mode, output, queue).await;
     }
 
     // If no daemon is running, inform user to start it first
@@ -179,12 +289,164 @@ async fn handle_record_command(matches: &clap::ArgMatches) -> Result<()> {
     std::process::exit(1);
 }
 
+/// Handle the transcribe subcommand - transcribe an audio file via the daemon
+async fn handle_transcribe_command(matches: &clap::ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("path").unwrap();
+    let socket_path = matches
+        .get_one::<PathBuf>("socket")
+        .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
+
+    init_cli_logger(log::LevelFilter::Info);
+
+    // The daemon only accepts absolute paths, so resolve relative ones here
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+
+    match send_transcribe_file_request_to_daemon(socket_path, &path).await {
+        Ok(transcription) => {
+            println!("{transcription}");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            error!("❌ Error transcribing file: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Handle the detect-language subcommand - identify the language of an audio file
+async fn handle_detect_language_command(matches: &clap::ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("path").unwrap();
+    let socket_path = matches
+        .get_one::<PathBuf>("socket")
+        .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
+
+    init_cli_logger(log::LevelFilter::Info);
+
+    // The daemon only accepts absolute paths, so resolve relative ones here
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+
+    match send_detect_language_request_to_daemon(socket_path, &path).await {
+        Ok(candidates) => {
+            for candidate in candidates {
+                println!("{}\t{:.3}", candidate.language, candidate.confidence);
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            error!("❌ Error detecting language: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Handle the bench subcommand - time a model on an audio file without the daemon
+fn handle_bench_command(matches: &clap::ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("path").unwrap();
+    let model = matches
+        .get_one::<STTModel>("model")
+        .copied()
+        .unwrap_or_else(|| DaemonConfig::load().transcription.preferred_model);
+    let device = matches.get_one::<String>("device").unwrap();
+    let runs = usize::from(*matches.get_one::<u16>("runs").unwrap());
+
+    init_cli_logger(log::LevelFilter::Warn);
+
+    match crate::bench::run(path, model, device, runs) {
+        Ok(report) if matches.get_flag("json") => {
+            println!("{}", serde_json::to_string(&report)?);
+            Ok(())
+        }
+        Ok(report) => {
+            report.print_table();
+            Ok(())
+        }
+        Err(e) => {
+            error!("❌ Benchmark failed: {e:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Handle the unload-model subcommand - free the daemon's model until it is next needed
+async fn handle_unload_model_command(matches: &clap::ArgMatches) -> Result<()> {
+    use super_stt_shared::models::protocol::DaemonRequest;
+
+    let socket_path = matches
+        .get_one::<PathBuf>("socket")
+        .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
+
+    init_cli_logger(log::LevelFilter::Info);
+
+    let request = DaemonRequest {
+        client_id: Some("unload_model_client".to_string()),
+        ..DaemonRequest::new("unload_model")
+    };
+
+    match send_request_to_daemon(socket_path, &request).await {
+        Ok(response) if response.status == "success" => {
+            info!("{}", response.message.unwrap_or_default());
+            std::process::exit(0);
+        }
+        Ok(response) => {
+            let message = response.message.unwrap_or("Unknown error".to_string());
+            error!("❌ Daemon error: {message}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("❌ Error unloading model: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Handle the rm-model subcommand - delete a downloaded model through the daemon
+async fn handle_rm_model_command(matches: &clap::ArgMatches) -> Result<()> {
+    use super_stt_shared::models::protocol::DaemonRequest;
+
+    let model = matches.get_one::<STTModel>("model").unwrap();
+    let socket_path = matches
+        .get_one::<PathBuf>("socket")
+        .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
+
+    init_cli_logger(log::LevelFilter::Info);
+
+    let request = DaemonRequest {
+        client_id: Some("rm_model_client".to_string()),
+        data: Some(serde_json::json!({ "model": model.to_string() })),
+        ..DaemonRequest::new("delete_model")
+    };
+
+    match send_request_to_daemon(socket_path, &request).await {
+        Ok(response) if response.status == "success" => {
+            info!("{}", response.message.unwrap_or_default());
+            std::process::exit(0);
+        }
+        Ok(response) => {
+            let message = response.message.unwrap_or("Unknown error".to_string());
+            error!("❌ Daemon error: {message}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("❌ Error deleting model: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Handle the ping command - check if daemon is running
-async fn handle_ping_command(matches: &clap::ArgMatches) -> Result<()> {
+async fn handle_ping_command(matches: &clap::ArgMatches, json: bool) -> Result<()> {
     let socket_path = matches
         .get_one::<PathBuf>("socket")
         .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
 
+    if json {
+        print_json_response(socket_path, "ping", "ping_client").await;
+    }
+
     // Check if socket exists and is accessible
     if socket_path.exists() {
         match tokio::net::UnixStream::connect(socket_path).await {
@@ -201,11 +463,17 @@ async fn handle_ping_command(matches: &clap::ArgMatches) -> Result<()> {
 }
 
 /// Handle the status command - get daemon status information
-async fn handle_status_command(matches: &clap::ArgMatches) -> Result<()> {
+async fn handle_status_command(matches: &clap::ArgMatches, json: bool) -> Result<()> {
     let socket_path = matches
         .get_one::<PathBuf>("socket")
         .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
 
+    if json {
+        print_json_response(socket_path, "status", "status_client").await;
+    }
+
+    init_cli_logger(log::LevelFilter::Info);
+
     // Try to connect to daemon and get status
     match send_status_request_to_daemon(socket_path).await {
         Ok(()) => std::process::exit(0),
@@ -216,8 +484,119 @@ async fn handle_status_command(matches: &clap::ArgMatches) -> Result<()> {
     }
 }
 
+/// Handle the health command - report subsystem state, exiting with 1 unless all are up
+async fn handle_health_command(matches: &clap::ArgMatches, json: bool) -> Result<()> {
+    use super_stt_shared::models::protocol::DaemonRequest;
+
+    let socket_path = matches
+        .get_one::<PathBuf>("socket")
+        .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
+
+    if json {
+        print_json_response(socket_path, "health", "health_client").await;
+    }
+
+    init_cli_logger(log::LevelFilter::Info);
+
+    let request = DaemonRequest {
+        client_id: Some("health_client".to_string()),
+        ..DaemonRequest::new("health")
+    };
+    let health = match send_request_to_daemon(socket_path, &request).await {
+        Ok(response) if response.status == "success" => response.health.unwrap_or_default(),
+        Ok(response) => {
+            error!(
+                "❌ Health check failed: {}",
+                response.message.unwrap_or_default()
+            );
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("❌ Error checking health: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mark = |up: bool| if up { "✅" } else { "❌" };
+    info!("Daemon Health:");
+    info!("  Model loaded: {}", mark(health.model_loaded));
+    info!("  UDP streamer bound: {}", mark(health.udp_streamer_bound));
+    info!("  D-Bus connected: {}", mark(health.dbus_connected));
+    info!(
+        "  Input device available: {}",
+        mark(health.input_device_available)
+    );
+    info!(
+        "  Connections: {}/{}",
+        health.connections, health.max_connections
+    );
+    for (subsystem, last_error) in &health.last_errors {
+        info!("  Last {subsystem} error: {last_error}");
+    }
+    std::process::exit(i32::from(!health.is_healthy()));
+}
+
+/// Send `command` to the daemon, print the response as JSON and exit with 0 on success
+///
+/// A daemon that cannot be reached is reported as an error response so the
+/// output is always a single JSON object.
+async fn print_json_response(socket_path: &PathBuf, command: &str, client_id: &str) -> ! {
+    use super_stt_shared::models::protocol::{DaemonRequest, DaemonResponse};
+
+    let request = DaemonRequest {
+        client_id: Some(client_id.to_string()),
+        ..DaemonRequest::new(command)
+    };
+
+    let response = send_request_to_daemon(socket_path, &request)
+        .await
+        .unwrap_or_else(|e| DaemonResponse::error(&format!("{e:#}")));
+    match serde_json::to_string(&response) {
+        Ok(json) => println!("{json}"),
+        Err(e) => {
+            eprintln!("Failed to serialize daemon response: {e}");
+            std::process::exit(1);
+        }
+    }
+    std::process::exit(i32::from(response.status != "success"));
+}
+
+/// Handle the preview-undo and preview-redo commands - correct the preview of the active recording
+async fn handle_preview_edit_command(matches: &clap::ArgMatches, command: &str) -> Result<()> {
+    use super_stt_shared::models::protocol::DaemonRequest;
+
+    let socket_path = matches
+        .get_one::<PathBuf>("socket")
+        .unwrap_or(&cli::DEFAULT_SOCKET_PATH);
+
+    init_cli_logger(log::LevelFilter::Info);
+
+    let request = DaemonRequest {
+        client_id: Some("preview_edit_client".to_string()),
+        ..DaemonRequest::new(command)
+    };
+
+    match send_request_to_daemon(socket_path, &request).await {
+        Ok(response) if response.status == "success" => std::process::exit(0),
+        Ok(response) => {
+            let message = response.message.unwrap_or("Unknown error".to_string());
+            error!("❌ Daemon error: {message}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("❌ Error sending {command}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Send a record request to an existing daemon and exit immediately
-async fn send_record_request_to_daemon(socket_path: &PathBuf, write_mode: bool) -> Result<()> {
+async fn send_record_request_to_daemon(
+    socket_path: &PathBuf,
+    write_mode: bool,
+    output: Option<&str>,
+    queue: bool,
+) -> Result<()> {
     use super_stt_shared::models::protocol::DaemonRequest;
     use tokio::io::AsyncWriteExt;
     use tokio::net::UnixStream;
@@ -228,20 +607,13 @@ async fn send_record_request_to_daemon(socket_path: &PathBuf, write_mode: bool)
 
     // Send record request
     let request = DaemonRequest {
-        command: "record".to_string(),
-        audio_data: None,
-        sample_rate: None,
-        event_types: None,
-        client_info: None,
-        since_timestamp: None,
-        limit: None,
-        event_type: None,
         client_id: Some("record_client".to_string()),
         data: Some(serde_json::json!({
-            "write_mode": write_mode
+            "write_mode": write_mode,
+            "output": output,
+            "queue": queue
         })),
-        language: None,
-        enabled: None,
+        ..DaemonRequest::new("record")
     };
 
     let request_data = serde_json::to_vec(&request)?;
@@ -253,14 +625,156 @@ async fn send_record_request_to_daemon(socket_path: &PathBuf, write_mode: bool)
 
     // Don't wait for response - just trigger the recording and exit
     info!("🎤 Recording request sent to daemon");
-    if write_mode {
-        info!("📝 Will type transcription when complete");
+    match output {
+        Some("clipboard") => info!("📋 Will copy transcription to the clipboard when complete"),
+        Some("both") => info!("📝 Will type and copy transcription when complete"),
+        _ if write_mode => info!("📝 Will type transcription when complete"),
+        _ => {}
     }
     info!("💡 Watch the daemon logs for results");
 
     Ok(())
 }
 
+/// Send a `record_stream` request and print partials until the final frame arrives
+async fn send_record_stream_request_to_daemon(
+    socket_path: &PathBuf,
+    write_mode: bool,
+) -> Result<()> {
+    use super_stt_shared::models::protocol::{DaemonRequest, DaemonResponse};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket_path)
+        .await
+        .context("Failed to connect to daemon")?;
+
+    let request = DaemonRequest {
+        client_id: Some("record_client".to_string()),
+        data: Some(serde_json::json!({
+            "write_mode": write_mode
+        })),
+        ..DaemonRequest::new("record_stream")
+    };
+
+    let request_data = serde_json::to_vec(&request)?;
+    let request_size = request_data.len() as u64;
+
+    // Send size then data
+    stream.write_all(&request_size.to_be_bytes()).await?;
+    stream.write_all(&request_data).await?;
+
+    info!("🎤 Recording request sent to daemon, streaming partials");
+
+    loop {
+        let mut size_bytes = [0u8; 8];
+        stream
+            .read_exact(&mut size_bytes)
+            .await
+            .context("Daemon closed the stream before the final transcription")?;
+        let response_len: usize = usize::try_from(u64::from_be_bytes(size_bytes))
+            .context("Response size does not fit into memory on this platform")?;
+        let mut response_data = vec![0u8; response_len];
+        stream.read_exact(&mut response_data).await?;
+
+        let response: DaemonResponse = serde_json::from_slice(&response_data)?;
+        if response.status != "success" {
+            anyhow::bail!(
+                "Daemon error: {}",
+                response.message.unwrap_or_else(|| "unknown".to_string())
+            );
+        }
+
+        let transcription = response.transcription.unwrap_or_default();
+        if response.is_final == Some(true) {
+            println!("{transcription}");
+            return Ok(());
+        }
+        info!("… {transcription}");
+    }
+}
+
+/// Send a request to the daemon and wait for its response
+async fn send_request_to_daemon(
+    socket_path: &PathBuf,
+    request: &super_stt_shared::models::protocol::DaemonRequest,
+) -> Result<super_stt_shared::models::protocol::DaemonResponse> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket_path)
+        .await
+        .context("Failed to connect to daemon")?;
+
+    let request_data = serde_json::to_vec(request)?;
+    let request_size = request_data.len() as u64;
+
+    // Send size then data
+    stream.write_all(&request_size.to_be_bytes()).await?;
+    stream.write_all(&request_data).await?;
+
+    // Read response size
+    let mut size_bytes = [0u8; 8];
+    stream.read_exact(&mut size_bytes).await?;
+    let response_size = u64::from_be_bytes(size_bytes);
+
+    // Read response data
+    let response_len: usize = usize::try_from(response_size)
+        .context("Response size does not fit into memory on this platform")?;
+    let mut response_data = vec![0u8; response_len];
+    stream.read_exact(&mut response_data).await?;
+
+    Ok(serde_json::from_slice(&response_data)?)
+}
+
+/// Send a transcribe file request to an existing daemon and return the transcription
+async fn send_transcribe_file_request_to_daemon(
+    socket_path: &PathBuf,
+    path: &std::path::Path,
+) -> Result<String> {
+    use super_stt_shared::models::protocol::DaemonRequest;
+
+    let request = DaemonRequest {
+        client_id: Some("transcribe_client".to_string()),
+        data: Some(serde_json::json!({
+            "path": path.to_string_lossy()
+        })),
+        ..DaemonRequest::new("transcribe_file")
+    };
+
+    let response = send_request_to_daemon(socket_path, &request).await?;
+    if response.status == "success" {
+        Ok(response.transcription.unwrap_or_default())
+    } else {
+        let message = response.message.unwrap_or("Unknown error".to_string());
+        Err(anyhow::anyhow!("Daemon error: {message}"))
+    }
+}
+
+/// Ask the daemon to detect the language of an audio file, best candidate first
+async fn send_detect_language_request_to_daemon(
+    socket_path: &PathBuf,
+    path: &std::path::Path,
+) -> Result<Vec<super_stt_shared::models::protocol::LanguageCandidate>> {
+    use super_stt_shared::models::protocol::DaemonRequest;
+
+    let request = DaemonRequest {
+        client_id: Some("detect_language_client".to_string()),
+        data: Some(serde_json::json!({
+            "path": path.to_string_lossy()
+        })),
+        ..DaemonRequest::new("detect_language")
+    };
+
+    let response = send_request_to_daemon(socket_path, &request).await?;
+    if response.status == "success" {
+        Ok(response.language_candidates.unwrap_or_default())
+    } else {
+        let message = response.message.unwrap_or("Unknown error".to_string());
+        Err(anyhow::anyhow!("Daemon error: {message}"))
+    }
+}
+
 /// Send a status request to an existing daemon and display the response
 async fn send_status_request_to_daemon(socket_path: &PathBuf) -> Result<()> {
     use super_stt_shared::models::protocol::{DaemonRequest, DaemonResponse};
@@ -273,18 +787,8 @@ async fn send_status_request_to_daemon(socket_path: &PathBuf) -> Result<()> {
 
     // Send status request
     let request = DaemonRequest {
-        command: "status".to_string(),
-        audio_data: None,
-        sample_rate: None,
-        event_types: None,
-        client_info: None,
-        since_timestamp: None,
-        limit: None,
-        event_type: None,
         client_id: Some("status_client".to_string()),
-        data: None,
-        language: None,
-        enabled: None,
+        ..DaemonRequest::new("status")
     };
 
     let request_data = serde_json::to_vec(&request)?;
@@ -317,6 +821,17 @@ async fn send_status_request_to_daemon(socket_path: &PathBuf) -> Result<()> {
                 "  Device: {}",
                 response.device.unwrap_or("unknown".to_string())
             );
+            if let (Some(used), Some(total)) =
+                (response.gpu_memory_used_mb, response.gpu_memory_total_mb)
+            {
+                info!("  GPU memory: {used} / {total} MB");
+            }
+            if let Some(rss) = response.model_memory_mb {
+                info!("  Daemon memory: {rss} MB");
+            }
+            if let Some(rate) = response.input_sample_rate {
+                info!("  Input sample rate: {rate} Hz");
+            }
         }
         "error" => {
             let message = response.message.unwrap_or("Unknown error".to_string());
@@ -19,11 +19,16 @@ pub struct DownloadProgressTracker {
     pub total_bytes: AtomicU64,
     pub status: Arc<RwLock<String>>,
     pub started_at: Instant,
+    /// When the current file started, as byte counts are per file
+    file_started_at: RwLock<Instant>,
+    /// Files download side by side, so byte counts and speed cover all of them
+    parallel: AtomicBool,
     pub started_at_str: String,
     pub cancelled: Arc<AtomicBool>,
     pub progress_sender: Option<mpsc::UnboundedSender<DownloadProgress>>,
     pub notification_manager: Option<Arc<NotificationManager>>,
     last_broadcast_percentage: AtomicU64, // Store as fixed point (percentage * 100)
+    last_broadcast_status: RwLock<String>,
 }
 
 impl DownloadProgressTracker {
@@ -37,11 +42,14 @@ impl DownloadProgressTracker {
             total_bytes: AtomicU64::new(0),
             status: Arc::new(RwLock::new("downloading".to_string())),
             started_at: Instant::now(),
+            file_started_at: RwLock::new(Instant::now()),
+            parallel: AtomicBool::new(false),
             started_at_str: Utc::now().to_rfc3339(),
             cancelled,
             progress_sender: None,
             notification_manager: None,
             last_broadcast_percentage: AtomicU64::new(0),
+            last_broadcast_status: RwLock::new(String::new()),
         }
     }
 
@@ -57,7 +65,11 @@ impl DownloadProgressTracker {
         self
     }
 
-    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
     pub fn get_progress(&self) -> DownloadProgress {
         let bytes_downloaded = self.bytes_downloaded.load(Ordering::Relaxed);
         let total_bytes = self.total_bytes.load(Ordering::Relaxed);
@@ -83,15 +95,14 @@ impl DownloadProgressTracker {
             0.0
         };
 
-        let elapsed = self.started_at.elapsed().as_secs();
-        let eta_seconds = if bytes_downloaded > 0 && total_bytes > bytes_downloaded {
-            let remaining_bytes = total_bytes - bytes_downloaded;
-            let bytes_per_second = bytes_downloaded / elapsed.max(1);
-            if bytes_per_second > 0 {
-                Some(remaining_bytes / bytes_per_second)
-            } else {
-                None
-            }
+        let elapsed = self.file_started_at.read().elapsed().as_secs_f64();
+        let speed_bps = if bytes_downloaded > 0 && elapsed > 0.0 {
+            bytes_downloaded as f64 / elapsed
+        } else {
+            0.0
+        };
+        let eta_seconds = if speed_bps > 0.0 && total_bytes > bytes_downloaded {
+            Some(((total_bytes - bytes_downloaded) as f64 / speed_bps).ceil() as u64)
         } else {
             None
         };
@@ -107,6 +118,7 @@ impl DownloadProgressTracker {
             status: self.status.read().clone(),
             started_at: self.started_at_str.clone(),
             eta_seconds,
+            speed_bps,
         }
     }
 
@@ -114,15 +126,21 @@ impl DownloadProgressTracker {
     pub async fn broadcast_progress(&self) {
         let progress = self.get_progress();
 
-        // Only broadcast at 1% intervals to avoid flooding
+        // Only broadcast at 1% intervals to avoid flooding, or when the status changes
         // Clamp, round and convert to a fixed-point integer (percentage * 100)
         #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
         let current_percentage = (progress.percentage.clamp(0.0, 100.0) * 100.0).round() as u64;
         let last_percentage = self.last_broadcast_percentage.load(Ordering::Relaxed);
+        let status_changed = *self.last_broadcast_status.read() != progress.status;
 
-        if current_percentage > last_percentage && current_percentage - last_percentage >= 100 {
+        if status_changed
+            || (current_percentage > last_percentage && current_percentage - last_percentage >= 100)
+        {
             self.last_broadcast_percentage
                 .store(current_percentage, Ordering::Relaxed);
+            self.last_broadcast_status
+                .write()
+                .clone_from(&progress.status);
 
             if let Some(ref nm) = self.notification_manager {
                 let _ = nm
@@ -139,6 +157,7 @@ impl DownloadProgressTracker {
                             "percentage": progress.percentage,
                             "status": progress.status,
                             "eta_seconds": progress.eta_seconds,
+                            "speed_bps": progress.speed_bps,
                             "timestamp": Utc::now().to_rfc3339()
                         }),
                     )
@@ -152,9 +171,22 @@ impl DownloadProgressTracker {
         }
     }
 
+    /// Count bytes and completed files across every file rather than per file,
+    /// for downloads that fetch several files at once
+    pub fn set_parallel(&self, parallel: bool) {
+        self.parallel.store(parallel, Ordering::Relaxed);
+    }
+
     pub fn start_file(&self, filename: &str, file_index: usize) {
         *self.current_file.write() = filename.to_string();
+        if self.parallel.load(Ordering::Relaxed) {
+            info!("Downloading {filename} alongside other files");
+            return;
+        }
         self.file_index.store(file_index, Ordering::Relaxed);
+        self.bytes_downloaded.store(0, Ordering::Relaxed);
+        self.total_bytes.store(0, Ordering::Relaxed);
+        *self.file_started_at.write() = Instant::now();
         info!(
             "Downloading file {}/{}: {}",
             file_index + 1,
@@ -163,6 +195,24 @@ impl DownloadProgressTracker {
         );
     }
 
+    /// Note that the file at `file_index` is done, downloaded or found in the cache
+    pub fn finish_file(&self, file_index: usize) {
+        if self.parallel.load(Ordering::Relaxed) {
+            self.file_index.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.file_index.store(file_index + 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Take back bytes of a download that is being retried, so they are not counted twice
+    pub fn discard_bytes(&self, bytes: u64) {
+        for counter in [&self.bytes_downloaded, &self.total_bytes] {
+            let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count.saturating_sub(bytes))
+            });
+        }
+    }
+
     pub fn is_cancelled(&self) -> bool {
         self.cancelled.load(Ordering::Relaxed)
     }
@@ -173,6 +223,15 @@ impl DownloadProgressTracker {
         warn!("Download cancelled for model: {}", self.model_name);
     }
 
+    pub fn mark_verifying(&self) {
+        *self.status.write() = "verifying".to_string();
+        info!(
+            "Verifying checksum of {} for model: {}",
+            self.current_file.read(),
+            self.model_name
+        );
+    }
+
     pub fn mark_completed(&self) {
         *self.status.write() = "completed".to_string();
         info!("Download completed for model: {}", self.model_name);
@@ -2,12 +2,35 @@
 
 use anyhow::Result;
 use log::warn;
+use parking_lot::RwLock;
+use std::time::{Duration, Instant};
 
 use super_stt_shared::audio_utils::{
     ResampleQuality, apply_pre_emphasis, normalize_audio, resample,
 };
 
-pub struct AudioProcessor;
+use crate::input::agc::apply_agc;
+use crate::input::noise::{NoiseSuppressionConfig, suppress_noise};
+use crate::input::trim::{TrimSilenceConfig, trim_silence};
+
+/// Audio ready for the model, with what processing did to it
+#[derive(Debug, Clone)]
+pub struct ProcessedAudio {
+    /// Samples at 16kHz
+    pub samples: Vec<f32>,
+    /// Time spent resampling to 16kHz
+    pub resample_time: Duration,
+    /// Seconds of leading silence trimmed, by which timestamps into `samples` trail the input
+    pub trimmed_secs: f32,
+}
+
+pub struct AudioProcessor {
+    noise_suppression: RwLock<NoiseSuppressionConfig>,
+    /// RMS level the automatic gain control aims for, `None` when it is off
+    agc_target_rms: RwLock<Option<f32>>,
+    trim_silence: RwLock<TrimSilenceConfig>,
+    resample_quality: RwLock<ResampleQuality>,
+}
 
 impl Default for AudioProcessor {
     fn default() -> Self {
@@ -18,7 +41,56 @@ impl Default for AudioProcessor {
 impl AudioProcessor {
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self {
+            noise_suppression: RwLock::new(NoiseSuppressionConfig::default()),
+            agc_target_rms: RwLock::new(None),
+            trim_silence: RwLock::new(TrimSilenceConfig::default()),
+            resample_quality: RwLock::new(ResampleQuality::default()),
+        }
+    }
+
+    #[must_use]
+    pub fn with_noise_suppression(self, config: NoiseSuppressionConfig) -> Self {
+        self.set_noise_suppression(config);
+        self
+    }
+
+    #[must_use]
+    pub fn with_agc_target_rms(self, target_rms: Option<f32>) -> Self {
+        self.set_agc_target_rms(target_rms);
+        self
+    }
+
+    #[must_use]
+    pub fn with_trim_silence(self, config: TrimSilenceConfig) -> Self {
+        self.set_trim_silence(config);
+        self
+    }
+
+    #[must_use]
+    pub fn with_resample_quality(self, quality: ResampleQuality) -> Self {
+        self.set_resample_quality(quality);
+        self
+    }
+
+    /// Replace the noise suppression settings used by later calls to `process_audio`
+    pub fn set_noise_suppression(&self, config: NoiseSuppressionConfig) {
+        *self.noise_suppression.write() = config;
+    }
+
+    /// Turn automatic gain control on with the given target level, or off with `None`
+    pub fn set_agc_target_rms(&self, target_rms: Option<f32>) {
+        *self.agc_target_rms.write() = target_rms;
+    }
+
+    /// Replace the silence trimming settings used by later calls to `process_audio`
+    pub fn set_trim_silence(&self, config: TrimSilenceConfig) {
+        *self.trim_silence.write() = config;
+    }
+
+    /// Choose the filter later calls to `process_audio` resample to 16kHz with
+    pub fn set_resample_quality(&self, quality: ResampleQuality) {
+        *self.resample_quality.write() = quality;
     }
 
     /// Process raw audio data for Whisper model input
@@ -27,15 +99,47 @@ impl AudioProcessor {
     ///
     /// Returns an error if the audio data is invalid.
     pub fn process_audio(&self, audio_data: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+        self.process_audio_detailed(audio_data, sample_rate)
+            .map(|processed| processed.samples)
+    }
+
+    /// Process audio like [`Self::process_audio`], also reporting how long
+    /// resampling to 16kHz took and how much leading silence was trimmed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the audio data is invalid.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn process_audio_detailed(
+        &self,
+        audio_data: &[f32],
+        sample_rate: u32,
+    ) -> Result<ProcessedAudio> {
         // Ensure audio data is in the correct range (-1 to 1)
         let mut processed = audio_data.to_vec();
         normalize_audio(&mut processed);
 
+        // Filter noise at the capture rate, before resampling discards the detail it needs
+        let noise_suppression = *self.noise_suppression.read();
+        suppress_noise(&mut processed, sample_rate, &noise_suppression);
+
+        // Level quiet microphones after the noise is gone, so it is not boosted with them
+        if let Some(target_rms) = *self.agc_target_rms.read() {
+            apply_agc(&mut processed, sample_rate, target_rms);
+        }
+
+        // Cut the silence around the speech once the levels are settled, so less is resampled
+        let trim = *self.trim_silence.read();
+        let trimmed = trim_silence(&mut processed, sample_rate, &trim);
+
         // Resample to 16kHz if needed (Whisper expects 16kHz)
+        let resample_start = Instant::now();
         if sample_rate != 16000 {
             warn!("Audio sample rate is {sample_rate}Hz, resampling to 16kHz");
-            processed = resample(&processed, sample_rate, 16000, ResampleQuality::Fast)?;
+            let quality = *self.resample_quality.read();
+            processed = resample(&processed, sample_rate, 16000, quality)?;
         }
+        let resample_time = resample_start.elapsed();
 
         // Apply pre-emphasis filter (common in speech processing)
         apply_pre_emphasis(&mut processed);
@@ -47,7 +151,11 @@ impl AudioProcessor {
             processed.resize(1600, 0.0);
         }
 
-        Ok(processed)
+        Ok(ProcessedAudio {
+            samples: processed,
+            resample_time,
+            trimmed_secs: trimmed as f32 / sample_rate.max(1) as f32,
+        })
     }
 
     /// Convert audio data to WAV format for debugging/testing
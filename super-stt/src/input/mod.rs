@@ -1,2 +1,5 @@
 // SPDX-License-Identifier: GPL-3.0-only
+pub mod agc;
 pub mod audio;
+pub mod noise;
+pub mod trim;
@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Noise reduction for recorded audio: a high-pass filter against hum and rumble,
+//! and a spectral gate against steady background noise such as fans
+
+use rustfft::{FftPlanner, num_complex::Complex};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Spectral gate frame length, 32ms at 16kHz
+const FRAME_SIZE: usize = 512;
+/// Half-frame hop, which the square-root Hann window reconstructs exactly
+const HOP_SIZE: usize = FRAME_SIZE / 2;
+/// Share of the quietest frames averaged into the noise profile
+const NOISE_FRAME_SHARE: f32 = 0.1;
+/// Per-frame factor by which a gated bin closes, so word endings fade instead of chopping
+const GATE_RELEASE: f32 = 0.6;
+
+/// Noise reduction settings, applied before audio is resampled for the model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseSuppressionConfig {
+    /// Off leaves the audio untouched at no cost
+    pub enabled: bool,
+    /// Cutoff of the high-pass filter, 0 skips it
+    pub high_pass_hz: f32,
+    /// How far above the noise profile a frequency bin must be to pass the gate
+    pub gate_threshold_db: f32,
+    /// Attenuation of bins that fail the gate, 0 skips the gate
+    pub gate_reduction_db: f32,
+}
+
+impl Default for NoiseSuppressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            high_pass_hz: 80.0,
+            gate_threshold_db: 6.0,
+            gate_reduction_db: 12.0,
+        }
+    }
+}
+
+/// Apply the filters enabled in `config` to `samples` in place
+pub fn suppress_noise(samples: &mut [f32], sample_rate: u32, config: &NoiseSuppressionConfig) {
+    if !config.enabled {
+        return;
+    }
+    if config.high_pass_hz > 0.0 {
+        high_pass(samples, sample_rate, config.high_pass_hz);
+    }
+    if config.gate_reduction_db > 0.0 {
+        spectral_gate(samples, config.gate_threshold_db, config.gate_reduction_db);
+    }
+}
+
+/// Second-order Butterworth high-pass filter
+#[allow(clippy::cast_precision_loss)]
+fn high_pass(samples: &mut [f32], sample_rate: u32, cutoff_hz: f32) {
+    let sample_rate = sample_rate as f32;
+    if cutoff_hz >= sample_rate / 2.0 {
+        return;
+    }
+
+    // Biquad coefficients from the Audio EQ Cookbook with Q = 1/sqrt(2)
+    let omega = 2.0 * PI * cutoff_hz / sample_rate;
+    let alpha = omega.sin() / 2.0_f32.sqrt();
+    let cos_omega = omega.cos();
+    let a0 = 1.0 + alpha;
+    let b0 = f32::midpoint(1.0, cos_omega) / a0;
+    let b1 = -(1.0 + cos_omega) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos_omega / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    for sample in samples.iter_mut() {
+        let x0 = *sample;
+        let y0 = b0 * x0 + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        (x2, x1, y2, y1) = (x1, x0, y1, y0);
+        *sample = y0;
+    }
+}
+
+/// Attenuate frequency bins that stay close to the noise profile of the quietest frames
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn spectral_gate(samples: &mut [f32], threshold_db: f32, reduction_db: f32) {
+    if samples.len() < FRAME_SIZE {
+        return;
+    }
+
+    // Pad so every sample lies in two frames and overlap-add restores it exactly
+    let mut padded = vec![0.0; HOP_SIZE];
+    padded.extend_from_slice(samples);
+    padded.resize(padded.len().div_ceil(HOP_SIZE) * HOP_SIZE + HOP_SIZE, 0.0);
+    let frame_count = (padded.len() - FRAME_SIZE) / HOP_SIZE + 1;
+
+    let window: Vec<f32> = (0..FRAME_SIZE)
+        .map(|i| (PI * i as f32 / FRAME_SIZE as f32).sin())
+        .collect();
+    let mut planner = FftPlanner::new();
+    let forward = planner.plan_fft_forward(FRAME_SIZE);
+    let inverse = planner.plan_fft_inverse(FRAME_SIZE);
+    let spectrum = |frame: usize, buffer: &mut Vec<Complex<f32>>| {
+        let start = frame * HOP_SIZE;
+        buffer.clear();
+        buffer.extend(
+            padded[start..start + FRAME_SIZE]
+                .iter()
+                .zip(&window)
+                .map(|(sample, w)| Complex::new(sample * w, 0.0)),
+        );
+        forward.process(buffer);
+    };
+
+    // Noise profile from the quietest frames, skipping the half-padded first and last
+    let inner = if frame_count > 2 {
+        1..frame_count - 1
+    } else {
+        0..frame_count
+    };
+    let mut quietest: Vec<(usize, f32)> = inner
+        .map(|frame| {
+            let start = frame * HOP_SIZE;
+            let energy = padded[start..start + FRAME_SIZE]
+                .iter()
+                .map(|s| s * s)
+                .sum();
+            (frame, energy)
+        })
+        .collect();
+    quietest.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let noise_frames = ((quietest.len() as f32 * NOISE_FRAME_SHARE) as usize).max(1);
+
+    let bins = FRAME_SIZE / 2 + 1;
+    let mut noise_profile = vec![0.0; bins];
+    let mut buffer = Vec::with_capacity(FRAME_SIZE);
+    for &(frame, _) in &quietest[..noise_frames] {
+        spectrum(frame, &mut buffer);
+        for (noise, bin) in noise_profile.iter_mut().zip(&buffer) {
+            *noise += bin.norm() / noise_frames as f32;
+        }
+    }
+
+    let threshold = 10.0_f32.powf(threshold_db / 20.0);
+    let floor = 10.0_f32.powf(-reduction_db / 20.0);
+    let mut gains = vec![1.0_f32; bins];
+    let mut output = vec![0.0; padded.len()];
+    for frame in 0..frame_count {
+        spectrum(frame, &mut buffer);
+        for (bin, gain) in gains.iter_mut().enumerate() {
+            let target = if buffer[bin].norm() > noise_profile[bin] * threshold {
+                1.0
+            } else {
+                floor
+            };
+            *gain = target.max(*gain * GATE_RELEASE);
+        }
+        // Mirror the gains onto the negative frequencies to keep the signal real
+        for (bin, value) in buffer.iter_mut().enumerate() {
+            *value *= gains[bin.min(FRAME_SIZE - bin)];
+        }
+        inverse.process(&mut buffer);
+
+        let start = frame * HOP_SIZE;
+        for (i, value) in buffer.iter().enumerate() {
+            output[start + i] += value.re * window[i] / FRAME_SIZE as f32;
+        }
+    }
+
+    samples.copy_from_slice(&output[HOP_SIZE..HOP_SIZE + samples.len()]);
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> NoiseSuppressionConfig {
+        NoiseSuppressionConfig {
+            enabled: true,
+            ..NoiseSuppressionConfig::default()
+        }
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_disabled_is_bypassed() {
+        let original: Vec<f32> = (0..4000).map(|i| (i as f32 * 0.01).sin() + 0.5).collect();
+        let mut samples = original.clone();
+        suppress_noise(&mut samples, 16000, &NoiseSuppressionConfig::default());
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_high_pass_removes_offset_and_gate_keeps_speech() {
+        // A DC offset under a 1kHz tone
+        let mut samples: Vec<f32> = (0..16000)
+            .map(|i| 0.3 + 0.5 * (2.0 * PI * 1000.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let config = NoiseSuppressionConfig {
+            gate_reduction_db: 0.0,
+            ..enabled()
+        };
+        suppress_noise(&mut samples, 16000, &config);
+        let mean = samples[8000..].iter().sum::<f32>() / 8000.0;
+        assert!(mean.abs() < 0.01, "offset left: {mean}");
+
+        // Quiet hiss for the first half, a loud tone on top of it for the second
+        let mut state = 1u32;
+        let mut samples: Vec<f32> = (0..32000)
+            .map(|i| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = 0.02 * (state as f32 / u32::MAX as f32 - 0.5);
+                let tone = if i >= 16000 {
+                    0.5 * (2.0 * PI * 440.0 * i as f32 / 16000.0).sin()
+                } else {
+                    0.0
+                };
+                noise + tone
+            })
+            .collect();
+        let noise_before = rms(&samples[2000..14000]);
+        let tone_before = rms(&samples[18000..30000]);
+        suppress_noise(&mut samples, 16000, &enabled());
+        assert!(rms(&samples[2000..14000]) < noise_before * 0.5);
+        assert!(rms(&samples[18000..30000]) > tone_before * 0.9);
+    }
+}
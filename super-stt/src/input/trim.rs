@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Trimming of the silence before and after speech, so the model does not spend
+//! time on it or fill it with made-up punctuation
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+/// Length of the blocks whose RMS is compared against the floor
+const BLOCK_MS: u32 = 10;
+
+/// Silence trimming settings, applied before audio is resampled for the model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrimSilenceConfig {
+    /// Off leaves the audio untouched at no cost
+    pub enabled: bool,
+    /// Blocks quieter than this RMS level count as silence
+    pub floor_rms: f32,
+    /// Silence kept on either side of the speech, so word ends are not clipped
+    pub padding_ms: u32,
+}
+
+impl Default for TrimSilenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            floor_rms: 0.01,
+            padding_ms: 200,
+        }
+    }
+}
+
+/// Drop the silence before the first and after the last block above the floor
+///
+/// Audio without any block above the floor is left as is, so quiet speech is
+/// never thrown away entirely. Returns how many samples were dropped from the
+/// front, by which positions in the trimmed audio trail the original.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+pub fn trim_silence(samples: &mut Vec<f32>, sample_rate: u32, config: &TrimSilenceConfig) -> usize {
+    if !config.enabled {
+        return 0;
+    }
+
+    let block_len = (sample_rate * BLOCK_MS / 1000).max(1) as usize;
+    let is_loud = |block: &[f32]| {
+        let rms = (block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32).sqrt();
+        rms >= config.floor_rms
+    };
+    let Some(first) = samples.chunks(block_len).position(is_loud) else {
+        return 0;
+    };
+    let last = samples
+        .chunks(block_len)
+        .rposition(is_loud)
+        .unwrap_or(first);
+
+    let padding = (u64::from(sample_rate) * u64::from(config.padding_ms) / 1000) as usize;
+    let start = (first * block_len).saturating_sub(padding);
+    let end = ((last + 1) * block_len)
+        .saturating_add(padding)
+        .min(samples.len());
+    if start == 0 && end == samples.len() {
+        return 0;
+    }
+
+    debug!(
+        "Trimmed {} leading and {} trailing silent samples",
+        start,
+        samples.len() - end
+    );
+    samples.truncate(end);
+    samples.drain(..start);
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_keeps_padding_around_speech() {
+        // One second of silence, half a second of tone, one second of silence
+        let mut samples = vec![0.0; 16000];
+        samples.extend((0..8000).map(|i| if i % 2 == 0 { 0.5 } else { -0.5 }));
+        samples.extend(vec![0.0; 16000]);
+
+        let dropped = trim_silence(&mut samples, 16000, &TrimSilenceConfig::default());
+
+        // 200ms of padding on each side of the tone
+        assert_eq!(dropped, 16000 - 3200);
+        assert_eq!(samples.len(), 3200 + 8000 + 3200);
+        assert!(samples[..3200].iter().all(|s| s.abs() < f32::EPSILON));
+        assert!((samples[3200] - 0.5).abs() < f32::EPSILON);
+        assert!((samples[11199] + 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_silent_or_disabled_is_bypassed() {
+        let original = vec![0.001; 16000];
+        let mut samples = original.clone();
+        assert_eq!(
+            trim_silence(&mut samples, 16000, &TrimSilenceConfig::default()),
+            0
+        );
+        assert_eq!(samples, original);
+
+        let mut samples = vec![0.0; 16000];
+        samples.push(0.9);
+        let disabled = TrimSilenceConfig {
+            enabled: false,
+            ..TrimSilenceConfig::default()
+        };
+        trim_silence(&mut samples, 16000, &disabled);
+        assert_eq!(samples.len(), 16001);
+    }
+}
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 pub mod audio;
+pub mod bench;
 pub mod cli;
 pub mod config;
 pub mod daemon;
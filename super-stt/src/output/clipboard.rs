@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! System clipboard access through `wl-clipboard` on Wayland and `xclip` on X11
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Whether the session runs on Wayland rather than X11
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// Clipboard contents saved by [`read`], with the type they were offered as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contents {
+    pub mime_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// X11 targets that describe the selection rather than hold its contents
+const X11_META_TARGETS: [&str; 4] = ["TARGETS", "TIMESTAMP", "MULTIPLE", "SAVE_TARGETS"];
+
+/// Types the clipboard is currently offered as, most preferred first
+fn offered_types() -> Vec<String> {
+    let mut command = if is_wayland() {
+        let mut command = Command::new("wl-paste");
+        command.arg("--list-types");
+        command
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-t", "TARGETS", "-o"]);
+        command
+    };
+
+    let Ok(output) = command.stderr(Stdio::null()).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|mime_type| !mime_type.is_empty() && !X11_META_TARGETS.contains(mime_type))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Current clipboard contents in their preferred type, or `None` when the clipboard
+/// is empty or unreadable
+#[must_use]
+pub fn read() -> Option<Contents> {
+    let mime_type = offered_types().into_iter().next();
+    let mut command = if is_wayland() {
+        let mut command = Command::new("wl-paste");
+        command.arg("--no-newline");
+        if let Some(mime_type) = &mime_type {
+            command.args(["--type", mime_type]);
+        }
+        command
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-o"]);
+        if let Some(mime_type) = &mime_type {
+            command.args(["-t", mime_type]);
+        }
+        command
+    };
+
+    let output = command.stderr(Stdio::null()).output().ok()?;
+    output.status.success().then_some(Contents {
+        mime_type,
+        data: output.stdout,
+    })
+}
+
+/// Replace the clipboard contents with text
+///
+/// # Errors
+///
+/// Returns an error if the clipboard tool is missing or fails.
+pub fn write(contents: &[u8]) -> Result<()> {
+    write_as(contents, None)
+}
+
+/// Replace the clipboard contents, offering them as `mime_type` when given
+fn write_as(contents: &[u8], mime_type: Option<&str>) -> Result<()> {
+    let (program, mut args): (&str, Vec<&str>) = if is_wayland() {
+        ("wl-copy", Vec::new())
+    } else {
+        ("xclip", vec!["-selection", "clipboard", "-i"])
+    };
+    if let Some(mime_type) = mime_type {
+        args.extend([if is_wayland() { "--type" } else { "-t" }, mime_type]);
+    }
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+    child
+        .stdin
+        .take()
+        .with_context(|| format!("{program} did not open stdin"))?
+        .write_all(contents)
+        .with_context(|| format!("Failed to write to {program}"))?;
+
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to wait for {program}"))?;
+    if !status.success() {
+        anyhow::bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+/// Put back contents saved with `read` in their original type, emptying the
+/// clipboard if there were none
+///
+/// # Errors
+///
+/// Returns an error if the clipboard tool is missing or fails.
+pub fn restore(saved: Option<&Contents>) -> Result<()> {
+    match saved {
+        Some(contents) => write_as(&contents.data, contents.mime_type.as_deref()),
+        None if is_wayland() => {
+            let status = Command::new("wl-copy")
+                .arg("--clear")
+                .status()
+                .context("Failed to run wl-copy")?;
+            if !status.success() {
+                anyhow::bail!("wl-copy exited with {status}");
+            }
+            Ok(())
+        }
+        None => write(&[]),
+    }
+}
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Spoken editing commands recognized while dictating
+//!
+//! Phrases like "period" or "new line" are turned into the keystrokes they stand
+//! for instead of being typed literally.
+
+/// Keyboard action a spoken command stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Punctuation attached to the preceding word
+    Punctuation(char),
+    /// Press Enter once
+    NewLine,
+    /// Press Enter twice
+    NewParagraph,
+    /// Backspace the last committed word
+    DeleteLastWord,
+}
+
+impl Action {
+    /// Whether the next word starts a new sentence
+    #[must_use]
+    pub fn ends_sentence(self) -> bool {
+        matches!(
+            self,
+            Action::Punctuation('.' | '?' | '!') | Action::NewLine | Action::NewParagraph
+        )
+    }
+}
+
+/// A piece of dictated text: literal words or a recognized command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Text(String),
+    Command(Action),
+}
+
+/// Spoken phrases and the actions they map to
+const PHRASES: &[(&str, Action)] = &[
+    ("period", Action::Punctuation('.')),
+    ("full stop", Action::Punctuation('.')),
+    ("comma", Action::Punctuation(',')),
+    ("question mark", Action::Punctuation('?')),
+    ("exclamation mark", Action::Punctuation('!')),
+    ("exclamation point", Action::Punctuation('!')),
+    ("colon", Action::Punctuation(':')),
+    ("semicolon", Action::Punctuation(';')),
+    ("new line", Action::NewLine),
+    ("new paragraph", Action::NewParagraph),
+    ("delete that", Action::DeleteLastWord),
+];
+
+/// Normalize a transcribed word for matching, dropping case and the punctuation
+/// models tend to attach to spoken commands ("Period." or ", comma,")
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| c.is_ascii_punctuation())
+        .to_lowercase()
+}
+
+/// Find the command whose phrase starts at `words[start..]`, preferring the longest phrase
+fn match_phrase(words: &[String], start: usize) -> Option<(Action, usize)> {
+    PHRASES
+        .iter()
+        .filter_map(|(phrase, action)| {
+            let phrase_words: Vec<&str> = phrase.split(' ').collect();
+            let candidate = words.get(start..start + phrase_words.len())?;
+            candidate
+                .iter()
+                .zip(&phrase_words)
+                .all(|(word, expected)| word == expected)
+                .then_some((*action, phrase_words.len()))
+        })
+        .max_by_key(|(_, len)| *len)
+}
+
+/// Split dictated text into literal text and commands, in spoken order
+#[must_use]
+pub fn parse(text: &str) -> Vec<Token> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+
+    let mut tokens = Vec::new();
+    let mut literal: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        if let Some((action, len)) = match_phrase(&normalized, i) {
+            // The spoken mark replaces any the model already put there
+            if let Action::Punctuation(_) = action
+                && let Some(last) = literal.last_mut()
+            {
+                *last = last.trim_end_matches(|c: char| c.is_ascii_punctuation());
+            }
+            if !literal.is_empty() {
+                tokens.push(Token::Text(literal.join(" ")));
+                literal.clear();
+            }
+            tokens.push(Token::Command(action));
+            i += len;
+        } else {
+            literal.push(words[i]);
+            i += 1;
+        }
+    }
+    if !literal.is_empty() {
+        tokens.push(Token::Text(literal.join(" ")));
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Token {
+        Token::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_parse_maps_phrases_to_actions() {
+        assert_eq!(
+            parse("hello comma world period"),
+            vec![
+                text("hello"),
+                Token::Command(Action::Punctuation(',')),
+                text("world"),
+                Token::Command(Action::Punctuation('.')),
+            ]
+        );
+        assert_eq!(
+            parse("first line new line second new paragraph"),
+            vec![
+                text("first line"),
+                Token::Command(Action::NewLine),
+                text("second"),
+                Token::Command(Action::NewParagraph),
+            ]
+        );
+        assert_eq!(
+            parse("is it working question mark"),
+            vec![
+                text("is it working"),
+                Token::Command(Action::Punctuation('?')),
+            ]
+        );
+        assert_eq!(
+            parse("oops delete that"),
+            vec![text("oops"), Token::Command(Action::DeleteLastWord)]
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_model_punctuation_and_case() {
+        assert_eq!(
+            parse("Hello, comma, world. Period."),
+            vec![
+                text("Hello"),
+                Token::Command(Action::Punctuation(',')),
+                text("world"),
+                Token::Command(Action::Punctuation('.')),
+            ]
+        );
+        assert_eq!(parse("New Line"), vec![Token::Command(Action::NewLine)]);
+    }
+
+    #[test]
+    fn test_parse_leaves_plain_text_alone() {
+        assert_eq!(parse("a new idea"), vec![text("a new idea")]);
+        assert_eq!(parse("delete the file"), vec![text("delete the file")]);
+        assert_eq!(parse(""), Vec::<Token>::new());
+    }
+}
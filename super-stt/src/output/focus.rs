@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Which application has keyboard focus, so text is only typed where it is wanted
+//!
+//! X11 sessions are asked through `xprop`. Wayland has no common protocol for it,
+//! so only compositors with a query tool (Sway and Hyprland) are supported; on the
+//! others the check is skipped.
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::{Command, Stdio};
+
+/// Applications typed into, matched against the focused window's app id or `WM_CLASS`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusFilterConfig {
+    /// When not empty, typing only goes to these applications
+    pub allow: Vec<String>,
+    /// Applications never typed into, e.g. a password manager
+    pub block: Vec<String>,
+}
+
+impl FocusFilterConfig {
+    /// Whether either list is set
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.allow.is_empty() || !self.block.is_empty()
+    }
+
+    /// Whether an application known by any of `names` may be typed into
+    #[must_use]
+    pub fn allows(&self, names: &[String]) -> bool {
+        let listed = |list: &[String]| {
+            list.iter()
+                .any(|entry| names.iter().any(|name| name.eq_ignore_ascii_case(entry)))
+        };
+        !listed(&self.block) && (self.allow.is_empty() || listed(&self.allow))
+    }
+
+    /// Whether the focused application may be typed into
+    ///
+    /// Always true when no list is set, and when the focused application cannot
+    /// be determined, which is logged.
+    #[must_use]
+    pub fn allows_focused(&self) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        let Some(names) = focused_app() else {
+            warn!("Cannot tell which window has focus here, typing without the focus check");
+            return true;
+        };
+        let allowed = self.allows(&names);
+        debug!("Focused window {names:?} is allowed: {allowed}");
+        allowed
+    }
+}
+
+/// Names of the focused application, app id or `WM_CLASS` instance and class,
+/// `None` when the session gives no way to find them
+fn focused_app() -> Option<Vec<String>> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return x11_focused_app();
+    }
+    if std::env::var_os("SWAYSOCK").is_some() {
+        let tree: Value = serde_json::from_slice(&run("swaymsg", &["-t", "get_tree"])?).ok()?;
+        return sway_focused_app(&tree);
+    }
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let window: Value =
+            serde_json::from_slice(&run("hyprctl", &["activewindow", "-j"])?).ok()?;
+        let names: Vec<String> = ["class", "initialClass"]
+            .iter()
+            .filter_map(|key| window.get(key)?.as_str())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        return (!names.is_empty()).then_some(names);
+    }
+    None
+}
+
+/// `WM_CLASS` of the active window, through `xprop`
+fn x11_focused_app() -> Option<Vec<String>> {
+    let active = String::from_utf8(run("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?).ok()?;
+    // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+    let window = active.split_whitespace().last()?;
+    let class = String::from_utf8(run("xprop", &["-id", window, "WM_CLASS"])?).ok()?;
+    parse_wm_class(&class)
+}
+
+/// The quoted names in `WM_CLASS(STRING) = "Navigator", "firefox"`
+fn parse_wm_class(output: &str) -> Option<Vec<String>> {
+    let (_, values) = output.split_once('=')?;
+    let names: Vec<String> = values
+        .split(',')
+        .map(|name| name.trim().trim_matches('"').to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// App id, or X11 class for windows under Xwayland, of the focused node in a Sway tree
+fn sway_focused_app(node: &Value) -> Option<Vec<String>> {
+    if node.get("focused").and_then(Value::as_bool) == Some(true) {
+        let names: Vec<String> = [
+            node.get("app_id"),
+            node.pointer("/window_properties/instance"),
+            node.pointer("/window_properties/class"),
+        ]
+        .into_iter()
+        .filter_map(|name| Some(name?.as_str()?.to_string()))
+        .collect();
+        return (!names.is_empty()).then_some(names);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(key)?.as_array())
+        .flatten()
+        .find_map(sway_focused_app)
+}
+
+/// Standard output of a successful run of `program`
+fn run(program: &str, args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new(program)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_allow_and_block_lists() {
+        let filter = FocusFilterConfig {
+            allow: Vec::new(),
+            block: names(&["KeePassXC"]),
+        };
+        assert!(!filter.allows(&names(&["keepassxc"])));
+        assert!(filter.allows(&names(&["Navigator", "firefox"])));
+
+        let filter = FocusFilterConfig {
+            allow: names(&["firefox", "code"]),
+            block: Vec::new(),
+        };
+        assert!(filter.allows(&names(&["Navigator", "firefox"])));
+        assert!(!filter.allows(&names(&["org.gnome.Terminal"])));
+    }
+
+    #[test]
+    fn test_focused_app_parsing() {
+        assert_eq!(
+            parse_wm_class("WM_CLASS(STRING) = \"Navigator\", \"firefox\"\n"),
+            Some(names(&["Navigator", "firefox"]))
+        );
+        assert_eq!(parse_wm_class("WM_CLASS:  not found.\n"), None);
+
+        let tree = serde_json::json!({
+            "focused": false,
+            "nodes": [{
+                "focused": false,
+                "nodes": [],
+                "floating_nodes": [{ "focused": true, "app_id": "org.keepassxc.KeePassXC" }]
+            }]
+        });
+        assert_eq!(
+            sway_focused_app(&tree),
+            Some(names(&["org.keepassxc.KeePassXC"]))
+        );
+    }
+}
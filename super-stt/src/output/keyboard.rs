@@ -1,12 +1,32 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use anyhow::Result;
+use crate::output::clipboard;
+use anyhow::{Context, Result};
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+// How long the focused app gets to read the clipboard before it is restored
+const CLIPBOARD_RESTORE_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// How text is sent to the focused window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypingBackend {
+    /// Type through enigo, which assumes a US-like layout for some characters
+    #[default]
+    Enigo,
+    /// Type through `xdotool`, which maps keysyms onto the active XKB layout
+    Xdotool,
+    /// Paste through the clipboard and Ctrl+V, for reliable Unicode on Wayland
+    ClipboardPaste,
+}
 
 /// Keyboard simulation utilities for text input
 pub struct Simulator {
     typing_chunk: usize,
     backspace_batch_size: usize,
+    backend: TypingBackend,
     enigo: Enigo,
 }
 
@@ -19,6 +39,7 @@ impl Default for Simulator {
         Self {
             typing_chunk: 64,
             backspace_batch_size: 20,
+            backend: TypingBackend::default(),
             enigo,
         }
     }
@@ -27,13 +48,31 @@ impl Default for Simulator {
 impl Simulator {
     // SPDX-License-Identifier: GPL-3.0-only
 
-    /// Type text using keyboard simulation
+    /// Choose how `type_text` sends text; keys like Backspace always go through enigo
+    pub fn set_backend(&mut self, backend: TypingBackend) {
+        self.backend = backend;
+    }
+
+    /// Type text using the configured backend
     ///
     /// # Errors
     ///
     /// Returns an error if keyboard input cannot be simulated or
     /// if the typing task fails to execute.
     pub fn type_text(&mut self, text: &str) -> Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        match self.backend {
+            TypingBackend::Enigo => self.type_with_enigo(text),
+            TypingBackend::Xdotool => type_with_xdotool(text),
+            TypingBackend::ClipboardPaste => self.paste_from_clipboard(text),
+        }
+    }
+
+    /// Type text through enigo
+    fn type_with_enigo(&mut self, text: &str) -> Result<()> {
         // Type in modest chunks to improve reliability
         let mut i = 0;
         let chars: Vec<char> = text.chars().collect();
@@ -50,6 +89,51 @@ impl Simulator {
         Ok(())
     }
 
+    /// Paste text with Ctrl+V, keeping whatever the user had on the clipboard
+    ///
+    /// Terminals that paste with Ctrl+Shift+V will not receive the text.
+    fn paste_from_clipboard(&mut self, text: &str) -> Result<()> {
+        let text = text.to_string();
+        let saved = off_async_worker(move || -> Result<Option<clipboard::Contents>> {
+            let saved = clipboard::read();
+            clipboard::write(text.as_bytes())?;
+            Ok(saved)
+        })??;
+
+        let pasted = self.press_paste();
+
+        // Give the focused app time to read the clipboard before putting the old contents back
+        let restored = off_async_worker(move || {
+            std::thread::sleep(CLIPBOARD_RESTORE_DELAY);
+            clipboard::restore(saved.as_ref())
+        })
+        .and_then(|restored| restored);
+
+        pasted?;
+        restored.context("Failed to restore clipboard contents")
+    }
+
+    /// Press Ctrl+V, always releasing Control again
+    fn press_paste(&mut self) -> Result<()> {
+        self.enigo
+            .key(Key::Control, Direction::Press)
+            .map_err(|e| anyhow::anyhow!("Failed to press Control: {}", e))?;
+        let pasted = self.enigo.key(Key::Unicode('v'), Direction::Click);
+        let _ = self.enigo.key(Key::Control, Direction::Release);
+        pasted.map_err(|e| anyhow::anyhow!("Failed to press Ctrl+V: {}", e))
+    }
+
+    /// Press Enter to start a new line
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key press cannot be simulated.
+    pub fn press_enter(&mut self) -> Result<()> {
+        self.enigo
+            .key(Key::Return, Direction::Click)
+            .map_err(|e| anyhow::anyhow!("Failed to press Enter: {}", e))
+    }
+
     /// Backspace a given number of characters
     ///
     /// # Errors
@@ -69,3 +153,27 @@ impl Simulator {
         Ok(())
     }
 }
+
+/// Run blocking `job` on tokio's blocking pool and wait for it, when called from the
+/// daemon's runtime, so clipboard tools and delays don't stall an async worker
+fn off_async_worker<T: Send + 'static>(job: impl FnOnce() -> T + Send + 'static) -> Result<T> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(tokio::task::spawn_blocking(job)))
+                .context("Clipboard task failed")
+        }
+        _ => Ok(job()),
+    }
+}
+
+/// Type text through `xdotool`, which resolves each character on the active layout
+fn type_with_xdotool(text: &str) -> Result<()> {
+    let status = Command::new("xdotool")
+        .args(["type", "--clearmodifiers", "--delay", "5", "--", text])
+        .status()
+        .context("Failed to run xdotool")?;
+    if !status.success() {
+        anyhow::bail!("xdotool exited with {status}");
+    }
+    Ok(())
+}
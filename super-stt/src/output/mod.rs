@@ -1,4 +1,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+pub mod clipboard;
+pub mod commands;
+pub mod focus;
 pub mod keyboard;
 pub mod preview;
+pub mod subtitle;
@@ -1,7 +1,129 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::output::keyboard::Simulator;
+use crate::output::clipboard;
+use crate::output::commands::{self, Action, Token};
+use crate::output::focus::FocusFilterConfig;
+use crate::output::keyboard::{Simulator, TypingBackend};
 use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use super_stt_shared::models::protocol::OutputMode;
+
+// How many committed units "delete that" can reach back through
+const MAX_COMMITTED_UNITS: usize = 256;
+
+// How many preview updates can be undone
+const MAX_PREVIEW_HISTORY: usize = 32;
+
+/// How many preview transcriptions are kept for stabilization, and so the
+/// longest commit delay
+pub const MAX_COMMIT_PASSES: usize = 10;
+
+/// A correction requested for the preview text on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewEdit {
+    /// Restore the text the most recent preview update replaced
+    Undo,
+    /// Reapply the most recently undone preview update
+    Redo,
+}
+
+/// Touch-ups applied to transcribed text before it is typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct FormattingConfig {
+    /// Uppercase the first letter, and each sentence start with voice commands
+    pub capitalize_first: bool,
+    /// End final text that stops on a letter or digit with a period
+    pub add_final_punctuation: bool,
+    /// Turn runs of spaces and line breaks into single spaces
+    pub collapse_whitespace: bool,
+    /// Type a space after final text, ready for the next utterance
+    pub trailing_space: bool,
+}
+
+impl Default for FormattingConfig {
+    fn default() -> Self {
+        Self {
+            capitalize_first: true,
+            add_final_punctuation: true,
+            collapse_whitespace: true,
+            trailing_space: true,
+        }
+    }
+}
+
+/// A unit of committed final text, remembered so "delete that" can remove it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Typed {
+    /// A word and its leading space, as a character count
+    Word(usize),
+    /// Punctuation attached to the preceding word
+    Punctuation(char),
+    /// The space typed after a finished utterance
+    Space,
+    /// A line break, which "delete that" never reaches across
+    LineBreak,
+}
+
+impl Typed {
+    fn char_count(self) -> usize {
+        match self {
+            Typed::Word(count) => count,
+            Typed::Punctuation(_) | Typed::Space | Typed::LineBreak => 1,
+        }
+    }
+}
+
+/// Preview updates as `(old_text, new_text)` that can be undone and redone
+#[derive(Debug, Default)]
+struct PreviewHistory {
+    /// Applied updates, oldest first
+    undo: VecDeque<(String, String)>,
+    /// Undone updates, most recent last
+    redo: Vec<(String, String)>,
+}
+
+impl PreviewHistory {
+    /// Remember an applied update; a new update drops the redo history
+    fn record(&mut self, old_text: &str, new_text: &str) {
+        if old_text == new_text {
+            return;
+        }
+        if self.undo.len() == MAX_PREVIEW_HISTORY {
+            self.undo.pop_front();
+        }
+        self.undo
+            .push_back((old_text.to_string(), new_text.to_string()));
+        self.redo.clear();
+    }
+
+    /// Move the most recent update to the redo history, returning it
+    fn undo(&mut self) -> Option<(String, String)> {
+        let update = self.undo.pop_back()?;
+        self.redo.push(update.clone());
+        Some(update)
+    }
+
+    /// Move the most recently undone update back, returning it
+    fn redo(&mut self) -> Option<(String, String)> {
+        let update = self.redo.pop()?;
+        self.undo.push_back(update.clone());
+        Some(update)
+    }
+
+    /// Whether `text` is what an undone update typed, so showing it again would
+    /// reverse the undo; this holds until a new update is recorded
+    fn is_undone(&self, text: &str) -> bool {
+        self.redo.iter().any(|(_, new_text)| new_text == text)
+    }
+
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
 
 /// State for tracking preview updates
 pub struct State {
@@ -15,6 +137,8 @@ pub struct State {
     pub text_storage: Vec<String>,
     /// Text confirmed by appearing in multiple transcriptions
     pub stabilized_text: String,
+    /// Final text typed with voice commands enabled, oldest first
+    pub committed: Vec<Typed>,
 }
 
 impl Default for State {
@@ -26,6 +150,7 @@ impl Default for State {
             last_growth_time: std::time::Instant::now(),
             text_storage: Vec::new(),
             stabilized_text: String::new(),
+            committed: Vec::new(),
         }
     }
 }
@@ -35,12 +160,68 @@ impl Default for State {
 pub struct Typer {
     keyboard_simulator: Simulator,
     state: State,
+    voice_commands: bool,
+    output: OutputMode,
+    /// How the last final text was actually delivered
+    final_output: Option<OutputMode>,
+    /// Preview updates that can be undone and redone
+    preview_history: PreviewHistory,
+    /// Preview passes a word must survive unchanged before it is typed, 0 or 1 types it right away
+    commit_passes: usize,
+    formatting: FormattingConfig,
+    focus_filter: FocusFilterConfig,
 }
 
 impl Typer {
+    /// Carry out spoken commands like "period" or "new line" in final text
+    /// instead of typing them; preview text is always typed literally
+    pub fn set_voice_commands(&mut self, enabled: bool) {
+        self.voice_commands = enabled;
+    }
+
+    /// Hold back preview words until `passes` consecutive previews agree on
+    /// them, trading a little latency for less backspacing and retyping
+    pub fn set_commit_passes(&mut self, passes: usize) {
+        self.commit_passes = passes.min(MAX_COMMIT_PASSES);
+    }
+
+    /// Choose the capitalization, period and trailing space added to text
+    pub fn set_formatting(&mut self, formatting: FormattingConfig) {
+        self.formatting = formatting;
+    }
+
+    /// Limit which focused applications text is typed into
+    pub fn set_focus_filter(&mut self, focus_filter: FocusFilterConfig) {
+        self.focus_filter = focus_filter;
+    }
+
+    /// Choose whether final text is typed, copied to the clipboard or both
+    pub fn set_output(&mut self, output: OutputMode) {
+        self.output = output;
+    }
+
+    /// Where final text is delivered
+    #[must_use]
+    pub fn output(&self) -> OutputMode {
+        self.output
+    }
+
+    /// How the last final text was delivered, which differs from `output` when
+    /// the clipboard was unavailable and the text was typed instead, or the focused
+    /// window was filtered out; `None` when it was neither typed nor copied
+    #[must_use]
+    pub fn final_output(&self) -> Option<OutputMode> {
+        self.final_output
+    }
+
+    /// Choose how text is sent to the focused window
+    pub fn set_typing_backend(&mut self, backend: TypingBackend) {
+        self.keyboard_simulator.set_backend(backend);
+    }
+
     /// Preprocess text - normalize, remove ellipses, capitalize
     #[must_use]
-    pub fn preprocess_text(text: &str, is_preview: bool) -> String {
+    pub fn preprocess_text(text: &str, is_preview: bool, formatting: &FormattingConfig) -> String {
         // Remove leading whitespaces
         let mut text = text.trim_start().to_string();
 
@@ -53,21 +234,30 @@ impl Typer {
         text = text.trim_start().to_string();
 
         // Normalize whitespace
-        text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if formatting.collapse_whitespace {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        } else {
+            text.truncate(text.trim_end().len());
+        }
 
         if text.is_empty() {
             return text;
         }
 
         // Uppercase the first letter
-        let mut chars: Vec<char> = text.chars().collect();
-        if let Some(first_char) = chars.first_mut() {
-            *first_char = first_char.to_ascii_uppercase();
+        if formatting.capitalize_first {
+            let mut chars: Vec<char> = text.chars().collect();
+            if let Some(first_char) = chars.first_mut() {
+                *first_char = first_char.to_ascii_uppercase();
+            }
+            text = chars.iter().collect();
         }
-        text = chars.iter().collect();
 
         // Add period for final output if it ends with alphanumeric
-        if !is_preview && text.chars().last().is_some_and(char::is_alphanumeric) {
+        if !is_preview
+            && formatting.add_final_punctuation
+            && text.chars().last().is_some_and(char::is_alphanumeric)
+        {
             text.push('.');
         }
 
@@ -141,7 +331,7 @@ impl Typer {
 
     /// Update preview text using two-phase approach
     pub fn update_preview(&mut self, new_text: &str, actually_typed: &mut String) {
-        let processed_text = Self::preprocess_text(new_text, true);
+        let processed_text = Self::preprocess_text(new_text, true, &self.formatting);
 
         info!(
             "Preview update: new='{}', prev='{}', typed='{}'",
@@ -166,7 +356,21 @@ impl Typer {
         self.update_with_stabilization(&processed_text);
 
         // PHASE 2: Decide what to show on screen
-        let display_text = self.build_display_text(&processed_text);
+        let mut display_text = self.build_display_text(&processed_text);
+        if self.commit_passes > 1 {
+            let recent = self
+                .state
+                .text_storage
+                .len()
+                .checked_sub(self.commit_passes)
+                .map_or(&[][..], |start| &self.state.text_storage[start..]);
+            display_text = Self::stable_words(&display_text, recent);
+            if display_text.is_empty() || display_text == *actually_typed {
+                debug!("No newly stable words, keeping the screen as is");
+                self.state.prev_text = processed_text;
+                return;
+            }
+        }
 
         info!(
             "Display logic: display='{}', session='{}', stabilized='{}'",
@@ -183,8 +387,20 @@ impl Typer {
                 .collect::<String>()
         );
 
-        // Apply the update to screen
-        self.apply_text_update(&display_text, actually_typed);
+        // The model keeps hearing the same audio, so keep an undone update undone
+        // until new speech changes what it would show
+        if self.preview_history.is_undone(&display_text) {
+            debug!("Preview would retype an undone update, keeping the screen as is");
+            self.state.prev_text = processed_text;
+            return;
+        }
+
+        // Apply the update to screen, unless the focused window is filtered out
+        if self.focus_filter.allows_focused() {
+            self.apply_text_update(&display_text, actually_typed);
+        } else {
+            debug!("Focused window is filtered out, not typing the preview");
+        }
         self.state.prev_text = processed_text;
     }
 
@@ -194,7 +410,7 @@ impl Typer {
         self.state.text_storage.push(new_preview_text.to_string());
 
         // Keep only recent texts for stabilization (prevent unbounded growth)
-        if self.state.text_storage.len() > 10 {
+        if self.state.text_storage.len() > MAX_COMMIT_PASSES {
             self.state.text_storage.remove(0);
         }
 
@@ -333,6 +549,37 @@ impl Typer {
         best_text.to_string()
     }
 
+    /// Cut `display_text` back to the whole words every text in `recent` agrees
+    /// on, empty when there are no recent texts to compare against
+    fn stable_words(display_text: &str, recent: &[String]) -> String {
+        if recent.is_empty() {
+            return String::new();
+        }
+
+        let chars: Vec<char> = display_text.chars().collect();
+        let stable = recent.iter().fold(chars.len(), |stable, text| {
+            stable.min(Self::find_common_prefix(display_text, text))
+        });
+        if stable == chars.len() {
+            return display_text.to_string();
+        }
+
+        // A word the next pass may still change stays hidden until it settles
+        let end = if chars[stable].is_whitespace() {
+            stable
+        } else {
+            chars[..stable]
+                .iter()
+                .rposition(|c| c.is_whitespace())
+                .unwrap_or(0)
+        };
+        chars[..end]
+            .iter()
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
     /// Find the position where the last 'n' characters of text1 match with a substring in text2.
     fn find_tail_match_in_text(text1: &str, text2: &str, length_of_match: usize) -> i32 {
         // Check if either text is too short
@@ -365,14 +612,40 @@ impl Typer {
     /// Process final text (completed sentence) - Uses full session audio
     pub fn process_final_text(&mut self, transcription_result: &str) {
         // No preview typing, type directly
-        let processed_text =
-            crate::output::preview::Typer::preprocess_text(transcription_result, false);
-        let final_text = format!("{processed_text} ");
-        if let Err(e) = self.keyboard_simulator.type_text(&final_text) {
-            warn!("Failed to type final transcription: {e}");
-        } else {
-            info!("Step 6 complete: Final transcription typed directly");
+        let processed_text = crate::output::preview::Typer::preprocess_text(
+            transcription_result,
+            false,
+            &self.formatting,
+        );
+
+        let may_type = self.focus_filter.allows_focused();
+        if !may_type {
+            info!("Focused window is filtered out, not typing the final transcription");
+        }
+        // Copy instead when typing is not allowed, so the text is still at hand
+        let copied =
+            (self.output.copies() || !may_type) && Self::copy_to_clipboard(&processed_text);
+        // Type instead when the clipboard is unavailable so the text isn't lost
+        let typed = may_type && (self.output.types() || !copied);
+        if typed {
+            if self.voice_commands {
+                self.type_with_commands(transcription_result);
+            } else {
+                let final_text = self.with_trailing_space(processed_text.clone());
+                if let Err(e) = self.keyboard_simulator.type_text(&final_text) {
+                    warn!("Failed to type final transcription: {e}");
+                } else {
+                    info!("Step 6 complete: Final transcription typed directly");
+                }
+            }
         }
+        self.final_output = match (typed, copied) {
+            (true, true) => Some(OutputMode::Both),
+            (false, true) => Some(OutputMode::Clipboard),
+            (true, false) => Some(OutputMode::Type),
+            // Only returned to the client
+            (false, false) => None,
+        };
 
         // Reset state for next sentence - but keep the full session text for user reference
         self.state.prev_text.clear();
@@ -392,8 +665,159 @@ impl Typer {
         self.state.full_session_text.clear();
     }
 
+    /// Place final text on the clipboard, returning whether it worked
+    fn copy_to_clipboard(text: &str) -> bool {
+        match clipboard::write(text.as_bytes()) {
+            Ok(()) => {
+                info!("Step 6 complete: Final transcription copied to clipboard");
+                true
+            }
+            Err(e) => {
+                warn!("Failed to copy final transcription to clipboard: {e}");
+                false
+            }
+        }
+    }
+
+    /// Type final text, carrying out spoken commands instead of typing them
+    ///
+    /// Text between key commands is sent in one `type_text` call, so the clipboard
+    /// backend pastes each run of words at once rather than word by word.
+    fn type_with_commands(&mut self, text: &str) {
+        let tokens = commands::parse(text);
+        let add_period = self.formatting.add_final_punctuation
+            && matches!(
+                tokens.last(),
+                Some(Token::Text(text)) if text.chars().last().is_some_and(char::is_alphanumeric)
+            );
+        let mut sentence_start = true;
+        // The previous utterance already left a trailing space
+        let mut needs_space = false;
+        // Text not sent yet, flushed before any key press
+        let mut pending = String::new();
+
+        for token in tokens {
+            let result = match token {
+                Token::Text(text) => {
+                    for word in text.split_whitespace() {
+                        let mut word = word.to_string();
+                        if sentence_start
+                            && self.formatting.capitalize_first
+                            && let Some(first) = word.get(..1)
+                        {
+                            word = first.to_ascii_uppercase() + &word[1..];
+                        }
+                        sentence_start = false;
+                        if needs_space {
+                            word.insert(0, ' ');
+                        }
+                        needs_space = true;
+                        pending.push_str(&word);
+                        self.commit(Typed::Word(word.chars().count()));
+                    }
+                    Ok(())
+                }
+                Token::Command(Action::Punctuation(mark)) => {
+                    sentence_start = Action::Punctuation(mark).ends_sentence();
+                    needs_space = true;
+                    self.commit(Typed::Punctuation(mark));
+                    pending.push(mark);
+                    Ok(())
+                }
+                Token::Command(action @ (Action::NewLine | Action::NewParagraph)) => {
+                    sentence_start = true;
+                    needs_space = false;
+                    let presses = if action == Action::NewParagraph { 2 } else { 1 };
+                    let mut result = self
+                        .keyboard_simulator
+                        .type_text(&std::mem::take(&mut pending));
+                    for _ in 0..presses {
+                        self.commit(Typed::LineBreak);
+                        result = result.and(self.keyboard_simulator.press_enter());
+                    }
+                    result
+                }
+                Token::Command(Action::DeleteLastWord) => {
+                    let count = self.uncommit_last_word();
+                    debug!("Deleting last word ({count} chars)");
+                    needs_space = matches!(
+                        self.state.committed.last(),
+                        Some(Typed::Word(_) | Typed::Punctuation(_))
+                    );
+                    sentence_start =
+                        matches!(self.state.committed.last(), None | Some(Typed::LineBreak));
+                    // Words still pending were never typed, so drop them instead
+                    if let Some(kept) = pending.chars().count().checked_sub(count) {
+                        pending = pending.chars().take(kept).collect();
+                        Ok(())
+                    } else {
+                        let typed = pending.chars().count();
+                        pending.clear();
+                        self.keyboard_simulator.backspace_n(count - typed)
+                    }
+                }
+            };
+            if let Err(e) = result {
+                warn!("Failed to type voice command output: {e}");
+            }
+        }
+
+        // Finish the utterance the same way plain final text is finished
+        if add_period {
+            self.commit(Typed::Punctuation('.'));
+            pending.push('.');
+        }
+        if needs_space && self.formatting.trailing_space {
+            self.commit(Typed::Space);
+            pending.push(' ');
+        }
+        if let Err(e) = self.keyboard_simulator.type_text(&pending) {
+            warn!("Failed to type voice command output: {e}");
+        }
+    }
+
+    /// Append the space after final text unless it is turned off
+    fn with_trailing_space(&self, mut text: String) -> String {
+        if self.formatting.trailing_space {
+            text.push(' ');
+        }
+        text
+    }
+
+    /// Remember a unit of committed text, forgetting the oldest beyond the limit
+    fn commit(&mut self, typed: Typed) {
+        self.state.committed.push(typed);
+        if self.state.committed.len() > MAX_COMMITTED_UNITS {
+            self.state.committed.remove(0);
+        }
+    }
+
+    /// Forget the last committed word and anything typed after it, returning how
+    /// many characters to backspace; nothing is removed across a line break
+    fn uncommit_last_word(&mut self) -> usize {
+        let Some(index) = self
+            .state
+            .committed
+            .iter()
+            .rposition(|typed| matches!(typed, Typed::Word(_) | Typed::LineBreak))
+        else {
+            return 0;
+        };
+        if self.state.committed[index] == Typed::LineBreak {
+            return 0;
+        }
+
+        self.state
+            .committed
+            .drain(index..)
+            .map(Typed::char_count)
+            .sum()
+    }
+
     /// Apply text update to screen (common logic)
     fn apply_text_update(&mut self, new_text: &str, actually_typed: &mut String) {
+        self.preview_history.record(actually_typed, new_text);
+
         let old_char_count = actually_typed.chars().count();
         let new_char_count = new_text.chars().count();
 
@@ -465,6 +889,71 @@ impl Typer {
         }
     }
 
+    /// Undo or redo a preview update on screen
+    ///
+    /// Returns whether there was an update to revert or reapply.
+    pub fn apply_edit(&mut self, edit: PreviewEdit, actually_typed: &mut String) -> bool {
+        match edit {
+            PreviewEdit::Undo => self.undo_last(actually_typed),
+            PreviewEdit::Redo => self.redo_last(actually_typed),
+        }
+    }
+
+    /// Revert the most recent preview update, restoring exactly the text it replaced
+    ///
+    /// Later previews that would type the undone text again are skipped until new
+    /// speech changes the transcription. Returns whether there was an update to revert.
+    pub fn undo_last(&mut self, actually_typed: &mut String) -> bool {
+        let Some((old_text, new_text)) = self.preview_history.undo() else {
+            debug!("Nothing to undo");
+            return false;
+        };
+
+        info!(
+            "Undoing preview update: '{}' -> '{}'",
+            new_text.chars().take(30).collect::<String>(),
+            old_text.chars().take(30).collect::<String>()
+        );
+        self.restore_text(actually_typed, &old_text);
+        true
+    }
+
+    /// Reapply the most recently undone preview update
+    ///
+    /// Returns whether there was an update to reapply.
+    pub fn redo_last(&mut self, actually_typed: &mut String) -> bool {
+        let Some((old_text, new_text)) = self.preview_history.redo() else {
+            debug!("Nothing to redo");
+            return false;
+        };
+
+        info!(
+            "Redoing preview update: '{}' -> '{}'",
+            old_text.chars().take(30).collect::<String>(),
+            new_text.chars().take(30).collect::<String>()
+        );
+        self.restore_text(actually_typed, &new_text);
+        true
+    }
+
+    /// Bring the screen from `actually_typed` to `target` by inverting the diff
+    fn restore_text(&mut self, actually_typed: &mut String, target: &str) {
+        if target.is_empty() {
+            // `apply_simple_diff` never deletes everything, so erase directly
+            if let Err(e) = self
+                .keyboard_simulator
+                .backspace_n(actually_typed.chars().count())
+            {
+                warn!("Failed to erase preview text: {e}");
+            }
+        } else {
+            self.apply_simple_diff(actually_typed, target);
+        }
+
+        actually_typed.clear();
+        actually_typed.push_str(target);
+    }
+
     /// Clear all typed text and reset state
     pub fn clear_preview(&mut self, actually_typed: &mut String) {
         info!("clear_preview called with actually_typed: '{actually_typed}'");
@@ -486,6 +975,7 @@ impl Typer {
         actually_typed.clear();
 
         // Also clear state when explicitly clearing preview
+        self.preview_history.clear();
         self.state.prev_text.clear();
         self.state.last_transcription.clear();
         self.state.full_session_text.clear();
@@ -500,8 +990,8 @@ impl Typer {
     /// This function can fail if the enigo initialization fails or if the text typing task fails.
     pub fn replace_preview_and_type(&mut self, preview_chars: usize, text: &str) {
         // Use unified preprocessor for final text (adds period, capitalizes)
-        let processed_text = Typer::preprocess_text(text, false);
-        let text_to_type = processed_text + " ";
+        let processed_text = Typer::preprocess_text(text, false, &self.formatting);
+        let text_to_type = self.with_trailing_space(processed_text);
 
         // Erase preview in batches
         if preview_chars > 0 {
@@ -518,25 +1008,60 @@ mod tests {
 
     #[test]
     fn test_preprocess_text() {
+        let formatting = FormattingConfig::default();
+
         // Basic functionality
-        assert_eq!(Typer::preprocess_text("hello world", true), "Hello world");
-        assert_eq!(Typer::preprocess_text("hello world", false), "Hello world.");
-        assert_eq!(Typer::preprocess_text("", true), "");
+        assert_eq!(
+            Typer::preprocess_text("hello world", true, &formatting),
+            "Hello world"
+        );
+        assert_eq!(
+            Typer::preprocess_text("hello world", false, &formatting),
+            "Hello world."
+        );
+        assert_eq!(Typer::preprocess_text("", true, &formatting), "");
 
         assert_eq!(
-            Typer::preprocess_text("...hello world", true),
+            Typer::preprocess_text("...hello world", true, &formatting),
             "Hello world"
         );
         assert_eq!(
-            Typer::preprocess_text("  ...  hello world  ", true),
+            Typer::preprocess_text("  ...  hello world  ", true, &formatting),
             "Hello world"
         );
         assert_eq!(
-            Typer::preprocess_text("  multiple   spaces  ", true),
+            Typer::preprocess_text("  multiple   spaces  ", true, &formatting),
             "Multiple spaces"
         );
     }
 
+    #[test]
+    fn test_preprocess_text_without_formatting() {
+        let plain = FormattingConfig {
+            capitalize_first: false,
+            add_final_punctuation: false,
+            collapse_whitespace: false,
+            trailing_space: false,
+        };
+        assert_eq!(
+            Typer::preprocess_text("see example.com", false, &plain),
+            "see example.com"
+        );
+        assert_eq!(
+            Typer::preprocess_text("  fn  main()\n", false, &plain),
+            "fn  main()"
+        );
+
+        let no_period = FormattingConfig {
+            add_final_punctuation: false,
+            ..FormattingConfig::default()
+        };
+        assert_eq!(
+            Typer::preprocess_text("hello world", false, &no_period),
+            "Hello world"
+        );
+    }
+
     #[test]
     fn test_is_simple_extension() {
         assert!(Typer::is_simple_extension("hello", "hello world"));
@@ -576,4 +1101,50 @@ mod tests {
         assert_eq!(Typer::find_common_prefix("abc", "def"), 0);
         assert_eq!(Typer::find_common_prefix("same text", "same text"), 9);
     }
+
+    #[test]
+    fn test_stable_words() {
+        let recent = vec![
+            "Hello wor".to_string(),
+            "Hello world and".to_string(),
+            "Hello world an".to_string(),
+        ];
+        // "wor" and "world" differ, so only the first word is settled
+        assert_eq!(Typer::stable_words("Hello world and", &recent), "Hello");
+        assert_eq!(
+            Typer::stable_words("Hello world", &recent[1..]),
+            "Hello world"
+        );
+        assert_eq!(Typer::stable_words("Hello world", &[]), "");
+    }
+
+    #[test]
+    fn test_undo_survives_next_preview() {
+        let mut history = PreviewHistory::default();
+        history.record("", "Hello");
+        history.record("Hello", "Hello word");
+        assert_eq!(
+            history.undo(),
+            Some(("Hello".to_string(), "Hello word".to_string()))
+        );
+
+        // The next pass still hears "word" and must not retype it
+        assert!(history.is_undone("Hello word"));
+        assert!(!history.is_undone("Hello"));
+
+        // Undoing further pins every undone text
+        history.undo();
+        assert!(history.is_undone("Hello word"));
+        assert!(history.is_undone("Hello"));
+
+        // Redo reapplies, so the redone text shows again
+        assert_eq!(history.redo(), Some((String::new(), "Hello".to_string())));
+        assert!(!history.is_undone("Hello"));
+        assert!(history.is_undone("Hello word"));
+
+        // New speech is typed normally and ends the pin
+        history.record("Hello", "Hello world");
+        assert!(!history.is_undone("Hello word"));
+        assert_eq!(history.redo(), None);
+    }
 }
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::fmt::Write;
+
+use super_stt_shared::models::protocol::{Segment, TranscriptFormat};
+
+/// Render timed segments in the requested transcript format
+#[must_use]
+pub fn format_segments(segments: &[Segment], format: TranscriptFormat) -> String {
+    match format {
+        TranscriptFormat::Text => segments
+            .iter()
+            .map(|segment| segment.text.trim())
+            .collect::<Vec<_>>()
+            .join(" "),
+        TranscriptFormat::Srt => format_srt(segments),
+        TranscriptFormat::Vtt => format_vtt(segments),
+    }
+}
+
+/// Render segments as `SubRip` cues numbered from 1
+///
+/// Cues carry a `[Speaker N]` prefix once diarization has told more than one speaker apart.
+#[must_use]
+pub fn format_srt(segments: &[Segment]) -> String {
+    let labelled = has_several_speakers(segments);
+    let mut output = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let _ = writeln!(
+            output,
+            "{}\n{} --> {}",
+            index + 1,
+            format_timestamp(segment.start, ','),
+            format_timestamp(segment.end, ',')
+        );
+        if labelled {
+            let _ = write!(output, "[{}] ", speaker_label(segment));
+        }
+        let _ = write!(output, "{}\n\n", segment.text.trim());
+    }
+    output
+}
+
+/// Render segments as a `WebVTT` document
+///
+/// Cues carry a `<v Speaker N>` voice span once diarization has told more than one
+/// speaker apart.
+#[must_use]
+pub fn format_vtt(segments: &[Segment]) -> String {
+    let labelled = has_several_speakers(segments);
+    let mut output = String::from("WEBVTT\n\n");
+    for segment in segments {
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_timestamp(segment.start, '.'),
+            format_timestamp(segment.end, '.')
+        );
+        if labelled {
+            let _ = write!(output, "<v {}>", speaker_label(segment));
+        }
+        let _ = write!(output, "{}\n\n", segment.text.trim());
+    }
+    output
+}
+
+/// Whether labels would tell anything apart; all segments are speaker 0 without diarization
+fn has_several_speakers(segments: &[Segment]) -> bool {
+    segments.iter().any(|segment| segment.speaker_id != 0)
+}
+
+/// Speaker name shown to readers, numbered from 1
+fn speaker_label(segment: &Segment) -> String {
+    format!("Speaker {}", segment.speaker_id + 1)
+}
+
+/// Format seconds as `HH:MM:SS<sep>mmm`
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_timestamp(seconds: f32, millis_separator: char) -> String {
+    let total_ms = (f64::from(seconds.max(0.0)) * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms / 60_000) % 60;
+    let secs = (total_ms / 1000) % 60;
+    let millis = total_ms % 1000;
+    format!("{hours:02}:{minutes:02}:{secs:02}{millis_separator}{millis:03}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments() -> Vec<Segment> {
+        vec![
+            Segment {
+                start: 0.0,
+                end: 2.5,
+                text: "Hello there.".to_string(),
+                speaker_id: 0,
+            },
+            Segment {
+                start: 3_725.042,
+                end: 3_730.0,
+                text: " General Kenobi. ".to_string(),
+                speaker_id: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0.0, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(61.5, '.'), "00:01:01.500");
+        assert_eq!(format_timestamp(3_725.042, ','), "01:02:05,042");
+        assert_eq!(format_timestamp(-1.0, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn test_format_srt() {
+        assert_eq!(
+            format_srt(&segments()),
+            "1\n00:00:00,000 --> 00:00:02,500\nHello there.\n\n\
+             2\n01:02:05,042 --> 01:02:10,000\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_vtt() {
+        assert_eq!(
+            format_vtt(&segments()),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:02.500\nHello there.\n\n\
+             01:02:05.042 --> 01:02:10.000\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_speaker_labels() {
+        let mut segments = segments();
+        segments[1].speaker_id = 1;
+        assert_eq!(
+            format_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:02,500\n[Speaker 1] Hello there.\n\n\
+             2\n01:02:05,042 --> 01:02:10,000\n[Speaker 2] General Kenobi.\n\n"
+        );
+        assert_eq!(
+            format_vtt(&segments),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:02.500\n<v Speaker 1>Hello there.\n\n\
+             01:02:05.042 --> 01:02:10.000\n<v Speaker 2>General Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_text() {
+        assert_eq!(
+            format_segments(&segments(), TranscriptFormat::Text),
+            "Hello there. General Kenobi."
+        );
+    }
+}
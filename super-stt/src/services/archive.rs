@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Saving every recording to disk, for an audit trail of what was transcribed
+//!
+//! Each recording is written as a 16kHz WAV file with a JSON sidecar of the same
+//! name holding its transcription. Files are named after the time they were saved,
+//! so the oldest sort first when the archive is trimmed to its retention limits.
+//! Only files following that naming are ever deleted.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_PREFIX: &str = "recording-";
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,              // Save each recording with its transcription
+    pub directory: Option<PathBuf>, // `None` saves to `recordings` in the data directory
+    pub max_recordings: u32,        // Oldest recordings beyond this many are deleted, 0 keeps all
+    pub max_total_mb: u64,          // Oldest recordings are deleted past this size, 0 for no limit
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            max_recordings: 1000,
+            max_total_mb: 1024,
+        }
+    }
+}
+
+impl ArchiveConfig {
+    /// The directory recordings are saved to
+    #[must_use]
+    pub fn directory(&self) -> PathBuf {
+        self.directory.clone().unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| {
+                    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+                    PathBuf::from(home).join(".local").join("share")
+                })
+                .join("super-stt")
+                .join("recordings")
+        })
+    }
+}
+
+/// Sidecar saved next to each archived recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    pub timestamp: String, // RFC 3339
+    pub transcription: String,
+    pub confidence: f32,
+    pub model: String,
+    pub device: String,
+    pub duration_ms: u64,
+}
+
+/// Create `directory` if needed and make sure files can be written to it
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created or written to.
+pub fn check_writable(directory: &Path) -> Result<()> {
+    fs::create_dir_all(directory)
+        .with_context(|| format!("Failed to create {}", directory.display()))?;
+    let probe = directory.join(".write-test");
+    fs::write(&probe, b"").with_context(|| format!("Cannot write to {}", directory.display()))?;
+    fs::remove_file(&probe)?;
+    Ok(())
+}
+
+/// Write 16kHz `audio` and its sidecar to the archive, then trim the archive to
+/// its retention limits, returning the path of the WAV file
+///
+/// # Errors
+///
+/// Returns an error if the timestamp is not RFC 3339 or a file cannot be written.
+pub fn save(config: &ArchiveConfig, audio: &[f32], entry: &ArchiveEntry) -> Result<PathBuf> {
+    let directory = config.directory();
+    fs::create_dir_all(&directory)
+        .with_context(|| format!("Failed to create {}", directory.display()))?;
+
+    let saved_at = DateTime::parse_from_rfc3339(&entry.timestamp)
+        .context("Invalid archive timestamp")?
+        .with_timezone(&Utc);
+    let stem = format!("{FILE_PREFIX}{}", saved_at.format("%Y%m%dT%H%M%S%.3fZ"));
+    let wav_path = directory.join(format!("{stem}.wav"));
+
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(&wav_path, spec)
+        .with_context(|| format!("Failed to create {}", wav_path.display()))?;
+    for &sample in audio {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    fs::write(
+        directory.join(format!("{stem}.json")),
+        serde_json::to_vec_pretty(entry)?,
+    )?;
+
+    prune(&directory, config)?;
+    Ok(wav_path)
+}
+
+/// Delete the oldest recordings until the archive is within its limits, always
+/// keeping the newest
+fn prune(directory: &Path, config: &ArchiveConfig) -> Result<()> {
+    let mut recordings: Vec<(PathBuf, u64)> = Vec::new();
+    for entry in fs::read_dir(directory)?.flatten() {
+        let path = entry.path();
+        let is_recording = path.extension().is_some_and(|ext| ext == "wav")
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(FILE_PREFIX));
+        if !is_recording {
+            continue;
+        }
+        let sidecar = path.with_extension("json");
+        let size =
+            entry.metadata().map_or(0, |m| m.len()) + fs::metadata(&sidecar).map_or(0, |m| m.len());
+        recordings.push((path, size));
+    }
+    recordings.sort();
+
+    let max_count = match config.max_recordings {
+        0 => usize::MAX,
+        max => usize::try_from(max).unwrap_or(usize::MAX),
+    };
+    let max_bytes = match config.max_total_mb {
+        0 => u64::MAX,
+        max => max.saturating_mul(BYTES_PER_MB),
+    };
+    let mut count = recordings.len();
+    let mut total: u64 = recordings.iter().map(|(_, size)| size).sum();
+
+    for (path, size) in recordings {
+        if count <= 1 || (count <= max_count && total <= max_bytes) {
+            break;
+        }
+        fs::remove_file(&path)?;
+        let _ = fs::remove_file(path.with_extension("json"));
+        count -= 1;
+        total -= size;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str) -> ArchiveEntry {
+        ArchiveEntry {
+            timestamp: timestamp.to_string(),
+            transcription: "Hello world.".to_string(),
+            confidence: 0.9,
+            model: "whisper-tiny".to_string(),
+            device: "cpu".to_string(),
+            duration_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn test_archive_keeps_newest_recordings() {
+        let root = std::env::temp_dir().join(format!("super-stt-archive-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        check_writable(&root).unwrap();
+        fs::write(root.join("notes.wav"), b"not ours").unwrap();
+
+        let config = ArchiveConfig {
+            enabled: true,
+            directory: Some(root.clone()),
+            max_recordings: 2,
+            max_total_mb: 0,
+        };
+        let audio = vec![0.25; 16000];
+        for timestamp in [
+            "2025-01-03T10:00:00+00:00",
+            "2025-01-03T11:00:00+00:00",
+            "2025-01-03T12:00:00+00:00",
+        ] {
+            save(&config, &audio, &entry(timestamp)).unwrap();
+        }
+
+        let mut names: Vec<String> = fs::read_dir(&root)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "notes.wav",
+                "recording-20250103T110000.000Z.json",
+                "recording-20250103T110000.000Z.wav",
+                "recording-20250103T120000.000Z.json",
+                "recording-20250103T120000.000Z.wav",
+            ]
+        );
+
+        let sidecar: ArchiveEntry = serde_json::from_slice(
+            &fs::read(root.join("recording-20250103T120000.000Z.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(sidecar, entry("2025-01-03T12:00:00+00:00"));
+        let reader =
+            hound::WavReader::open(root.join("recording-20250103T120000.000Z.wav")).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.len(), 16000);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
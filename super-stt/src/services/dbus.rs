@@ -2,8 +2,17 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 use zbus::{Connection, interface, object_server::SignalEmitter};
 
+/// Work asked of the daemon through D-Bus methods, handled outside the bus callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DBusRequest {
+    StartRecording { write_mode: bool },
+    StopRecording,
+}
+
 /// D-Bus interface for Super STT service
 #[derive(Debug, Serialize, Deserialize, zbus::zvariant::Type)]
 pub struct ListeningEvent {
@@ -12,6 +21,8 @@ pub struct ListeningEvent {
     pub write_mode: bool,
     pub timeout_seconds: u64,
     pub audio_level: f32,
+    /// Rate the input device records at, 0 when unknown
+    pub input_sample_rate: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, zbus::zvariant::Type)]
@@ -35,7 +46,10 @@ pub struct TranscriptionCompletedEvent {
     pub client_id: String,
     pub timestamp: String,
     pub transcription: String,
+    /// Time the transcription took, from the start of the recording for recordings
     pub duration_ms: u64,
+    /// Model confidence in the transcription, from 0 to 1
+    pub confidence: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, zbus::zvariant::Type)]
@@ -46,7 +60,17 @@ pub struct AudioLevelEvent {
     pub is_speech: bool,
 }
 
-pub struct SuperSTTDBusService;
+pub struct SuperSTTDBusService {
+    requests: UnboundedSender<DBusRequest>,
+}
+
+impl SuperSTTDBusService {
+    fn request(&self, request: DBusRequest) -> zbus::fdo::Result<()> {
+        self.requests
+            .send(request)
+            .map_err(|_| zbus::fdo::Error::Failed("Daemon is shutting down".to_string()))
+    }
+}
 
 #[interface(name = "com.github.jorge_menjivar.SuperSTT1")]
 impl SuperSTTDBusService {
@@ -82,6 +106,26 @@ impl SuperSTTDBusService {
     #[zbus(signal)]
     pub async fn audio_level(ctxt: &SignalEmitter<'_>, event: AudioLevelEvent) -> zbus::Result<()>;
 
+    /// Method to start a recording that runs until `StopRecording`, like the socket
+    /// `record_start` command
+    ///
+    /// Returns once the recording is requested; the listening signals follow its progress.
+    ///
+    /// # Errors
+    /// Returns an error if the daemon is shutting down.
+    pub fn start_recording(&self, write_mode: bool) -> zbus::fdo::Result<()> {
+        self.request(DBusRequest::StartRecording { write_mode })
+    }
+
+    /// Method to stop the recording started over D-Bus, then transcribe and type it
+    /// like the socket `record_stop` command
+    ///
+    /// # Errors
+    /// Returns an error if the daemon is shutting down.
+    pub fn stop_recording(&self) -> zbus::fdo::Result<()> {
+        self.request(DBusRequest::StopRecording)
+    }
+
     /// Method to check if daemon is running
     #[must_use]
     pub fn ping(&self) -> String {
@@ -100,6 +144,8 @@ impl SuperSTTDBusService {
 
 pub struct DBusManager {
     connection: Connection,
+    // Requests from the D-Bus methods, until the daemon takes them to serve
+    requests: Mutex<Option<UnboundedReceiver<DBusRequest>>>,
 }
 
 impl DBusManager {
@@ -116,12 +162,31 @@ impl DBusManager {
             .await?;
 
         // Serve the interface
+        let (requests_tx, requests_rx) = unbounded_channel();
         connection
             .object_server()
-            .at("/com/github/jorge_menjivar/SuperSTT", SuperSTTDBusService)
+            .at(
+                "/com/github/jorge_menjivar/SuperSTT",
+                SuperSTTDBusService {
+                    requests: requests_tx,
+                },
+            )
             .await?;
 
-        Ok(Self { connection })
+        Ok(Self {
+            connection,
+            requests: Mutex::new(Some(requests_rx)),
+        })
+    }
+
+    /// Take the receiver of requests made through the D-Bus methods
+    ///
+    /// Returns `None` once it has been taken.
+    pub fn take_requests(&self) -> Option<UnboundedReceiver<DBusRequest>> {
+        match self.requests.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        }
     }
 
     /// Emit a signal indicating that listening has started.
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, params};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use super_stt_shared::models::protocol::HistoryEntry;
+
+use crate::config::HistoryConfig;
+
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Final transcriptions kept in a `SQLite` database, newest first on read
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryStore {
+    /// Where the daemon keeps its history database
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+                PathBuf::from(home).join(".local").join("share")
+            })
+            .join("super-stt")
+            .join("history.sqlite3")
+    }
+
+    /// Open the database at `path`, creating it and its directory if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or database cannot be created.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        // Transcriptions are private; create the database readable by the owner only
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .mode(0o600)
+                .open(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+        }
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp_ms INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                model TEXT NOT NULL,
+                device TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS history_timestamp ON history (timestamp_ms);",
+        )
+        .context("Failed to create history table")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Store a transcription, then drop whatever falls outside `retention`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timestamp is not RFC 3339 or the database write fails.
+    pub fn record(&self, entry: &HistoryEntry, retention: &HistoryConfig) -> Result<()> {
+        let timestamp_ms = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .context("Invalid history timestamp")?
+            .timestamp_millis();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("History database lock poisoned"))?;
+
+        conn.execute(
+            "INSERT INTO history (timestamp_ms, text, model, device, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                timestamp_ms,
+                entry.text,
+                entry.model,
+                entry.device,
+                i64::try_from(entry.duration_ms).unwrap_or(i64::MAX),
+            ],
+        )?;
+
+        if retention.max_age_days > 0 {
+            let cutoff = timestamp_ms - i64::from(retention.max_age_days) * MS_PER_DAY;
+            conn.execute("DELETE FROM history WHERE timestamp_ms < ?1", [cutoff])?;
+        }
+        conn.execute(
+            "DELETE FROM history WHERE id NOT IN
+             (SELECT id FROM history ORDER BY id DESC LIMIT ?1)",
+            [retention.max_entries],
+        )?;
+        Ok(())
+    }
+
+    /// Up to `limit` transcriptions newer than `since_timestamp`, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `since_timestamp` is not RFC 3339 or the database read fails.
+    pub fn recent(&self, since_timestamp: Option<&str>, limit: u32) -> Result<Vec<HistoryEntry>> {
+        let since_ms = match since_timestamp {
+            Some(since) => DateTime::parse_from_rfc3339(since)
+                .context("Invalid since_timestamp")?
+                .timestamp_millis(),
+            None => i64::MIN,
+        };
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("History database lock poisoned"))?;
+
+        let mut statement = conn.prepare(
+            "SELECT timestamp_ms, text, model, device, duration_ms FROM history
+             WHERE timestamp_ms > ?1 ORDER BY timestamp_ms DESC, id DESC LIMIT ?2",
+        )?;
+        let entries = statement
+            .query_map(params![since_ms, limit], |row| {
+                let timestamp_ms: i64 = row.get(0)?;
+                let duration_ms: i64 = row.get(4)?;
+                Ok(HistoryEntry {
+                    timestamp: DateTime::<Utc>::from_timestamp_millis(timestamp_ms)
+                        .unwrap_or_default()
+                        .to_rfc3339(),
+                    text: row.get(1)?,
+                    model: row.get(2)?,
+                    device: row.get(3)?,
+                    duration_ms: u64::try_from(duration_ms).unwrap_or_default(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: timestamp.to_string(),
+            text: text.to_string(),
+            model: "whisper-tiny".to_string(),
+            device: "cpu".to_string(),
+            duration_ms: 1500,
+        }
+    }
+
+    #[test]
+    fn test_history_pagination_and_retention() {
+        let store = HistoryStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let retention = HistoryConfig {
+            enabled: true,
+            max_entries: 2,
+            max_age_days: 1,
+        };
+
+        store
+            .record(&entry("2025-01-01T10:00:00+00:00", "stale"), &retention)
+            .unwrap();
+        store
+            .record(&entry("2025-01-03T10:00:00+00:00", "first"), &retention)
+            .unwrap();
+        store
+            .record(&entry("2025-01-03T11:00:00+00:00", "second"), &retention)
+            .unwrap();
+        store
+            .record(&entry("2025-01-03T12:00:00+00:00", "third"), &retention)
+            .unwrap();
+
+        // "stale" aged out and "first" fell beyond max_entries
+        let all = store.recent(None, 10).unwrap();
+        let texts: Vec<&str> = all.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["third", "second"]);
+        assert_eq!(all[0].duration_ms, 1500);
+
+        let newer = store.recent(Some("2025-01-03T11:00:00Z"), 10).unwrap();
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].text, "third");
+
+        assert_eq!(store.recent(None, 1).unwrap().len(), 1);
+        assert!(store.recent(Some("yesterday"), 10).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_history_database_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("super-stt-history-{}", std::process::id()));
+        let path = root.join("history.sqlite3");
+        HistoryStore::open(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
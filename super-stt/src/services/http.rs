@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! OpenAI-compatible HTTP transcription API
+//!
+//! `POST /v1/audio/transcriptions` takes the same multipart form as OpenAI's
+//! endpoint, so existing clients can point their base URL at the daemon. The audio
+//! in the `file` field is transcribed with the loaded model; `model` is accepted
+//! but ignored, and `response_format` may be `json` (the default) or `text`.
+//!
+//! Requests must carry the UDP auth secret as a bearer token in their
+//! `Authorization` header, the way OpenAI clients send their API key.
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::extract::multipart::MultipartError;
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Multipart, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use log::{info, warn};
+use serde_json::json;
+use std::net::SocketAddr;
+use super_stt_shared::validation;
+use tokio::net::TcpListener;
+
+use crate::daemon::types::SuperSTTDaemon;
+
+/// Room for the multipart boundaries and other fields on top of the audio itself
+const FORM_OVERHEAD_BYTES: usize = 64 * 1024;
+
+/// Serve the HTTP API on `addr` until the daemon shuts down
+///
+/// # Errors
+///
+/// Returns an error if the listener cannot bind to `addr`.
+pub async fn spawn(daemon: SuperSTTDaemon, addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind HTTP API to {addr}"))?;
+    if !addr.ip().is_loopback() {
+        warn!("HTTP API bound to non-loopback address {addr}");
+    }
+    info!("HTTP API listening on {addr}");
+
+    let max_file_bytes = daemon.config.read().await.transcription.max_file_size_mb * 1024 * 1024;
+    let body_limit = usize::try_from(max_file_bytes)
+        .unwrap_or(usize::MAX)
+        .saturating_add(FORM_OVERHEAD_BYTES);
+    let mut shutdown_rx = daemon.shutdown_tx.subscribe();
+    let app = Router::new()
+        .route("/v1/audio/transcriptions", post(transcribe))
+        .layer(DefaultBodyLimit::max(body_limit))
+        .with_state(daemon);
+
+    tokio::spawn(async move {
+        let server = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.recv().await;
+        });
+        if let Err(e) = server.await {
+            warn!("HTTP API stopped: {e}");
+        }
+    });
+    Ok(())
+}
+
+/// Error body in the shape OpenAI clients expect
+fn error_response(status: StatusCode, message: &str) -> Response {
+    let kind = if status.is_server_error() {
+        "server_error"
+    } else {
+        "invalid_request_error"
+    };
+    let body = json!({ "error": { "message": message, "type": kind } });
+    (status, axum::Json(body)).into_response()
+}
+
+fn authorized(daemon: &SuperSTTDaemon, headers: &HeaderMap) -> bool {
+    headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| daemon.verify_api_token(token))
+}
+
+/// Fields of a transcription form that the daemon uses
+#[derive(Default)]
+struct TranscriptionForm {
+    file: Option<Vec<u8>>,
+    language: Option<String>,
+    text_response: bool,
+}
+
+/// Read the form, failing with 413 when the body is over the size limit and 400
+/// when it is malformed
+async fn read_form(mut multipart: Multipart) -> Result<TranscriptionForm, Response> {
+    let invalid = |context: &str, e: &MultipartError| {
+        error_response(e.status(), &format!("{context}: {}", e.body_text()))
+    };
+    let mut form = TranscriptionForm::default();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| invalid("Invalid multipart body", &e))?
+    {
+        match field.name() {
+            Some("file") => {
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| invalid("Failed to read file", &e))?;
+                form.file = Some(data.to_vec());
+            }
+            Some("language") => {
+                let language = field
+                    .text()
+                    .await
+                    .map_err(|e| invalid("Failed to read language", &e))?;
+                form.language = Some(language).filter(|l| !l.is_empty());
+            }
+            Some("response_format") => match field
+                .text()
+                .await
+                .map_err(|e| invalid("Failed to read response_format", &e))?
+            {
+                format if format == "json" => form.text_response = false,
+                format if format == "text" => form.text_response = true,
+                format => {
+                    return Err(error_response(
+                        StatusCode::BAD_REQUEST,
+                        &format!("Unsupported response_format: {format}"),
+                    ));
+                }
+            },
+            // model, prompt, temperature and the like have no counterpart here
+            _ => {}
+        }
+    }
+    Ok(form)
+}
+
+async fn transcribe(
+    State(daemon): State<SuperSTTDaemon>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Response {
+    if !authorized(&daemon, &headers) {
+        warn!("HTTP client {peer} rejected: invalid or missing bearer token");
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid or missing bearer token");
+    }
+
+    let connection_id = format!("http_{}", uuid::Uuid::new_v4());
+    if let Err(e) = daemon
+        .resource_manager
+        .register_client_connection(
+            connection_id.clone(),
+            format!("http:{}", peer.ip()),
+            Some(peer),
+        )
+        .await
+    {
+        warn!("HTTP request rejected due to resource limits: {e}");
+        return error_response(StatusCode::TOO_MANY_REQUESTS, &e.to_string());
+    }
+    let response = match daemon.resource_manager.record_request(&connection_id).await {
+        Ok(()) => handle_transcription(&daemon, &connection_id, multipart).await,
+        Err(e) => error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &format!("Rate limit exceeded: {e}"),
+        ),
+    };
+    daemon
+        .resource_manager
+        .unregister_connection(&connection_id)
+        .await;
+    response
+}
+
+async fn handle_transcription(
+    daemon: &SuperSTTDaemon,
+    connection_id: &str,
+    multipart: Multipart,
+) -> Response {
+    let form = match read_form(multipart).await {
+        Ok(form) => form,
+        Err(response) => return response,
+    };
+    let Some(file) = form.file else {
+        return error_response(StatusCode::BAD_REQUEST, "Missing file field");
+    };
+
+    let max_bytes = daemon.config.read().await.transcription.max_file_size_mb * 1024 * 1024;
+    if file.len() as u64 > max_bytes {
+        return error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            &format!(
+                "File too large: {} bytes (max {max_bytes} bytes)",
+                file.len()
+            ),
+        );
+    }
+    if let Some(language) = form.language.as_deref() {
+        let valid = match validation::validate_language(language) {
+            Ok(()) => daemon.validate_language_for_model(Some(language)).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = valid {
+            return error_response(StatusCode::BAD_REQUEST, &format!("Invalid language: {e}"));
+        }
+    }
+
+    // Decode here so audio that cannot be read is the client's error, not ours
+    let samples = match tokio::task::spawn_blocking(move || {
+        SuperSTTDaemon::decode_audio_bytes(file)
+    })
+    .await
+    {
+        Ok(Ok(samples)) => samples,
+        Ok(Err(e)) => {
+            return error_response(StatusCode::BAD_REQUEST, &format!("Invalid audio file: {e}"));
+        }
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Audio decoding task failed: {e}"),
+            );
+        }
+    };
+
+    let response = daemon
+        .handle_transcribe_samples(samples, connection_id.to_string(), form.language)
+        .await;
+    if response.status != "success" {
+        let message = response.message.unwrap_or_default();
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, &message);
+    }
+
+    let text = response.transcription.unwrap_or_default();
+    if form.text_response {
+        text.into_response()
+    } else {
+        axum::Json(json!({ "text": text })).into_response()
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Prometheus metrics of transcriptions, model loads, UDP clients and downloads
+//!
+//! Everything here is a no-op unless the daemon is built with the `metrics`
+//! feature. The exporter serves the text format on `GET /metrics`; gauges that
+//! mirror daemon state, like the loaded model, are refreshed on every scrape.
+
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use {
+    crate::daemon::types::SuperSTTDaemon,
+    anyhow::{Context, Result},
+    prometheus::{Gauge, Histogram, HistogramOpts, IntCounter, IntGauge, IntGaugeVec, Opts},
+    std::net::SocketAddr,
+    std::sync::LazyLock,
+};
+
+/// Upper bounds of the transcription duration buckets, in seconds
+#[cfg(feature = "metrics")]
+const TRANSCRIPTION_BUCKETS: [f64; 9] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+#[cfg(feature = "metrics")]
+struct Metrics {
+    registry: prometheus::Registry,
+    transcriptions: IntCounter,
+    transcription_duration: Histogram,
+    model_load: Gauge,
+    udp_clients: IntGauge,
+    download_bytes: IntCounter,
+    model_info: IntGaugeVec,
+}
+
+#[cfg(feature = "metrics")]
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = prometheus::Registry::new_custom(Some("super_stt".to_string()), None)?;
+        let metrics = Self {
+            transcriptions: IntCounter::new("transcriptions_total", "Completed transcriptions")?,
+            transcription_duration: Histogram::with_opts(
+                HistogramOpts::new(
+                    "transcription_duration_seconds",
+                    "Time the model spent on each transcription",
+                )
+                .buckets(TRANSCRIPTION_BUCKETS.to_vec()),
+            )?,
+            model_load: Gauge::new("model_load_seconds", "Duration of the last model load")?,
+            udp_clients: IntGauge::new("udp_clients", "Clients registered for UDP audio")?,
+            download_bytes: IntCounter::new(
+                "download_bytes_total",
+                "Bytes of model files downloaded",
+            )?,
+            model_info: IntGaugeVec::new(
+                Opts::new("model_info", "Loaded model and the device it runs on"),
+                &["model", "device"],
+            )?,
+            registry,
+        };
+        metrics
+            .registry
+            .register(Box::new(metrics.transcriptions.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.transcription_duration.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.model_load.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.udp_clients.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.download_bytes.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.model_info.clone()))?;
+        Ok(metrics)
+    }
+}
+
+#[cfg(feature = "metrics")]
+static METRICS: LazyLock<Metrics> =
+    LazyLock::new(|| Metrics::new().expect("metric names and labels are valid"));
+
+/// Count a completed transcription that took `duration` in the model
+pub fn record_transcription(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        METRICS.transcriptions.inc();
+        METRICS
+            .transcription_duration
+            .observe(duration.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = duration;
+}
+
+/// Note how long the latest model load took
+pub fn record_model_load(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    METRICS.model_load.set(duration.as_secs_f64());
+    #[cfg(not(feature = "metrics"))]
+    let _ = duration;
+}
+
+/// Count bytes of a model file that arrived
+pub fn add_download_bytes(bytes: u64) {
+    #[cfg(feature = "metrics")]
+    METRICS.download_bytes.inc_by(bytes);
+    #[cfg(not(feature = "metrics"))]
+    let _ = bytes;
+}
+
+/// Refresh the gauges that mirror daemon state and render every metric
+#[cfg(feature = "metrics")]
+async fn render(daemon: &SuperSTTDaemon) -> Result<String> {
+    let udp_clients = daemon.udp_streamer.client_count().await;
+    METRICS
+        .udp_clients
+        .set(i64::try_from(udp_clients).unwrap_or(i64::MAX));
+
+    METRICS.model_info.reset();
+    if let Some(model) = *daemon.model_type.read().await {
+        let device = daemon.actual_device.read().await.clone();
+        METRICS
+            .model_info
+            .with_label_values(&[model.to_string(), device])
+            .set(1);
+    }
+
+    let families = METRICS.registry.gather();
+    Ok(prometheus::TextEncoder::new().encode_to_string(&families)?)
+}
+
+#[cfg(feature = "metrics")]
+async fn scrape(
+    axum::extract::State(daemon): axum::extract::State<SuperSTTDaemon>,
+) -> axum::response::Response {
+    use axum::http::{StatusCode, header};
+    use axum::response::IntoResponse;
+
+    match render(&daemon).await {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => {
+            log::warn!("Failed to render metrics: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Serve `GET /metrics` on `addr` until the daemon shuts down
+///
+/// # Errors
+///
+/// Returns an error if the listener cannot bind to `addr`.
+#[cfg(feature = "metrics")]
+pub async fn spawn(daemon: SuperSTTDaemon, addr: SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind metrics exporter to {addr}"))?;
+    if !addr.ip().is_loopback() {
+        log::warn!("Metrics exporter bound to non-loopback address {addr}");
+    }
+    log::info!("Metrics exporter listening on {addr}");
+
+    let mut shutdown_rx = daemon.shutdown_tx.subscribe();
+    let app = axum::Router::new()
+        .route("/metrics", axum::routing::get(scrape))
+        .with_state(daemon);
+
+    tokio::spawn(async move {
+        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+            let _ = shutdown_rx.recv().await;
+        });
+        if let Err(e) = server.await {
+            log::warn!("Metrics exporter stopped: {e}");
+        }
+    });
+    Ok(())
+}
@@ -1,6 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-only
+pub mod archive;
 pub mod dbus;
+pub mod history;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod metrics;
+pub mod power;
+pub mod systemd;
 pub mod transcription;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 // Re-export commonly used types
 pub use dbus::{DBusManager, SuperSTTDBusService};
@@ -0,0 +1,251 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Saving power while the laptop runs on battery
+//!
+//! The power source is polled from `/sys/class/power_supply`. Once on battery the
+//! daemon moves its model from the GPU to the CPU or unloads it, as configured by
+//! `device.battery_action`, and undoes that when external power is back. Switches go
+//! through the usual device and model paths, so clients see the same status events as
+//! for a switch they requested, but a device switch never touches the saved
+//! preference. A switch refused because the model is in use is retried on the next poll.
+
+use crate::daemon::types::SuperSTTDaemon;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// How often the power source is read
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// What the daemon does with its model while on battery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryAction {
+    /// Leave the model where it is
+    #[default]
+    Keep,
+    /// Move a model running on CUDA to the CPU
+    Cpu,
+    /// Unload the model, to be reloaded by the next request
+    Unload,
+}
+
+/// What the watcher changed for the battery, and has to undo on external power
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Applied {
+    Nothing,
+    SwitchedToCpu,
+    Unloaded,
+}
+
+/// Watch the power source until shutdown, applying `device.battery_action`
+pub fn spawn(daemon: SuperSTTDaemon) {
+    let mut shutdown_rx = daemon.shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        let mut applied = Applied::Nothing;
+        loop {
+            let action = daemon.config.read().await.device.battery_action;
+            if action != BatteryAction::Keep || applied != Applied::Nothing {
+                let on_battery = on_battery(Path::new(POWER_SUPPLY_DIR)).unwrap_or(false);
+                let wanted = if on_battery {
+                    action
+                } else {
+                    BatteryAction::Keep
+                };
+                applied = apply(&daemon, applied, wanted).await;
+            }
+
+            tokio::select! {
+                () = tokio::time::sleep(POWER_CHECK_INTERVAL) => {}
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+}
+
+/// Take one step from `applied` towards `wanted`, returning what is applied after it
+async fn apply(daemon: &SuperSTTDaemon, applied: Applied, wanted: BatteryAction) -> Applied {
+    match (applied, wanted) {
+        (Applied::Nothing, BatteryAction::Cpu) => {
+            if daemon.actual_device.read().await.as_str() != "cuda" {
+                return Applied::Nothing;
+            }
+            info!("Running on battery, moving the model to the CPU");
+            let response = daemon.switch_device_for_session("cpu".to_string()).await;
+            if response.status == "success" {
+                Applied::SwitchedToCpu
+            } else {
+                debug!("Battery switch to CPU postponed: {:?}", response.message);
+                Applied::Nothing
+            }
+        }
+        (Applied::Nothing, BatteryAction::Unload) => {
+            match daemon.unload_model("on battery").await {
+                Ok(_) => Applied::Unloaded,
+                Err(reason) => {
+                    debug!("Battery unload skipped: {reason}");
+                    Applied::Nothing
+                }
+            }
+        }
+        (Applied::SwitchedToCpu, BatteryAction::Keep | BatteryAction::Unload) => {
+            restore_cuda(daemon).await
+        }
+        (Applied::Unloaded, BatteryAction::Keep | BatteryAction::Cpu) => {
+            info!("Back on external power, reloading the model");
+            match daemon.ensure_model_loaded().await {
+                Ok(()) => Applied::Nothing,
+                Err(e) => {
+                    debug!("Reload after battery postponed: {e}");
+                    Applied::Unloaded
+                }
+            }
+        }
+        (applied, _) => applied,
+    }
+}
+
+/// Move the model back to CUDA after a switch made for the battery
+async fn restore_cuda(daemon: &SuperSTTDaemon) -> Applied {
+    // Switched back by hand in the meantime
+    if daemon.preferred_device.read().await.as_str() != "cpu" {
+        return Applied::Nothing;
+    }
+    // Without a model there is nothing to move, so only the preference is restored
+    if daemon.model_type.read().await.is_none() {
+        *daemon.preferred_device.write().await = "cuda".to_string();
+        return Applied::Nothing;
+    }
+
+    info!("Back on external power, moving the model to CUDA");
+    let response = daemon.switch_device_for_session("cuda".to_string()).await;
+    if response.status == "success" {
+        Applied::Nothing
+    } else {
+        debug!("Switch back to CUDA postponed: {:?}", response.message);
+        Applied::SwitchedToCpu
+    }
+}
+
+/// Whether the machine runs on battery, `None` when it has no battery
+///
+/// Supplies scoped to a device, such as the battery of a wireless mouse, do not
+/// power the machine and are skipped, as `UPower` does by reporting them with
+/// `PowerSupply=false`.
+fn on_battery(power_supply: &Path) -> Option<bool> {
+    let mut has_battery = false;
+    let mut discharging = false;
+    let mut external_seen = false;
+    let mut external_online = false;
+
+    for entry in fs::read_dir(power_supply).ok()?.flatten() {
+        let read = |name: &str| {
+            fs::read_to_string(entry.path().join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        if read("scope") == "Device" {
+            continue;
+        }
+        match read("type").as_str() {
+            "Battery" => {
+                has_battery = true;
+                discharging |= read("status") == "Discharging";
+            }
+            "Mains" | "USB" => {
+                external_seen = true;
+                external_online |= read("online") == "1";
+            }
+            _ => {}
+        }
+    }
+
+    if !has_battery {
+        return None;
+    }
+    // Some laptops expose no adapter, leaving the battery status to go by
+    Some(if external_seen {
+        !external_online
+    } else {
+        discharging
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DaemonConfig;
+
+    fn supply(root: &Path, name: &str, fields: &[(&str, &str)]) {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for (field, value) in fields {
+            fs::write(dir.join(field), format!("{value}\n")).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_power_source_detection() {
+        let root = std::env::temp_dir().join(format!("super-stt-power-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        // A desktop without a battery, but with a wireless mouse running down its own
+        supply(&root, "AC", &[("type", "Mains"), ("online", "1")]);
+        supply(
+            &root,
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("status", "Discharging"),
+            ],
+        );
+        assert_eq!(on_battery(&root), None);
+
+        supply(
+            &root,
+            "BAT0",
+            &[("type", "Battery"), ("status", "Charging")],
+        );
+        assert_eq!(on_battery(&root), Some(false));
+
+        supply(&root, "AC", &[("online", "0")]);
+        supply(&root, "BAT0", &[("status", "Discharging")]);
+        assert_eq!(on_battery(&root), Some(true));
+
+        // No adapter listed, so the battery status decides
+        fs::remove_dir_all(root.join("AC")).unwrap();
+        assert_eq!(on_battery(&root), Some(true));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_battery_actions_without_a_model() {
+        let mut config = DaemonConfig::default();
+        config.device.preferred_device = "cpu".to_string();
+        let daemon = SuperSTTDaemon::for_tests(config).await;
+
+        // Nothing runs on CUDA to move, and nothing is loaded to unload
+        assert_eq!(
+            apply(&daemon, Applied::Nothing, BatteryAction::Cpu).await,
+            Applied::Nothing
+        );
+        assert_eq!(
+            apply(&daemon, Applied::Nothing, BatteryAction::Unload).await,
+            Applied::Nothing
+        );
+
+        // The model went away while on battery, so only the preference returns to CUDA
+        assert_eq!(
+            apply(&daemon, Applied::SwitchedToCpu, BatteryAction::Keep).await,
+            Applied::Nothing
+        );
+        assert_eq!(daemon.preferred_device.read().await.as_str(), "cuda");
+    }
+}
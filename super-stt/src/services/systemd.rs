@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Readiness and watchdog notifications for systemd `Type=notify` units
+//!
+//! Everything here is a no-op unless the daemon is built with the `systemd`
+//! feature and started by systemd with `NOTIFY_SOCKET` set.
+
+use tokio::sync::broadcast;
+
+#[cfg(feature = "systemd")]
+fn notify(state: &[sd_notify::NotifyState]) {
+    if let Err(e) = sd_notify::notify(false, state) {
+        log::warn!("Failed to notify systemd: {e}");
+    }
+}
+
+/// Tell systemd what the daemon is doing while it starts up
+pub fn notify_status(status: &str) {
+    #[cfg(feature = "systemd")]
+    notify(&[sd_notify::NotifyState::Status(status)]);
+    #[cfg(not(feature = "systemd"))]
+    let _ = status;
+}
+
+/// Tell systemd the daemon is accepting connections
+pub fn notify_ready() {
+    #[cfg(feature = "systemd")]
+    notify(&[sd_notify::NotifyState::Ready]);
+}
+
+/// Tell systemd the daemon is shutting down
+pub fn notify_stopping() {
+    #[cfg(feature = "systemd")]
+    notify(&[sd_notify::NotifyState::Stopping]);
+}
+
+/// Ping the systemd watchdog at half the unit's `WatchdogSec` until shutdown
+pub fn spawn_watchdog(shutdown_tx: &broadcast::Sender<()>) {
+    #[cfg(feature = "systemd")]
+    {
+        let mut usec = 0;
+        if !sd_notify::watchdog_enabled(false, &mut usec) {
+            return;
+        }
+        let period = std::time::Duration::from_micros(usec / 2);
+        log::info!("systemd watchdog enabled, pinging every {period:?}");
+
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => notify(&[sd_notify::NotifyState::Watchdog]),
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        });
+    }
+    #[cfg(not(feature = "systemd"))]
+    let _ = shutdown_tx;
+}
@@ -10,6 +10,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::daemon::types::STTModelInstance;
 use crate::input::audio::AudioProcessor;
+use crate::stt_models::TextRules;
 use super_stt_shared::services::notification::NotificationManager;
 use super_stt_shared::stt_model::STTModel;
 
@@ -21,6 +22,7 @@ pub struct RealTimeSession {
     pub resampler: FastFixedIn<f32>,
     pub input_sample_rate: u32,
     pub language: Option<String>,
+    pub rules: TextRules,
     pub language_token_set: bool,
     pub tx: broadcast::Sender<String>,
     pub decoding: bool,
@@ -64,6 +66,7 @@ impl RealTimeSession {
             resampler,
             input_sample_rate,
             language,
+            rules: TextRules::default(),
             language_token_set: false,
             tx,
             decoding: false,
@@ -248,6 +251,7 @@ impl RealTimeTranscriptionManager {
         client_id: String,
         sample_rate: Option<u32>,
         language: Option<String>,
+        rules: TextRules,
     ) -> Result<broadcast::Receiver<String>> {
         let sample_rate = sample_rate.unwrap_or(16000);
 
@@ -261,7 +265,9 @@ impl RealTimeTranscriptionManager {
             }
         };
 
-        let session = RealTimeSession::new(client_id.clone(), sample_rate, language, min_interval)?;
+        let mut session =
+            RealTimeSession::new(client_id.clone(), sample_rate, language, min_interval)?;
+        session.rules = rules;
         let receiver = session.subscribe();
 
         let mut sessions = self.sessions.write().await;
@@ -386,6 +392,12 @@ impl RealTimeTranscriptionManager {
         // Prepare and submit audio to model (works for Whisper and Voxtral)
         let resampled_len = audio_data.len();
         let processed = audio_processor.process_audio(&audio_data, 16000)?;
+        let (language, rules) = sessions
+            .read()
+            .await
+            .get(client_id)
+            .map(|session| (session.language.clone(), session.rules.clone()))
+            .unwrap_or_default();
 
         let transcription_result = tokio::task::spawn_blocking({
             let model_clone = Arc::clone(model);
@@ -393,7 +405,7 @@ impl RealTimeTranscriptionManager {
             move || {
                 let mut model_guard = model_clone.blocking_write();
                 if let Some(model) = model_guard.as_mut() {
-                    model.transcribe_audio(&audio, 16000)
+                    model.transcribe_audio(&audio, 16000, language.as_deref(), &rules)
                 } else {
                     Err(anyhow::anyhow!("Model not loaded"))
                 }
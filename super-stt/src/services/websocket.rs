@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! WebSocket gateway for clients that cannot open the Unix socket, such as browsers
+//!
+//! Every text frame carries one `DaemonRequest` and is answered with one
+//! `DaemonResponse`. After a successful `subscribe` the connection also receives
+//! the subscribed events and periodic heartbeats, and `record_stream` sends a frame per partial
+//! transcription, both exactly as they appear on the Unix socket.
+//!
+//! The upgrade request must carry the UDP auth secret as a bearer token, in an
+//! `Authorization` header or, since browsers cannot set headers on WebSockets, an
+//! `access_token` query parameter.
+
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use log::{debug, info, warn};
+use std::net::SocketAddr;
+use super_stt_shared::models::protocol::{
+    DaemonRequest, DaemonResponse, HEARTBEAT_INTERVAL, NotificationEvent,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+
+use crate::daemon::client_management::{Replay, ResponseSink};
+use crate::daemon::types::SuperSTTDaemon;
+
+type Socket = WebSocketStream<TcpStream>;
+
+/// Events subscribed to on a connection
+struct Subscription {
+    id: String,
+    receiver: broadcast::Receiver<NotificationEvent>,
+    /// Timestamp of the last replayed event, live events up to it were already sent
+    replayed_until: Option<String>,
+}
+
+/// Accept WebSocket clients on `addr` until the daemon shuts down
+///
+/// # Errors
+///
+/// Returns an error if the listener cannot bind to `addr`.
+pub async fn spawn(daemon: SuperSTTDaemon, addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind WebSocket gateway to {addr}"))?;
+    if !addr.ip().is_loopback() {
+        warn!("WebSocket gateway bound to non-loopback address {addr}");
+    }
+    info!("WebSocket gateway listening on {addr}");
+
+    let mut shutdown_rx = daemon.shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                result = listener.accept() => match result {
+                    Ok((stream, peer)) => {
+                        let daemon = daemon.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(daemon, stream, peer).await {
+                                debug!("WebSocket client {peer} closed: {e}");
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept WebSocket connection: {e}"),
+                },
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Bearer token of an upgrade request, from its header or `access_token` query parameter
+fn bearer_token(request: &Request) -> Option<String> {
+    if let Some(header) = request.headers().get("authorization")
+        && let Ok(value) = header.to_str()
+    {
+        return value.strip_prefix("Bearer ").map(str::to_string);
+    }
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("access_token="))
+        .map(str::to_string)
+}
+
+fn unauthorized() -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some("Invalid or missing bearer token".to_string()));
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    response
+}
+
+// tungstenite's handshake callback returns its `ErrorResponse` by value
+#[allow(clippy::result_large_err)]
+async fn handle_connection(
+    daemon: SuperSTTDaemon,
+    stream: TcpStream,
+    peer: SocketAddr,
+) -> Result<()> {
+    let mut socket =
+        tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+            match bearer_token(request) {
+                Some(token) if daemon.verify_api_token(&token) => Ok(response),
+                _ => {
+                    warn!("WebSocket client {peer} rejected: invalid or missing bearer token");
+                    Err(unauthorized())
+                }
+            }
+        })
+        .await
+        .context("WebSocket handshake failed")?;
+
+    let connection_id = format!("ws_{}", uuid::Uuid::new_v4());
+    if let Err(e) = daemon
+        .resource_manager
+        .register_client_connection(
+            connection_id.clone(),
+            format!("ws:{}", peer.ip()),
+            Some(peer),
+        )
+        .await
+    {
+        warn!("WebSocket connection rejected due to resource limits: {e}");
+        let response = DaemonResponse::error(&format!("Connection rejected: {e}"));
+        send_json(&mut socket, &response).await?;
+        return Ok(());
+    }
+    info!("WebSocket client connected from {peer}");
+
+    let mut events: Option<Subscription> = None;
+    let result = serve(&daemon, &mut socket, &connection_id, &mut events).await;
+
+    if let Some(subscription) = events {
+        daemon.notification_manager.unsubscribe(&subscription.id);
+        daemon.ensure_audio_monitoring_stopped().await;
+    }
+    daemon
+        .resource_manager
+        .unregister_connection(&connection_id)
+        .await;
+    result
+}
+
+/// Answer requests and forward subscribed events until the client goes away
+async fn serve(
+    daemon: &SuperSTTDaemon,
+    socket: &mut Socket,
+    connection_id: &str,
+    events: &mut Option<Subscription>,
+) -> Result<()> {
+    let mut heartbeat =
+        tokio::time::interval_at(Instant::now() + HEARTBEAT_INTERVAL, HEARTBEAT_INTERVAL);
+    loop {
+        tokio::select! {
+            // Keep an idle event stream visibly alive
+            _ = heartbeat.tick(), if events.is_some() => {
+                send_json(socket, &NotificationEvent::heartbeat()).await?;
+            }
+            message = socket.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    // Pings are answered by tungstenite itself
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                };
+                handle_request(daemon, socket, connection_id, events, text.as_str()).await?;
+            }
+            event = next_event(events) => match event {
+                Ok(event) => send_json(socket, &event).await?,
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    warn!("WebSocket client lagged behind, some events may be lost");
+                }
+                Err(broadcast::error::RecvError::Closed) => *events = None,
+            },
+        }
+    }
+}
+
+/// Wait for the next subscribed event, or forever when not subscribed
+async fn next_event(
+    events: &mut Option<Subscription>,
+) -> Result<NotificationEvent, broadcast::error::RecvError> {
+    let Some(subscription) = events else {
+        return std::future::pending().await;
+    };
+    loop {
+        let event = subscription.receiver.recv().await?;
+        if !Replay::already_sent(&event, subscription.replayed_until.as_deref()) {
+            return Ok(event);
+        }
+    }
+}
+
+async fn handle_request(
+    daemon: &SuperSTTDaemon,
+    socket: &mut Socket,
+    connection_id: &str,
+    events: &mut Option<Subscription>,
+    text: &str,
+) -> Result<()> {
+    let request: DaemonRequest = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Failed to parse WebSocket request: {e}");
+            return send_json(socket, &DaemonResponse::error("Invalid JSON request")).await;
+        }
+    };
+    if let Err(e) = daemon.resource_manager.record_request(connection_id).await {
+        let response = DaemonResponse::error(&format!("Rate limit exceeded: {e}"));
+        return send_json(socket, &response).await;
+    }
+
+    if request.command == "record_stream" {
+        return daemon.stream_recording(request, socket).await;
+    }
+
+    let subscribing = request.command == "subscribe";
+    let replay = Replay::from_request(&request);
+    let response = daemon.handle_command(request).await;
+    let mut missed = Vec::new();
+    if subscribing
+        && events.is_none()
+        && let Some(subscriber_id) = response.client_id.as_ref()
+        && let Some(subscriber) = daemon.notification_manager.subscribers.get(subscriber_id)
+    {
+        let receiver = subscriber.sender.subscribe();
+        drop(subscriber);
+        missed = daemon.missed_events(replay);
+        *events = Some(Subscription {
+            id: subscriber_id.clone(),
+            receiver,
+            replayed_until: missed.last().map(|event| event.timestamp.clone()),
+        });
+    }
+    send_json(socket, &response).await?;
+    for event in &missed {
+        send_json(socket, event).await?;
+    }
+    Ok(())
+}
+
+impl ResponseSink for Socket {
+    /// Send `response` as a text frame
+    async fn send_response(&mut self, response: &DaemonResponse) -> Result<()> {
+        send_json(self, response).await
+    }
+}
+
+async fn send_json(socket: &mut Socket, value: &impl serde::Serialize) -> Result<()> {
+    let json = serde_json::to_string(value)?;
+    socket.send(Message::text(json)).await?;
+    Ok(())
+}
@@ -4,54 +4,302 @@ use anyhow::Result;
 use futures::StreamExt;
 use log::{debug, info, warn};
 use ring::digest::{Context, SHA256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use super_stt_shared::stt_model::STTModel;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
-/// Get the `HuggingFace` Hub URL for a model file
-fn get_hf_url(model_id: &str, revision: &str, filename: &str) -> String {
-    format!("https://huggingface.co/{model_id}/resolve/{revision}/{filename}")
+const HF_BASE_URL: &str = "https://huggingface.co";
+
+/// Bytes between progress broadcasts while a file streams in
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// Tries at a file before a checksum mismatch is reported as an error
+const MAX_DOWNLOAD_ATTEMPTS: usize = 3;
+
+/// How fast and how many files at once a model downloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadLimits {
+    /// Combined rate of all files in kilobits per second, 0 for unlimited
+    pub max_bandwidth_kbps: u32,
+    /// Files fetched at the same time, at least 1
+    pub parallel_files: usize,
 }
 
-/// Get the cache paths for the Hugging Face-like cache layout.
-/// Returns the symlink path under `snapshots/<revision>/<filename>` and the `blobs` directory path.
-fn get_cache_paths(model_id: &str, revision: &str, filename: &str) -> Result<(PathBuf, PathBuf)> {
+impl Default for DownloadLimits {
+    fn default() -> Self {
+        Self {
+            max_bandwidth_kbps: 0,
+            parallel_files: 1,
+        }
+    }
+}
+
+/// Token bucket shared by every file of a download
+///
+/// Reading a chunk spends its size in tokens, and a reader that overdraws the bucket
+/// sleeps until the refill has paid the debt back, which backs off the connection.
+struct Throttle {
+    bytes_per_sec: f64,
+    // Tokens available, negative while in debt, and when they were last refilled
+    bucket: parking_lot::Mutex<(f64, std::time::Instant)>,
+}
+
+impl Throttle {
+    /// A throttle for `kbps` kilobits per second, `None` when unlimited
+    fn new(kbps: u32) -> Option<Self> {
+        let bytes_per_sec = f64::from(kbps) * 1000.0 / 8.0;
+        (kbps > 0).then(|| Self {
+            bytes_per_sec,
+            // Start with a second's worth, the most the bucket holds
+            bucket: parking_lot::Mutex::new((bytes_per_sec, std::time::Instant::now())),
+        })
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    async fn consume(&self, bytes: usize) {
+        let wait = {
+            let mut bucket = self.bucket.lock();
+            let (tokens, refilled_at) = &mut *bucket;
+            let refill = refilled_at.elapsed().as_secs_f64() * self.bytes_per_sec;
+            *tokens = (*tokens + refill).min(self.bytes_per_sec) - bytes as f64;
+            *refilled_at = std::time::Instant::now();
+            (*tokens < 0.0)
+                .then(|| std::time::Duration::from_secs_f64(-*tokens / self.bytes_per_sec))
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Where model files are fetched from
+///
+/// Written in config as `hf://` for the `HuggingFace` Hub, `file:///path` for a
+/// local directory or the base URL of a Hub mirror.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ModelSource {
+    #[default]
+    HuggingFace,
+    /// Pre-downloaded models, each in `<path>/<model id>/` (e.g. `openai/whisper-base`)
+    Local(PathBuf),
+    /// Server with the Hub's `<model id>/resolve/<revision>/<file>` layout
+    Mirror(String),
+}
+
+impl FromStr for ModelSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "hf://" {
+            Ok(Self::HuggingFace)
+        } else if let Some(path) = s.strip_prefix("file://") {
+            if Path::new(path).is_absolute() {
+                Ok(Self::Local(PathBuf::from(path)))
+            } else {
+                Err(format!(
+                    "'{s}' must be an absolute path, e.g. file:///srv/models"
+                ))
+            }
+        } else if s.starts_with("https://") || s.starts_with("http://") {
+            Ok(Self::Mirror(s.trim_end_matches('/').to_string()))
+        } else {
+            Err(format!(
+                "'{s}' is not a model source, expected hf://, file:///path or an http(s) URL"
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for ModelSource {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ModelSource> for String {
+    fn from(source: ModelSource) -> Self {
+        source.to_string()
+    }
+}
+
+impl std::fmt::Display for ModelSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HuggingFace => write!(f, "hf://"),
+            Self::Local(path) => write!(f, "file://{}", path.display()),
+            Self::Mirror(base_url) => write!(f, "{base_url}"),
+        }
+    }
+}
+
+/// Files a model needs, in the order the loaders expect them
+fn model_files(model: STTModel) -> Vec<&'static str> {
+    // Build the file list based on the specific model
+    let mut files = if model.is_voxtral() {
+        vec!["config.json", "tekken.json"]
+    } else {
+        vec!["config.json", "tokenizer.json"]
+    };
+
+    // Add model-specific safetensors files
+    let safetensors_files = match model {
+        STTModel::VoxtralMini => vec![
+            "model-00001-of-00002.safetensors",
+            "model-00002-of-00002.safetensors",
+        ],
+        STTModel::VoxtralSmall => vec![
+            "model-00001-of-00011.safetensors",
+            "model-00002-of-00011.safetensors",
+            "model-00003-of-00011.safetensors",
+            "model-00004-of-00011.safetensors",
+            "model-00005-of-00011.safetensors",
+            "model-00006-of-00011.safetensors",
+            "model-00007-of-00011.safetensors",
+            "model-00008-of-00011.safetensors",
+            "model-00009-of-00011.safetensors",
+            "model-00010-of-00011.safetensors",
+            "model-00011-of-00011.safetensors",
+        ],
+        _ => vec!["model.safetensors"], // Any whisper model
+    };
+
+    files.extend(safetensors_files);
+
+    files
+}
+
+/// Paths of a model's files under a local model source, checking they are all there
+fn local_model_files(root: &Path, model: STTModel) -> Result<Vec<PathBuf>> {
+    let (model_id, _) = model.model_and_revision();
+    let model_dir = root.join(model_id);
+    if !model_dir.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Model directory not found: {}",
+            model_dir.display()
+        ));
+    }
+
+    let file_paths: Vec<PathBuf> = model_files(model)
+        .into_iter()
+        .map(|filename| model_dir.join(filename))
+        .collect();
+    if let Some(missing) = file_paths.iter().find(|path| !path.is_file()) {
+        return Err(anyhow::anyhow!(
+            "Model file not found: {}",
+            missing.display()
+        ));
+    }
+    Ok(file_paths)
+}
+
+/// Get the Hub URL for a model file, on the Hub itself or a mirror
+fn get_hf_url(base_url: &str, model_id: &str, revision: &str, filename: &str) -> String {
+    format!("{base_url}/{model_id}/resolve/{revision}/{filename}")
+}
+
+/// Get the folder holding a model's snapshots and blobs in the Hugging Face-like cache layout
+fn get_model_cache_dir(model_id: &str) -> Result<PathBuf> {
     // Get HF cache directory
     let cache_dir = dirs::cache_dir()
         .ok_or_else(|| anyhow::anyhow!("Cannot determine cache directory"))?
         .join("huggingface")
         .join("hub");
 
-    // Build the model folder path
-    let model_folder = format!("models--{}", model_id.replace('/', "--"));
-    let snapshot_dir = cache_dir
-        .join(&model_folder)
-        .join("snapshots")
-        .join(revision);
+    Ok(cache_dir.join(format!("models--{}", model_id.replace('/', "--"))))
+}
+
+/// Get the cache paths for the Hugging Face-like cache layout.
+/// Returns the symlink path under `snapshots/<revision>/<filename>` and the `blobs` directory path.
+fn get_cache_paths(model_id: &str, revision: &str, filename: &str) -> Result<(PathBuf, PathBuf)> {
+    let model_dir = get_model_cache_dir(model_id)?;
+    let snapshot_dir = model_dir.join("snapshots").join(revision);
 
     // The symlink path (what the user sees)
     let symlink_path = snapshot_dir.join(filename);
 
     // The actual blob storage directory
-    let blobs_dir = cache_dir.join(&model_folder).join("blobs");
+    let blobs_dir = model_dir.join("blobs");
 
     Ok((symlink_path, blobs_dir))
 }
 
+#[derive(Deserialize)]
+struct RepoInfo {
+    siblings: Vec<RepoFile>,
+}
+
+#[derive(Deserialize)]
+struct RepoFile {
+    rfilename: String,
+    lfs: Option<LfsInfo>,
+}
+
+#[derive(Deserialize)]
+struct LfsInfo {
+    sha256: String,
+}
+
+/// Expected SHA-256 of each file the Hub stores in LFS, keyed by filename
+///
+/// Small files such as configs are kept in git and have no published SHA-256, and
+/// a mirror without the Hub API yields no checksums at all; those files are trusted as is.
+async fn expected_checksums(
+    base_url: &str,
+    model_id: &str,
+    revision: &str,
+) -> HashMap<String, String> {
+    let url = format!("{base_url}/api/models/{model_id}/revision/{revision}?blobs=true");
+    let fetch = async {
+        let response = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?;
+        let info: RepoInfo = serde_json::from_str(&response.text().await?)?;
+        anyhow::Ok(info)
+    };
+
+    match fetch.await {
+        Ok(info) => info
+            .siblings
+            .into_iter()
+            .filter_map(|file| Some((file.rfilename, file.lfs?.sha256)))
+            .collect(),
+        Err(e) => {
+            warn!("Could not fetch checksums for {model_id}, files will not be verified: {e}");
+            HashMap::new()
+        }
+    }
+}
+
 /// Async function to download a file with progress tracking and cancellation support
+///
+/// A file with an `expected_sha256` is fetched again when its content does not match,
+/// up to [`MAX_DOWNLOAD_ATTEMPTS`] times, and a cached copy that does not match is replaced.
+#[allow(clippy::too_many_arguments)]
 async fn cancellable_download(
+    base_url: &str,
     model_id: &str,
     revision: &str,
     filename: &str,
+    expected_sha256: Option<&str>,
     tracker: Arc<DownloadProgressTracker>,
+    throttle: Option<&Throttle>,
     file_index: usize,
 ) -> Result<Option<PathBuf>> {
     // Update progress for this file
-    tracker.file_index.store(file_index, Ordering::Relaxed);
     tracker.start_file(filename, file_index);
 
     // Broadcast progress update
@@ -71,21 +319,67 @@ async fn cancellable_download(
 
     // Check if file already exists and is valid
     if symlink_path.exists() {
-        // If the symlink exists, assume cached; optional: verify target exists
-        info!("File already cached: {filename}");
-        tracker.file_index.store(file_index + 1, Ordering::Relaxed);
-        return Ok(Some(symlink_path));
+        // Blobs are named by their SHA-256, so the link target tells whether it is intact
+        let cached_sha256 = fs::read_link(&symlink_path)
+            .await
+            .ok()
+            .and_then(|target| Some(target.file_name()?.to_string_lossy().into_owned()));
+        match (expected_sha256, cached_sha256) {
+            (Some(expected), Some(cached)) if expected != cached => {
+                warn!("Cached {filename} does not match its checksum, downloading it again");
+                fs::remove_file(&symlink_path).await?;
+            }
+            _ => {
+                info!("File already cached: {filename}");
+                tracker.finish_file(file_index);
+                return Ok(Some(symlink_path));
+            }
+        }
     }
 
     // Build the download URL
-    let url = get_hf_url(model_id, revision, filename);
+    let url = get_hf_url(base_url, model_id, revision, filename);
 
     // Ensure blobs directory exists
     fs::create_dir_all(&blobs_dir).await?;
 
     // Download into blobs directory, compute SHA-256, and finalize to blobs/<sha256>
-    let final_blob_path =
-        download_and_hash_with_cancellation(&url, &blobs_dir, Arc::clone(&tracker)).await?;
+    let mut attempt = 1;
+    let final_blob_path = loop {
+        let blob_path =
+            download_and_hash_with_cancellation(&url, &blobs_dir, Arc::clone(&tracker), throttle)
+                .await?;
+        let Some(expected) = expected_sha256 else {
+            break blob_path;
+        };
+
+        tracker.mark_verifying();
+        tracker.broadcast_progress().await;
+        let actual = blob_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if actual == expected {
+            *tracker.status.write() = "downloading".to_string();
+            break blob_path;
+        }
+
+        let received = fs::metadata(&blob_path).await.map_or(0, |md| md.len());
+        tracker.discard_bytes(received);
+        let _ = fs::remove_file(&blob_path).await;
+        if attempt == MAX_DOWNLOAD_ATTEMPTS {
+            tracker.mark_error("checksum mismatch");
+            return Err(anyhow::anyhow!(
+                "Checksum mismatch for {filename} after {MAX_DOWNLOAD_ATTEMPTS} attempts: \
+                 expected SHA-256 {expected}, got {actual}"
+            ));
+        }
+        warn!(
+            "Checksum mismatch for {filename} (attempt {attempt}/{MAX_DOWNLOAD_ATTEMPTS}), downloading it again"
+        );
+        *tracker.status.write() = "downloading".to_string();
+        attempt += 1;
+    };
 
     // Create the snapshot directory for the symlink
     if let Some(parent) = symlink_path.parent() {
@@ -115,7 +409,7 @@ async fn cancellable_download(
         if let Err(e) = symlink(&blob_relative_path, &symlink_path) {
             warn!("Failed to create symlink for {filename}: {e}");
             // Fall back to returning the blob path directly
-            tracker.file_index.store(file_index + 1, Ordering::Relaxed);
+            tracker.finish_file(file_index);
             return Ok(Some(final_blob_path));
         }
     }
@@ -124,12 +418,12 @@ async fn cancellable_download(
     {
         // On non-Unix systems, just return the blob path
         warn!("Symlinks not supported on this platform, using blob path directly");
-        tracker.file_index.store(file_index + 1, Ordering::Relaxed);
+        tracker.finish_file(file_index);
         return Ok(Some(final_blob_path));
     }
 
     // Update file index to show progress
-    tracker.file_index.store(file_index + 1, Ordering::Relaxed);
+    tracker.finish_file(file_index);
 
     // Broadcast final progress update
     let tracker_clone = Arc::clone(&tracker);
@@ -142,10 +436,14 @@ async fn cancellable_download(
 }
 
 /// Download to a temp file in `blobs_dir`, compute SHA-256 while streaming, and finalize to `blobs/<sha256>`
+///
+/// Bytes are added to the tracker's counts, which [`DownloadProgressTracker::start_file`]
+/// resets unless several files download at once.
 async fn download_and_hash_with_cancellation(
     url: &str,
     blobs_dir: &Path,
     tracker: Arc<DownloadProgressTracker>,
+    throttle: Option<&Throttle>,
 ) -> Result<PathBuf> {
     debug!(
         "Starting download with hashing from {url} into {}",
@@ -167,7 +465,7 @@ async fn download_and_hash_with_cancellation(
     }
 
     if let Some(size) = response.content_length() {
-        tracker.total_bytes.store(size, Ordering::Relaxed);
+        tracker.total_bytes.fetch_add(size, Ordering::Relaxed);
     }
 
     // Prepare temp file path
@@ -192,13 +490,19 @@ async fn download_and_hash_with_cancellation(
         let chunk = chunk_result?;
         hasher.update(&chunk);
         file.write_all(&chunk).await?;
+        if let Some(throttle) = throttle {
+            throttle.consume(chunk.len()).await;
+        }
 
+        // Broadcast each time another MiB has arrived, whatever the chunk sizes
+        let previous_step = downloaded / PROGRESS_STEP_BYTES;
         downloaded += chunk.len() as u64;
+        crate::services::metrics::add_download_bytes(chunk.len() as u64);
         tracker
             .bytes_downloaded
-            .store(downloaded, Ordering::Relaxed);
+            .fetch_add(chunk.len() as u64, Ordering::Relaxed);
 
-        if downloaded % (1024 * 1024) == 0 {
+        if downloaded / PROGRESS_STEP_BYTES > previous_step {
             let tracker_clone = Arc::clone(&tracker);
             tokio::spawn(async move {
                 tracker_clone.broadcast_progress().await;
@@ -238,41 +542,20 @@ async fn download_and_hash_with_cancellation(
 
 /// Download model files with progress tracking
 ///
+/// Files from a local source are only checked, since there is nothing to fetch.
+/// `limits` caps the combined bandwidth and how many files download at once.
+///
 /// # Errors
 ///
 /// Returns an error if any file download, file system operation,
 /// or progress update fails.
-pub async fn with_progress(model: &STTModel, tracker: Arc<DownloadProgressTracker>) -> Result<()> {
-    // Build the file list based on the specific model
-    let mut files = if model.is_voxtral() {
-        vec!["config.json", "tekken.json"]
-    } else {
-        vec!["config.json", "tokenizer.json"]
-    };
-
-    // Add model-specific safetensors files
-    let safetensors_files = match model {
-        STTModel::VoxtralMini => vec![
-            "model-00001-of-00002.safetensors",
-            "model-00002-of-00002.safetensors",
-        ],
-        STTModel::VoxtralSmall => vec![
-            "model-00001-of-00011.safetensors",
-            "model-00002-of-00011.safetensors",
-            "model-00003-of-00011.safetensors",
-            "model-00004-of-00011.safetensors",
-            "model-00005-of-00011.safetensors",
-            "model-00006-of-00011.safetensors",
-            "model-00007-of-00011.safetensors",
-            "model-00008-of-00011.safetensors",
-            "model-00009-of-00011.safetensors",
-            "model-00010-of-00011.safetensors",
-            "model-00011-of-00011.safetensors",
-        ],
-        _ => vec!["model.safetensors"], // Any whisper model
-    };
-
-    files.extend(safetensors_files);
+pub async fn with_progress(
+    model: &STTModel,
+    source: &ModelSource,
+    limits: DownloadLimits,
+    tracker: Arc<DownloadProgressTracker>,
+) -> Result<()> {
+    let files = model_files(*model);
 
     // Set total file count
     tracker.total_files.store(files.len(), Ordering::Relaxed);
@@ -283,14 +566,48 @@ pub async fn with_progress(model: &STTModel, tracker: Arc<DownloadProgressTracke
     // Use the model's own method to get the correct model ID and revision
     let (model_id, revision) = model.model_and_revision();
 
-    // Download each file
-    for (index, filename) in files.iter().enumerate() {
-        if tracker.is_cancelled() {
-            return Err(anyhow::anyhow!("Download was cancelled"));
+    let base_url = match source {
+        ModelSource::HuggingFace => HF_BASE_URL,
+        ModelSource::Mirror(base_url) => base_url.as_str(),
+        ModelSource::Local(root) => {
+            // Nothing to fetch, but fail now rather than when the model loads
+            local_model_files(root, *model)?;
+            tracker.mark_completed();
+            tracker.broadcast_progress().await;
+            return Ok(());
         }
+    };
 
-        cancellable_download(model_id, revision, filename, Arc::clone(&tracker), index).await?;
-    }
+    let checksums = expected_checksums(base_url, model_id, revision).await;
+
+    // Download the files, in order when only one may run at a time since the
+    // semaphore hands out permits first come, first served
+    let throttle = Throttle::new(limits.max_bandwidth_kbps);
+    let permits = tokio::sync::Semaphore::new(limits.parallel_files.max(1));
+    tracker.set_parallel(limits.parallel_files > 1);
+    let downloads = files.iter().enumerate().map(|(index, filename)| {
+        let (tracker, throttle, permits) = (Arc::clone(&tracker), throttle.as_ref(), &permits);
+        let expected_sha256 = checksums.get(*filename).map(String::as_str);
+        async move {
+            let _permit = permits.acquire().await?;
+            if tracker.is_cancelled() {
+                return Err(anyhow::anyhow!("Download was cancelled"));
+            }
+            cancellable_download(
+                base_url,
+                model_id,
+                revision,
+                filename,
+                expected_sha256,
+                tracker,
+                throttle,
+                index,
+            )
+            .await
+        }
+    });
+    // The first failure drops the other downloads
+    futures::future::try_join_all(downloads).await?;
 
     // Mark download as complete
     tracker.mark_completed();
@@ -299,49 +616,21 @@ pub async fn with_progress(model: &STTModel, tracker: Arc<DownloadProgressTracke
     Ok(())
 }
 
-/// Get the file paths for an already downloaded model
+/// Get the file paths for an already downloaded model, or its files under a local source
 ///
 /// # Errors
 ///
 /// Returns an error if any expected model file is missing or if
 /// cache path calculation fails.
-pub fn get_model_file_paths(model: &STTModel) -> Result<Vec<PathBuf>> {
+pub fn get_model_file_paths(model: &STTModel, source: &ModelSource) -> Result<Vec<PathBuf>> {
+    if let ModelSource::Local(root) = source {
+        return local_model_files(root, *model);
+    }
     let (model_id, revision) = model.model_and_revision();
 
-    // Build the file list based on the specific model
-    let mut files = if model.is_voxtral() {
-        vec!["config.json", "tekken.json"]
-    } else {
-        vec!["config.json", "tokenizer.json"]
-    };
-
-    // Add model-specific safetensors files
-    let safetensors_files = match model {
-        STTModel::VoxtralMini => vec![
-            "model-00001-of-00002.safetensors",
-            "model-00002-of-00002.safetensors",
-        ],
-        STTModel::VoxtralSmall => vec![
-            "model-00001-of-00011.safetensors",
-            "model-00002-of-00011.safetensors",
-            "model-00003-of-00011.safetensors",
-            "model-00004-of-00011.safetensors",
-            "model-00005-of-00011.safetensors",
-            "model-00006-of-00011.safetensors",
-            "model-00007-of-00011.safetensors",
-            "model-00008-of-00011.safetensors",
-            "model-00009-of-00011.safetensors",
-            "model-00010-of-00011.safetensors",
-            "model-00011-of-00011.safetensors",
-        ],
-        _ => vec!["model.safetensors"], // Any whisper model
-    };
-
-    files.extend(safetensors_files);
-
     // Get the cache paths for all files
     let mut file_paths = Vec::new();
-    for filename in files {
+    for filename in model_files(*model) {
         let (symlink_path, _blob_path) = get_cache_paths(model_id, revision, filename)?;
         // Use symlink path if it exists, otherwise check blob path
         if symlink_path.exists() {
@@ -353,3 +642,129 @@ pub fn get_model_file_paths(model: &STTModel) -> Result<Vec<PathBuf>> {
 
     Ok(file_paths)
 }
+
+/// Space a model's files take on disk, or `None` when any of them is missing
+#[must_use]
+pub fn downloaded_model_size(model: STTModel, source: &ModelSource) -> Option<u64> {
+    get_model_file_paths(&model, source)
+        .ok()?
+        .iter()
+        .map(|path| std::fs::metadata(path).map(|metadata| metadata.len()).ok())
+        .sum()
+}
+
+/// Remove a downloaded model's files from the cache, returning the number of bytes freed
+///
+/// Only the snapshot links of the model's files and the blobs they resolve to are
+/// removed, and a link resolving outside the model's cache folder is left alone.
+///
+/// # Errors
+///
+/// Returns an error if the model is not in the cache or a file cannot be removed.
+pub async fn delete_cached_model(model: STTModel) -> Result<u64> {
+    let (model_id, revision) = model.model_and_revision();
+    let model_dir = fs::canonicalize(get_model_cache_dir(model_id)?)
+        .await
+        .map_err(|_| anyhow::anyhow!("Model {model} is not downloaded"))?;
+
+    let mut found = false;
+    let mut freed_bytes = 0;
+    for filename in model_files(model) {
+        let (symlink_path, _) = get_cache_paths(model_id, revision, filename)?;
+        if fs::symlink_metadata(&symlink_path).await.is_err() {
+            continue;
+        }
+        found = true;
+
+        // A dangling link has no target left to free
+        if let Ok(target) = fs::canonicalize(&symlink_path).await {
+            if target.starts_with(&model_dir) {
+                freed_bytes += fs::metadata(&target).await?.len();
+                fs::remove_file(&target).await?;
+            } else {
+                warn!(
+                    "Not deleting {}: it is outside the model cache {}",
+                    target.display(),
+                    model_dir.display()
+                );
+            }
+        }
+        // Already gone when the file was stored in place of a link
+        if let Err(e) = fs::remove_file(&symlink_path).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            return Err(e.into());
+        }
+    }
+
+    if !found {
+        return Err(anyhow::anyhow!("Model {model} is not downloaded"));
+    }
+
+    // Only succeeds once nothing else is left in the snapshot
+    let _ = fs::remove_dir(model_dir.join("snapshots").join(revision)).await;
+
+    info!("Deleted cached model {model}, freed {freed_bytes} bytes");
+    Ok(freed_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_throttle_holds_the_bandwidth() {
+        assert!(Throttle::new(0).is_none());
+
+        // 800kbps is 100kB a second, the first of which is in the bucket from the start
+        let throttle = Throttle::new(800).unwrap();
+        let start = std::time::Instant::now();
+        throttle.consume(100_000).await;
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+
+        throttle.consume(20_000).await;
+        assert!(start.elapsed() >= std::time::Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_local_model_source() {
+        let root = std::env::temp_dir().join(format!("super-stt-models-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let model = STTModel::WhisperTinyEn;
+        let source: ModelSource = format!("file://{}", root.display()).parse().unwrap();
+
+        let err = get_model_file_paths(&model, &source).unwrap_err();
+        assert!(err.to_string().contains("Model directory not found"));
+
+        let dir = root.join(model.model_and_revision().0);
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["config.json", "tokenizer.json"] {
+            std::fs::write(dir.join(file), "{}").unwrap();
+        }
+        let err = get_model_file_paths(&model, &source).unwrap_err();
+        assert!(err.to_string().ends_with("model.safetensors"));
+
+        std::fs::write(dir.join("model.safetensors"), "weights").unwrap();
+        let paths = get_model_file_paths(&model, &source).unwrap();
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().all(|path| path.starts_with(&dir)));
+        assert_eq!(downloaded_model_size(model, &source), Some(2 + 2 + 7));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_mirror_model_source() {
+        let source: ModelSource = "https://hf-mirror.example.com/".parse().unwrap();
+        let ModelSource::Mirror(base_url) = source else {
+            panic!("not a mirror: {source}");
+        };
+        assert_eq!(
+            get_hf_url(&base_url, "openai/whisper-base", "main", "config.json"),
+            "https://hf-mirror.example.com/openai/whisper-base/resolve/main/config.json"
+        );
+
+        assert!("ftp://models".parse::<ModelSource>().is_err());
+        assert!("file://relative/path".parse::<ModelSource>().is_err());
+    }
+}
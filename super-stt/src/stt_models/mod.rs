@@ -2,6 +2,36 @@
 pub mod download;
 pub mod voxtral;
 pub mod whisper;
+pub mod word_filter;
 
 pub use voxtral::VoxtralModel;
 pub use whisper::WhisperModel;
+
+use word_filter::WordFilterConfig;
+
+/// Apply user-defined `(pattern, replacement)` rules to `text`, in order
+#[must_use]
+pub fn apply_replacements(text: &str, replacements: &[(String, String)]) -> String {
+    replacements
+        .iter()
+        .filter(|(pattern, _)| !pattern.is_empty())
+        .fold(text.to_string(), |text, (pattern, replacement)| {
+            text.replace(pattern, replacement)
+        })
+}
+
+/// User-defined cleanup of transcribed text: replacement rules, then the word filter
+#[derive(Debug, Clone, Default)]
+pub struct TextRules {
+    pub replacements: Vec<(String, String)>,
+    pub word_filter: WordFilterConfig,
+}
+
+impl TextRules {
+    /// Apply the replacement rules in order, then hide the filtered words
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        self.word_filter
+            .apply(&apply_replacements(text, &self.replacements))
+    }
+}
@@ -4,6 +4,7 @@ use candle_core::{Device, Tensor};
 use symphonia::core::audio::{AudioBufferRef, Signal};
 use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
 use symphonia::core::conv::FromSample;
+use symphonia::core::io::MediaSource;
 
 fn conv<T>(samples: &mut Vec<f32>, data: &symphonia::core::audio::AudioBuffer<T>)
 where
@@ -38,8 +39,21 @@ where
 /// or if the audio stream cannot be processed.
 pub fn pcm_decode<P: AsRef<std::path::Path>>(path: P) -> Result<(Vec<f32>, u32)> {
     let src = std::fs::File::open(path)?;
+    decode_source(Box::new(src))
+}
+
+/// Decode an in-memory audio file to PCM samples
+///
+/// # Errors
+///
+/// Returns an error if the data cannot be decoded.
+pub fn pcm_decode_bytes(data: Vec<u8>) -> Result<(Vec<f32>, u32)> {
+    decode_source(Box::new(std::io::Cursor::new(data)))
+}
+
+fn decode_source(src: Box<dyn MediaSource>) -> Result<(Vec<f32>, u32)> {
     let mss = symphonia::core::io::MediaSourceStream::new(
-        Box::new(src),
+        src,
         symphonia::core::io::MediaSourceStreamOptions::default(),
     );
     let hint = symphonia::core::probe::Hint::new();
@@ -2,29 +2,81 @@
 use std::path::PathBuf;
 
 use anyhow::{Context, Error, Result};
-use candle_core::{DType, Device, Tensor, utils};
+use candle_core::{DType, Device, IndexOp, Tensor, utils};
 use candle_nn::VarBuilder;
+use candle_transformers::generation::LogitsProcessor;
 use candle_transformers::models::voxtral::{
     VoxtralCache, VoxtralConfig, VoxtralEncoderConfig, VoxtralForConditionalGeneration,
-    VoxtralGenerationConfig, VoxtralLlamaConfig, audio,
+    VoxtralLlamaConfig, audio,
 };
 use log::{debug, info, warn};
 use serde_json;
 use tekken::Tekkenizer;
 
+use crate::stt_models::TextRules;
+use crate::stt_models::download::ModelSource;
+
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::io::Cursor;
 use super_stt_shared::{
+    models::protocol::{DecodingOverride, Segment},
     stt_model::STTModel,
     utils::audio::{ResampleQuality, resample},
+    validation,
 };
 
 const SAMPLE_RATE: u32 = 16000;
 
+/// Languages Voxtral was trained to transcribe
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en", "es", "fr", "pt", "hi", "de", "nl", "it"];
+
 #[derive(Debug, serde::Serialize)]
 pub struct TranscriptionResult {
     pub text: String,
     pub tokens: Vec<u32>,
+    /// Timed segments, when the model can align its output to the audio
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<Segment>>,
+    /// Confidence in `0.0..=1.0`; always 0.0 because generation does not expose token scores
+    pub confidence: f32,
+}
+
+/// How Voxtral turns its predictions into text
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DecodingConfig {
+    /// Upper bound on the tokens generated for one transcription; longer speech is cut off
+    pub max_new_tokens: usize,
+    /// Sampling temperature; 0.0 always picks the most likely token
+    pub temperature: f64,
+    /// Nucleus sampling cutoff, used when the temperature is above 0.0
+    pub top_p: Option<f64>,
+    /// Times a phrase may repeat back to back before generation stops; 0 never stops
+    pub repetition_limit: u32,
+}
+
+impl Default for DecodingConfig {
+    fn default() -> Self {
+        Self {
+            max_new_tokens: 1000,
+            temperature: 0.0,
+            top_p: None,
+            repetition_limit: 4,
+        }
+    }
+}
+
+impl DecodingConfig {
+    /// Apply per-request overrides on top of these settings
+    #[must_use]
+    pub fn with_override(self, decoding: &DecodingOverride) -> Self {
+        Self {
+            max_new_tokens: decoding.max_new_tokens.unwrap_or(self.max_new_tokens),
+            temperature: decoding.temperature.unwrap_or(self.temperature),
+            top_p: decoding.top_p.or(self.top_p),
+            repetition_limit: decoding.repetition_limit.unwrap_or(self.repetition_limit),
+        }
+    }
 }
 
 pub struct VoxtralModel {
@@ -34,6 +86,7 @@ pub struct VoxtralModel {
     config: VoxtralConfig,
     audio_token_id: usize,
     cache: VoxtralCache,
+    decoding: DecodingConfig,
 }
 
 impl VoxtralModel {
@@ -45,7 +98,7 @@ impl VoxtralModel {
     ///
     /// Panics if expected file names are not valid UTF-8 or missing
     /// when inspecting cached paths (due to `unwrap()` on file names).
-    pub fn new(stt_model: &STTModel, force_cpu: bool) -> Result<Self> {
+    pub fn new(stt_model: &STTModel, source: &ModelSource, force_cpu: bool) -> Result<Self> {
         info!("Loading Voxtral {stt_model:?} model...");
 
         // Determine device
@@ -62,7 +115,7 @@ impl VoxtralModel {
         };
 
         // Get file paths from the unified download system
-        let file_paths = crate::stt_models::download::get_model_file_paths(stt_model)?;
+        let file_paths = crate::stt_models::download::get_model_file_paths(stt_model, source)?;
 
         // Extract the specific files we need
         let config_path = file_paths
@@ -120,11 +173,14 @@ impl VoxtralModel {
             config,
             audio_token_id,
             cache,
+            decoding: DecodingConfig::default(),
         })
     }
 
     /// Transcribe audio and return both text and tokens
     ///
+    /// The user's text `rules` are applied after the built-in cleanup.
+    ///
     /// # Errors
     ///
     /// Returns an error if the audio data cannot be transcribed.
@@ -132,20 +188,37 @@ impl VoxtralModel {
         &mut self,
         audio_data: &[f32],
         sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
     ) -> Result<TranscriptionResult> {
-        let (transcription, tokens) = self.transcribe_audio_internal(audio_data, sample_rate)?;
+        let (transcription, tokens) =
+            self.transcribe_audio_internal(audio_data, sample_rate, language, rules)?;
 
+        // Voxtral generates free-running text without alignment information or
+        // token scores, so there is no confidence to report
         Ok(TranscriptionResult {
             text: transcription,
             tokens,
+            segments: None,
+            confidence: 0.0,
         })
     }
 
+    /// Transcribe audio in `language` (English when `None`), applying the user's
+    /// text `rules` after the built-in cleanup
+    ///
     /// # Errors
     ///
-    /// Returns an error if the audio data cannot be transcribed.
-    pub fn transcribe_audio(&mut self, audio_data: &[f32], sample_rate: u32) -> Result<String> {
-        let (transcription, _) = self.transcribe_audio_internal(audio_data, sample_rate)?;
+    /// Returns an error if the language is unsupported or the audio data cannot be transcribed.
+    pub fn transcribe_audio(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<String> {
+        let (transcription, _) =
+            self.transcribe_audio_internal(audio_data, sample_rate, language, rules)?;
         Ok(transcription)
     }
 
@@ -158,7 +231,11 @@ impl VoxtralModel {
         &mut self,
         audio_data: &[f32],
         sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
     ) -> Result<(String, Vec<u32>)> {
+        let language_tokens = self.language_tokens(language.unwrap_or("en"))?;
+
         // Resample to 16kHz if needed
         let audio = if sample_rate == SAMPLE_RATE {
             audio_data.to_vec()
@@ -189,16 +266,29 @@ impl VoxtralModel {
 
         let audio_features = audio::extract_features(&padded_audio, &mel_filters, &self.device)?;
 
-        let (result, tokens) = transcribe_with_voxtral(
+        let (decoded_text, tokens) = transcribe_with_voxtral(
             &self.model,
             &self.tokenizer,
             &audio_features,
             self.audio_token_id,
+            &language_tokens,
             &self.device,
             &self.cache.clone(),
+            self.decoding,
         )?;
 
-        Ok((result, tokens))
+        // Post-process the transcription to clean up formatting artifacts
+        let transcription = post_process_transcription(&decoded_text, rules)?;
+
+        Ok((transcription, tokens))
+    }
+
+    /// Tokenize the `lang:<code>` prompt that selects the transcription language
+    fn language_tokens(&self, code: &str) -> Result<Vec<u32>> {
+        validation::validate_language_supported(code, SUPPORTED_LANGUAGES)?;
+        self.tokenizer
+            .encode(&format!("lang:{code}"), false, false)
+            .map_err(|e| anyhow::anyhow!("Failed to encode language prompt: {}", e))
     }
 
     #[must_use]
@@ -206,6 +296,16 @@ impl VoxtralModel {
         &self.device
     }
 
+    #[must_use]
+    pub fn decoding(&self) -> DecodingConfig {
+        self.decoding
+    }
+
+    /// Use `decoding` for later transcriptions
+    pub fn set_decoding(&mut self, decoding: DecodingConfig) {
+        self.decoding = decoding;
+    }
+
     #[must_use]
     pub fn config(&self) -> &VoxtralConfig {
         &self.config
@@ -216,12 +316,13 @@ impl VoxtralModel {
 ///
 /// This function handles common formatting issues that arise from different token
 /// generation between Python and Rust implementations, particularly when the first
-/// token is a quote character instead of regular text.
+/// token is a quote character instead of regular text. User-defined `rules`, the
+/// replacements and then the word filter, are applied once the built-in fixes are done.
 ///
 /// # Errors
 ///
 /// Returns an error if the transcription is invalid (empty or just punctuation).
-pub fn post_process_transcription(text: &str) -> Result<String> {
+pub fn post_process_transcription(text: &str, rules: &TextRules) -> Result<String> {
     let mut cleaned = text.trim().to_string();
 
     // Handle the case where transcription starts with quotes and has extra spaces
@@ -263,11 +364,14 @@ pub fn post_process_transcription(text: &str) -> Result<String> {
     cleaned = cleaned.replace(" \"' ", " ");
     cleaned = cleaned.replace(" '\" ", " ");
 
+    cleaned = rules.apply(&cleaned);
+
     // Handle case where Rust mel generation produces just "."
-    if cleaned == "." || cleaned.trim().is_empty() {
-        return Err(anyhow::anyhow!(
-            "Mel feature generation produced invalid output. This is a known issue with Candle's mel spectrogram implementation."
-        ));
+    if cleaned == "." {
+        return Err(MelGenerationFailed.into());
+    }
+    if cleaned.trim().is_empty() {
+        return Err(anyhow::anyhow!("Transcription came out empty"));
     }
 
     // Remove any trailing quotes
@@ -279,13 +383,29 @@ pub fn post_process_transcription(text: &str) -> Result<String> {
     Ok(cleaned)
 }
 
+/// Error returned when the output collapses to a lone "." because the mel features
+/// were invalid, a known issue with Candle's mel spectrogram implementation
+#[derive(Debug)]
+pub struct MelGenerationFailed;
+
+impl std::fmt::Display for MelGenerationFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Mel feature generation produced invalid output")
+    }
+}
+
+impl std::error::Error for MelGenerationFailed {}
+
+#[allow(clippy::too_many_arguments)]
 fn transcribe_with_voxtral(
     model: &VoxtralForConditionalGeneration,
     tokenizer: &Tekkenizer,
     audio_features: &Tensor,
     audio_token_id: usize,
+    language_tokens: &[u32],
     device: &Device,
     cache: &VoxtralCache,
+    decoding: DecodingConfig,
 ) -> Result<(String, Vec<u32>)> {
     // Validate audio features shape
     let audio_dims = audio_features.dims();
@@ -306,7 +426,7 @@ fn transcribe_with_voxtral(
     // Create the exact token sequence that HuggingFace processor generates
     let mut input_tokens = Vec::new();
 
-    // Pattern: <s>[INST][BEGIN_AUDIO][AUDIO]*N[/INST]lang:en[TRANSCRIBE]
+    // Pattern: <s>[INST][BEGIN_AUDIO][AUDIO]*N[/INST]lang:xx[TRANSCRIBE]
     input_tokens.push(1u32); // BOS: <s>
     input_tokens.push(3u32); // [INST]
     input_tokens.push(25u32); // [BEGIN_AUDIO]
@@ -327,51 +447,107 @@ fn transcribe_with_voxtral(
     }
 
     input_tokens.push(4u32); // [/INST]
-    input_tokens.push(9909u32); // lang
-    input_tokens.push(1058u32); // :
-    input_tokens.push(1262u32); // en
+    input_tokens.extend_from_slice(language_tokens); // lang:xx
     input_tokens.push(34u32); // [TRANSCRIBE]
 
-    let input_len = input_tokens.len();
     let input_ids = Tensor::new(input_tokens, device)?.unsqueeze(0)?;
 
     // Calculate approximate memory usage
     let _input_elements = input_ids.dims().iter().product::<usize>();
     let _audio_elements = audio_features.dims().iter().product::<usize>();
 
-    let config = VoxtralGenerationConfig {
-        max_new_tokens: 1000,
-        temperature: 0.0,
-        top_p: None,
-        device: device.clone(),
-        cache: Some(cache.clone()),
-    };
-
-    // Generate response using the model (match Python parameters)
-    let generated_tokens = model
-        .generate(
-            &input_ids,
-            Some(audio_features), // Audio features will be processed and inserted at audio token position
-            config,
-        )
-        .map_err(|e| anyhow::anyhow!("Failed to generate tokens: {}", e))?;
-
-    // Decode only the newly generated tokens (skip input prompt)
-    let new_tokens = if generated_tokens.len() > input_len {
-        &generated_tokens[input_len..]
-    } else {
-        &generated_tokens
-    };
+    let new_tokens = generate_tokens(model, &input_ids, audio_features, device, cache, decoding)?;
 
     let decoded_text = tokenizer
-        .decode(new_tokens, tekken::SpecialTokenPolicy::Ignore)
+        .decode(&new_tokens, tekken::SpecialTokenPolicy::Ignore)
         .map_err(|e| anyhow::anyhow!("Failed to decode tokens: {}", e))?;
 
-    // Post-process the transcription to clean up formatting artifacts
-    let transcription = post_process_transcription(&decoded_text)?;
+    // Return both the raw transcription and tokens
+    Ok((decoded_text, new_tokens))
+}
+
+/// Generate the transcription tokens that follow the prompt in `input_ids`
+///
+/// Mirrors `VoxtralForConditionalGeneration::generate`, but watches the output for
+/// a repetition loop and stops there, dropping the repeated copies.
+fn generate_tokens(
+    model: &VoxtralForConditionalGeneration,
+    input_ids: &Tensor,
+    audio_features: &Tensor,
+    device: &Device,
+    cache: &VoxtralCache,
+    decoding: DecodingConfig,
+) -> Result<Vec<u32>> {
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() ^ u64::from(d.subsec_nanos()));
+    let mut sampler = LogitsProcessor::new(seed, Some(decoding.temperature), decoding.top_p);
+    let mut cache = cache.clone();
+    let prompt_len = input_ids.dim(1)?;
+    let mut tokens: Vec<u32> = Vec::new();
+
+    for step in 0..decoding.max_new_tokens {
+        // The first pass reads the whole prompt with the audio; later ones only the last token
+        let logits = if let Some(&last) = tokens.last() {
+            let input = Tensor::new(&[last], device)?.unsqueeze(0)?;
+            model.forward(&input, None, &mut cache, prompt_len + step - 1)
+        } else {
+            model.forward(input_ids, Some(audio_features), &mut cache, 0)
+        }
+        .map_err(|e| anyhow::anyhow!("Failed to generate tokens: {}", e))?;
+
+        let logits = if logits.dims().len() == 3 {
+            logits.i((.., logits.dim(1)? - 1, ..))?
+        } else {
+            logits
+        };
+        let next_token = sampler.sample(&logits.squeeze(0)?)?;
+        if EOS_TOKENS.contains(&next_token) {
+            break;
+        }
+        tokens.push(next_token);
+
+        if let Some(repeated) = repeated_tail(&tokens, decoding.repetition_limit) {
+            warn!(
+                "Voxtral output is repeating itself, stopping early and dropping {repeated} tokens"
+            );
+            tokens.truncate(tokens.len() - repeated);
+            break;
+        }
+    }
 
-    // Return both transcription and tokens
-    Ok((transcription, new_tokens.to_vec()))
+    Ok(tokens)
+}
+
+/// End-of-sequence tokens of the Mistral tokenizers, as `generate` checks them
+const EOS_TOKENS: [u32; 4] = [2, 128_001, 128_009, 128_256];
+
+/// Longest phrase, in tokens, checked for repetition
+const MAX_REPEATED_PHRASE_TOKENS: usize = 32;
+
+/// Shortest run of repeats treated as a loop, so a long number with repeated digits is not
+const MIN_REPEATED_RUN_TOKENS: usize = 16;
+
+/// Number of tokens to drop when `tokens` ends with a phrase repeated back to back
+/// more than `limit` times, keeping its first copy
+///
+/// Returns `None` when there is no such loop or `limit` is 0.
+fn repeated_tail(tokens: &[u32], limit: u32) -> Option<usize> {
+    if limit == 0 {
+        return None;
+    }
+    let limit = limit as usize;
+    (1..=MAX_REPEATED_PHRASE_TOKENS).find_map(|len| {
+        let copies = (limit + 1).max(MIN_REPEATED_RUN_TOKENS.div_ceil(len));
+        if tokens.len() < len * copies {
+            return None;
+        }
+        let tail = &tokens[tokens.len() - len * copies..];
+        let phrase = &tail[..len];
+        tail.chunks_exact(len)
+            .all(|chunk| chunk == phrase)
+            .then_some(len * (copies - 1))
+    })
 }
 
 /// Load model weights from safetensors files
@@ -593,3 +769,64 @@ fn parse_text_config(json: &serde_json::Value) -> Result<VoxtralLlamaConfig> {
             .unwrap_or(false),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_tail_keeps_first_copy() {
+        let phrase = [10u32, 11, 12, 13, 14];
+        let mut tokens = vec![1, 2, 3];
+        for _ in 0..5 {
+            tokens.extend_from_slice(&phrase);
+        }
+        assert_eq!(repeated_tail(&tokens, 4), Some(4 * phrase.len()));
+        assert_eq!(repeated_tail(&tokens, 5), None);
+        assert_eq!(repeated_tail(&tokens, 0), None);
+
+        // Short runs such as the digits of a large number are not a loop
+        let digits = [7u32, 0, 0, 0, 0, 0, 0];
+        assert_eq!(repeated_tail(&digits, 4), None);
+        assert_eq!(repeated_tail(&[0; 17], 4), Some(15));
+    }
+
+    #[test]
+    fn test_decoding_override_keeps_unset_settings() {
+        let config = DecodingConfig {
+            temperature: 0.2,
+            top_p: Some(0.9),
+            ..DecodingConfig::default()
+        };
+
+        let decoding = config.with_override(&DecodingOverride {
+            max_new_tokens: Some(200),
+            temperature: Some(0.0),
+            ..DecodingOverride::default()
+        });
+        assert_eq!(
+            decoding,
+            DecodingConfig {
+                max_new_tokens: 200,
+                temperature: 0.0,
+                top_p: Some(0.9),
+                repetition_limit: config.repetition_limit,
+            }
+        );
+        assert_eq!(config.with_override(&DecodingOverride::default()), config);
+    }
+
+    #[test]
+    fn test_lone_period_is_a_mel_failure() {
+        let rules = TextRules::default();
+        let err = post_process_transcription(" . ", &rules).unwrap_err();
+        assert!(err.is::<MelGenerationFailed>());
+
+        let err = post_process_transcription("  ", &rules).unwrap_err();
+        assert!(!err.is::<MelGenerationFailed>());
+        assert_eq!(
+            post_process_transcription("Hello.", &rules).unwrap(),
+            "Hello."
+        );
+    }
+}
@@ -1,4 +1,4 @@
 // SPDX-License-Identifier: GPL-3.0-only
 mod model;
 
-pub use model::WhisperModel;
+pub use model::{TranscriptionResult, WhisperModel};
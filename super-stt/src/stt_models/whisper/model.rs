@@ -2,19 +2,46 @@
 use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
 use candle_core::utils::cuda_is_available;
-use candle_core::{Device, IndexOp, Tensor};
+use candle_core::{D, Device, IndexOp, Tensor};
 use candle_nn::{VarBuilder, ops::softmax};
 use candle_transformers::models::whisper::{self as m, Config, audio};
 use log::{debug, info, warn};
 use std::io::Cursor;
 use super_stt_shared::audio_utils::ResampleQuality;
+use super_stt_shared::models::protocol::{LanguageCandidate, Segment};
 use super_stt_shared::stt_model::STTModel;
+use super_stt_shared::validation::{LANGUAGE_CODES, ValidationError};
 use tokenizers::Tokenizer;
 
+use crate::stt_models::TextRules;
+use crate::stt_models::download::ModelSource;
+
 use super_stt_shared::utils::audio::resample;
 
 const SAMPLE_RATE: u32 = 16000;
 
+#[derive(Debug, serde::Serialize)]
+pub struct TranscriptionResult {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<Segment>>,
+    /// Geometric mean of the decoded token probabilities, in `0.0..=1.0`
+    pub confidence: f32,
+}
+
+/// Text decoded from one window, with the log-probabilities of its tokens
+#[derive(Default)]
+struct DecodedWindow {
+    text: String,
+    /// Generated tokens, timestamps included, without the prompt and end of text
+    tokens: Vec<u32>,
+    sum_logprob: f32,
+    token_count: usize,
+}
+
+/// Timestamp tokens per second of audio, 20ms apart
+const TIMESTAMPS_PER_SECOND: f32 = 50.0;
+
 pub enum Model {
     Normal(m::model::Whisper),
 }
@@ -63,6 +90,7 @@ pub struct WhisperModel {
     transcribe_token: u32,
     eot_token: u32,
     no_timestamps_token: u32,
+    multilingual: bool,
 }
 
 impl WhisperModel {
@@ -75,7 +103,7 @@ impl WhisperModel {
     /// Panics if file paths from the model cache cannot be converted to valid UTF-8
     /// or if a required path component is unexpectedly missing when extracting
     /// `config.json`, `tokenizer.json`, or `model.safetensors`.
-    pub fn new(stt_model: &STTModel, force_cpu: bool) -> Result<Self> {
+    pub fn new(stt_model: &STTModel, source: &ModelSource, force_cpu: bool) -> Result<Self> {
         info!("Loading Whisper {stt_model:?} model...");
 
         // Determine device
@@ -92,7 +120,7 @@ impl WhisperModel {
         };
 
         // Get file paths from the unified download system
-        let file_paths = crate::stt_models::download::get_model_file_paths(stt_model)?;
+        let file_paths = crate::stt_models::download::get_model_file_paths(stt_model, source)?;
 
         // Extract the specific files we need
         let config_path = file_paths
@@ -168,14 +196,103 @@ impl WhisperModel {
             transcribe_token,
             eot_token,
             no_timestamps_token,
+            multilingual: stt_model.is_multilingual(),
         })
     }
 
+    /// Transcribe audio, decoding in `language` (English when `None`) and applying
+    /// the user's text `rules` to the decoded text
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the language is not supported by this checkpoint or the
+    /// audio data cannot be converted to a mel spectrogram.
+    pub fn transcribe_audio(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<String> {
+        let result = self.transcribe(audio_data, sample_rate, language, rules, false)?;
+        Ok(result.text)
+    }
+
+    /// Whether this checkpoint was trained on more than English
+    #[must_use]
+    pub fn is_multilingual(&self) -> bool {
+        self.multilingual
+    }
+
+    /// Languages this checkpoint can decode
+    #[must_use]
+    pub fn supported_languages(&self) -> &'static [&'static str] {
+        if self.multilingual {
+            LANGUAGE_CODES
+        } else {
+            &["en"]
+        }
+    }
+
+    /// Resolve the decoder prompt token for the requested language
+    fn language_token(&self, language: Option<&str>) -> Result<Option<u32>> {
+        let code = language.unwrap_or("en");
+        let unsupported = || ValidationError::UnsupportedLanguage {
+            code: code.to_string(),
+        };
+
+        if !self.supported_languages().contains(&code) {
+            return Err(unsupported().into());
+        }
+
+        match self.tokenizer.token_to_id(&format!("<|{code}|>")) {
+            Some(token) => Ok(Some(token)),
+            // English-only checkpoints may not carry a language token at all
+            None if !self.multilingual => Ok(None),
+            None => Err(unsupported().into()),
+        }
+    }
+
+    /// Transcribe audio and return the text together with segment timings
+    ///
+    /// Segments come from the timestamp tokens Whisper predicts along with the text.
+    /// Text the model left without timestamps spans the rest of its 30 second
+    /// decoding window. The user's text `rules` are applied to each segment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the language is not supported by this checkpoint or the
+    /// audio data cannot be converted to a mel spectrogram.
+    pub fn transcribe_audio_with_timestamps(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+    ) -> Result<TranscriptionResult> {
+        self.transcribe(audio_data, sample_rate, language, rules, true)
+    }
+
+    /// Transcribe audio into text, segments and confidence
+    ///
+    /// Without `timestamps` the model is told not to predict them, which is faster,
+    /// and each segment is a whole 30 second decoding window.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the audio data cannot be converted to a mel spectrogram.
-    pub fn transcribe_audio(&mut self, audio_data: &[f32], sample_rate: u32) -> Result<String> {
+    /// Returns an error if the language is not supported by this checkpoint or the
+    /// audio data cannot be converted to a mel spectrogram.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn transcribe(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        rules: &TextRules,
+        timestamps: bool,
+    ) -> Result<TranscriptionResult> {
         debug!("Transcribing audio with sample rate {sample_rate}Hz");
+        let language_token = self.language_token(language)?;
 
         // Resample to 16kHz if needed
         let audio = if sample_rate == SAMPLE_RATE {
@@ -184,6 +301,7 @@ impl WhisperModel {
             warn!("Audio sample rate is {sample_rate}Hz, resampling to {SAMPLE_RATE}Hz");
             resample(audio_data, sample_rate, SAMPLE_RATE, ResampleQuality::Fast)?
         };
+        let duration = audio.len() as f32 / SAMPLE_RATE as f32;
 
         // Use optimized Candle audio processing
         let mel = audio::pcm_to_mel(&self.config, &audio, &self.mel_filters);
@@ -199,48 +317,191 @@ impl WhisperModel {
         )
         .context("Failed to create mel tensor")?;
 
-        let result = self.run_segmented(&mel)?;
+        let (mut segments, confidence) =
+            self.run_segmented(&mel, duration, language_token, timestamps)?;
+        for segment in &mut segments {
+            segment.text = rules.apply(&segment.text).trim().to_string();
+        }
+        segments.retain(|segment| !segment.text.is_empty());
+
+        let text = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim()
+            .to_string();
+
+        Ok(TranscriptionResult {
+            text,
+            segments: Some(segments),
+            confidence,
+        })
+    }
+
+    /// Rank the most likely spoken languages using the first 30 seconds of audio
+    ///
+    /// Scores come from the decoder's language-token probabilities after the
+    /// start-of-transcript token, as in Whisper's own language identification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for English-only checkpoints, or if the audio cannot be
+    /// converted to a mel spectrogram or run through the model.
+    pub fn detect_language(
+        &mut self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        top_n: usize,
+    ) -> Result<Vec<LanguageCandidate>> {
+        if !self.multilingual {
+            return Err(anyhow::anyhow!(
+                "Language detection requires a multilingual Whisper model"
+            ));
+        }
+
+        let audio = if sample_rate == SAMPLE_RATE {
+            audio_data.to_vec()
+        } else {
+            resample(audio_data, sample_rate, SAMPLE_RATE, ResampleQuality::Fast)?
+        };
+        let window = audio.len().min(SAMPLE_RATE as usize * 30);
+
+        let mel = audio::pcm_to_mel(&self.config, &audio[..window], &self.mel_filters);
+        let mel_len = mel.len();
+        let mel = Tensor::from_vec(
+            mel,
+            (
+                1,
+                self.config.num_mel_bins,
+                mel_len / self.config.num_mel_bins,
+            ),
+            &self.device,
+        )
+        .context("Failed to create mel tensor")?;
+        let (_, _, frames) = mel.dims3()?;
+        let mel = mel.narrow(2, 0, usize::min(frames, 3000))?;
+
+        let (codes, token_ids): (Vec<&str>, Vec<u32>) = LANGUAGE_CODES
+            .iter()
+            .filter_map(|&code| {
+                self.tokenizer
+                    .token_to_id(&format!("<|{code}|>"))
+                    .map(|token| (code, token))
+            })
+            .unzip();
+        let token_ids = Tensor::new(token_ids.as_slice(), &self.device)?;
+
+        let audio_features = self.model.encoder_forward(&mel, true)?;
+        let tokens = Tensor::new(&[[self.sot_token]], &self.device)?;
+        let ys = self.model.decoder_forward(&tokens, &audio_features, true)?;
+        let logits = self
+            .model
+            .decoder_final_linear(&ys.i(..1)?)?
+            .i(0)?
+            .i(0)?
+            .index_select(&token_ids, 0)?;
+        let probs: Vec<f32> = softmax(&logits, D::Minus1)?.to_vec1()?;
+
+        let mut candidates: Vec<LanguageCandidate> = codes
+            .into_iter()
+            .zip(probs)
+            .map(|(code, confidence)| LanguageCandidate {
+                language: code.to_string(),
+                confidence,
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        candidates.truncate(top_n);
 
-        Ok(result)
+        Ok(candidates)
     }
 
-    fn run_segmented(&mut self, mel: &Tensor) -> Result<String> {
+    /// Decode every 30 second window, returning its segments and overall confidence
+    #[allow(clippy::cast_precision_loss)]
+    fn run_segmented(
+        &mut self,
+        mel: &Tensor,
+        duration: f32,
+        language_token: Option<u32>,
+        timestamps: bool,
+    ) -> Result<(Vec<Segment>, f32)> {
         let (_, _, content_frames) = mel.dims3()?;
         let mut seek = 0;
-        let mut all_text = Vec::new();
+        let mut segments = Vec::new();
+        let mut sum_logprob = 0.0;
+        let mut token_count = 0;
 
         let n_frames = 3000;
+        let frame_seconds = m::HOP_LENGTH as f32 / m::SAMPLE_RATE as f32;
 
         while seek < content_frames {
             // Calculate segment size
             let segment_size = usize::min(content_frames - seek, n_frames);
 
+            // The mel spectrogram is padded past the end of the audio
+            let start = seek as f32 * frame_seconds;
+            if start >= duration {
+                break;
+            }
+
             // Extract mel segment using narrow
             let mel_segment = mel.narrow(2, seek, segment_size)?;
 
             // Decode this segment with fallback temperatures
-            let segment_result = self.decode_with_fallback(&mel_segment)?;
-
-            if !segment_result.trim().is_empty() {
-                all_text.push(segment_result);
+            let segment_result =
+                self.decode_with_fallback(&mel_segment, language_token, timestamps)?;
+
+            if !segment_result.text.trim().is_empty() {
+                let window_end = ((seek + segment_size) as f32 * frame_seconds).min(duration);
+                for (span_start, span_end, text_tokens) in
+                    split_at_timestamps(&segment_result.tokens, self.no_timestamps_token + 1)
+                {
+                    let text = self
+                        .tokenizer
+                        .decode(&text_tokens, true)
+                        .map_err(|e| anyhow::anyhow!("Tokenizer decode error: {}", e))?;
+                    if text.trim().is_empty() {
+                        continue;
+                    }
+                    let end = span_end.map_or(window_end, |end| (start + end).min(window_end));
+                    segments.push(Segment {
+                        start: (start + span_start).min(end),
+                        end,
+                        text: text.trim().to_string(),
+                        speaker_id: 0,
+                    });
+                }
+                sum_logprob += segment_result.sum_logprob;
+                token_count += segment_result.token_count;
             }
 
             seek += segment_size;
         }
 
-        // Join all segment results
-        let final_text = all_text.join(" ").trim().to_string();
-        Ok(final_text)
+        // Nothing was decoded, so there is nothing to be confident about
+        let confidence = if token_count == 0 {
+            0.0
+        } else {
+            (sum_logprob / token_count as f32).exp()
+        };
+
+        Ok((segments, confidence))
     }
 
-    fn decode_with_fallback(&mut self, mel_segment: &Tensor) -> Result<String> {
+    fn decode_with_fallback(
+        &mut self,
+        mel_segment: &Tensor,
+        language_token: Option<u32>,
+        timestamps: bool,
+    ) -> Result<DecodedWindow> {
         let temperatures = [0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
 
         for (i, &temperature) in temperatures.iter().enumerate() {
-            match self.decode_simple(mel_segment, temperature) {
+            match self.decode_simple(mel_segment, temperature, language_token, timestamps) {
                 Ok(result) => {
                     // Simple quality check - if we get reasonable text, use it
-                    if !result.trim().is_empty() && result.len() > 5 {
+                    if !result.text.trim().is_empty() && result.text.len() > 5 {
                         return Ok(result);
                     }
                 }
@@ -252,10 +513,16 @@ impl WhisperModel {
             }
         }
 
-        Ok(String::new())
+        Ok(DecodedWindow::default())
     }
 
-    fn decode_simple(&mut self, mel: &Tensor, temperature: f64) -> Result<String> {
+    fn decode_simple(
+        &mut self,
+        mel: &Tensor,
+        temperature: f64,
+        language_token: Option<u32>,
+        timestamps: bool,
+    ) -> Result<DecodedWindow> {
         let audio_features = self.model.encoder_forward(mel, true)?;
 
         let suppress_tokens: Vec<f32> = (0..u32::try_from(self.config.vocab_size).unwrap())
@@ -272,13 +539,19 @@ impl WhisperModel {
         let sample_len = self.config.max_target_positions / 2;
         let mut tokens = vec![self.sot_token];
 
-        // Add language token if available (optimize - check once)
-        if let Some(en_token) = self.tokenizer.token_to_id("<|en|>") {
-            tokens.push(en_token);
+        // Add language token if the checkpoint has one
+        if let Some(language_token) = language_token {
+            tokens.push(language_token);
         }
 
         tokens.push(self.transcribe_token);
-        tokens.push(self.no_timestamps_token);
+        if !timestamps {
+            tokens.push(self.no_timestamps_token);
+        }
+        let prompt_len = tokens.len();
+
+        let mut sum_logprob = 0.0;
+        let mut token_count = 0;
 
         for i in 0..sample_len {
             let tokens_t = Tensor::new(tokens.as_slice(), mel.device())?;
@@ -297,13 +570,14 @@ impl WhisperModel {
 
             // Apply suppress tokens
             let logits = logits.broadcast_add(&suppress_tokens_tensor)?;
+            let logits_v: Vec<f32> = logits.to_vec1()?;
 
             // Optimized token selection - greedy only for speed
             let next_token = if temperature > 0f64 {
                 // Simplified sampling for performance
                 let prs = softmax(&(&logits / temperature)?, 0)?;
-                let logits_v: Vec<f32> = prs.to_vec1()?;
-                logits_v
+                let prs_v: Vec<f32> = prs.to_vec1()?;
+                prs_v
                     .iter()
                     .enumerate()
                     .max_by(|(_, u), (_, v)| u.total_cmp(v))
@@ -311,7 +585,6 @@ impl WhisperModel {
                     .unwrap()
             } else {
                 // Greedy decoding (fastest)
-                let logits_v: Vec<f32> = logits.to_vec1()?;
                 logits_v
                     .iter()
                     .enumerate()
@@ -320,6 +593,8 @@ impl WhisperModel {
                     .unwrap()
             };
 
+            sum_logprob += token_logprob(&logits_v, next_token as usize);
+            token_count += 1;
             tokens.push(next_token);
 
             if next_token == self.eot_token || tokens.len() > self.config.max_target_positions {
@@ -327,14 +602,29 @@ impl WhisperModel {
             }
         }
 
-        // Decode tokens to text
+        let mut generated = tokens.split_off(prompt_len);
+        if generated.last() == Some(&self.eot_token) {
+            generated.pop();
+        }
+
+        // Decode tokens to text, leaving out the timestamps
+        let timestamp_begin = self.no_timestamps_token + 1;
+        let text_tokens: Vec<u32> = generated
+            .iter()
+            .copied()
+            .filter(|&token| token < timestamp_begin)
+            .collect();
         let text = self
             .tokenizer
-            .decode(&tokens, true)
+            .decode(&text_tokens, true)
             .map_err(|e| anyhow::anyhow!("Tokenizer decode error: {}", e))?;
 
-        let text = text.trim_start();
-        Ok(text.to_string())
+        Ok(DecodedWindow {
+            text: text.trim_start().to_string(),
+            tokens: generated,
+            sum_logprob,
+            token_count,
+        })
     }
 
     pub fn device(&self) -> &Device {
@@ -345,3 +635,79 @@ impl WhisperModel {
         &self.config
     }
 }
+
+/// Split a window's tokens at the timestamp tokens, from `timestamp_begin` on, into
+/// text tokens with their start and end in seconds from the window start
+///
+/// Text before the first timestamp starts with the window, and text after the last
+/// one has no end, so it runs to the end of the window.
+#[allow(clippy::cast_precision_loss)]
+fn split_at_timestamps(tokens: &[u32], timestamp_begin: u32) -> Vec<(f32, Option<f32>, Vec<u32>)> {
+    let mut spans = Vec::new();
+    let mut start = 0.0;
+    let mut text = Vec::new();
+    for &token in tokens {
+        if token >= timestamp_begin {
+            let time = (token - timestamp_begin) as f32 / TIMESTAMPS_PER_SECOND;
+            if !text.is_empty() {
+                spans.push((start, Some(time), std::mem::take(&mut text)));
+            }
+            start = time;
+        } else {
+            text.push(token);
+        }
+    }
+    if !text.is_empty() {
+        spans.push((start, None, text));
+    }
+    spans
+}
+
+/// Log-probability of `token` under the softmax of `logits`
+fn token_logprob(logits: &[f32], token: usize) -> f32 {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let log_sum_exp = max + logits.iter().map(|l| (l - max).exp()).sum::<f32>().ln();
+    logits[token] - log_sum_exp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_logprob_is_normalized() {
+        let uniform = [0.5f32; 4];
+        assert!((token_logprob(&uniform, 2) - 0.25f32.ln()).abs() < 1e-6);
+
+        // Suppressed tokens carry no probability mass
+        let logits = [2.0, f32::NEG_INFINITY, 2.0];
+        assert!((token_logprob(&logits, 0) - 0.5f32.ln()).abs() < 1e-6);
+        assert!(token_logprob(&logits, 1).is_infinite());
+    }
+
+    #[test]
+    fn test_split_at_timestamps() {
+        const BEGIN: u32 = 100;
+
+        // <|0.00|> 1 2 <|1.50|><|1.50|> 3 <|2.40|>
+        let spans = split_at_timestamps(
+            &[BEGIN, 1, 2, BEGIN + 75, BEGIN + 75, 3, BEGIN + 120],
+            BEGIN,
+        );
+        assert_eq!(
+            spans,
+            [(0.0, Some(1.5), vec![1, 2]), (1.5, Some(2.4), vec![3])]
+        );
+
+        // Text the model did not close runs to the end of the window
+        let spans = split_at_timestamps(&[BEGIN + 50, 4, 5], BEGIN);
+        assert_eq!(spans, [(1.0, None, vec![4, 5])]);
+
+        // Decoded without timestamps, the whole window is one span
+        assert_eq!(
+            split_at_timestamps(&[6, 7], BEGIN),
+            [(0.0, None, vec![6, 7])]
+        );
+        assert!(split_at_timestamps(&[BEGIN, BEGIN + 10], BEGIN).is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Hiding of unwanted words in transcriptions, matched as whole words so that a
+//! filtered word inside a longer one is left alone
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use super_stt_shared::models::protocol::{WordFilterMode, WordFilterOverride};
+
+/// Words hidden in every transcription, off while the list is empty
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WordFilterConfig {
+    /// Matched case-insensitively against whole words
+    pub words: Vec<String>,
+    pub mode: WordFilterMode,
+}
+
+impl WordFilterConfig {
+    /// These settings with the fields set in `overrides` replaced
+    #[must_use]
+    pub fn with_override(&self, overrides: &WordFilterOverride) -> Self {
+        Self {
+            words: overrides
+                .words
+                .clone()
+                .unwrap_or_else(|| self.words.clone()),
+            mode: overrides.mode.unwrap_or(self.mode),
+        }
+    }
+
+    /// Mask or drop the filtered words in `text`
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        if self.words.is_empty() {
+            return text.to_string();
+        }
+        let words: HashSet<String> = self.words.iter().map(|w| w.to_lowercase()).collect();
+
+        let mut filtered = String::with_capacity(text.len());
+        let mut rest = text;
+        // Set after a word dropped at the start, whose following space goes instead
+        let mut skip_space = false;
+        while let Some(start) = rest.find(is_word_char) {
+            let end = rest[start..]
+                .find(|c| !is_word_char(c))
+                .map_or(rest.len(), |len| start + len);
+            let word = &rest[start..end];
+            let between = &rest[..start];
+
+            if skip_space {
+                filtered.push_str(between.trim_start());
+            } else {
+                filtered.push_str(between);
+            }
+            skip_space = false;
+
+            if words.contains(&word.to_lowercase()) {
+                match self.mode {
+                    WordFilterMode::Mask => filtered.extend(word.chars().map(|_| '*')),
+                    WordFilterMode::Drop => {
+                        filtered.truncate(filtered.trim_end().len());
+                        skip_space = filtered.is_empty();
+                    }
+                }
+            } else {
+                filtered.push_str(word);
+            }
+            rest = &rest[end..];
+        }
+
+        if skip_space {
+            filtered.push_str(rest.trim_start());
+        } else {
+            filtered.push_str(rest);
+        }
+        filtered
+    }
+}
+
+/// Apostrophes split words, so a possessive like "word's" still matches "word"
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(mode: WordFilterMode) -> WordFilterConfig {
+        WordFilterConfig {
+            words: vec!["darn".to_string(), "Heck".to_string()],
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_masks_whole_words_only() {
+        let mask = filter(WordFilterMode::Mask);
+        assert_eq!(
+            mask.apply("Darn it, what the heck. Darning socks."),
+            "**** it, what the ****. Darning socks."
+        );
+        assert_eq!(mask.apply("It's darn's fault"), "It's ****'s fault");
+        assert_eq!(WordFilterConfig::default().apply("darn"), "darn");
+    }
+
+    #[test]
+    fn test_drops_words_with_their_space() {
+        let drop = filter(WordFilterMode::Drop);
+        assert_eq!(drop.apply("Darn it, what the heck."), "it, what the.");
+        assert_eq!(drop.apply("this is darn good"), "this is good");
+        assert_eq!(drop.apply("heck"), "");
+    }
+}